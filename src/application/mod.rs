@@ -1,2 +1,3 @@
 pub mod orchestrator;
+pub mod proof_batcher;
 pub mod ports;
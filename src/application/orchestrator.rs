@@ -1,22 +1,218 @@
-use crate::application::ports::{BridgeReader, DaStrategy, ProofProvider, Storage};
+use crate::application::ports::{
+    Attestor, BridgeReader, Clock, ConfirmationSource, DaStrategy, NodeStatus, ProofProvider, ProofRequest,
+    ReadinessProbe, Storage, SystemClock,
+};
+use crate::application::proof_batcher::ProofBatcher;
 use crate::domain::{
-    batch::{Batch, BatchStatus},
+    batch::{Batch, BatchId, BatchStatus, ProofSystem},
+    blackout::BlackoutWindow,
     errors::DomainError,
+    fees::FeeEscalation,
+    public_inputs::{check_public_inputs, reduce_to_field, RootPacking, PUBLIC_INPUTS_LEN},
 };
 use ethers::types::{H256, U256};
-use metrics::{counter, histogram};
+use futures::future::join_all;
+use metrics::{counter, gauge, histogram};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{error, info, warn};
 
-// BN254 Scalar Field Modulus
-// 21888242871839275222246405745257275088548364400416034343698204186575808495617
-const SNARK_SCALAR_FIELD: U256 = U256([
-    0x43e1f593f0000001,
-    0x2833e84879b97091,
-    0xb85045b68181585d,
-    0x30644e72e131a029,
-]);
+/// How the orchestrator decides a batch's data file is completely written before using it, for
+/// producers that do not write files atomically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataFileCheck {
+    /// Use the file as soon as the batch is pending.
+    #[default]
+    None,
+    /// Wait for a `<data_file>.ready` marker next to it.
+    ReadyMarker,
+    /// Wait until the file's size is the same across two reads taken a settle delay apart.
+    StableSize,
+}
+
+/// Where the batches pending at the start of an [`Orchestrator::run_once`] pass ended up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CycleSummary {
+    pub processed: usize,
+    pub confirmed: usize,
+    pub failed: usize,
+    // Not yet Confirmed or Failed after the pass
+    pub pending: usize,
+}
+
+impl CycleSummary {
+    /// Process exit code for the pass: `1` if any batch ended `Failed`, else `0`.
+    pub fn exit_code(&self) -> i32 {
+        if self.failed > 0 { 1 } else { 0 }
+    }
+
+    /// Adds up the summaries of several rollups.
+    pub fn merge(self, other: CycleSummary) -> CycleSummary {
+        CycleSummary {
+            processed: self.processed + other.processed,
+            confirmed: self.confirmed + other.confirmed,
+            failed: self.failed + other.failed,
+            pending: self.pending + other.pending,
+        }
+    }
+}
+
+/// Puts a `Failed` batch back into the pipeline with a fresh attempt budget: at `Proved` if it
+/// kept its proof, otherwise at `Discovered`. Batches in any other state are refused.
+pub async fn requeue_batch(rollup: &str, storage: &dyn Storage, id: BatchId) -> Result<Batch, DomainError> {
+    let mut batch = storage
+        .get_batch(id)
+        .await?
+        .ok_or_else(|| DomainError::InvalidBatch(format!("Batch {} not found", id)))?;
+    match batch.status {
+        BatchStatus::Failed => {}
+        BatchStatus::Confirmed => {
+            return Err(DomainError::InvalidBatch(format!("Batch {} is already Confirmed", id)));
+        }
+        ref other => {
+            return Err(DomainError::InvalidBatch(format!(
+                "Batch {} is {}; only Failed batches can be requeued",
+                id, other
+            )));
+        }
+    }
+
+    let resume_at = if batch.proof.is_some() { BatchStatus::Proved } else { BatchStatus::Discovered };
+    batch.transition_to(resume_at)?;
+    batch.attempts = 0;
+    batch.tx_hash = None;
+    batch.signed_tx = None;
+    batch.da_reference = None;
+    batch.fee_bumps = 0;
+    batch.next_attempt_at = None;
+    storage.save_batch(&batch).await?;
+    let event = storage
+        .record_event(batch.id, BatchStatus::Failed, batch.status.clone(), Some("requeued".into()))
+        .await;
+    if let Err(e) = event {
+        warn!("Failed to record event for batch {}: {}", batch.id, e);
+    }
+
+    info!("Batch {} requeued as {}", batch.id, batch.status);
+    counter!("batches_requeued_total", "rollup" => rollup.to_string()).increment(1);
+    Ok(batch)
+}
+
+/// `base * 2^attempts`, less up to half of it (`jitter` in `[0, 1)` picks how much).
+fn retry_delay(base: Duration, attempts: u32, jitter: f64) -> Duration {
+    let full = base.saturating_mul(2u32.saturating_pow(attempts));
+    full.saturating_sub(full.mul_f64(jitter.clamp(0.0, 1.0) / 2.0))
+}
+
+/// What seeding does with a batch whose payload another batch already got on chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentDedup {
+    /// Do not look for duplicates.
+    #[default]
+    Off,
+    /// Log the duplicate and enqueue the batch anyway.
+    Warn,
+    /// Log the duplicate and do not enqueue the batch.
+    Skip,
+}
+
+/// Looks for another batch with `batch`'s `data_hash` that is already `Submitted` or
+/// `Confirmed`, and applies `policy` to it. Returns whether `batch` should still be enqueued.
+pub async fn check_duplicate_content(
+    rollup: &str,
+    storage: &dyn Storage,
+    batch: &Batch,
+    policy: ContentDedup,
+) -> Result<bool, DomainError> {
+    let Some(data_hash) = batch.data_hash.as_deref().filter(|_| policy != ContentDedup::Off) else {
+        return Ok(true);
+    };
+    let duplicate = storage
+        .get_batches_by_data_hash(data_hash)
+        .await?
+        .into_iter()
+        .find(|b| b.id != batch.id && matches!(b.status, BatchStatus::Submitted | BatchStatus::Confirmed));
+    let Some(duplicate) = duplicate else {
+        return Ok(true);
+    };
+    let skip = policy == ContentDedup::Skip;
+    warn!(
+        "Batch {} carries the same payload as {} batch {}{}",
+        batch.id,
+        duplicate.status,
+        duplicate.id,
+        if skip { "; skipping it" } else { "" }
+    );
+    counter!(
+        "batches_duplicate_content_total",
+        "rollup" => rollup.to_string(),
+        "action" => if skip { "skip" } else { "warn" }
+    )
+    .increment(1);
+    Ok(!skip)
+}
+
+/// Stores a newly discovered batch unless `max_pending` non-terminal batches are already queued.
+/// Returns whether the batch was stored.
+pub async fn enqueue_batch(
+    rollup: &str,
+    storage: &dyn Storage,
+    batch: &Batch,
+    max_pending: Option<u64>,
+) -> Result<bool, DomainError> {
+    if let Some(max) = max_pending {
+        let pending = storage.count_pending().await?;
+        if pending >= max {
+            warn!("Rejecting batch {}: {} batches pending (max {})", batch.id, pending, max);
+            counter!("batches_rejected_total", "rollup" => rollup.to_string(), "reason" => "max_pending")
+                .increment(1);
+            return Ok(false);
+        }
+    }
+    storage.save_batch(batch).await?;
+    Ok(true)
+}
+
+/// Stores `batches` if all of them fit under `max_pending`, and none otherwise, so a chain of
+/// chunk batches is never cut short. Returns whether they were stored.
+pub async fn enqueue_batches(
+    rollup: &str,
+    storage: &dyn Storage,
+    batches: &[Batch],
+    max_pending: Option<u64>,
+) -> Result<bool, DomainError> {
+    if let Some(max) = max_pending {
+        let pending = storage.count_pending().await?;
+        if pending + batches.len() as u64 > max {
+            warn!(
+                "Rejecting {} batches: {} batches pending (max {})",
+                batches.len(),
+                pending,
+                max
+            );
+            counter!("batches_rejected_total", "rollup" => rollup.to_string(), "reason" => "max_pending")
+                .increment(batches.len() as u64);
+            return Ok(false);
+        }
+    }
+    for batch in batches {
+        storage.save_batch(batch).await?;
+    }
+    Ok(true)
+}
+
+/// Index for a new batch that was not given one: one past the highest index stored, or 0.
+pub async fn next_batch_index(storage: &dyn Storage) -> Result<u64, DomainError> {
+    Ok(storage.max_batch_index().await?.map_or(0, |index| index + 1))
+}
+
+/// An audit trail entry awaiting its batch's save: the move's `from`, `to` and detail.
+type PendingEvent = (BatchStatus, BatchStatus, Option<String>);
 
 pub struct Orchestrator {
     storage: Arc<dyn Storage>,
@@ -24,6 +220,45 @@ pub struct Orchestrator {
     da_strategy: Arc<dyn DaStrategy>,
     bridge_reader: Arc<dyn BridgeReader>,
     max_attempts: u32,
+    max_concurrent_confirmations: Option<usize>,
+    // Cycle in which each Submitted batch last had its confirmation checked
+    confirmation_checked: std::sync::Mutex<(u64, HashMap<BatchId, u64>)>,
+    // Audit trail entries of each batch held back until the batch they describe is saved
+    pending_events: std::sync::Mutex<HashMap<BatchId, Vec<PendingEvent>>>,
+    aggregated_proofs: bool,
+    submit_blackout: Vec<BlackoutWindow>,
+    clock: Arc<dyn Clock>,
+    confirmation_source: Option<Arc<dyn ConfirmationSource>>,
+    attestor: Option<Arc<dyn Attestor>>,
+    node_status: Option<Arc<dyn NodeStatus>>,
+    health_node: Option<Arc<dyn NodeStatus>>,
+    ready: AtomicBool,
+    root_packing: RootPacking,
+    fee_escalation: FeeEscalation,
+    max_fee_per_gas: Option<U256>,
+    prover_budget: Option<Duration>,
+    strict_public_inputs: bool,
+    max_proving_age: Option<Duration>,
+    max_submitted_age: Option<Duration>,
+    retry_unavailable_data: bool,
+    stuck_threshold: Option<Duration>,
+    batched_proving: bool,
+    readiness_probes: Vec<Arc<dyn ReadinessProbe>>,
+    transition_events: bool,
+    verify_onchain_commitment: bool,
+    precheck_proofs: bool,
+    check_batch_index: bool,
+    check_root_chain: bool,
+    ordered_submission: bool,
+    data_file_preflight: bool,
+    cleanup_data_files: bool,
+    initial_root_retries: u32,
+    initial_root_retry_delay: Duration,
+    rollup: String,
+    retry_backoff: Option<Duration>,
+    last_confirmation: std::sync::Mutex<Option<chrono::DateTime<chrono::Utc>>>,
+    data_file_check: DataFileCheck,
+    data_file_settle: Duration,
 }
 
 impl Orchestrator {
@@ -40,383 +275,3539 @@ impl Orchestrator {
             da_strategy,
             bridge_reader,
             max_attempts,
+            max_concurrent_confirmations: None,
+            confirmation_checked: std::sync::Mutex::new((0, HashMap::new())),
+            pending_events: std::sync::Mutex::new(HashMap::new()),
+            aggregated_proofs: false,
+            submit_blackout: Vec::new(),
+            clock: Arc::new(SystemClock),
+            confirmation_source: None,
+            attestor: None,
+            node_status: None,
+            health_node: None,
+            ready: AtomicBool::new(true),
+            root_packing: RootPacking::default(),
+            fee_escalation: FeeEscalation::default(),
+            max_fee_per_gas: None,
+            prover_budget: None,
+            strict_public_inputs: false,
+            max_proving_age: None,
+            max_submitted_age: None,
+            retry_unavailable_data: false,
+            stuck_threshold: None,
+            batched_proving: false,
+            readiness_probes: Vec::new(),
+            transition_events: false,
+            verify_onchain_commitment: false,
+            precheck_proofs: false,
+            check_batch_index: false,
+            check_root_chain: false,
+            ordered_submission: false,
+            data_file_preflight: false,
+            cleanup_data_files: false,
+            initial_root_retries: 0,
+            initial_root_retry_delay: Duration::ZERO,
+            rollup: "default".to_string(),
+            retry_backoff: None,
+            last_confirmation: std::sync::Mutex::new(None),
+            data_file_check: DataFileCheck::None,
+            data_file_settle: Duration::ZERO,
         }
     }
 
-    pub async fn run(&self) -> Result<(), DomainError> {
-        info!("Orchestrator started");
-        loop {
-            if let Err(e) = self.process_pending_batches().await {
-                error!("Error processing batches: {}", e);
-            }
-            tokio::time::sleep(Duration::from_secs(5)).await;
-        }
+    /// Names the rollup this orchestrator serves; every metric it records carries it as the
+    /// `rollup` label.
+    pub fn with_rollup(mut self, name: impl Into<String>) -> Self {
+        self.rollup = name.into();
+        self
     }
 
-    pub async fn process_pending_batches(&self) -> Result<(), DomainError> {
-        let batches = self.storage.get_pending_batches().await?;
+    /// The rollup name set with [`Orchestrator::with_rollup`] (`default` if unset).
+    pub fn rollup(&self) -> &str {
+        &self.rollup
+    }
 
-        for mut batch in batches {
-            self.process_batch(&mut batch).await?;
-        }
-        Ok(())
+    /// Leaves batches that have not been submitted yet alone until their data file passes
+    /// `check`; `settle` is the delay between the two size reads of [`DataFileCheck::StableSize`].
+    pub fn with_data_file_check(mut self, check: DataFileCheck, settle: Duration) -> Self {
+        self.data_file_check = check;
+        self.data_file_settle = settle;
+        self
     }
 
-    async fn handle_failure(
-        &self,
-        batch: &mut Batch,
-        error_msg: String,
-    ) -> Result<(), DomainError> {
-        batch.attempts += 1;
+    async fn data_file_ready(&self, batch: &Batch) -> bool {
+        let ready = match self.data_file_check {
+            // Object stores only expose fully written objects
+            _ if !batch.has_local_data_file() => return true,
+            DataFileCheck::None => return true,
+            DataFileCheck::ReadyMarker => {
+                tokio::fs::try_exists(format!("{}.ready", batch.data_file)).await.unwrap_or(false)
+            }
+            DataFileCheck::StableSize => {
+                let size = || async { tokio::fs::metadata(&batch.data_file).await.map(|m| m.len()).ok() };
+                let first = size().await;
+                tokio::time::sleep(self.data_file_settle).await;
+                first.is_some() && first == size().await
+            }
+        };
+        if !ready {
+            info!("Data file {} of batch {} is not ready yet", batch.data_file, batch.id);
+            counter!("data_file_not_ready_total", "rollup" => self.rollup.clone()).increment(1);
+        }
+        ready
+    }
 
-        counter!("batch_failures_total", "batch_id" => batch.id.to_string()).increment(1);
+    /// When this orchestrator last moved a batch to `Confirmed`; `None` until it has.
+    pub fn last_confirmation(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        *self.last_confirmation.lock().unwrap()
+    }
 
-        if batch.attempts >= self.max_attempts {
-            warn!(
-                "Batch {} FAILED permanently after {} attempts: {}",
-                batch.id, batch.attempts, error_msg
-            );
-            batch.transition_to(BatchStatus::Failed);
-            counter!("batches_failed_permanent_total").increment(1);
-        } else {
-            warn!(
-                "Batch {} failed (attempt {}/{}): {}. Retrying...",
-                batch.id, batch.attempts, self.max_attempts, error_msg
-            );
-        }
-        self.storage.save_batch(batch).await
+    /// Holds a batch back after a failed attempt: the next try waits `base * 2^attempts`, with
+    /// up to half of that taken off at random so batches failing together spread out.
+    pub fn with_retry_backoff(mut self, base: Option<Duration>) -> Self {
+        self.retry_backoff = base;
+        self
     }
 
-    #[tracing::instrument(skip(self, batch), fields(batch_id = %batch.id, status = %batch.status))]
-    async fn process_batch(&self, batch: &mut Batch) -> Result<(), DomainError> {
-        info!("Processing batch");
-        let start = Instant::now();
+    /// See [`requeue_batch`].
+    pub async fn requeue_batch(&self, id: BatchId) -> Result<Batch, DomainError> {
+        requeue_batch(&self.rollup, self.storage.as_ref(), id).await
+    }
 
-        match batch.status {
-            BatchStatus::Discovered => {
-                batch.transition_to(BatchStatus::Proving);
-                self.storage.save_batch(batch).await?;
-                counter!("batch_transitions_total", "from" => "Discovered", "to" => "Proving")
-                    .increment(1);
-            }
-            BatchStatus::Proving => {
-                // 1. Fetch L1 Context (BridgeReader)
-                let old_root_res = self.bridge_reader.state_root().await;
-                // 2. Compute Commitment (DaStrategy)
-                let commitment_res = self.da_strategy.compute_commitment(batch);
-
-                match (old_root_res, commitment_res) {
-                    (Ok(old_root_h256), Ok(commitment_h256)) => {
-                        // 3. Sanitize Inputs (Orchestrator)
-                        let da_input = U256::from_big_endian(commitment_h256.as_bytes()) % SNARK_SCALAR_FIELD;
-                        let old_root_input = U256::from_big_endian(old_root_h256.as_bytes()) % SNARK_SCALAR_FIELD;
-
-                        // Parse new_root from hex string
-                        let new_root_val = match batch.new_root.parse::<H256>() {
-                            Ok(h) => U256::from_big_endian(h.as_bytes()) % SNARK_SCALAR_FIELD,
-                            Err(e) => {
-                                self.handle_failure(batch, format!("Invalid new_root: {}", e))
-                                    .await?;
-                                return Ok(());
-                            }
-                        };
-
-                        // 4. Request Proof
-                        // Format public inputs as bytes. The Prover likely expects 32-byte chunks.
-                        // Order: daCommitment, oldRoot, newRoot
-                        let mut public_inputs = Vec::with_capacity(96);
-                        let mut buf = [0u8; 32];
-                        da_input.to_big_endian(&mut buf);
-                        public_inputs.extend_from_slice(&buf);
-                        old_root_input.to_big_endian(&mut buf);
-                        public_inputs.extend_from_slice(&buf);
-                        new_root_val.to_big_endian(&mut buf);
-                        public_inputs.extend_from_slice(&buf);
-
-                        match self.prover.get_proof(&batch.id, &public_inputs).await {
-                            Ok(response) => {
-                                batch.proof = Some(response.proof);
-                                batch.transition_to(BatchStatus::Proved);
-                                batch.attempts = 0;
-                                self.storage.save_batch(batch).await?;
-
-                                counter!("batch_transitions_total", "from" => "Proving", "to" => "Proved")
-                                    .increment(1);
-                                histogram!("prove_duration_seconds").record(start.elapsed().as_secs_f64());
-                            }
-                            Err(e) => {
-                                self.handle_failure(batch, e.to_string()).await?;
-                            }
-                        }
-                    }
-                    (Err(e), _) => {
-                         self.handle_failure(batch, format!("Failed to fetch state root: {}", e)).await?;
-                    }
-                    (_, Err(e)) => {
-                        self.handle_failure(batch, format!("Failed to compute commitment: {}", e)).await?;
-                    }
-                }
-            }
-            BatchStatus::Proved => {
-                batch.transition_to(BatchStatus::Submitting);
-                self.storage.save_batch(batch).await?;
-                counter!("batch_transitions_total", "from" => "Proved", "to" => "Submitting")
-                    .increment(1);
-            }
-            BatchStatus::Submitting => {
-                if let Some(proof) = &batch.proof {
-                    match self.da_strategy.submit(batch, proof).await {
-                        Ok(tx_hash) => {
-                            batch.tx_hash = Some(tx_hash);
-                            batch.transition_to(BatchStatus::Submitted);
-                            batch.attempts = 0;
-                            self.storage.save_batch(batch).await?;
+    /// Deletes a batch's data file once it confirms, unless another pending batch reads it too.
+    pub fn with_data_file_cleanup(mut self, enabled: bool) -> Self {
+        self.cleanup_data_files = enabled;
+        self
+    }
 
-                            counter!("batch_transitions_total", "from" => "Submitting", "to" => "Submitted").increment(1);
-                            histogram!("submit_tx_duration_seconds")
-                                .record(start.elapsed().as_secs_f64());
-                        }
-                        Err(e) => {
-                            self.handle_failure(batch, e.to_string()).await?;
-                        }
-                    }
-                } else {
-                    error!("Missing proof for batch {}", batch.id);
-                    batch.transition_to(BatchStatus::Failed);
-                    self.storage.save_batch(batch).await?;
-                    counter!("batches_failed_permanent_total", "reason" => "missing_proof")
-                        .increment(1);
-                }
-            }
-            BatchStatus::Submitted => {
-                if let Some(tx_hash) = &batch.tx_hash {
-                    match self.da_strategy.check_confirmation(tx_hash).await {
-                        Ok(confirmed) => {
-                            if confirmed {
-                                batch.transition_to(BatchStatus::Confirmed);
-                                self.storage.save_batch(batch).await?;
-                                info!("Batch {} CONFIRMED", batch.id);
+    /// Retries a failed `state_root` read up to `retries` times, `delay` apart, while a batch has
+    /// no failed attempts yet, so a node that is briefly unavailable right after startup does not
+    /// cost the batch an attempt.
+    pub fn with_initial_state_root_retry(mut self, retries: u32, delay: Duration) -> Self {
+        self.initial_root_retries = retries;
+        self.initial_root_retry_delay = delay;
+        self
+    }
 
-                                counter!("batch_transitions_total", "from" => "Submitted", "to" => "Confirmed").increment(1);
-                                counter!("batches_completed_total").increment(1);
+    /// After confirmation, reads back the commitment the bridge recorded at the batch's index and
+    /// alerts if it differs from the one computed locally.
+    pub fn with_onchain_commitment_check(mut self, enabled: bool) -> Self {
+        self.verify_onchain_commitment = enabled;
+        self
+    }
 
-                                // Calculate total duration since creation
-                                let total_duration =
-                                    chrono::Utc::now().signed_duration_since(batch.created_at);
-                                histogram!("batch_e2e_duration_seconds")
-                                    .record(total_duration.num_seconds() as f64);
-                            } else {
-                                info!("Batch {} still pending confirmation", batch.id);
-                            }
-                        }
-                        Err(e) => {
-                            warn!("Error checking confirmation for {}: {}", batch.id, e);
-                            // If it's a transient check error, we might not want to count as failure attempt?
-                            // But if the check fails permanently (e.g. reverted), we should handle failure.
-                            // Currently check_confirmation returns false if pending, Error if reverted or rpc error.
-                            // Ideally we distinguish Revert vs RPC Error. For now treat as failure.
-                            self.handle_failure(batch, e.to_string()).await?;
-                        }
-                    }
-                } else {
-                    batch.transition_to(BatchStatus::Submitting);
-                    self.storage.save_batch(batch).await?;
-                    counter!("batch_reverted_to_submitting_total").increment(1);
-                }
-            }
-            _ => {}
-        }
-        Ok(())
+    /// Before submitting a Groth16 batch, runs the bridge's `verifyProof` view on its proof and
+    /// fails the batch permanently if the verifier rejects it, so bad proofs cost no gas.
+    pub fn with_proof_precheck(mut self, enabled: bool) -> Self {
+        self.precheck_proofs = enabled;
+        self
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::application::ports::{BridgeReader, DaStrategy, ProofProvider, ProofResponse, Storage};
-    use crate::domain::{
-        batch::{Batch, BatchId},
-        errors::DomainError,
-    };
-    use async_trait::async_trait;
-    use std::sync::{Arc, Mutex};
+    /// Before each submission, compares the batch's `index` with the bridge's `nextBatchIndex()`
+    /// instead of letting an out-of-order commit revert on chain.
+    pub fn with_batch_index_check(mut self, enabled: bool) -> Self {
+        self.check_batch_index = enabled;
+        self
+    }
 
-    // Mocks
-    struct MockStorage {
-        batch: Mutex<Option<Batch>>,
+    /// Submits batches strictly in `index` order: a `Submitting` batch waits while any pending
+    /// batch with a lower index has not confirmed yet.
+    pub fn with_ordered_submission(mut self, enabled: bool) -> Self {
+        self.ordered_submission = enabled;
+        self
     }
 
-    #[async_trait]
-    impl Storage for MockStorage {
-        async fn save_batch(&self, batch: &Batch) -> Result<(), DomainError> {
-            *self.batch.lock().unwrap() = Some(batch.clone());
-            Ok(())
-        }
-        async fn get_batch(&self, _id: BatchId) -> Result<Option<Batch>, DomainError> {
-            Ok(self.batch.lock().unwrap().clone())
-        }
-        async fn get_pending_batches(&self) -> Result<Vec<Batch>, DomainError> {
-            let b = self.batch.lock().unwrap().clone();
-            Ok(b.into_iter().collect())
+    /// Takes out of this cycle the `Submitting` batches that must wait for a lower-index batch
+    /// under [`Orchestrator::with_ordered_submission`], and puts the rest in `index` order.
+    ///
+    /// Only the index right after the last confirmed one may be submitted, so a lower batch that
+    /// failed holds the rest back until it is retried. Before anything has confirmed, the lowest
+    /// pending index goes first.
+    async fn hold_out_of_order(&self, batches: &mut Vec<Batch>) -> Result<(), DomainError> {
+        if !self.ordered_submission {
+            return Ok(());
         }
+        batches.sort_by_key(|b| b.index);
+        let next = match self.storage.last_confirmed_index().await? {
+            Some(confirmed) => confirmed + 1,
+            None => match batches.first() {
+                Some(first) => first.index,
+                None => return Ok(()),
+            },
+        };
+        batches.retain(|batch| {
+            if batch.status != BatchStatus::Submitting || batch.index <= next {
+                return true;
+            }
+            info!("Holding batch {} (index {}): index {} is not confirmed yet", batch.id, batch.index, next);
+            counter!("batch_order_held_total", "rollup" => self.rollup.clone()).increment(1);
+            false
+        });
+        Ok(())
     }
 
-    struct MockProver {
-        should_fail: bool,
+    /// Before each submission, checks that the batch's data file still exists and fails the
+    /// batch at once (reason `data_file_missing`) if it does not, without asking the DA layer.
+    pub fn with_data_file_preflight(mut self, enabled: bool) -> Self {
+        self.data_file_preflight = enabled;
+        self
     }
 
-    #[async_trait]
-    impl ProofProvider for MockProver {
-        async fn get_proof(
-            &self,
-            _id: &BatchId,
-            _input: &[u8],
-        ) -> Result<ProofResponse, DomainError> {
-            if self.should_fail {
-                Err(DomainError::Prover("fail".into()))
-            } else {
-                Ok(ProofResponse { proof: "p".into() })
-            }
-        }
+    /// Before each submission, checks that the batch builds on the `new_root` of the last batch
+    /// confirmed here, so a mis-ordered or duplicate batch is held back instead of sent.
+    pub fn with_root_chain_check(mut self, enabled: bool) -> Self {
+        self.check_root_chain = enabled;
+        self
     }
 
-    struct MockDa {
-        should_fail_submit: bool,
-        should_fail_confirm: bool,
-        confirm_result: bool,
+    /// Why `batch` does not extend the last confirmed root under
+    /// [`Orchestrator::with_root_chain_check`], or `None` when it does. A batch with nothing
+    /// confirmed before it, or without a recorded old root, passes.
+    async fn root_chain_break(&self, batch: &Batch) -> Result<Option<String>, DomainError> {
+        if !self.check_root_chain {
+            return Ok(None);
+        }
+        let Some(last_root) = self.storage.last_confirmed_root().await? else {
+            return Ok(None);
+        };
+        let last: H256 = last_root
+            .parse()
+            .map_err(|e| DomainError::Storage(format!("Invalid confirmed new_root {}: {}", last_root, e)))?;
+        // The proved old root is the second public input word, packed like every root
+        let extends = match (&batch.public_inputs, &batch.expected_old_root) {
+            (Some(inputs), _) if inputs.len() >= 64 => {
+                inputs[32..64] == self.root_packing.pack(last.0)?
+            }
+            (_, Some(expected)) => expected.parse::<H256>().is_ok_and(|expected| expected == last),
+            _ => return Ok(None),
+        };
+        Ok((!extends).then(|| {
+            format!(
+                "Batch {} does not extend the last confirmed root {:?}; holding it until the chain is repaired",
+                batch.id, last
+            )
+        }))
     }
 
-    #[async_trait]
-    impl DaStrategy for MockDa {
-        fn da_id(&self) -> u8 { 0 }
-        fn compute_commitment(&self, _batch: &Batch) -> Result<H256, DomainError> {
-            Ok(H256::zero())
+    /// Whether the bridge expects `batch` next under [`Orchestrator::with_batch_index_check`].
+    /// A batch ahead of the bridge is held until its predecessors land; one the bridge has
+    /// already moved past fails permanently. A failed read counts as a failed attempt.
+    async fn batch_index_expected(&self, batch: &mut Batch) -> Result<bool, DomainError> {
+        if !self.check_batch_index {
+            return Ok(true);
         }
-        fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
-             Ok(vec![])
+        let expected = match self.bridge_reader.next_batch_index().await {
+            Ok(expected) => expected,
+            Err(e) => {
+                self.handle_failure(batch, format!("Next batch index check failed: {}", e)).await?;
+                return Ok(false);
+            }
+        };
+        let index = U256::from(batch.index);
+        if index == expected {
+            return Ok(true);
+        }
+        if index > expected {
+            info!("Holding batch {}: index {} but the bridge expects {} next", batch.id, index, expected);
+            counter!("batch_index_held_total", "rollup" => self.rollup.clone()).increment(1);
+        } else {
+            self.fail_permanently(
+                batch,
+                format!("Batch index {} is behind the bridge, which expects {} next", index, expected),
+            )
+            .await?;
         }
+        Ok(false)
+    }
 
-        async fn submit(&self, _b: &Batch, _p: &str) -> Result<String, DomainError> {
-            if self.should_fail_submit {
-                Err(DomainError::Da("fail".into()))
-            } else {
-                Ok("0xhash".into())
-            }
+    /// Whether `batch` may go on to `Submitting` under [`Orchestrator::with_proof_precheck`].
+    /// A rejected proof fails the batch; a check that could not run counts as a failed attempt.
+    async fn proof_passes_precheck(&self, batch: &mut Batch) -> Result<bool, DomainError> {
+        if !self.precheck_proofs || batch.proof_system != ProofSystem::Groth16 {
+            return Ok(true);
         }
-        async fn check_confirmation(&self, _tx: &str) -> Result<bool, DomainError> {
-            if self.should_fail_confirm {
-                Err(DomainError::Da("revert".into()))
-            } else {
-                Ok(self.confirm_result)
+        let (Some(proof), Some(inputs)) = (batch.proof.clone(), batch.public_inputs.clone()) else {
+            warn!("Batch {} has no stored proof inputs; skipping the verifyProof check", batch.id);
+            return Ok(true);
+        };
+        match self.bridge_reader.verify_proof(batch.proof_system, &proof, &inputs).await {
+            Ok(true) => Ok(true),
+            Ok(false) => {
+                error!("Batch {} FAILED permanently: bridge verifier rejected its proof", batch.id);
+                self.transition_with(batch, BatchStatus::Failed, Some("bridge verifier rejected its proof".into())).await?;
+                counter!("batches_failed_permanent_total", "rollup" => self.rollup.clone(), "reason" => "proof_rejected")
+                    .increment(1);
+                self.save(batch).await?;
+                Ok(false)
+            }
+            Err(e @ DomainError::InvalidBatch(_)) => {
+                self.fail_permanently(batch, e.to_string()).await?;
+                Ok(false)
+            }
+            Err(e) => {
+                self.handle_failure(batch, format!("verifyProof check failed: {}", e)).await?;
+                Ok(false)
             }
         }
     }
 
-    struct MockBridgeReader;
-    #[async_trait]
-    impl BridgeReader for MockBridgeReader {
-        async fn state_root(&self) -> Result<H256, DomainError> {
-            Ok(H256::zero())
-        }
+    /// Emits one `batch.transition` event (fields `batch_id`, `from`, `to`, `attempts`) per
+    /// status change, for log pipelines that key on a stable event name.
+    pub fn with_transition_events(mut self, enabled: bool) -> Self {
+        self.transition_events = enabled;
+        self
     }
 
-    fn create_orchestrator(
-        batch: Batch,
-        prover_fail: bool,
-        da_fail: bool,
-        da_confirm_fail: bool,
-    ) -> (Orchestrator, Arc<MockStorage>) {
-        let storage = Arc::new(MockStorage {
-            batch: Mutex::new(Some(batch)),
-        });
-        let prover = Arc::new(MockProver {
-            should_fail: prover_fail,
-        });
-        let da = Arc::new(MockDa {
-            should_fail_submit: da_fail,
-            should_fail_confirm: da_confirm_fail,
-            confirm_result: true,
-        });
-        let reader = Arc::new(MockBridgeReader);
+    /// Treats the outstanding proof request as abandoned once a batch has been `Proving` this long,
+    /// and sends a fresh one.
+    pub fn with_max_proving_age(mut self, max_age: Option<Duration>) -> Self {
+        self.max_proving_age = max_age;
+        self
+    }
 
-        (
-            Orchestrator::new(storage.clone(), prover, da, reader, 5),
-            storage,
-        )
+    /// Expires a batch once it has been `Submitted` this long without confirming: its pending
+    /// transaction is cancelled through [`DaStrategy::cancel`] so the nonce does not hold up later
+    /// submissions, and the batch fails.
+    pub fn with_max_submitted_age(mut self, max_age: Option<Duration>) -> Self {
+        self.max_submitted_age = max_age;
+        self
     }
 
-    // Valid 32-byte hex for tests
-    const VALID_HASH: &str = "0x0000000000000000000000000000000000000000000000000000000000000000";
+    /// Leaves a batch for the next cycle, without using an attempt, when reading its data
+    /// fails with [`DomainError::DataUnavailable`] instead of counting a failure.
+    pub fn with_unavailable_data_retry(mut self, enabled: bool) -> Self {
+        self.retry_unavailable_data = enabled;
+        self
+    }
 
-    #[tokio::test]
-    async fn test_proving_success() {
-        let batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
-        let (orch, store) = create_orchestrator(batch.clone(), false, false, false);
+    /// Proves the cycle's `Proving` batches concurrently and sends the proof requests made within
+    /// `window` of each other to the prover as one batch (see [`ProofBatcher`]).
+    pub fn with_prove_batch_window(mut self, window: Option<Duration>) -> Self {
+        if let Some(window) = window {
+            self.prover =
+                Arc::new(ProofBatcher::new(self.prover.clone(), window).with_rollup(self.rollup.clone()));
+            self.batched_proving = true;
+        }
+        self
+    }
 
-        // Discovered -> Proving
-        orch.process_pending_batches().await.unwrap();
-        // Proving -> Proved
-        orch.process_pending_batches().await.unwrap();
+    /// Warns about (and counts in `batch_stuck_total`) pending batches whose last state change
+    /// is older than `threshold`.
+    pub fn with_stuck_threshold(mut self, threshold: Option<Duration>) -> Self {
+        self.stuck_threshold = threshold;
+        self
+    }
 
-        let updated = store.get_batch(batch.id).await.unwrap().unwrap();
-        assert_eq!(updated.status, BatchStatus::Proved);
-        assert!(updated.proof.is_some());
+    /// Fails a batch before proving if its public inputs are not the full set the circuit needs.
+    pub fn with_strict_public_inputs(mut self, strict: bool) -> Self {
+        self.strict_public_inputs = strict;
+        self
     }
 
-    #[tokio::test]
-    async fn test_proving_retry() {
-        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
-        batch.status = BatchStatus::Proving;
+    /// Sends `deadline = now + budget` with each proof request so the prover can drop work
+    /// we no longer want.
+    pub fn with_prover_deadline(mut self, budget: Option<Duration>) -> Self {
+        self.prover_budget = budget;
+        self
+    }
 
-        let (orch, store) = create_orchestrator(batch.clone(), true, false, false);
+    /// Replaces unconfirmed submissions with higher fees as they age past each step,
+    /// never exceeding `max_fee_per_gas`.
+    pub fn with_fee_escalation(mut self, escalation: FeeEscalation, max_fee_per_gas: Option<U256>) -> Self {
+        self.fee_escalation = escalation;
+        self.max_fee_per_gas = max_fee_per_gas;
+        self
+    }
 
-        orch.process_pending_batches().await.unwrap();
+    /// Controls how old/new roots are placed into the proof's public inputs.
+    pub fn with_root_packing(mut self, packing: RootPacking) -> Self {
+        self.root_packing = packing;
+        self
+    }
 
-        let updated = store.get_batch(batch.id).await.unwrap().unwrap();
-        assert_eq!(updated.status, BatchStatus::Proving);
-        assert_eq!(updated.attempts, 1);
+    /// Skips processing cycles (and reports not-ready) while the node says it is syncing.
+    pub fn with_sync_check(mut self, node_status: Option<Arc<dyn NodeStatus>>) -> Self {
+        self.node_status = node_status;
+        self
     }
 
-    #[tokio::test]
-    async fn test_proving_dead_letter() {
-        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+    /// Node that `GET /health` checks, sharing this orchestrator's provider.
+    pub fn with_health_node(mut self, node: Option<Arc<dyn NodeStatus>>) -> Self {
+        self.health_node = node;
+        self
+    }
+
+    pub fn health_node(&self) -> Option<Arc<dyn NodeStatus>> {
+        self.health_node.clone()
+    }
+
+    /// False while processing is paused because the node is not synced.
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Relaxed)
+    }
+
+    /// Adds a dependency whose failures [`Orchestrator::readiness_problems`] reports.
+    pub fn with_readiness_probe(mut self, probe: Arc<dyn ReadinessProbe>) -> Self {
+        self.readiness_probes.push(probe);
+        self
+    }
+
+    /// Reasons the pipeline cannot currently make progress; empty when ready.
+    pub async fn readiness_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !self.is_ready() {
+            problems.push("node is syncing".to_string());
+        }
+        for probe in &self.readiness_probes {
+            if let Err(problem) = probe.check().await {
+                problems.push(problem);
+            }
+        }
+        problems
+    }
+
+    async fn node_synced(&self) -> bool {
+        let Some(node) = &self.node_status else {
+            return true;
+        };
+        let synced = match node.is_syncing().await {
+            Ok(syncing) => !syncing,
+            Err(e) => {
+                warn!("Sync status check failed: {}", e);
+                false
+            }
+        };
+        if !synced && self.ready.load(Ordering::Relaxed) {
+            warn!("Node is syncing; pausing batch processing until it catches up");
+        } else if synced && !self.ready.load(Ordering::Relaxed) {
+            info!("Node synced; resuming batch processing");
+        }
+        self.ready.store(synced, Ordering::Relaxed);
+        gauge!("node_synced", "rollup" => self.rollup.clone()).set(if synced { 1.0 } else { 0.0 });
+        synced
+    }
+
+    /// Overrides how `Submitted` batches are confirmed (e.g. via an indexer instead of node receipts).
+    pub fn with_confirmation_source(mut self, source: Option<Arc<dyn ConfirmationSource>>) -> Self {
+        self.confirmation_source = source;
+        self
+    }
+
+    /// Signs an attestation for every batch that confirms, kept in `batch.attestation`.
+    pub fn with_attestor(mut self, attestor: Option<Arc<dyn Attestor>>) -> Self {
+        self.attestor = attestor;
+        self
+    }
+
+    /// Holds `Proved -> Submitting` while the current time falls in any of these windows.
+    /// Proving continues as normal.
+    pub fn with_submit_blackout(mut self, windows: Vec<BlackoutWindow>) -> Self {
+        self.submit_blackout = windows;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Accepts prover responses carrying one proof per sub-batch. The first proof belongs to
+    /// the batch being proved; the rest are assigned along its root chain (each sub-batch's
+    /// `expected_old_root` is the previous batch's `new_root`).
+    pub fn with_aggregated_proofs(mut self, enabled: bool) -> Self {
+        self.aggregated_proofs = enabled;
+        self
+    }
+
+    /// Limits how many `Submitted` batches have their confirmation checked concurrently.
+    /// The least recently checked batches go first; the rest are left for the next cycle.
+    pub fn with_max_concurrent_confirmations(mut self, limit: Option<usize>) -> Self {
+        self.max_concurrent_confirmations = limit;
+        self
+    }
+
+    pub async fn run(&self) -> Result<(), DomainError> {
+        self.run_until(Arc::new(AtomicBool::new(false))).await
+    }
+
+    /// Like [`Orchestrator::run`], but returns once `stop` is set. A cycle that is already running
+    /// is finished first, so a broadcast transaction always has its hash saved.
+    pub async fn run_until(&self, stop: Arc<AtomicBool>) -> Result<(), DomainError> {
+        info!("Orchestrator started for rollup {}", self.rollup);
+        while !stop.load(Ordering::SeqCst) {
+            if let Err(e) = self.process_pending_batches().await {
+                error!("Error processing batches: {}", e);
+            }
+            // Sleep in short slices so a stop request doesn't wait out the whole interval
+            for _ in 0..50 {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+        info!("Orchestrator stopped");
+        Ok(())
+    }
+
+    /// Runs a single [`Orchestrator::process_pending_batches`] pass, for cron-style operation,
+    /// and reports where the batches pending at its start ended up.
+    pub async fn run_once(&self) -> Result<CycleSummary, DomainError> {
+        let ids: Vec<BatchId> = self.storage.get_pending_batches().await?.iter().map(|b| b.id).collect();
+        self.process_pending_batches().await?;
+
+        let mut summary = CycleSummary { processed: ids.len(), ..Default::default() };
+        for id in ids {
+            match self.storage.get_batch(id).await?.map(|b| b.status) {
+                Some(BatchStatus::Confirmed) => summary.confirmed += 1,
+                Some(BatchStatus::Failed) => summary.failed += 1,
+                _ => summary.pending += 1,
+            }
+        }
+        info!(
+            "Single pass for rollup {}: {} batches, {} confirmed, {} failed, {} pending",
+            self.rollup, summary.processed, summary.confirmed, summary.failed, summary.pending
+        );
+        Ok(summary)
+    }
+
+    /// Sets `batches_in_state` for every state, so a state that empties reads 0 rather than
+    /// its last count.
+    async fn record_backlog(&self) {
+        match self.storage.count_by_status().await {
+            Ok(counts) => {
+                for (status, count) in counts {
+                    gauge!("batches_in_state", "rollup" => self.rollup.clone(), "status" => status.to_string())
+                        .set(count as f64);
+                }
+            }
+            Err(e) => warn!("Failed to count batches by status: {}", e),
+        }
+    }
+
+    pub async fn process_pending_batches(&self) -> Result<(), DomainError> {
+        if !self.node_synced().await {
+            counter!("cycles_skipped_total", "rollup" => self.rollup.clone(), "reason" => "node_syncing").increment(1);
+            return Ok(());
+        }
+
+        self.record_backlog().await;
+        let mut batches = self.storage.get_pending_batches().await?;
+        self.report_stuck_batches(&batches);
+        self.hold_out_of_order(&mut batches).await?;
+        let now = self.clock.now();
+        batches.retain(|batch| match batch.next_attempt_at {
+            Some(at) if at > now => {
+                counter!("batch_backoff_skips_total", "rollup" => self.rollup.clone()).increment(1);
+                false
+            }
+            _ => true,
+        });
+        if self.data_file_check != DataFileCheck::None {
+            let mut ready = Vec::with_capacity(batches.len());
+            for batch in batches {
+                let unsubmitted = matches!(
+                    batch.status,
+                    BatchStatus::Discovered | BatchStatus::Proving | BatchStatus::Proved
+                );
+                if !unsubmitted || self.data_file_ready(&batch).await {
+                    ready.push(batch);
+                }
+            }
+            batches = ready;
+        }
+
+        if self.batched_proving {
+            let (proving, rest): (Vec<Batch>, Vec<Batch>) =
+                batches.into_iter().partition(|b| b.status == BatchStatus::Proving);
+            let proofs = proving.into_iter().map(|mut batch| async move { self.process_batch(&mut batch).await });
+            join_all(proofs).await.into_iter().collect::<Result<(), _>>()?;
+            batches = rest;
+        }
+
+        let Some(limit) = self.max_concurrent_confirmations else {
+            for mut batch in batches {
+                self.process_batch(&mut batch).await?;
+            }
+            return Ok(());
+        };
+
+        let (submitted, others): (Vec<Batch>, Vec<Batch>) = batches
+            .into_iter()
+            .partition(|b| b.status == BatchStatus::Submitted);
+
+        for mut batch in others {
+            self.process_batch(&mut batch).await?;
+        }
+
+        let submitted = self.pick_confirmation_checks(submitted, limit);
+        let checks = submitted.into_iter().map(|mut batch| async move { self.process_batch(&mut batch).await });
+        join_all(checks).await.into_iter().collect()
+    }
+
+    /// Picks up to `limit` batches to check this cycle, least recently checked first, so a
+    /// backlog larger than the limit rotates through instead of starving the tail.
+    fn pick_confirmation_checks(&self, mut submitted: Vec<Batch>, limit: usize) -> Vec<Batch> {
+        let mut checked = self.confirmation_checked.lock().unwrap();
+        let (cycle, last_checked) = &mut *checked;
+        *cycle += 1;
+        last_checked.retain(|id, _| submitted.iter().any(|b| b.id == *id));
+        submitted.sort_by_key(|b| last_checked.get(&b.id).copied().unwrap_or(0));
+
+        let deferred = submitted.len().saturating_sub(limit);
+        if deferred > 0 {
+            counter!("confirmation_checks_deferred_total", "rollup" => self.rollup.clone()).increment(deferred as u64);
+        }
+        submitted.truncate(limit);
+        for batch in &submitted {
+            last_checked.insert(batch.id, *cycle);
+        }
+        submitted
+    }
+
+    /// Advances just the batch `id` by one pipeline step, leaving every other batch alone, and
+    /// returns it as stored afterwards. For stepping a single batch by hand while debugging.
+    pub async fn process_one(&self, id: BatchId) -> Result<Batch, DomainError> {
+        let mut batch = self
+            .storage
+            .get_batch(id)
+            .await?
+            .ok_or_else(|| DomainError::InvalidBatch(format!("Batch {} not found", id)))?;
+        self.process_batch(&mut batch).await?;
+        Ok(self.storage.get_batch(id).await?.unwrap_or(batch))
+    }
+
+    fn report_stuck_batches(&self, batches: &[Batch]) {
+        let Some(threshold) = self.stuck_threshold else {
+            return;
+        };
+        let now = self.clock.now();
+        for batch in batches {
+            let idle = (now - batch.updated_at).to_std().unwrap_or_default();
+            if idle > threshold {
+                warn!("Batch {} has been {} for {:?} without progress", batch.id, batch.status, idle);
+                counter!("batch_stuck_total", "rollup" => self.rollup.clone(), "status" => batch.status.to_string()).increment(1);
+            }
+        }
+    }
+
+    async fn handle_failure(
+        &self,
+        batch: &mut Batch,
+        error_msg: String,
+    ) -> Result<(), DomainError> {
+        batch.attempts += 1;
+
+        counter!("batch_failures_total", "rollup" => self.rollup.clone(), "batch_id" => batch.id.to_string()).increment(1);
+
+        if batch.attempts >= self.max_attempts {
+            warn!(
+                "Batch {} FAILED permanently after {} attempts: {}",
+                batch.id, batch.attempts, error_msg
+            );
+            self.transition_with(batch, BatchStatus::Failed, Some(error_msg)).await?;
+            counter!("batches_failed_permanent_total", "rollup" => self.rollup.clone()).increment(1);
+            return self.save(batch).await;
+        }
+        self.record_event(batch, batch.status.clone(), Some(error_msg.clone()));
+        if let Some(base) = self.retry_backoff {
+            let delay = retry_delay(base, batch.attempts, rand::random::<f64>());
+            batch.next_attempt_at = chrono::Duration::from_std(delay)
+                .ok()
+                .and_then(|delay| self.clock.now().checked_add_signed(delay));
+            warn!(
+                "Batch {} failed (attempt {}/{}): {}. Retrying in {:?}",
+                batch.id, batch.attempts, self.max_attempts, error_msg, delay
+            );
+        } else {
+            warn!(
+                "Batch {} failed (attempt {}/{}): {}. Retrying...",
+                batch.id, batch.attempts, self.max_attempts, error_msg
+            );
+        }
+        self.save(batch).await
+    }
+
+    async fn escalate_fees(&self, batch: &mut Batch) -> Result<(), DomainError> {
+        if self.fee_escalation.is_empty() {
+            return Ok(());
+        }
+        // updated_at marks the Submitted transition; replacements keep the status unchanged
+        let unconfirmed_for = (self.clock.now() - batch.updated_at).to_std().unwrap_or_default();
+        let due = self.fee_escalation.due_step(unconfirmed_for);
+        let applied = batch.fee_bumps as usize;
+        if due <= applied {
+            return Ok(());
+        }
+
+        let ratio = self.fee_escalation.bump_ratio(applied, due);
+        match self.da_strategy.resubmit(batch, ratio, self.max_fee_per_gas).await {
+            Ok(sent) => {
+                info!("Fee escalation step {} for batch {}: tx={}", due, batch.id, sent.hash);
+                batch.tx_hash = Some(sent.hash);
+                // A capped bump hands back the original without its signed form; keep ours
+                if sent.raw.is_some() {
+                    batch.signed_tx = sent.raw;
+                }
+                batch.fee_bumps = due as u32;
+                self.save(batch).await?;
+                counter!("fee_escalations_total", "rollup" => self.rollup.clone()).increment(1);
+            }
+            Err(e) => {
+                // The original tx is still pending; try again next cycle
+                warn!("Fee escalation for batch {} failed: {}", batch.id, e);
+                counter!("fee_escalation_failed_total", "rollup" => self.rollup.clone()).increment(1);
+            }
+        }
+        Ok(())
+    }
+
+    /// Commits a `Submitted` batch whose data an off-L1 DA layer holds to the bridge, once the
+    /// layer has the data available. Until then the batch waits without a `tx_hash`.
+    async fn commit_published(&self, batch: &mut Batch) -> Result<(), DomainError> {
+        let Some(proof) = batch.proof.clone() else {
+            return self.handle_failure(batch, "missing proof".into()).await;
+        };
+        match self.da_strategy.commit_published(batch, &proof).await {
+            Ok(Some(sent)) => {
+                info!("Batch {} committed to the bridge: tx={}", batch.id, sent.hash);
+                batch.tx_hash = Some(sent.hash);
+                batch.signed_tx = sent.raw;
+                // Fee escalation and expiry time the commit transaction, not the publishing
+                batch.updated_at = self.clock.now();
+                self.save(batch).await?;
+                counter!("batch_bridge_commits_total", "rollup" => self.rollup.clone()).increment(1);
+            }
+            Ok(None) => info!("Batch {} data not available on its DA layer yet", batch.id),
+            Err(DomainError::DaTransient(reason)) => {
+                warn!("Transient error committing batch {}: {}", batch.id, reason);
+                counter!("confirmation_check_transient_total", "rollup" => self.rollup.clone()).increment(1);
+            }
+            Err(DomainError::FeeCapExceeded(reason)) => {
+                info!("Holding commit of batch {}: {}", batch.id, reason);
+                counter!("batch_deferred_total", "rollup" => self.rollup.clone(), "reason" => "base_fee_cap")
+                    .increment(1);
+            }
+            Err(e @ DomainError::DaReverted(_)) => {
+                // The layer lost the data; without a reference the batch goes back to publishing
+                batch.da_reference = None;
+                self.handle_failure(batch, e.to_string()).await?;
+            }
+            Err(e) => self.handle_failure(batch, e.to_string()).await?,
+        }
+        Ok(())
+    }
+
+    async fn assign_sub_batch_proofs(
+        &self,
+        batch: &Batch,
+        proofs: Vec<String>,
+    ) -> Result<(), DomainError> {
+        if proofs.is_empty() {
+            return Ok(());
+        }
+        let pending = self.storage.get_pending_batches().await?;
+        let mut prev_root = batch.new_root.parse::<H256>().ok();
+
+        for proof in proofs {
+            let next = pending.iter().find(|b| {
+                b.expected_old_root.as_deref().and_then(|r| r.parse::<H256>().ok()) == prev_root
+                    && prev_root.is_some()
+            });
+            let Some(sub) = next else {
+                warn!("No sub-batch found after root {:?}; dropping remaining aggregated proofs", prev_root);
+                counter!("aggregated_proofs_unassigned_total", "rollup" => self.rollup.clone()).increment(1);
+                break;
+            };
+            prev_root = sub.new_root.parse::<H256>().ok();
+
+            // Sub-batches are only assigned once they are waiting on a proof
+            if sub.status != BatchStatus::Proving {
+                warn!("Sub-batch {} is {}, not assigning aggregated proof", sub.id, sub.status);
+                continue;
+            }
+            let mut sub = sub.clone();
+            sub.proof = Some(proof);
+            self.transition(&mut sub, BatchStatus::Proved).await?;
+            sub.attempts = 0;
+            self.save(&sub).await?;
+            info!("Assigned aggregated proof to sub-batch {}", sub.id);
+            counter!("batch_transitions_total", "rollup" => self.rollup.clone(), "from" => "Proving", "to" => "Proved").increment(1);
+        }
+        Ok(())
+    }
+
+    /// Copies gas used and price paid from the confirmed transaction's receipt onto `batch`.
+    /// A receipt that cannot be read only costs the accounting, not the confirmation.
+    async fn record_gas(&self, batch: &mut Batch) {
+        let Some(tx_hash) = batch.tx_hash.clone() else {
+            return;
+        };
+        let receipt = match self.da_strategy.confirmation_details(&tx_hash).await {
+            Ok(Some(receipt)) => receipt,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Could not read receipt of {} for batch {}: {}", tx_hash, batch.id, e);
+                return;
+            }
+        };
+        batch.gas_used = receipt.gas_used.map(|g| g.low_u64());
+        batch.effective_gas_price = receipt.effective_gas_price.map(|p| p.low_u64());
+        if let Some(gas_used) = batch.gas_used {
+            histogram!("batch_gas_used", "rollup" => self.rollup.clone()).record(gas_used as f64);
+        }
+    }
+
+    /// Signs the confirmed batch's attestation. A batch that cannot be attested still confirms.
+    async fn attest(&self, batch: &mut Batch) {
+        let Some(attestor) = &self.attestor else {
+            return;
+        };
+        let signed = match (self.da_strategy.compute_commitment(batch).await, batch.new_root.parse::<H256>()) {
+            (Ok(data_hash), Ok(new_root)) => attestor.attest(batch.index, data_hash, new_root),
+            (Err(e), _) => Err(e),
+            (_, Err(e)) => Err(DomainError::InvalidBatch(format!("Invalid new_root: {}", e))),
+        };
+        match signed {
+            Ok(signature) => {
+                batch.attestation = Some(signature);
+                counter!("attestations_signed_total", "rollup" => self.rollup.clone()).increment(1);
+            }
+            Err(e) => {
+                warn!("Could not attest batch {}: {}", batch.id, e);
+                counter!("attestations_failed_total", "rollup" => self.rollup.clone()).increment(1);
+            }
+        }
+    }
+
+    async fn transition(&self, batch: &mut Batch, to: BatchStatus) -> Result<(), DomainError> {
+        self.transition_with(batch, to, None).await
+    }
+
+    /// Like [`Orchestrator::transition`], noting `detail` in the batch's audit trail.
+    async fn transition_with(&self, batch: &mut Batch, to: BatchStatus, detail: Option<String>) -> Result<(), DomainError> {
+        let from = batch.status.clone();
+        if let Err(e) = batch.transition_to(to) {
+            error!("{}", e);
+            return Err(e);
+        }
+        self.record_event(batch, from.clone(), detail);
+        if self.transition_events {
+            info!(
+                name: "batch.transition",
+                target: "batch.transition",
+                batch_id = %batch.id,
+                from = %from,
+                to = %batch.status,
+                attempts = batch.attempts,
+                "batch.transition"
+            );
+        }
+        if matches!(batch.status, BatchStatus::Confirmed | BatchStatus::Failed) {
+            histogram!("batch_attempts_at_terminal", "rollup" => self.rollup.clone(), "status" => batch.status.to_string())
+                .record(batch.attempts as f64);
+        }
+        Ok(())
+    }
+
+    /// Queues a move from `from` to the batch's current status for its audit trail, written by
+    /// the next [`Orchestrator::save`] of the batch so the trail never runs ahead of storage.
+    fn record_event(&self, batch: &Batch, from: BatchStatus, detail: Option<String>) {
+        self.pending_events
+            .lock()
+            .unwrap()
+            .entry(batch.id)
+            .or_default()
+            .push((from, batch.status.clone(), detail));
+    }
+
+    /// Saves the batch, then appends the events queued for it to its audit trail. The trail is
+    /// best effort: a failed write is logged and the pipeline carries on. Events of a batch
+    /// whose save fails are dropped, as the moves they describe never reached storage.
+    async fn save(&self, batch: &Batch) -> Result<(), DomainError> {
+        let saved = self.storage.save_batch(batch).await;
+        let events = self.pending_events.lock().unwrap().remove(&batch.id).unwrap_or_default();
+        saved?;
+        for (from, to, detail) in events {
+            if let Err(e) = self.storage.record_event(batch.id, from, to, detail).await {
+                warn!("Failed to record event for batch {}: {}", batch.id, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn read_state_root(&self, batch: &Batch) -> Result<H256, DomainError> {
+        let mut res = self.bridge_reader.state_root().await;
+        if batch.attempts > 0 {
+            return res;
+        }
+        let mut retries = 0;
+        while let Err(e) = &res {
+            if retries >= self.initial_root_retries {
+                break;
+            }
+            retries += 1;
+            warn!(
+                "State root read failed for batch {} ({}); retry {}/{} in {:?}",
+                batch.id, e, retries, self.initial_root_retries, self.initial_root_retry_delay
+            );
+            counter!("state_root_initial_retries_total", "rollup" => self.rollup.clone()).increment(1);
+            tokio::time::sleep(self.initial_root_retry_delay).await;
+            res = self.bridge_reader.state_root().await;
+        }
+        res
+    }
+
+    async fn remove_data_file(&self, batch: &Batch) -> Result<(), DomainError> {
+        if !batch.has_local_data_file() {
+            return Ok(());
+        }
+        let users = self.storage.count_pending_with_data_file(&batch.data_file).await?;
+        if users > 0 {
+            info!("Keeping {}: still used by {} pending batches", batch.data_file, users);
+            return Ok(());
+        }
+        match tokio::fs::remove_file(&batch.data_file).await {
+            Ok(()) => {
+                info!("Removed data file {} of batch {}", batch.data_file, batch.id);
+                counter!("data_files_removed_total", "rollup" => self.rollup.clone()).increment(1);
+            }
+            Err(e) => warn!("Failed to remove data file {}: {}", batch.data_file, e),
+        }
+        Ok(())
+    }
+
+    /// Compares the bridge's recorded commitment for `batch.index` against our own. A mismatch
+    /// means the bridge and the submitter encode batch data differently.
+    async fn check_onchain_commitment(&self, batch: &Batch) {
+        let local = match self.da_strategy.compute_commitment(batch).await {
+            Ok(c) => c,
+            Err(e) => {
+                warn!("Cannot recompute commitment of batch {}: {}", batch.id, e);
+                return;
+            }
+        };
+        match self.bridge_reader.committed_data_hash(batch.index).await {
+            Ok(onchain) if onchain == local => {
+                info!("On-chain commitment matches for batch {}", batch.id);
+            }
+            Ok(onchain) => {
+                error!(
+                    "COMMITMENT MISMATCH for batch {} (index {}): on-chain {:?}, local {:?}",
+                    batch.id, batch.index, onchain, local
+                );
+                counter!("commitment_mismatch_total", "rollup" => self.rollup.clone()).increment(1);
+            }
+            Err(e) => {
+                warn!("Could not read on-chain commitment of batch {}: {}", batch.id, e);
+                counter!("commitment_check_failed_total", "rollup" => self.rollup.clone()).increment(1);
+            }
+        }
+    }
+
+    /// Fails the batch immediately for errors that retrying cannot fix.
+    async fn fail_permanently(&self, batch: &mut Batch, error_msg: String) -> Result<(), DomainError> {
+        self.fail_with_reason(batch, "invalid_batch", error_msg).await
+    }
+
+    /// Like [`Orchestrator::fail_permanently`], counted under `reason`.
+    async fn fail_with_reason(&self, batch: &mut Batch, reason: &'static str, error_msg: String) -> Result<(), DomainError> {
+        error!("Batch {} FAILED permanently: {}", batch.id, error_msg);
+        self.transition_with(batch, BatchStatus::Failed, Some(error_msg)).await?;
+        counter!("batches_failed_permanent_total", "rollup" => self.rollup.clone(), "reason" => reason).increment(1);
+        self.save(batch).await
+    }
+
+    /// Whether `e` is a transient data-source error to skip under
+    /// [`Orchestrator::with_unavailable_data_retry`]; logs and counts the skip if so.
+    fn skip_unavailable_data(&self, batch: &Batch, e: &DomainError) -> bool {
+        if !self.retry_unavailable_data || !matches!(e, DomainError::DataUnavailable(_)) {
+            return false;
+        }
+        warn!("Skipping batch {} until its data is reachable: {}", batch.id, e);
+        counter!("data_source_unavailable_total", "rollup" => self.rollup.clone()).increment(1);
+        true
+    }
+
+    /// Cancels and fails `batch` once it is past `max_submitted_age`. A failed cancel leaves the
+    /// batch `Submitted`, to be tried again next cycle.
+    async fn expire_submitted(&self, batch: &mut Batch) -> Result<bool, DomainError> {
+        let Some(max_age) = self.max_submitted_age else {
+            return Ok(false);
+        };
+        // updated_at marks the move to Submitted; fee bumps keep it
+        let pending_for = (self.clock.now() - batch.updated_at).to_std().unwrap_or_default();
+        if pending_for <= max_age {
+            return Ok(false);
+        }
+        match self.da_strategy.cancel(batch).await {
+            Ok(cancel_hash) => {
+                error!(
+                    "Batch {} FAILED permanently: expired after {:?} in Submitted; cancelled with tx {}",
+                    batch.id, pending_for, cancel_hash
+                );
+                let detail = format!("expired after {:?} in Submitted; cancelled with tx {}", pending_for, cancel_hash);
+                self.transition_with(batch, BatchStatus::Failed, Some(detail)).await?;
+                self.save(batch).await?;
+                counter!("batches_failed_permanent_total", "rollup" => self.rollup.clone(), "reason" => "expired").increment(1);
+                Ok(true)
+            }
+            Err(e) => {
+                warn!("Failed to cancel expired batch {}: {}", batch.id, e);
+                counter!("tx_cancel_failed_total", "rollup" => self.rollup.clone()).increment(1);
+                Ok(false)
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self, batch), fields(batch_id = %batch.id, status = %batch.status))]
+    async fn process_batch(&self, batch: &mut Batch) -> Result<(), DomainError> {
+        info!("Processing batch");
+        let start = Instant::now();
+
+        match batch.status {
+            BatchStatus::Discovered => {
+                self.transition(batch, BatchStatus::Proving).await?;
+                self.save(batch).await?;
+                counter!("batch_transitions_total", "rollup" => self.rollup.clone(), "from" => "Discovered", "to" => "Proving")
+                    .increment(1);
+            }
+            BatchStatus::Proving => {
+                // An aggregated proof handled earlier this cycle may already have proved this batch
+                if self.aggregated_proofs {
+                    if let Some(current) = self.storage.get_batch(batch.id).await? {
+                        if current.status != BatchStatus::Proving {
+                            *batch = current;
+                            return Ok(());
+                        }
+                    }
+                }
+                // updated_at marks the start of the current proof request
+                if let Some(max_age) = self.max_proving_age {
+                    let proving_for = (self.clock.now() - batch.updated_at).to_std().unwrap_or_default();
+                    if proving_for > max_age {
+                        warn!(
+                            "Batch {} has been Proving for {:?} without a proof; re-requesting",
+                            batch.id, proving_for
+                        );
+                        self.prover.abandon(batch.id).await;
+                        batch.updated_at = self.clock.now();
+                        self.save(batch).await?;
+                        counter!("proof_requests_abandoned_total", "rollup" => self.rollup.clone()).increment(1);
+                    }
+                }
+
+                // Reuse the inputs of an earlier attempt: the bridge root may have moved on since
+                let public_inputs = match batch.public_inputs.clone() {
+                    Some(inputs) => inputs,
+                    None => {
+                        // 1. Fetch L1 Context (BridgeReader)
+                        let old_root_res = self.read_state_root(batch).await;
+                        // 2. Compute Commitment (DaStrategy)
+                        let commitment_res = self.da_strategy.compute_commitment(batch).await;
+
+                        match (old_root_res, commitment_res) {
+                            (Ok(old_root_h256), Ok(commitment_h256)) => {
+                                // Only prove against the root this batch was built to extend
+                                if let Some(expected) = &batch.expected_old_root {
+                                    match expected.parse::<H256>() {
+                                        Ok(expected) if expected != old_root_h256 => {
+                                            warn!(
+                                                "Bridge root {:?} does not match expected predecessor {:?}. Deferring proof.",
+                                                old_root_h256, expected
+                                            );
+                                            counter!("batch_deferred_total", "rollup" => self.rollup.clone(), "reason" => "old_root_mismatch")
+                                                .increment(1);
+                                            return Ok(());
+                                        }
+                                        Ok(_) => {}
+                                        Err(e) => {
+                                            self.handle_failure(batch, format!("Invalid expected_old_root: {}", e))
+                                                .await?;
+                                            return Ok(());
+                                        }
+                                    }
+                                }
+
+                                // 3. Sanitize Inputs (Orchestrator)
+                                let da_input = reduce_to_field(commitment_h256.0);
+                                let old_root_input = match self.root_packing.pack(old_root_h256.0) {
+                                    Ok(v) => v,
+                                    Err(e) => {
+                                        self.handle_failure(batch, format!("Invalid old root: {}", e)).await?;
+                                        return Ok(());
+                                    }
+                                };
+
+                                // Parse new_root from hex string
+                                let new_root_val = match batch.new_root.parse::<H256>() {
+                                    Ok(h) => match self.root_packing.pack(h.0) {
+                                        Ok(v) => v,
+                                        Err(e) => {
+                                            self.handle_failure(batch, format!("Invalid new_root: {}", e))
+                                                .await?;
+                                            return Ok(());
+                                        }
+                                    },
+                                    Err(e) => {
+                                        self.handle_failure(batch, format!("Invalid new_root: {}", e))
+                                            .await?;
+                                        return Ok(());
+                                    }
+                                };
+
+                                // 4. Request Proof
+                                // Format public inputs as bytes. The Prover likely expects 32-byte chunks.
+                                // Order: daCommitment, oldRoot, newRoot
+                                let mut public_inputs = Vec::with_capacity(PUBLIC_INPUTS_LEN);
+                                public_inputs.extend_from_slice(&da_input);
+                                public_inputs.extend_from_slice(&old_root_input);
+                                public_inputs.extend_from_slice(&new_root_val);
+
+                                if self.strict_public_inputs {
+                                    if let Err(e) = check_public_inputs(&public_inputs) {
+                                        self.fail_permanently(batch, e.to_string()).await?;
+                                        return Ok(());
+                                    }
+                                }
+
+                                batch.public_inputs = Some(public_inputs.clone());
+                                self.save(batch).await?;
+                                public_inputs
+                            }
+                            (Err(e), _) => {
+                                self.handle_failure(batch, format!("Failed to fetch state root: {}", e)).await?;
+                                return Ok(());
+                            }
+                            (_, Err(e @ DomainError::InvalidBatch(_))) => {
+                                self.fail_permanently(batch, format!("Failed to compute commitment: {}", e)).await?;
+                                return Ok(());
+                            }
+                            (_, Err(e @ DomainError::DataFileMissing(_))) => {
+                                self.fail_with_reason(batch, "data_file_missing", format!("Failed to compute commitment: {}", e))
+                                    .await?;
+                                return Ok(());
+                            }
+                            (_, Err(e)) if self.skip_unavailable_data(batch, &e) => return Ok(()),
+                            (_, Err(e)) => {
+                                self.handle_failure(batch, format!("Failed to compute commitment: {}", e)).await?;
+                                return Ok(());
+                            }
+                        }
+                    }
+                };
+
+                let mut request = ProofRequest::new(batch.id, public_inputs);
+                request.proof_system = batch.proof_system;
+                request.deadline = self
+                    .prover_budget
+                    .and_then(|budget| chrono::Duration::from_std(budget).ok())
+                    .map(|budget| self.clock.now() + budget);
+
+                match self.prover.get_proof(&request).await {
+                    Ok(response) if response.proofs.len() > 1 && !self.aggregated_proofs => {
+                        self.handle_failure(
+                            batch,
+                            format!("Prover returned {} proofs but aggregated proofs are disabled", response.proofs.len()),
+                        )
+                        .await?;
+                    }
+                    Ok(mut response) => {
+                        if !response.proofs.is_empty() {
+                            let sub_proofs = response.proofs.split_off(1);
+                            self.assign_sub_batch_proofs(batch, sub_proofs).await?;
+                            response.proof = response.proofs.remove(0);
+                        }
+                        batch.proof = Some(response.proof);
+                        self.transition(batch, BatchStatus::Proved).await?;
+                        batch.attempts = 0;
+                        self.save(batch).await?;
+
+                        counter!("batch_transitions_total", "rollup" => self.rollup.clone(), "from" => "Proving", "to" => "Proved")
+                            .increment(1);
+                        histogram!("prove_duration_seconds", "rollup" => self.rollup.clone(), "da_mode" => batch.da_mode_label()).record(start.elapsed().as_secs_f64());
+                    }
+                    Err(DomainError::ProofNotReady(reason)) => {
+                        // The proof is produced elsewhere; keep polling without burning an attempt
+                        info!("Proof not ready yet: {}", reason);
+                        counter!("proof_not_ready_total", "rollup" => self.rollup.clone()).increment(1);
+                    }
+                    Err(e @ DomainError::InvalidBatch(_)) => {
+                        self.fail_permanently(batch, e.to_string()).await?;
+                    }
+                    Err(e) => {
+                        self.handle_failure(batch, e.to_string()).await?;
+                    }
+                }
+            }
+            BatchStatus::Proved => {
+                let now = self.clock.now();
+                if self.submit_blackout.iter().any(|w| w.contains(now)) {
+                    info!("Submission blackout in effect, holding batch at Proved");
+                    counter!("submit_blackout_held_total", "rollup" => self.rollup.clone()).increment(1);
+                    return Ok(());
+                }
+                if !self.proof_passes_precheck(batch).await? {
+                    return Ok(());
+                }
+                self.transition(batch, BatchStatus::Submitting).await?;
+                self.save(batch).await?;
+                counter!("batch_transitions_total", "rollup" => self.rollup.clone(), "from" => "Proved", "to" => "Submitting")
+                    .increment(1);
+            }
+            BatchStatus::Submitting => {
+                if self.data_file_preflight
+                    && batch.has_local_data_file()
+                    && !tokio::fs::try_exists(&batch.data_file).await.unwrap_or(true)
+                {
+                    let msg = format!("Data file {} no longer exists", batch.data_file);
+                    self.fail_with_reason(batch, "data_file_missing", msg).await?;
+                    return Ok(());
+                }
+                if !self.batch_index_expected(batch).await? {
+                    return Ok(());
+                }
+                if let Some(reason) = self.root_chain_break(batch).await? {
+                    warn!("{}", reason);
+                    counter!("batch_deferred_total", "rollup" => self.rollup.clone(), "reason" => "root_chain_break")
+                        .increment(1);
+                    return Ok(());
+                }
+                if let Some(proof) = &batch.proof {
+                    match self.da_strategy.submit_tx(batch, proof).await {
+                        Ok(sent) => {
+                            let detail = if self.da_strategy.publishes_off_chain() {
+                                // The bridge commit follows once the DA layer has the data
+                                let detail = format!("published at {}", sent.hash);
+                                batch.da_reference = Some(sent.hash);
+                                batch.tx_hash = None;
+                                batch.signed_tx = None;
+                                detail
+                            } else {
+                                let detail = format!("tx {}", sent.hash);
+                                batch.tx_hash = Some(sent.hash);
+                                batch.signed_tx = sent.raw;
+                                detail
+                            };
+                            self.transition_with(batch, BatchStatus::Submitted, Some(detail)).await?;
+                            batch.attempts = 0;
+                            self.save(batch).await?;
+
+                            counter!("batch_transitions_total", "rollup" => self.rollup.clone(), "from" => "Submitting", "to" => "Submitted").increment(1);
+                            histogram!("submit_tx_duration_seconds", "rollup" => self.rollup.clone(), "da_mode" => batch.da_mode_label())
+                                .record(start.elapsed().as_secs_f64());
+                        }
+                        Err(DomainError::FeeCapExceeded(reason)) => {
+                            // Waiting out a fee spike is not the batch's fault; keep the attempt
+                            info!("Holding batch {}: {}", batch.id, reason);
+                            counter!("batch_deferred_total", "rollup" => self.rollup.clone(), "reason" => "base_fee_cap")
+                                .increment(1);
+                        }
+                        Err(e @ DomainError::InvalidBatch(_)) => {
+                            self.fail_permanently(batch, e.to_string()).await?;
+                        }
+                        Err(e @ DomainError::DataFileMissing(_)) => {
+                            self.fail_with_reason(batch, "data_file_missing", e.to_string()).await?;
+                        }
+                        Err(e) if self.skip_unavailable_data(batch, &e) => {}
+                        Err(e) => {
+                            self.handle_failure(batch, e.to_string()).await?;
+                        }
+                    }
+                } else {
+                    error!("Missing proof for batch {}", batch.id);
+                    self.transition_with(batch, BatchStatus::Failed, Some("missing proof".into())).await?;
+                    self.save(batch).await?;
+                    counter!("batches_failed_permanent_total", "rollup" => self.rollup.clone(), "reason" => "missing_proof")
+                        .increment(1);
+                }
+            }
+            BatchStatus::Submitted => {
+                if batch.tx_hash.is_some() {
+                    let confirmation = match &self.confirmation_source {
+                        Some(source) => source.is_confirmed(batch).await,
+                        None => self.da_strategy.check_batch_confirmation(batch).await,
+                    };
+                    match confirmation {
+                        Ok(confirmed) => {
+                            if confirmed {
+                                self.transition(batch, BatchStatus::Confirmed).await?;
+                                self.record_gas(batch).await;
+                                self.attest(batch).await;
+                                self.save(batch).await?;
+                                info!(
+                                    batch_id = %batch.id,
+                                    tx_hash = batch.tx_hash.as_deref(),
+                                    gas_used = batch.gas_used,
+                                    effective_gas_price = batch.effective_gas_price,
+                                    "Batch {} CONFIRMED",
+                                    batch.id
+                                );
+                                if self.verify_onchain_commitment {
+                                    self.check_onchain_commitment(batch).await;
+                                }
+                                if self.cleanup_data_files {
+                                    self.remove_data_file(batch).await?;
+                                }
+
+                                counter!("batch_transitions_total", "rollup" => self.rollup.clone(), "from" => "Submitted", "to" => "Confirmed").increment(1);
+                                counter!("batches_completed_total", "rollup" => self.rollup.clone()).increment(1);
+                                let confirmed_at = self.clock.now();
+                                *self.last_confirmation.lock().unwrap() = Some(confirmed_at);
+                                gauge!("last_confirmation_timestamp_seconds", "rollup" => self.rollup.clone())
+                                    .set(confirmed_at.timestamp() as f64);
+
+                                // Calculate total duration since creation
+                                let total_duration =
+                                    chrono::Utc::now().signed_duration_since(batch.created_at);
+                                histogram!("batch_e2e_duration_seconds", "rollup" => self.rollup.clone(), "da_mode" => batch.da_mode_label())
+                                    .record(total_duration.num_seconds() as f64);
+                            } else {
+                                info!("Batch {} still pending confirmation", batch.id);
+                                if !self.expire_submitted(batch).await? {
+                                    self.escalate_fees(batch).await?;
+                                }
+                            }
+                        }
+                        Err(DomainError::DaTransient(reason)) => {
+                            // A provider blip says nothing about the tx; check again next cycle
+                            warn!("Transient error checking confirmation for {}: {}", batch.id, reason);
+                            counter!("confirmation_check_transient_total", "rollup" => self.rollup.clone()).increment(1);
+                        }
+                        Err(e) => {
+                            warn!("Error checking confirmation for {}: {}", batch.id, e);
+                            self.handle_failure(batch, e.to_string()).await?;
+                        }
+                    }
+                } else if batch.da_reference.is_some() && self.da_strategy.publishes_off_chain() {
+                    self.commit_published(batch).await?;
+                } else {
+                    self.transition(batch, BatchStatus::Submitting).await?;
+                    self.save(batch).await?;
+                    counter!("batch_reverted_to_submitting_total", "rollup" => self.rollup.clone()).increment(1);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::ports::{BatchEvent, BridgeReader, CircuitSnapshot, Clock, DaStrategy, ProofProvider, ProofRequest, ProofResponse, SentTx, Storage};
+    use crate::domain::{
+        batch::{Batch, BatchId},
+        errors::DomainError,
+    };
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+
+    // Mocks
+    struct MockStorage {
+        batches: Mutex<Vec<Batch>>,
+        events: Mutex<Vec<BatchEvent>>,
+        // Makes save_batch fail, like a database that went away
+        fail_saves: std::sync::atomic::AtomicBool,
+    }
+
+    impl MockStorage {
+        fn new(batches: Vec<Batch>) -> Self {
+            Self {
+                batches: Mutex::new(batches),
+                events: Mutex::new(Vec::new()),
+                fail_saves: std::sync::atomic::AtomicBool::new(false),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Storage for MockStorage {
+        async fn save_batch(&self, batch: &Batch) -> Result<(), DomainError> {
+            if self.fail_saves.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err(DomainError::Storage("database is gone".into()));
+            }
+            let mut batches = self.batches.lock().unwrap();
+            match batches.iter_mut().find(|b| b.id == batch.id) {
+                Some(existing) => *existing = batch.clone(),
+                None => batches.push(batch.clone()),
+            }
+            Ok(())
+        }
+        async fn get_batch(&self, id: BatchId) -> Result<Option<Batch>, DomainError> {
+            Ok(self.batches.lock().unwrap().iter().find(|b| b.id == id).cloned())
+        }
+        async fn get_pending_batches(&self) -> Result<Vec<Batch>, DomainError> {
+            Ok(self
+                .batches
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|b| !matches!(b.status, BatchStatus::Confirmed | BatchStatus::Failed))
+                .cloned()
+                .collect())
+        }
+        async fn get_batches_by_status(&self, status: BatchStatus) -> Result<Vec<Batch>, DomainError> {
+            Ok(self.batches.lock().unwrap().iter().filter(|b| b.status == status).cloned().collect())
+        }
+        async fn count_pending(&self) -> Result<u64, DomainError> {
+            Ok(self.get_pending_batches().await?.len() as u64)
+        }
+        async fn count_pending_with_data_file(&self, data_file: &str) -> Result<u64, DomainError> {
+            Ok(self.get_pending_batches().await?.iter().filter(|b| b.data_file == data_file).count() as u64)
+        }
+        async fn ping(&self) -> Result<(), DomainError> {
+            Ok(())
+        }
+        async fn count_by_status(&self) -> Result<Vec<(BatchStatus, u64)>, DomainError> {
+            let batches = self.batches.lock().unwrap();
+            Ok(BatchStatus::ALL
+                .into_iter()
+                .map(|status| {
+                    let count = batches.iter().filter(|b| b.status == status).count() as u64;
+                    (status, count)
+                })
+                .collect())
+        }
+        async fn last_confirmed_root(&self) -> Result<Option<String>, DomainError> {
+            Ok(self
+                .batches
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|b| b.status == BatchStatus::Confirmed)
+                .max_by_key(|b| (b.updated_at, b.index))
+                .map(|b| b.new_root.clone()))
+        }
+        async fn max_batch_index(&self) -> Result<Option<u64>, DomainError> {
+            Ok(self.batches.lock().unwrap().iter().map(|b| b.index).max())
+        }
+        async fn last_confirmed_index(&self) -> Result<Option<u64>, DomainError> {
+            Ok(self
+                .batches
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|b| b.status == BatchStatus::Confirmed)
+                .map(|b| b.index)
+                .max())
+        }
+        async fn save_circuit_state(&self, _key: &str, _snapshot: &CircuitSnapshot) -> Result<(), DomainError> {
+            Ok(())
+        }
+        async fn load_circuit_state(&self, _key: &str) -> Result<Option<CircuitSnapshot>, DomainError> {
+            Ok(None)
+        }
+        async fn record_event(
+            &self,
+            batch_id: BatchId,
+            from: BatchStatus,
+            to: BatchStatus,
+            detail: Option<String>,
+        ) -> Result<(), DomainError> {
+            self.events.lock().unwrap().push(BatchEvent { batch_id, from, to, detail, at: chrono::Utc::now() });
+            Ok(())
+        }
+        async fn get_events(&self, batch_id: BatchId) -> Result<Vec<BatchEvent>, DomainError> {
+            Ok(self.events.lock().unwrap().iter().filter(|e| e.batch_id == batch_id).cloned().collect())
+        }
+        async fn get_batches_by_data_hash(&self, data_hash: &str) -> Result<Vec<Batch>, DomainError> {
+            Ok(self
+                .batches
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|b| b.data_hash.as_deref() == Some(data_hash))
+                .cloned()
+                .collect())
+        }
+    }
+
+    struct MockProver {
+        should_fail: bool,
+    }
+
+    #[async_trait]
+    impl ProofProvider for MockProver {
+        async fn get_proof(&self, _request: &ProofRequest) -> Result<ProofResponse, DomainError> {
+            if self.should_fail {
+                Err(DomainError::Prover("fail".into()))
+            } else {
+                Ok(ProofResponse {
+                    proof: "p".into(),
+                    proofs: Vec::new(),
+                })
+            }
+        }
+    }
+
+    struct MockDa {
+        should_fail_submit: bool,
+        should_fail_confirm: bool,
+        confirm_result: bool,
+    }
+
+    #[async_trait]
+    impl DaStrategy for MockDa {
+        fn da_id(&self) -> u8 { 0 }
+        async fn compute_commitment(&self, _batch: &Batch) -> Result<H256, DomainError> {
+            Ok(H256::zero())
+        }
+        fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
+             Ok(vec![])
+        }
+
+        async fn submit(&self, _b: &Batch, _p: &str) -> Result<String, DomainError> {
+            if self.should_fail_submit {
+                Err(DomainError::Da("fail".into()))
+            } else {
+                Ok("0xhash".into())
+            }
+        }
+        async fn check_confirmation(&self, _tx: &str) -> Result<bool, DomainError> {
+            if self.should_fail_confirm {
+                Err(DomainError::DaReverted("revert".into()))
+            } else {
+                Ok(self.confirm_result)
+            }
+        }
+    }
+
+    /// Takes `delay` to broadcast, like a slow RPC node.
+    struct SlowDa {
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl DaStrategy for SlowDa {
+        fn da_id(&self) -> u8 { 0 }
+        async fn compute_commitment(&self, _batch: &Batch) -> Result<H256, DomainError> {
+            Ok(H256::zero())
+        }
+        fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
+            Ok(vec![])
+        }
+        async fn submit(&self, _b: &Batch, _p: &str) -> Result<String, DomainError> {
+            tokio::time::sleep(self.delay).await;
+            Ok("0xslow".into())
+        }
+        async fn check_confirmation(&self, _tx: &str) -> Result<bool, DomainError> {
+            Ok(false)
+        }
+    }
+
+    struct MockBridgeReader;
+    #[async_trait]
+    impl BridgeReader for MockBridgeReader {
+        async fn state_root(&self) -> Result<H256, DomainError> {
+            Ok(H256::zero())
+        }
+    }
+
+    fn create_orchestrator(
+        batch: Batch,
+        prover_fail: bool,
+        da_fail: bool,
+        da_confirm_fail: bool,
+    ) -> (Orchestrator, Arc<MockStorage>) {
+        let storage = Arc::new(MockStorage::new(vec![batch]));
+        let prover = Arc::new(MockProver {
+            should_fail: prover_fail,
+        });
+        let da = Arc::new(MockDa {
+            should_fail_submit: da_fail,
+            should_fail_confirm: da_confirm_fail,
+            confirm_result: true,
+        });
+        let reader = Arc::new(MockBridgeReader);
+
+        (
+            Orchestrator::new(storage.clone(), prover, da, reader, 5),
+            storage,
+        )
+    }
+
+    // Valid 32-byte hex for tests
+    const VALID_HASH: &str = "0x0000000000000000000000000000000000000000000000000000000000000000";
+
+    #[tokio::test]
+    async fn test_proving_success() {
+        let batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        let (orch, store) = create_orchestrator(batch.clone(), false, false, false);
+
+        // Discovered -> Proving
+        orch.process_pending_batches().await.unwrap();
+        // Proving -> Proved
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = store.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Proved);
+        assert!(updated.proof.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_proving_retry() {
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Proving;
+
+        let (orch, store) = create_orchestrator(batch.clone(), true, false, false);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = store.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Proving);
+        assert_eq!(updated.attempts, 1);
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_with_jitter() {
+        let base = Duration::from_secs(10);
+        assert_eq!(retry_delay(base, 1, 0.0), Duration::from_secs(20));
+        assert_eq!(retry_delay(base, 3, 0.0), Duration::from_secs(80));
+        assert_eq!(retry_delay(base, 3, 0.5), Duration::from_secs(60));
+        assert!(retry_delay(base, 3, 0.999) > Duration::from_secs(40));
+        assert_eq!(retry_delay(base, 200, 0.0), base.saturating_mul(u32::MAX));
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_waits_out_backoff() {
+        let now = chrono::Utc::now();
+        let clock = Arc::new(FixedClock(Mutex::new(now)));
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Proving;
+        let (orch, store) = create_orchestrator(batch.clone(), true, false, false);
+        let orch = orch
+            .with_clock(clock.clone())
+            .with_retry_backoff(Some(Duration::from_secs(60)));
+
+        orch.process_pending_batches().await.unwrap();
+        let failed = store.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(failed.attempts, 1);
+        let retry_at = failed.next_attempt_at.unwrap();
+        assert!(retry_at >= now + chrono::Duration::seconds(60));
+        assert!(retry_at <= now + chrono::Duration::seconds(120));
+
+        // The very next cycle leaves it alone
+        orch.process_pending_batches().await.unwrap();
+        assert_eq!(store.get_batch(batch.id).await.unwrap().unwrap().attempts, 1);
+
+        *clock.0.lock().unwrap() = retry_at;
+        orch.process_pending_batches().await.unwrap();
+        assert_eq!(store.get_batch(batch.id).await.unwrap().unwrap().attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_proving_dead_letter() {
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Proving;
+        batch.attempts = 4; // Max is 5
+
+        let (orch, store) = create_orchestrator(batch.clone(), true, false, false);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = store.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_submitting_missing_proof() {
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Submitting;
+        batch.proof = None; // Should fail
+
+        let (orch, store) = create_orchestrator(batch.clone(), false, false, false);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = store.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_submitted_revert() {
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Submitted;
+        batch.tx_hash = Some("0x123".into());
+
+        // Simulate Revert (error in check_confirmation)
+        let (orch, store) = create_orchestrator(batch.clone(), false, false, true);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = store.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.attempts, 1); // Should count as failure
+    }
+
+    struct SlowConfirmDa {
+        in_flight: std::sync::atomic::AtomicUsize,
+        max_in_flight: std::sync::atomic::AtomicUsize,
+        checks: std::sync::atomic::AtomicUsize,
+        checked: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl DaStrategy for SlowConfirmDa {
+        fn da_id(&self) -> u8 { 0 }
+        async fn compute_commitment(&self, _batch: &Batch) -> Result<H256, DomainError> {
+            Ok(H256::zero())
+        }
+        fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
+            Ok(vec![])
+        }
+        async fn submit(&self, _b: &Batch, _p: &str) -> Result<String, DomainError> {
+            Ok("0xhash".into())
+        }
+        async fn check_confirmation(&self, tx: &str) -> Result<bool, DomainError> {
+            use std::sync::atomic::Ordering;
+            self.checked.lock().unwrap().push(tx.to_string());
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            self.checks.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(false)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_checks_are_bounded() {
+        use std::sync::atomic::Ordering;
+
+        let batches: Vec<Batch> = (0..10)
+            .map(|i| {
+                let mut b = Batch::new(1, "b", "f".into(), format!("h{}", i), VALID_HASH.into(), "m".into());
+                b.status = BatchStatus::Submitted;
+                b.tx_hash = Some(format!("0x{:03}", i));
+                b
+            })
+            .collect();
+        let storage = Arc::new(MockStorage::new(batches));
+        let da = Arc::new(SlowConfirmDa {
+            in_flight: Default::default(),
+            max_in_flight: Default::default(),
+            checks: Default::default(),
+            checked: Default::default(),
+        });
+        let orch = Orchestrator::new(
+            storage,
+            Arc::new(MockProver { should_fail: false }),
+            da.clone(),
+            Arc::new(MockBridgeReader),
+            5,
+        )
+        .with_max_concurrent_confirmations(Some(2));
+
+        orch.process_pending_batches().await.unwrap();
+
+        assert_eq!(da.max_in_flight.load(Ordering::SeqCst), 2);
+        // The remaining batches are left for the next cycle
+        assert_eq!(da.checks.load(Ordering::SeqCst), 2);
+        let first: Vec<String> = da.checked.lock().unwrap().drain(..).collect();
+
+        // Deferred batches are checked before the ones already checked last cycle
+        orch.process_pending_batches().await.unwrap();
+        let second: Vec<String> = da.checked.lock().unwrap().drain(..).collect();
+        assert_eq!(second.len(), 2);
+        assert!(second.iter().all(|tx| !first.contains(tx)));
+
+        // Every batch gets its turn before any is checked a second time
+        for _ in 0..3 {
+            orch.process_pending_batches().await.unwrap();
+        }
+        let mut rest: Vec<String> = da.checked.lock().unwrap().drain(..).collect();
+        rest.extend(first);
+        rest.extend(second);
+        rest.sort();
+        rest.dedup();
+        assert_eq!(rest.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_proving_deferred_on_old_root_mismatch() {
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Proving;
+        batch.expected_old_root = Some(format!("{:#x}", H256::repeat_byte(0x11)));
+
+        let (orch, store) = create_orchestrator(batch.clone(), false, false, false);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = store.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Proving);
+        assert_eq!(updated.attempts, 0);
+        assert!(updated.proof.is_none());
+    }
+
+    struct NotReadyProver;
+
+    #[async_trait]
+    impl ProofProvider for NotReadyProver {
+        async fn get_proof(&self, _request: &ProofRequest) -> Result<ProofResponse, DomainError> {
+            Err(DomainError::ProofNotReady("missing".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_proof_not_ready_keeps_attempts() {
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Proving;
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(NotReadyProver), da, Arc::new(MockBridgeReader), 5);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Proving);
+        assert_eq!(updated.attempts, 0);
+    }
+
+    /// Refuses every submission because of a fee spike.
+    struct FeeSpikeDa;
+
+    #[async_trait]
+    impl DaStrategy for FeeSpikeDa {
+        fn da_id(&self) -> u8 { 0 }
+        async fn compute_commitment(&self, _batch: &Batch) -> Result<H256, DomainError> {
+            Ok(H256::zero())
+        }
+        fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
+            Ok(vec![])
+        }
+        async fn submit(&self, _b: &Batch, _p: &str) -> Result<String, DomainError> {
+            Err(DomainError::FeeCapExceeded("base fee too high, deferring".into()))
+        }
+        async fn check_confirmation(&self, _tx: &str) -> Result<bool, DomainError> {
+            Ok(false)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fee_cap_defers_without_spending_attempts() {
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Submitting;
+        batch.proof = Some("0x00".into());
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), Arc::new(FeeSpikeDa), Arc::new(MockBridgeReader), 1);
+
+        orch.process_pending_batches().await.unwrap();
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Submitting);
+        assert_eq!(updated.attempts, 0);
+        assert!(updated.tx_hash.is_none());
+    }
+
+    /// Reads the batch's data file on submit, as the real strategies do.
+    struct FileReadingDa;
+
+    #[async_trait]
+    impl DaStrategy for FileReadingDa {
+        fn da_id(&self) -> u8 { 0 }
+        async fn compute_commitment(&self, _batch: &Batch) -> Result<H256, DomainError> {
+            Ok(H256::zero())
+        }
+        fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
+            Ok(vec![])
+        }
+        async fn submit(&self, batch: &Batch, _p: &str) -> Result<String, DomainError> {
+            std::fs::read(&batch.data_file)
+                .map_err(|e| crate::infrastructure::payload::payload_read_error("Failed to read batch file", e))?;
+            Ok("0xhash".into())
+        }
+        async fn check_confirmation(&self, _tx: &str) -> Result<bool, DomainError> {
+            Ok(false)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_missing_data_file_fails_without_retries() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_file = dir.path().join("batch.bin");
+        std::fs::write(&data_file, b"payload").unwrap();
+        let mut batch = Batch::new(1, "b", data_file.to_string_lossy().into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Submitting;
+        batch.proof = Some("0x00".into());
+        std::fs::remove_file(&data_file).unwrap();
+
+        // Detected by the DA strategy's read
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), Arc::new(FileReadingDa), Arc::new(MockBridgeReader), 5);
+        orch.process_pending_batches().await.unwrap();
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Failed);
+        assert_eq!(updated.attempts, 0);
+
+        // Detected before the DA layer is asked at all
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, Arc::new(MockBridgeReader), 5)
+            .with_data_file_preflight(true);
+        orch.process_pending_batches().await.unwrap();
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Failed);
+        assert_eq!(updated.attempts, 0);
+        assert!(updated.tx_hash.is_none());
+    }
+
+    /// Fails the first `failures` state root reads, then succeeds.
+    struct FlakyBridgeReader {
+        failures: usize,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl BridgeReader for FlakyBridgeReader {
+        async fn state_root(&self) -> Result<H256, DomainError> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                Err(DomainError::Internal("node unavailable".into()))
+            } else {
+                Ok(H256::zero())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_initial_state_root_failure_retried_without_attempt() {
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Proving;
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let reader = Arc::new(FlakyBridgeReader { failures: 1, calls: Default::default() });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, reader.clone(), 5)
+            .with_initial_state_root_retry(2, Duration::ZERO);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Proved);
+        assert_eq!(updated.attempts, 0);
+        assert_eq!(reader.calls.load(Ordering::SeqCst), 2);
+    }
+
+    struct SettableBridgeReader(Mutex<H256>);
+
+    #[async_trait]
+    impl BridgeReader for SettableBridgeReader {
+        async fn state_root(&self) -> Result<H256, DomainError> {
+            Ok(*self.0.lock().unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reproving_reuses_persisted_public_inputs() {
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Proving;
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let reader = Arc::new(SettableBridgeReader(Mutex::new(H256::repeat_byte(0x01))));
+        let prover = Arc::new(RecordingProver { requests: Mutex::new(Vec::new()) });
+        let orch = Orchestrator::new(storage.clone(), prover.clone(), da, reader.clone(), 5);
+
+        orch.process_pending_batches().await.unwrap();
+        let proved = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(proved.status, BatchStatus::Proved);
+        let first_inputs = proved.public_inputs.clone().expect("inputs persisted");
+        assert_eq!(&first_inputs[32..64], H256::repeat_byte(0x01).as_bytes());
+
+        // Crash before submission, then the bridge root moves on
+        let mut again = proved;
+        again.status = BatchStatus::Proving;
+        storage.save_batch(&again).await.unwrap();
+        *reader.0.lock().unwrap() = H256::repeat_byte(0x02);
+
+        orch.process_pending_batches().await.unwrap();
+        let reproved = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(reproved.public_inputs, Some(first_inputs.clone()));
+        let requests = prover.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].public_inputs, first_inputs);
+    }
+
+    struct AggregatedProver;
+
+    #[async_trait]
+    impl ProofProvider for AggregatedProver {
+        async fn get_proof(&self, _request: &ProofRequest) -> Result<ProofResponse, DomainError> {
+            Ok(serde_json::from_str(r#"{"proofs": ["p0", "p1", "p2"]}"#).unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_aggregated_proofs_assigned_along_root_chain() {
+        let root = |n: u8| format!("{:#x}", H256::repeat_byte(n));
+        let mut batches = Vec::new();
+        for i in 0..3u8 {
+            let mut b = Batch::new(1, "b", format!("f{}", i), "h".into(), root(i + 1), "m".into());
+            b.status = BatchStatus::Proving;
+            b.expected_old_root = Some(if i == 0 { VALID_HASH.to_string() } else { root(i) });
+            batches.push(b);
+        }
+        // Storage order must not matter for the mapping
+        batches.swap(1, 2);
+        let ids: Vec<BatchId> = batches.iter().map(|b| b.id).collect();
+
+        let storage = Arc::new(MockStorage::new(batches));
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(AggregatedProver), da, Arc::new(MockBridgeReader), 5)
+            .with_aggregated_proofs(true);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let proof_of = |id| {
+            let b = storage.batches.lock().unwrap().iter().find(|b| b.id == id).cloned().unwrap();
+            assert_eq!(b.status, BatchStatus::Proved);
+            b.proof.unwrap()
+        };
+        assert_eq!(proof_of(ids[0]), "p0");
+        assert_eq!(proof_of(ids[2]), "p1");
+        assert_eq!(proof_of(ids[1]), "p2");
+    }
+
+    struct CountingAggregatedProver(std::sync::atomic::AtomicUsize);
+
+    #[async_trait]
+    impl ProofProvider for CountingAggregatedProver {
+        async fn get_proof(&self, _request: &ProofRequest) -> Result<ProofResponse, DomainError> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(serde_json::from_str(r#"{"proofs": ["p0", "p1"]}"#).unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_aggregated_sub_batches_in_same_cycle_are_not_reproved() {
+        let root = |n: u8| format!("{:#x}", H256::repeat_byte(n));
+        let mut parent = Batch::new(1, "b", "f0".into(), "h".into(), root(1), "m".into());
+        parent.status = BatchStatus::Proving;
+        parent.expected_old_root = Some(VALID_HASH.to_string());
+        let mut sub = Batch::new(1, "b", "f1".into(), "h".into(), root(2), "m".into());
+        sub.status = BatchStatus::Proving;
+        sub.expected_old_root = Some(root(1));
+        // An earlier attempt already fixed the sub-batch's inputs, so it would go straight to the prover
+        sub.public_inputs = Some(vec![0u8; PUBLIC_INPUTS_LEN]);
+
+        // Both batches come back in the same pending list, parent first
+        let storage = Arc::new(MockStorage::new(vec![parent.clone(), sub.clone()]));
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let prover = Arc::new(CountingAggregatedProver(Default::default()));
+        let orch = Orchestrator::new(storage.clone(), prover.clone(), da, Arc::new(MockBridgeReader), 5)
+            .with_aggregated_proofs(true);
+
+        orch.process_pending_batches().await.unwrap();
+
+        assert_eq!(prover.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let sub = storage.get_batch(sub.id).await.unwrap().unwrap();
+        assert_eq!(sub.status, BatchStatus::Proved);
+        assert_eq!(sub.proof.as_deref(), Some("p1"));
+    }
+
+    #[tokio::test]
+    async fn test_aggregated_proofs_rejected_when_disabled() {
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Proving;
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(AggregatedProver), da, Arc::new(MockBridgeReader), 5);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Proving);
+        assert_eq!(updated.attempts, 1);
+    }
+
+    struct FixedClock(Mutex<chrono::DateTime<chrono::Utc>>);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> chrono::DateTime<chrono::Utc> {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_held_during_blackout() {
+        use chrono::TimeZone;
+
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Proving;
+        let (orch, store) = create_orchestrator(batch.clone(), false, false, false);
+        let clock = Arc::new(FixedClock(Mutex::new(
+            chrono::Utc.with_ymd_and_hms(2024, 1, 1, 9, 30, 0).unwrap(),
+        )));
+        let orch = orch
+            .with_submit_blackout(vec!["09:00-10:00".parse().unwrap()])
+            .with_clock(clock.clone());
+
+        // Proving still happens inside the window
+        orch.process_pending_batches().await.unwrap();
+        assert_eq!(store.get_batch(batch.id).await.unwrap().unwrap().status, BatchStatus::Proved);
+
+        orch.process_pending_batches().await.unwrap();
+        assert_eq!(store.get_batch(batch.id).await.unwrap().unwrap().status, BatchStatus::Proved);
+
+        *clock.0.lock().unwrap() = chrono::Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+        orch.process_pending_batches().await.unwrap();
+        assert_eq!(store.get_batch(batch.id).await.unwrap().unwrap().status, BatchStatus::Submitting);
+    }
+
+    #[tokio::test]
+    async fn test_indexer_confirmation_skips_node() {
+        use crate::infrastructure::confirmation::IndexerConfirmationSource;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let indexer = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/batch/{}/status", VALID_HASH)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "status": "confirmed" })))
+            .mount(&indexer)
+            .await;
+
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Submitted;
+        batch.tx_hash = Some("0xhash".into());
+        // The node check would fail, so confirming proves it was never consulted
+        let (orch, store) = create_orchestrator(batch.clone(), false, false, true);
+        let orch = orch.with_confirmation_source(Some(Arc::new(IndexerConfirmationSource::new(indexer.uri()))));
+
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = store.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Confirmed);
+        assert_eq!(updated.attempts, 0);
+    }
+
+    #[derive(Clone, Default)]
+    struct TransitionCapture(Arc<Mutex<Vec<std::collections::HashMap<String, String>>>>);
+
+    struct FieldMap(std::collections::HashMap<String, String>);
+
+    impl tracing::field::Visit for FieldMap {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for TransitionCapture {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+            if event.metadata().name() == "batch.transition" {
+                let mut fields = FieldMap(Default::default());
+                event.record(&mut fields);
+                self.0.lock().unwrap().push(fields.0);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transition_events_emitted() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let capture = TransitionCapture::default();
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(capture.clone()));
+
+        let batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        let (orch, _store) = create_orchestrator(batch.clone(), false, false, false);
+        let orch = orch.with_transition_events(true);
+        for _ in 0..5 {
+            orch.process_pending_batches().await.unwrap();
+        }
+
+        let events = capture.0.lock().unwrap();
+        let steps: Vec<(&str, &str)> = events
+            .iter()
+            .map(|e| (e["from"].as_str(), e["to"].as_str()))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                ("Discovered", "Proving"),
+                ("Proving", "Proved"),
+                ("Proved", "Submitting"),
+                ("Submitting", "Submitted"),
+                ("Submitted", "Confirmed"),
+            ]
+        );
+        for event in events.iter() {
+            assert_eq!(event["batch_id"], batch.id.to_string());
+            assert_eq!(event["attempts"], "0");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requeue_failed_batch() {
+        use crate::infrastructure::storage_sqlite::SqliteStorage;
+
+        let storage = Arc::new(SqliteStorage::new("sqlite::memory:").await.unwrap());
+        let batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        storage.save_batch(&batch).await.unwrap();
+        let orch = Orchestrator::new(
+            storage.clone(),
+            Arc::new(MockProver { should_fail: true }),
+            Arc::new(MockDa { should_fail_submit: false, should_fail_confirm: false, confirm_result: true }),
+            Arc::new(MockBridgeReader),
+            1,
+        );
+
+        // Discovered -> Proving, then the prover failure exhausts the single attempt
+        orch.process_pending_batches().await.unwrap();
+        orch.process_pending_batches().await.unwrap();
+        assert_eq!(storage.get_batch(batch.id).await.unwrap().unwrap().status, BatchStatus::Failed);
+        assert!(storage.get_pending_batches().await.unwrap().is_empty());
+
+        let requeued = orch.requeue_batch(batch.id).await.unwrap();
+        assert_eq!(requeued.status, BatchStatus::Discovered);
+        assert_eq!(requeued.attempts, 0);
+        let pending = storage.get_pending_batches().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, batch.id);
+
+        // A batch that kept its proof skips straight back to submission
+        let mut proved = Batch::new(1, "b", "f".into(), "h2".into(), VALID_HASH.into(), "m".into());
+        proved.status = BatchStatus::Failed;
+        proved.proof = Some("0xproof".into());
+        proved.tx_hash = Some("0xreverted".into());
+        storage.save_batch(&proved).await.unwrap();
+        let requeued = orch.requeue_batch(proved.id).await.unwrap();
+        assert_eq!(requeued.status, BatchStatus::Proved);
+        assert_eq!(requeued.tx_hash, None);
+
+        // Confirmed is terminal; other states are not Failed and are left alone
+        let mut confirmed = Batch::new(1, "b", "f".into(), "h3".into(), VALID_HASH.into(), "m".into());
+        confirmed.status = BatchStatus::Confirmed;
+        storage.save_batch(&confirmed).await.unwrap();
+        let err = orch.requeue_batch(confirmed.id).await.unwrap_err();
+        assert!(err.to_string().contains("already Confirmed"));
+        assert!(orch.requeue_batch(batch.id).await.is_err());
+        assert!(orch.requeue_batch(BatchId::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_event_log_records_each_transition() {
+        use crate::infrastructure::storage_sqlite::SqliteStorage;
+        use BatchStatus::*;
+
+        let storage = Arc::new(SqliteStorage::new("sqlite::memory:").await.unwrap());
+        let batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        storage.save_batch(&batch).await.unwrap();
+        let da = || Arc::new(MockDa { should_fail_submit: false, should_fail_confirm: false, confirm_result: true });
+        let failing = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: true }), da(), Arc::new(MockBridgeReader), 2);
+
+        // One retried prover failure, then a second one that exhausts the attempts
+        for _ in 0..3 {
+            failing.process_pending_batches().await.unwrap();
+        }
+        assert_eq!(storage.get_batch(batch.id).await.unwrap().unwrap().status, Failed);
+        failing.requeue_batch(batch.id).await.unwrap();
+
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da(), Arc::new(MockBridgeReader), 2);
+        for _ in 0..10 {
+            if storage.get_batch(batch.id).await.unwrap().unwrap().status == Confirmed {
+                break;
+            }
+            orch.process_pending_batches().await.unwrap();
+        }
+
+        let events = storage.get_events(batch.id).await.unwrap();
+        let moves: Vec<_> = events.iter().map(|e| (e.from.clone(), e.to.clone())).collect();
+        assert_eq!(
+            moves,
+            vec![
+                (Discovered, Proving),
+                (Proving, Proving),
+                (Proving, Failed),
+                (Failed, Discovered),
+                (Discovered, Proving),
+                (Proving, Proved),
+                (Proved, Submitting),
+                (Submitting, Submitted),
+                (Submitted, Confirmed),
+            ]
+        );
+        assert!(events[1].detail.is_some() && events[2].detail.is_some());
+        assert_eq!(events[3].detail.as_deref(), Some("requeued"));
+        assert!(events[7].detail.as_deref().unwrap().starts_with("tx "));
+        assert!(events.windows(2).all(|w| w[0].at <= w[1].at));
+        assert!(storage.get_events(BatchId::new()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_event_is_not_recorded_when_the_save_fails() {
+        let batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        let (orch, store) = create_orchestrator(batch.clone(), false, false, false);
+
+        store.fail_saves.store(true, std::sync::atomic::Ordering::SeqCst);
+        assert!(orch.process_pending_batches().await.is_err());
+        assert!(store.get_events(batch.id).await.unwrap().is_empty());
+
+        // The stored batch is still Discovered, so the retry makes the same move once more
+        store.fail_saves.store(false, std::sync::atomic::Ordering::SeqCst);
+        orch.process_pending_batches().await.unwrap();
+        let moves: Vec<_> = store.get_events(batch.id).await.unwrap().into_iter().map(|e| (e.from, e.to)).collect();
+        assert_eq!(moves, vec![(BatchStatus::Discovered, BatchStatus::Proving)]);
+    }
+
+    #[tokio::test]
+    async fn test_run_once_summarises_a_single_pass() {
+        let mut submitted = Batch::new(1, "b", "f".into(), "h1".into(), VALID_HASH.into(), "m".into());
+        submitted.status = BatchStatus::Submitted;
+        submitted.tx_hash = Some("0x1".into());
+        let mut proving = Batch::new(1, "b", "f".into(), "h2".into(), VALID_HASH.into(), "m".into());
+        proving.status = BatchStatus::Proving;
+        let discovered = Batch::new(1, "b", "f".into(), "h3".into(), VALID_HASH.into(), "m".into());
+        let storage = Arc::new(MockStorage::new(vec![submitted, proving, discovered.clone()]));
+        let orch = Orchestrator::new(
+            storage.clone(),
+            Arc::new(MockProver { should_fail: true }),
+            Arc::new(MockDa { should_fail_submit: false, should_fail_confirm: false, confirm_result: true }),
+            Arc::new(MockBridgeReader),
+            1,
+        );
+
+        let summary = orch.run_once().await.unwrap();
+        assert_eq!(summary, CycleSummary { processed: 3, confirmed: 1, failed: 1, pending: 1 });
+        assert_eq!(summary.exit_code(), 1);
+        // One pass moves the Discovered batch a single step
+        assert_eq!(storage.get_batch(discovered.id).await.unwrap().unwrap().status, BatchStatus::Proving);
+
+        // The Proving batch is all that is left; the failing prover fails it too
+        let summary = orch.run_once().await.unwrap();
+        assert_eq!(summary, CycleSummary { processed: 1, confirmed: 0, failed: 1, pending: 0 });
+
+        let idle = orch.run_once().await.unwrap();
+        assert_eq!(idle, CycleSummary::default());
+        assert_eq!(idle.exit_code(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_process_one_advances_only_that_batch() {
+        let target = Batch::new(1, "b", "f".into(), "h1".into(), VALID_HASH.into(), "m".into());
+        let other = Batch::new(1, "b", "f".into(), "h2".into(), VALID_HASH.into(), "m".into());
+        let storage = Arc::new(MockStorage::new(vec![target.clone(), other.clone()]));
+        let orch = Orchestrator::new(
+            storage.clone(),
+            Arc::new(MockProver { should_fail: false }),
+            Arc::new(MockDa { should_fail_submit: false, should_fail_confirm: false, confirm_result: true }),
+            Arc::new(MockBridgeReader),
+            5,
+        );
+
+        let stepped = orch.process_one(target.id).await.unwrap();
+        assert_eq!(stepped.status, BatchStatus::Proving);
+        let stepped = orch.process_one(target.id).await.unwrap();
+        assert_eq!(stepped.status, BatchStatus::Proved);
+
+        let untouched = storage.get_batch(other.id).await.unwrap().unwrap();
+        assert_eq!(untouched.status, BatchStatus::Discovered);
+        assert_eq!(untouched.updated_at, other.updated_at);
+
+        let err = orch.process_one(BatchId::new()).await.unwrap_err();
+        assert!(err.to_string().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_data_file_without_ready_marker_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let marked = dir.path().join("marked.bin");
+        let unmarked = dir.path().join("unmarked.bin");
+        std::fs::write(&marked, b"complete").unwrap();
+        std::fs::write(dir.path().join("marked.bin.ready"), b"").unwrap();
+        std::fs::write(&unmarked, b"still writi").unwrap();
+
+        let ready = Batch::new(1, "b", marked.to_string_lossy().into(), "h1".into(), VALID_HASH.into(), "m".into());
+        let pending = Batch::new(1, "b", unmarked.to_string_lossy().into(), "h2".into(), VALID_HASH.into(), "m".into());
+        let storage = Arc::new(MockStorage::new(vec![ready.clone(), pending.clone()]));
+        let orch = Orchestrator::new(
+            storage.clone(),
+            Arc::new(MockProver { should_fail: false }),
+            Arc::new(MockDa { should_fail_submit: false, should_fail_confirm: false, confirm_result: true }),
+            Arc::new(MockBridgeReader),
+            5,
+        )
+        .with_data_file_check(DataFileCheck::ReadyMarker, Duration::ZERO);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let processed = storage.get_batch(ready.id).await.unwrap().unwrap();
+        assert_eq!(processed.status, BatchStatus::Proving);
+        let skipped = storage.get_batch(pending.id).await.unwrap().unwrap();
+        assert_eq!(skipped.status, BatchStatus::Discovered);
+        assert_eq!(skipped.updated_at, pending.updated_at);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_content_flagged_or_skipped() {
+        let mut confirmed = Batch::new(1, "b", "f".into(), "same".into(), "0x01".into(), "m".into());
+        confirmed.status = BatchStatus::Confirmed;
+        let reseeded = Batch::new(1, "b", "f".into(), "same".into(), "0x02".into(), "m".into());
+        let fresh = Batch::new(1, "b", "f".into(), "other".into(), "0x02".into(), "m".into());
+        let storage = MockStorage::new(vec![confirmed.clone()]);
+
+        assert!(check_duplicate_content("default", &storage, &reseeded, ContentDedup::Off).await.unwrap());
+        assert!(check_duplicate_content("default", &storage, &reseeded, ContentDedup::Warn).await.unwrap());
+        assert!(!check_duplicate_content("default", &storage, &reseeded, ContentDedup::Skip).await.unwrap());
+        assert!(check_duplicate_content("default", &storage, &fresh, ContentDedup::Skip).await.unwrap());
+        // The batch itself is not its own duplicate
+        assert!(check_duplicate_content("default", &storage, &confirmed, ContentDedup::Skip).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_rejected_at_max_pending() {
+        use crate::infrastructure::storage_sqlite::SqliteStorage;
+
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        let batch = |n: &str| Batch::new(1, "b", "f".into(), n.into(), VALID_HASH.into(), "m".into());
+
+        assert!(enqueue_batch("default", &storage, &batch("h1"), Some(2)).await.unwrap());
+        let mut done = batch("h2");
+        done.status = BatchStatus::Confirmed;
+        storage.save_batch(&done).await.unwrap();
+        assert!(enqueue_batch("default", &storage, &batch("h3"), Some(2)).await.unwrap());
+        assert_eq!(storage.count_pending().await.unwrap(), 2);
+
+        // Terminal batches don't count towards the cap, pending ones do
+        let rejected = batch("h4");
+        assert!(!enqueue_batch("default", &storage, &rejected, Some(2)).await.unwrap());
+        assert!(storage.get_batch(rejected.id).await.unwrap().is_none());
+
+        // Once the backlog drains new batches are accepted again
+        let mut drained = batch("h1");
+        drained.status = BatchStatus::Confirmed;
+        storage.save_batch(&drained).await.unwrap();
+        assert!(enqueue_batch("default", &storage, &rejected, Some(2)).await.unwrap());
+        assert!(enqueue_batch("default", &storage, &batch("h5"), None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_data_file_cleanup_keeps_shared_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let own = dir.path().join("own.bin").to_string_lossy().to_string();
+        let shared = dir.path().join("shared.bin").to_string_lossy().to_string();
+        std::fs::write(&own, b"own").unwrap();
+        std::fs::write(&shared, b"shared").unwrap();
+
+        let submitted = |file: &str, hash: &str| {
+            let mut b = Batch::new(1, "b", file.into(), hash.into(), VALID_HASH.into(), "m".into());
+            b.status = BatchStatus::Submitted;
+            b.tx_hash = Some("0x01".into());
+            b
+        };
+        let confirming = vec![submitted(&own, "h1"), submitted(&shared, "h2")];
+        let mut waiting = Batch::new(1, "b", shared.clone(), "h3".into(), VALID_HASH.into(), "m".into());
+        waiting.status = BatchStatus::Proving;
+
+        let storage = Arc::new(MockStorage::new(confirming.clone()));
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, Arc::new(MockBridgeReader), 5)
+            .with_data_file_cleanup(true);
+        storage.save_batch(&waiting).await.unwrap();
+        for batch in &confirming {
+            let mut batch = batch.clone();
+            orch.process_batch(&mut batch).await.unwrap();
+            assert_eq!(batch.status, BatchStatus::Confirmed);
+        }
+
+        assert!(!std::path::Path::new(&own).exists());
+        assert!(std::path::Path::new(&shared).exists());
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_batch_is_attested() {
+        struct RecordingAttestor(std::sync::Mutex<Vec<(u64, H256, H256)>>);
+        impl Attestor for RecordingAttestor {
+            fn attest(&self, batch_index: u64, data_hash: H256, new_root: H256) -> Result<String, DomainError> {
+                self.0.lock().unwrap().push((batch_index, data_hash, new_root));
+                Ok("0xsigned".into())
+            }
+        }
+
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Submitted;
+        batch.tx_hash = Some("0x01".into());
+        batch.index = 4;
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let attestor = Arc::new(RecordingAttestor(Default::default()));
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, Arc::new(MockBridgeReader), 5)
+            .with_attestor(Some(attestor.clone()));
+
+        orch.process_batch(&mut batch).await.unwrap();
+
+        let stored = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, BatchStatus::Confirmed);
+        assert_eq!(stored.attestation.as_deref(), Some("0xsigned"));
+        let root: H256 = VALID_HASH.parse().unwrap();
+        assert_eq!(*attestor.0.lock().unwrap(), vec![(4, H256::zero(), root)]);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_in_flight_submit() {
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Submitting;
+        batch.proof = Some("p".into());
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let da = Arc::new(SlowDa { delay: Duration::from_millis(300) });
+        let orch = Arc::new(Orchestrator::new(
+            storage.clone(),
+            Arc::new(MockProver { should_fail: false }),
+            da,
+            Arc::new(MockBridgeReader),
+            5,
+        ));
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let running = tokio::spawn({
+            let orch = orch.clone();
+            let stop = stop.clone();
+            async move { orch.run_until(stop).await }
+        });
+
+        // Shut down while the submit is still in flight
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        stop.store(true, Ordering::SeqCst);
+        tokio::time::timeout(Duration::from_secs(2), running).await.unwrap().unwrap().unwrap();
+
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Submitted);
+        assert_eq!(updated.tx_hash.as_deref(), Some("0xslow"));
+    }
+
+    /// Answers `get_proofs` with one proof per request, tagged by position, and counts the calls.
+    #[derive(Default)]
+    struct BatchCountingProver {
+        calls: Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl ProofProvider for BatchCountingProver {
+        async fn get_proof(&self, request: &ProofRequest) -> Result<ProofResponse, DomainError> {
+            self.get_proofs(std::slice::from_ref(request)).await.remove(0)
+        }
+
+        async fn get_proofs(&self, requests: &[ProofRequest]) -> Vec<Result<ProofResponse, DomainError>> {
+            self.calls.lock().unwrap().push(requests.len());
+            requests
+                .iter()
+                .map(|r| Ok(serde_json::from_value(serde_json::json!({"proof": format!("proof-{}", r.batch_id)})).unwrap()))
+                .collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_proofs_within_window_share_one_request() {
+        let proving = |hash: &str| {
+            let mut b = Batch::new(1, "b", "f".into(), hash.into(), VALID_HASH.into(), "m".into());
+            b.status = BatchStatus::Proving;
+            b
+        };
+        let batches = vec![proving("h1"), proving("h2")];
+        let storage = Arc::new(MockStorage::new(batches.clone()));
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let prover = Arc::new(BatchCountingProver::default());
+        let orch = Orchestrator::new(storage.clone(), prover.clone(), da, Arc::new(MockBridgeReader), 5)
+            .with_prove_batch_window(Some(Duration::from_millis(50)));
+
+        orch.process_pending_batches().await.unwrap();
+
+        assert_eq!(*prover.calls.lock().unwrap(), vec![2]);
+        for batch in &batches {
+            let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+            assert_eq!(updated.status, BatchStatus::Proved);
+            assert_eq!(updated.proof, Some(format!("proof-{}", batch.id)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transient_confirmation_error_keeps_attempts() {
+        use crate::contracts::ZKRollupBridge;
+        use crate::infrastructure::da_calldata::CalldataStrategy;
+        use crate::test_utils::MockClient;
+        use ethers::providers::Provider;
+        use ethers::types::Address;
+
+        let mock = MockClient::new();
+        mock.push_error("connection reset by peer");
+        let bridge = ZKRollupBridge::new(Address::zero(), Arc::new(Provider::new(mock)));
+        let da = Arc::new(CalldataStrategy::new(bridge, None));
+
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "calldata".into());
+        batch.status = BatchStatus::Submitted;
+        batch.tx_hash = Some(format!("{:?}", H256::zero()));
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, Arc::new(MockBridgeReader), 5);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Submitted);
+        assert_eq!(updated.attempts, 0);
+    }
+
+    #[test]
+    fn test_stuck_batch_counted_by_status() {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let now = chrono::Utc::now();
+        let mut stuck = Batch::new(1, "b", "f".into(), "h1".into(), VALID_HASH.into(), "m".into());
+        stuck.status = BatchStatus::Proving;
+        stuck.updated_at = now - chrono::Duration::hours(3);
+        let mut fresh = Batch::new(1, "b", "f".into(), "h2".into(), VALID_HASH.into(), "m".into());
+        fresh.status = BatchStatus::Proving;
+        fresh.updated_at = now - chrono::Duration::minutes(5);
+
+        let storage = Arc::new(MockStorage::new(vec![stuck, fresh]));
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let orch = Orchestrator::new(storage, Arc::new(NotReadyProver), da, Arc::new(MockBridgeReader), 5)
+            .with_clock(Arc::new(FixedClock(Mutex::new(now))))
+            .with_stuck_threshold(Some(Duration::from_secs(3600)));
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            rt.block_on(orch.process_pending_batches()).unwrap();
+        });
+
+        let stuck_counts: Vec<_> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .filter(|(key, _, _, _)| key.key().name() == "batch_stuck_total")
+            .map(|(key, _, _, value)| (key.key().labels().map(|l| l.value().to_string()).collect::<Vec<_>>(), value))
+            .collect();
+        assert_eq!(
+            stuck_counts,
+            vec![(vec!["default".to_string(), "Proving".to_string()], DebugValue::Counter(1))]
+        );
+    }
+
+    #[test]
+    fn test_durations_are_labeled_by_da_mode() {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "Calldata".into());
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let da = Arc::new(MockDa { should_fail_submit: false, should_fail_confirm: false, confirm_result: true });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, Arc::new(MockBridgeReader), 5);
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            rt.block_on(async {
+                for _ in 0..10 {
+                    orch.process_pending_batches().await.unwrap();
+                }
+            });
+        });
+        assert_eq!(storage.batches.lock().unwrap()[0].status, BatchStatus::Confirmed);
+
+        let snapshot = snapshotter.snapshot().into_vec();
+        for name in ["prove_duration_seconds", "submit_tx_duration_seconds", "batch_e2e_duration_seconds"] {
+            let (key, _, _, value) = snapshot
+                .iter()
+                .find(|(key, _, _, _)| key.key().name() == name)
+                .unwrap_or_else(|| panic!("{} not recorded", name));
+            let labels: Vec<_> = key.key().labels().map(|l| (l.key().to_string(), l.value().to_string())).collect();
+            assert!(labels.contains(&("da_mode".to_string(), "calldata".to_string())), "{}: {:?}", name, labels);
+            assert!(matches!(value, DebugValue::Histogram(samples) if samples.len() == 1), "{}", name);
+        }
+
+        let unknown = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        assert_eq!(unknown.da_mode_label(), "other");
+    }
+
+    #[test]
+    fn test_confirmation_sets_last_confirmation_gauge() {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Submitted;
+        batch.tx_hash = Some("0x1".into());
+        let storage = Arc::new(MockStorage::new(vec![batch]));
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let orch = Orchestrator::new(storage, Arc::new(MockProver { should_fail: false }), da, Arc::new(MockBridgeReader), 5);
+        assert!(orch.last_confirmation().is_none());
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            rt.block_on(orch.process_pending_batches()).unwrap();
+        });
+
+        let now = chrono::Utc::now();
+        let last = orch.last_confirmation().expect("confirmation recorded");
+        assert!((now - last).num_seconds().abs() <= 5);
+
+        let gauge = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .find(|(key, _, _, _)| key.key().name() == "last_confirmation_timestamp_seconds")
+            .map(|(_, _, _, value)| value);
+        match gauge {
+            Some(DebugValue::Gauge(value)) => {
+                assert!((value.into_inner() - now.timestamp() as f64).abs() <= 5.0)
+            }
+            other => panic!("expected gauge, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_confirmation_stores_receipt_gas() {
+        use ethers::types::TransactionReceipt;
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        struct ReceiptDa;
+        #[async_trait]
+        impl DaStrategy for ReceiptDa {
+            fn da_id(&self) -> u8 { 0 }
+            async fn compute_commitment(&self, _batch: &Batch) -> Result<H256, DomainError> {
+                Ok(H256::zero())
+            }
+            fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
+                Ok(vec![])
+            }
+            async fn submit(&self, _b: &Batch, _p: &str) -> Result<String, DomainError> {
+                Ok("0x1".into())
+            }
+            async fn check_confirmation(&self, _tx: &str) -> Result<bool, DomainError> {
+                Ok(true)
+            }
+            async fn confirmation_details(&self, _tx: &str) -> Result<Option<TransactionReceipt>, DomainError> {
+                Ok(Some(TransactionReceipt {
+                    gas_used: Some(U256::from(184_000)),
+                    effective_gas_price: Some(U256::from(12_000_000_000u64)),
+                    ..Default::default()
+                }))
+            }
+        }
+
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Submitted;
+        batch.tx_hash = Some("0x1".into());
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), Arc::new(ReceiptDa), Arc::new(MockBridgeReader), 5);
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            rt.block_on(orch.process_pending_batches()).unwrap();
+        });
+
+        let stored = storage.batches.lock().unwrap().iter().find(|b| b.id == batch.id).cloned().unwrap();
+        assert_eq!(stored.status, BatchStatus::Confirmed);
+        assert_eq!(stored.gas_used, Some(184_000));
+        assert_eq!(stored.effective_gas_price, Some(12_000_000_000));
+
+        let gas = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .find(|(key, _, _, _)| key.key().name() == "batch_gas_used")
+            .map(|(_, _, _, value)| value);
+        assert_eq!(gas, Some(DebugValue::Histogram(vec![184_000.0.into()])));
+    }
+
+    #[test]
+    fn test_terminal_transition_records_attempts() {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Submitted;
+        batch.tx_hash = Some("0x1".into());
+        batch.attempts = 3;
+        let storage = Arc::new(MockStorage::new(vec![batch]));
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let orch = Orchestrator::new(storage, Arc::new(MockProver { should_fail: false }), da, Arc::new(MockBridgeReader), 5);
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            rt.block_on(orch.process_pending_batches()).unwrap();
+        });
+
+        let recorded: Vec<_> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .filter(|(key, _, _, _)| key.key().name() == "batch_attempts_at_terminal")
+            .map(|(key, _, _, value)| (key.key().labels().map(|l| l.value().to_string()).collect::<Vec<_>>(), value))
+            .collect();
+        assert_eq!(
+            recorded,
+            vec![(
+                vec!["default".to_string(), "Confirmed".to_string()],
+                DebugValue::Histogram(vec![3.0.into()])
+            )]
+        );
+    }
+
+    #[test]
+    fn test_onchain_commitment_mismatch_alerts() {
+        use crate::contracts::ZKRollupBridge;
+        use crate::infrastructure::da_calldata::CalldataStrategy;
+        use crate::infrastructure::ethereum_adapter::RealBridgeClient;
+        use crate::test_utils::MockClient;
+        use ethers::providers::Provider;
+        use ethers::types::{Address, TransactionReceipt, U64};
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+        use std::io::Write;
+
+        let mut data = tempfile::NamedTempFile::new().unwrap();
+        data.write_all(b"batch payload").unwrap();
+
+        let node = MockClient::new();
+        node.push(TransactionReceipt {
+            status: Some(U64::from(1)),
+            block_number: Some(U64::from(100)),
+            ..Default::default()
+        });
+        node.push(U64::from(101));
+        let da = Arc::new(CalldataStrategy::new(
+            ZKRollupBridge::new(Address::zero(), Arc::new(Provider::new(node))),
+            None,
+        ));
+
+        // The bridge recorded something other than keccak256(payload)
+        let bridge_node = MockClient::new();
+        bridge_node.push(H256::repeat_byte(0x42));
+        let reader = Arc::new(RealBridgeClient::new(ZKRollupBridge::new(
+            Address::zero(),
+            Arc::new(Provider::new(bridge_node.clone())),
+        )));
+
+        let mut batch = Batch::new(1, "b", data.path().to_string_lossy().into(), "h".into(), VALID_HASH.into(), "calldata".into());
+        batch.status = BatchStatus::Submitted;
+        batch.tx_hash = Some(format!("{:?}", H256::zero()));
+        batch.index = 7;
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, reader, 5)
+            .with_onchain_commitment_check(true);
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            rt.block_on(orch.process_pending_batches()).unwrap();
+            let updated = rt.block_on(storage.get_batch(batch.id)).unwrap().unwrap();
+            assert_eq!(updated.status, BatchStatus::Confirmed);
+        });
+
+        let alerted = snapshotter.snapshot().into_vec().into_iter().any(|(key, _, _, value)| {
+            key.key().name() == "commitment_mismatch_total" && value == DebugValue::Counter(1)
+        });
+        assert!(alerted);
+
+        // The view was queried with the batch's index
+        let calls = bridge_node.requests();
+        let call_data = calls.iter().find(|(m, _)| m == "eth_call").map(|(_, p)| p.to_string()).unwrap();
+        assert!(call_data.contains(&format!("{:064x}", 7)));
+    }
+
+    #[tokio::test]
+    async fn test_calldata_public_inputs_commit_to_file_keccak() {
+        use crate::contracts::ZKRollupBridge;
+        use crate::infrastructure::da_calldata::CalldataStrategy;
+        use crate::test_utils::MockClient;
+        use ethers::providers::Provider;
+        use ethers::types::Address;
+        use ethers::utils::keccak256;
+        use std::io::Write;
+
+        // keccak256("abc") is above the field modulus, so the reduction is exercised
+        let mut data = tempfile::NamedTempFile::new().unwrap();
+        data.write_all(b"abc").unwrap();
+        let da = Arc::new(CalldataStrategy::new(
+            ZKRollupBridge::new(Address::zero(), Arc::new(Provider::new(MockClient::new()))),
+            None,
+        ));
+
+        let mut batch = Batch::new(1, "b", data.path().to_string_lossy().into(), "h".into(), VALID_HASH.into(), "calldata".into());
         batch.status = BatchStatus::Proving;
-        batch.attempts = 4; // Max is 5
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, Arc::new(MockBridgeReader), 5);
 
-        let (orch, store) = create_orchestrator(batch.clone(), true, false, false);
+        orch.process_pending_batches().await.unwrap();
+
+        let proved = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(proved.status, BatchStatus::Proved);
+        let inputs = proved.public_inputs.expect("inputs persisted");
+        let field = U256::from_big_endian(&crate::domain::public_inputs::SNARK_SCALAR_FIELD);
+        let digest = U256::from_big_endian(&keccak256(b"abc"));
+        assert!(digest >= field);
+        assert_eq!(U256::from_big_endian(&inputs[..32]), digest % field);
+    }
+
+    #[tokio::test]
+    async fn test_state_root_read_once_per_cycle() {
+        use crate::infrastructure::ethereum_adapter::CachingBridgeReader;
+        use std::sync::atomic::AtomicU32;
+
+        struct CountingReader(AtomicU32);
+        #[async_trait]
+        impl BridgeReader for CountingReader {
+            async fn state_root(&self) -> Result<H256, DomainError> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Ok(H256::zero())
+            }
+        }
+
+        let batches = (0..3)
+            .map(|i| {
+                let mut batch = Batch::new(1, "b", "f".into(), format!("h{}", i), VALID_HASH.into(), "m".into());
+                batch.status = BatchStatus::Proving;
+                batch
+            })
+            .collect::<Vec<_>>();
+        let storage = Arc::new(MockStorage::new(batches));
+        let counting = Arc::new(CountingReader(AtomicU32::new(0)));
+        let reader = Arc::new(CachingBridgeReader::new(counting.clone(), Duration::from_secs(60)));
+        let da = Arc::new(MockDa { should_fail_submit: false, should_fail_confirm: false, confirm_result: true });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, reader, 5);
 
         orch.process_pending_batches().await.unwrap();
 
-        let updated = store.get_batch(batch.id).await.unwrap().unwrap();
-        assert_eq!(updated.status, BatchStatus::Failed);
+        assert_eq!(counting.0.load(Ordering::SeqCst), 1);
+        let proved = storage.get_batches_by_status(BatchStatus::Proved).await.unwrap();
+        assert_eq!(proved.len(), 3);
     }
 
     #[tokio::test]
-    async fn test_submitting_missing_proof() {
+    async fn test_submission_refused_on_batch_index_mismatch() {
+        use crate::application::ports::MockBridgeReader as MockReader;
+
+        let batch_at = |index: u64, hash: &str| {
+            let mut batch = Batch::new(1, "b", "f".into(), hash.into(), VALID_HASH.into(), "m".into());
+            batch.status = BatchStatus::Submitting;
+            batch.proof = Some("0xproof".into());
+            batch.index = index;
+            batch
+        };
+        let ahead = batch_at(7, "h1");
+        let behind = batch_at(3, "h2");
+        let next = batch_at(5, "h3");
+        let storage = Arc::new(MockStorage::new(vec![ahead.clone(), behind.clone(), next.clone()]));
+
+        let mut reader = MockReader::new();
+        reader.expect_next_batch_index().returning(|| Ok(U256::from(5)));
+        let da = Arc::new(MockDa { should_fail_submit: false, should_fail_confirm: false, confirm_result: true });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, Arc::new(reader), 5)
+            .with_batch_index_check(true);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let status = |id| {
+            let storage = storage.clone();
+            async move { storage.get_batch(id).await.unwrap().unwrap().status }
+        };
+        assert_eq!(status(ahead.id).await, BatchStatus::Submitting);
+        assert_eq!(status(behind.id).await, BatchStatus::Failed);
+        assert_eq!(status(next.id).await, BatchStatus::Submitted);
+    }
+
+    #[tokio::test]
+    async fn test_ordered_submission_waits_for_lower_index() {
+        let submitting = |index: u64, hash: &str| {
+            let mut batch = Batch::new(1, "b", "f".into(), hash.into(), VALID_HASH.into(), "m".into());
+            batch.status = BatchStatus::Submitting;
+            batch.proof = Some("0xproof".into());
+            batch.index = index;
+            batch
+        };
+        let second = submitting(2, "h2");
+        let first = submitting(1, "h1");
+        let storage = Arc::new(MockStorage::new(vec![second.clone(), first.clone()]));
+        let da = Arc::new(MockDa { should_fail_submit: false, should_fail_confirm: false, confirm_result: true });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, Arc::new(MockBridgeReader), 5)
+            .with_ordered_submission(true);
+        let status = |id| {
+            let storage = storage.clone();
+            async move { storage.get_batch(id).await.unwrap().unwrap().status }
+        };
+
+        orch.process_pending_batches().await.unwrap();
+        assert_eq!(status(first.id).await, BatchStatus::Submitted);
+        assert_eq!(status(second.id).await, BatchStatus::Submitting);
+
+        // Still held while index 1 is submitted but not confirmed
+        orch.process_pending_batches().await.unwrap();
+        assert_eq!(status(first.id).await, BatchStatus::Confirmed);
+        assert_eq!(status(second.id).await, BatchStatus::Submitting);
+
+        orch.process_pending_batches().await.unwrap();
+        assert_eq!(status(second.id).await, BatchStatus::Submitted);
+    }
+
+    #[tokio::test]
+    async fn test_ordered_submission_held_behind_failed_lower_index() {
+        let batch = |index: u64, hash: &str, status: BatchStatus| {
+            let mut batch = Batch::new(1, "b", "f".into(), hash.into(), VALID_HASH.into(), "m".into());
+            batch.status = status;
+            batch.proof = Some("0xproof".into());
+            batch.index = index;
+            batch
+        };
+        let confirmed = batch(0, "h0", BatchStatus::Confirmed);
+        let failed = batch(1, "h1", BatchStatus::Failed);
+        let later = batch(2, "h2", BatchStatus::Submitting);
+        let storage = Arc::new(MockStorage::new(vec![confirmed, failed, later.clone()]));
+        let da = Arc::new(MockDa { should_fail_submit: false, should_fail_confirm: false, confirm_result: true });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, Arc::new(MockBridgeReader), 5)
+            .with_ordered_submission(true);
+
+        // Index 1 is not pending, but index 2 must still not skip over it
+        orch.process_pending_batches().await.unwrap();
+        assert_eq!(storage.get_batch(later.id).await.unwrap().unwrap().status, BatchStatus::Submitting);
+    }
+
+    #[tokio::test]
+    async fn test_submission_held_when_old_root_breaks_the_chain() {
+        let confirmed_root = H256::from_low_u64_be(0x11);
+        let mut confirmed = Batch::new(1, "b", "f".into(), "h1".into(), format!("{:?}", confirmed_root), "m".into());
+        confirmed.status = BatchStatus::Confirmed;
+
+        let submitting = |hash: &str| {
+            let mut batch = Batch::new(1, "b", "f".into(), hash.into(), VALID_HASH.into(), "m".into());
+            batch.status = BatchStatus::Submitting;
+            batch.proof = Some("0xproof".into());
+            batch
+        };
+        let mut stray = submitting("h2");
+        stray.expected_old_root = Some(format!("{:?}", H256::from_low_u64_be(0x22)));
+        let mut chained = submitting("h3");
+        let mut inputs = vec![0u8; 96];
+        inputs[32..64].copy_from_slice(confirmed_root.as_bytes());
+        chained.public_inputs = Some(inputs);
+
+        let storage = Arc::new(MockStorage::new(vec![confirmed, stray.clone(), chained.clone()]));
+        let da = Arc::new(MockDa { should_fail_submit: false, should_fail_confirm: false, confirm_result: true });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, Arc::new(MockBridgeReader), 5)
+            .with_root_chain_check(true);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let held = storage.get_batch(stray.id).await.unwrap().unwrap();
+        assert_eq!(held.status, BatchStatus::Submitting);
+        assert_eq!(held.attempts, 0);
+        let reason = orch.root_chain_break(&held).await.unwrap().expect("chain break reported");
+        assert!(reason.contains("does not extend the last confirmed root"), "{}", reason);
+        assert!(reason.contains(&format!("{:?}", confirmed_root)), "{}", reason);
+        assert_eq!(storage.get_batch(chained.id).await.unwrap().unwrap().status, BatchStatus::Submitted);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_proof_fails_before_submission() {
+        use crate::contracts::ZKRollupBridge;
+        use crate::infrastructure::ethereum_adapter::RealBridgeClient;
+        use crate::test_utils::MockClient;
+        use ethers::providers::Provider;
+        use ethers::types::Address;
+
+        // verifyProof returns false
+        let node = MockClient::new();
+        node.push(H256::zero());
+        let reader = Arc::new(RealBridgeClient::new(ZKRollupBridge::new(
+            Address::zero(),
+            Arc::new(Provider::new(node.clone())),
+        )));
+
         let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
-        batch.status = BatchStatus::Submitting;
-        batch.proof = None; // Should fail
+        batch.status = BatchStatus::Proved;
+        batch.proof = Some(format!("0x{}", "00".repeat(256)));
+        batch.public_inputs = Some(vec![1u8; 96]);
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let da = Arc::new(MockDa { should_fail_submit: false, should_fail_confirm: false, confirm_result: true });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, reader, 5)
+            .with_proof_precheck(true);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Failed);
+        assert!(updated.tx_hash.is_none());
+        assert!(node.requests().iter().any(|(m, _)| m == "eth_call"));
+    }
 
+    #[tokio::test]
+    async fn test_processing_gated_until_node_synced() {
+        use crate::contracts::ZKRollupBridge;
+        use crate::infrastructure::ethereum_adapter::RealBridgeClient;
+        use crate::test_utils::MockClient;
+        use ethers::providers::Provider;
+        use ethers::types::Address;
+
+        let mock = MockClient::new();
+        mock.push(serde_json::json!({
+            "startingBlock": "0x0",
+            "currentBlock": "0x10",
+            "highestBlock": "0x100"
+        }));
+        mock.push(false);
+        let bridge = ZKRollupBridge::new(Address::zero(), Arc::new(Provider::new(mock)));
+        let node = Arc::new(RealBridgeClient::new(bridge));
+
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Proved;
         let (orch, store) = create_orchestrator(batch.clone(), false, false, false);
+        let orch = orch.with_sync_check(Some(node));
 
         orch.process_pending_batches().await.unwrap();
+        assert!(!orch.is_ready());
+        assert_eq!(store.get_batch(batch.id).await.unwrap().unwrap().status, BatchStatus::Proved);
 
-        let updated = store.get_batch(batch.id).await.unwrap().unwrap();
-        assert_eq!(updated.status, BatchStatus::Failed);
+        orch.process_pending_batches().await.unwrap();
+        assert!(orch.is_ready());
+        assert_eq!(store.get_batch(batch.id).await.unwrap().unwrap().status, BatchStatus::Submitting);
+    }
+
+    struct EscalatingDa {
+        ratios: Mutex<Vec<f64>>,
+    }
+
+    #[async_trait]
+    impl DaStrategy for EscalatingDa {
+        fn da_id(&self) -> u8 { 0 }
+        async fn compute_commitment(&self, _batch: &Batch) -> Result<H256, DomainError> {
+            Ok(H256::zero())
+        }
+        fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
+            Ok(vec![])
+        }
+        async fn submit(&self, _b: &Batch, _p: &str) -> Result<String, DomainError> {
+            Ok("0xhash".into())
+        }
+        async fn check_confirmation(&self, _tx: &str) -> Result<bool, DomainError> {
+            Ok(false)
+        }
+        async fn resubmit(&self, _b: &Batch, ratio: f64, _cap: Option<U256>) -> Result<SentTx, DomainError> {
+            let mut ratios = self.ratios.lock().unwrap();
+            ratios.push(ratio);
+            Ok(SentTx { hash: format!("0xbump{}", ratios.len()), raw: None })
+        }
     }
 
     #[tokio::test]
-    async fn test_submitted_revert() {
+    async fn test_fee_escalation_steps() {
+        use crate::domain::fees::FeeStep;
+
+        let submitted_at = chrono::Utc::now();
         let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
         batch.status = BatchStatus::Submitted;
-        batch.tx_hash = Some("0x123".into());
+        batch.tx_hash = Some("0xhash".into());
+        batch.updated_at = submitted_at;
 
-        // Simulate Revert (error in check_confirmation)
-        let (orch, store) = create_orchestrator(batch.clone(), false, false, true);
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let da = Arc::new(EscalatingDa { ratios: Mutex::new(Vec::new()) });
+        let clock = Arc::new(FixedClock(Mutex::new(submitted_at + chrono::Duration::seconds(60))));
+        let escalation = FeeEscalation::new(vec![
+            FeeStep { after: Duration::from_secs(120), multiplier: 1.125 },
+            FeeStep { after: Duration::from_secs(240), multiplier: 1.3 },
+        ])
+        .unwrap();
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da.clone(), Arc::new(MockBridgeReader), 5)
+            .with_clock(clock.clone())
+            .with_fee_escalation(escalation, None);
 
+        // Before the first threshold nothing is replaced
         orch.process_pending_batches().await.unwrap();
+        assert!(da.ratios.lock().unwrap().is_empty());
 
-        let updated = store.get_batch(batch.id).await.unwrap().unwrap();
-        assert_eq!(updated.attempts, 1); // Should count as failure
+        *clock.0.lock().unwrap() = submitted_at + chrono::Duration::seconds(130);
+        orch.process_pending_batches().await.unwrap();
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.fee_bumps, 1);
+        assert_eq!(updated.tx_hash.as_deref(), Some("0xbump1"));
+
+        // Same step is not re-applied
+        orch.process_pending_batches().await.unwrap();
+        assert_eq!(da.ratios.lock().unwrap().len(), 1);
+
+        *clock.0.lock().unwrap() = submitted_at + chrono::Duration::seconds(250);
+        orch.process_pending_batches().await.unwrap();
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.fee_bumps, 2);
+        assert_eq!(updated.tx_hash.as_deref(), Some("0xbump2"));
+
+        let ratios = da.ratios.lock().unwrap().clone();
+        assert_eq!(ratios[0], 1.125);
+        // The second bump lifts the fees from the first replacement to 1.3x the original
+        assert!((ratios[0] * ratios[1] - 1.3).abs() < 1e-9);
+    }
+
+    struct RecordingProver {
+        requests: Mutex<Vec<ProofRequest>>,
+    }
+
+    #[async_trait]
+    impl ProofProvider for RecordingProver {
+        async fn get_proof(&self, request: &ProofRequest) -> Result<ProofResponse, DomainError> {
+            self.requests.lock().unwrap().push(request.clone());
+            Ok(ProofResponse {
+                proof: "p".into(),
+                proofs: Vec::new(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_proof_request_carries_deadline() {
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        batch.status = BatchStatus::Proving;
+        let now = chrono::Utc::now();
+        let prover = Arc::new(RecordingProver { requests: Mutex::new(Vec::new()) });
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let orch = Orchestrator::new(Arc::new(MockStorage::new(vec![batch])), prover.clone(), da, Arc::new(MockBridgeReader), 5)
+            .with_clock(Arc::new(FixedClock(Mutex::new(now))))
+            .with_prover_deadline(Some(Duration::from_secs(300)));
+
+        orch.process_pending_batches().await.unwrap();
+
+        let requests = prover.requests.lock().unwrap();
+        assert_eq!(requests[0].deadline, Some(now + chrono::Duration::seconds(300)));
+        assert_eq!(requests[0].public_inputs.len(), 96);
+    }
+
+    /// Accepts requests but never produces a proof. Records which requests resume an earlier
+    /// one and which start fresh after being abandoned.
+    struct StalledProver {
+        in_flight: Mutex<std::collections::HashSet<BatchId>>,
+        fresh: Mutex<Vec<BatchId>>,
+        abandoned: Mutex<Vec<BatchId>>,
+    }
+
+    #[async_trait]
+    impl ProofProvider for StalledProver {
+        async fn get_proof(&self, request: &ProofRequest) -> Result<ProofResponse, DomainError> {
+            if self.in_flight.lock().unwrap().insert(request.batch_id) {
+                self.fresh.lock().unwrap().push(request.batch_id);
+            }
+            Err(DomainError::ProofNotReady("still proving".into()))
+        }
+
+        async fn abandon(&self, batch_id: BatchId) {
+            self.in_flight.lock().unwrap().remove(&batch_id);
+            self.abandoned.lock().unwrap().push(batch_id);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stale_proving_batch_re_requests_proof() {
+        let now = chrono::Utc::now();
+        let mut stale = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        stale.status = BatchStatus::Proving;
+        stale.updated_at = now - chrono::Duration::hours(2);
+        let mut recent = Batch::new(2, "b", "f".into(), "h".into(), VALID_HASH.into(), "m".into());
+        recent.status = BatchStatus::Proving;
+        recent.updated_at = now - chrono::Duration::minutes(10);
+        let storage = Arc::new(MockStorage::new(vec![stale.clone(), recent.clone()]));
+        let prover = Arc::new(StalledProver {
+            in_flight: Mutex::new([stale.id, recent.id].into_iter().collect()),
+            fresh: Mutex::new(Vec::new()),
+            abandoned: Mutex::new(Vec::new()),
+        });
+        let da = Arc::new(MockDa {
+            should_fail_submit: false,
+            should_fail_confirm: false,
+            confirm_result: true,
+        });
+        let orch = Orchestrator::new(storage.clone(), prover.clone(), da, Arc::new(MockBridgeReader), 5)
+            .with_clock(Arc::new(FixedClock(Mutex::new(now))))
+            .with_max_proving_age(Some(Duration::from_secs(3600)));
+
+        orch.process_pending_batches().await.unwrap();
+
+        // Only the stale batch's request is dropped and sent again; the recent one keeps waiting
+        assert_eq!(*prover.abandoned.lock().unwrap(), vec![stale.id]);
+        assert_eq!(*prover.fresh.lock().unwrap(), vec![stale.id]);
+        // The stale request was abandoned: its clock restarts without burning an attempt
+        let stale = storage.get_batch(stale.id).await.unwrap().unwrap();
+        assert_eq!(stale.updated_at, now);
+        assert_eq!(stale.attempts, 0);
+        let recent_after = storage.get_batch(recent.id).await.unwrap().unwrap();
+        assert_eq!(recent_after.updated_at, recent.updated_at);
+    }
+
+    /// Reads batch data from a mount that is down.
+    struct UnreachableDataDa;
+
+    #[async_trait]
+    impl DaStrategy for UnreachableDataDa {
+        fn da_id(&self) -> u8 { 0 }
+        async fn compute_commitment(&self, _batch: &Batch) -> Result<H256, DomainError> {
+            let timed_out = std::io::Error::from(std::io::ErrorKind::TimedOut);
+            Err(crate::infrastructure::payload::payload_read_error("Failed to read batch file", timed_out))
+        }
+        fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
+            Ok(vec![])
+        }
+        async fn submit(&self, _b: &Batch, _p: &str) -> Result<String, DomainError> {
+            let refused = std::io::Error::from(std::io::ErrorKind::ConnectionRefused);
+            Err(crate::infrastructure::payload::payload_read_error("Failed to read batch file", refused))
+        }
+        async fn check_confirmation(&self, _tx: &str) -> Result<bool, DomainError> {
+            Ok(false)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_data_is_retried_without_an_attempt() {
+        let mut proving = Batch::new(1, "b", "f".into(), "h1".into(), VALID_HASH.into(), "m".into());
+        proving.status = BatchStatus::Proving;
+        let mut submitting = Batch::new(1, "b", "f".into(), "h2".into(), VALID_HASH.into(), "m".into());
+        submitting.status = BatchStatus::Submitting;
+        submitting.proof = Some("0xproof".into());
+        let storage = Arc::new(MockStorage::new(vec![proving.clone(), submitting.clone()]));
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), Arc::new(UnreachableDataDa), Arc::new(MockBridgeReader), 5)
+            .with_unavailable_data_retry(true);
+
+        for _ in 0..3 {
+            orch.process_pending_batches().await.unwrap();
+        }
+        for (id, status) in [(proving.id, BatchStatus::Proving), (submitting.id, BatchStatus::Submitting)] {
+            let batch = storage.get_batch(id).await.unwrap().unwrap();
+            assert_eq!(batch.status, status);
+            assert_eq!(batch.attempts, 0);
+        }
+
+        // Without the option the read error counts as a failed attempt
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), Arc::new(UnreachableDataDa), Arc::new(MockBridgeReader), 5);
+        orch.process_pending_batches().await.unwrap();
+        assert_eq!(storage.get_batch(submitting.id).await.unwrap().unwrap().attempts, 1);
+    }
+
+    /// Never confirms; records the batches it was asked to cancel.
+    struct CancellingDa {
+        cancelled: Mutex<Vec<BatchId>>,
+    }
+
+    #[async_trait]
+    impl DaStrategy for CancellingDa {
+        fn da_id(&self) -> u8 { 0 }
+        async fn compute_commitment(&self, _batch: &Batch) -> Result<H256, DomainError> {
+            Ok(H256::zero())
+        }
+        fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
+            Ok(vec![])
+        }
+        async fn submit(&self, _b: &Batch, _p: &str) -> Result<String, DomainError> {
+            Ok("0xhash".into())
+        }
+        async fn check_confirmation(&self, _tx: &str) -> Result<bool, DomainError> {
+            Ok(false)
+        }
+        async fn cancel(&self, batch: &Batch) -> Result<String, DomainError> {
+            self.cancelled.lock().unwrap().push(batch.id);
+            Ok("0xcancel".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expired_submitted_batch_is_cancelled() {
+        let now = chrono::Utc::now();
+        let mut expired = Batch::new(1, "b", "f".into(), "h1".into(), VALID_HASH.into(), "m".into());
+        expired.status = BatchStatus::Submitted;
+        expired.tx_hash = Some("0xold".into());
+        expired.updated_at = now - chrono::Duration::hours(2);
+        let mut recent = Batch::new(1, "b", "f".into(), "h2".into(), VALID_HASH.into(), "m".into());
+        recent.status = BatchStatus::Submitted;
+        recent.tx_hash = Some("0xnew".into());
+        recent.updated_at = now - chrono::Duration::minutes(10);
+        let storage = Arc::new(MockStorage::new(vec![expired.clone(), recent.clone()]));
+        let da = Arc::new(CancellingDa { cancelled: Mutex::new(Vec::new()) });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da.clone(), Arc::new(MockBridgeReader), 5)
+            .with_clock(Arc::new(FixedClock(Mutex::new(now))))
+            .with_max_submitted_age(Some(Duration::from_secs(3600)));
+
+        orch.process_pending_batches().await.unwrap();
+
+        assert_eq!(*da.cancelled.lock().unwrap(), vec![expired.id]);
+        assert_eq!(storage.get_batch(expired.id).await.unwrap().unwrap().status, BatchStatus::Failed);
+        assert_eq!(storage.get_batch(recent.id).await.unwrap().unwrap().status, BatchStatus::Submitted);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_versioned_hash_fails_fast() {
+        use crate::contracts::ZKRollupBridge;
+        use crate::infrastructure::da_blob::BlobStrategy;
+        use crate::test_utils::MockClient;
+        use ethers::providers::Provider;
+        use ethers::types::Address;
+
+        let bridge = ZKRollupBridge::new(Address::zero(), Arc::new(Provider::new(MockClient::new())));
+        let da = Arc::new(BlobStrategy::new(bridge, H256::zero(), 0, false, None));
+
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "blob".into());
+        batch.status = BatchStatus::Proving;
+        batch.blob_versioned_hash = Some("0xzz".into());
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, Arc::new(MockBridgeReader), 5);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Failed);
+        assert_eq!(updated.attempts, 0);
+    }
+
+    /// Publishes off L1 and only commits to the bridge once `available` is set.
+    struct OffChainDa {
+        available: std::sync::atomic::AtomicBool,
+        fail_availability: bool,
+    }
+
+    #[async_trait]
+    impl DaStrategy for OffChainDa {
+        fn da_id(&self) -> u8 { 2 }
+        async fn compute_commitment(&self, _batch: &Batch) -> Result<H256, DomainError> {
+            Ok(H256::zero())
+        }
+        fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
+            Ok(vec![])
+        }
+        async fn submit(&self, _b: &Batch, _p: &str) -> Result<String, DomainError> {
+            Ok("42:0xblob".into())
+        }
+        async fn check_confirmation(&self, tx: &str) -> Result<bool, DomainError> {
+            Ok(tx == "0xcommit")
+        }
+        fn publishes_off_chain(&self) -> bool {
+            true
+        }
+        async fn commit_published(&self, batch: &Batch, _proof: &str) -> Result<Option<SentTx>, DomainError> {
+            assert_eq!(batch.da_reference.as_deref(), Some("42:0xblob"));
+            if self.fail_availability {
+                return Err(DomainError::DaReverted("blob dropped".into()));
+            }
+            if !self.available.load(std::sync::atomic::Ordering::SeqCst) {
+                return Ok(None);
+            }
+            Ok(Some(SentTx { hash: "0xcommit".into(), raw: None }))
+        }
+    }
+
+    fn published_batch() -> Batch {
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), VALID_HASH.into(), "celestia".into());
+        batch.status = BatchStatus::Submitted;
+        batch.proof = Some("0xproof".into());
+        batch.da_reference = Some("42:0xblob".into());
+        batch
+    }
+
+    #[tokio::test]
+    async fn test_off_chain_batch_committed_once_available() {
+        let batch = published_batch();
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let da = Arc::new(OffChainDa { available: false.into(), fail_availability: false });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da.clone(), Arc::new(MockBridgeReader), 5);
+
+        orch.process_pending_batches().await.unwrap();
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Submitted);
+        assert_eq!(updated.tx_hash, None);
+
+        da.available.store(true, std::sync::atomic::Ordering::SeqCst);
+        orch.process_pending_batches().await.unwrap();
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Submitted);
+        assert_eq!(updated.tx_hash.as_deref(), Some("0xcommit"));
+        assert_eq!(updated.da_reference.as_deref(), Some("42:0xblob"));
+
+        orch.process_pending_batches().await.unwrap();
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Confirmed);
+    }
+
+    #[tokio::test]
+    async fn test_off_chain_publish_keeps_reference_not_tx_hash() {
+        let mut batch = published_batch();
+        batch.status = BatchStatus::Submitting;
+        batch.da_reference = None;
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let da = Arc::new(OffChainDa { available: false.into(), fail_availability: false });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, Arc::new(MockBridgeReader), 5);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.status, BatchStatus::Submitted);
+        assert_eq!(updated.da_reference.as_deref(), Some("42:0xblob"));
+        assert_eq!(updated.tx_hash, None);
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_off_chain_data_drops_reference() {
+        let batch = published_batch();
+        let storage = Arc::new(MockStorage::new(vec![batch.clone()]));
+        let da = Arc::new(OffChainDa { available: false.into(), fail_availability: true });
+        let orch = Orchestrator::new(storage.clone(), Arc::new(MockProver { should_fail: false }), da, Arc::new(MockBridgeReader), 5);
+
+        orch.process_pending_batches().await.unwrap();
+
+        let updated = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(updated.da_reference, None);
+        assert_eq!(updated.tx_hash, None);
+        assert_eq!(updated.attempts, 1);
     }
 }
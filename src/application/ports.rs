@@ -1,37 +1,210 @@
 use crate::domain::{
-    batch::{Batch, BatchId},
+    batch::{Batch, BatchId, BatchStatus, ProofSystem},
     errors::DomainError,
 };
 use async_trait::async_trait;
-use ethers::types::H256;
+use chrono::{DateTime, Utc};
+use ethers::types::{TransactionReceipt, H256, U256};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait BridgeReader: Send + Sync {
     /// Fetches the current state root from the L1 ZKRollupBridge contract.
     async fn state_root(&self) -> Result<H256, DomainError>;
+
+    /// DA commitment the bridge recorded for the batch at `index`.
+    async fn committed_data_hash(&self, _index: u64) -> Result<H256, DomainError> {
+        Err(DomainError::Internal("Reading committed data hashes is not supported".into()))
+    }
+
+    /// Index the bridge will accept for the next committed batch.
+    async fn next_batch_index(&self) -> Result<U256, DomainError> {
+        Err(DomainError::Internal("Reading the next batch index is not supported".into()))
+    }
+
+    /// Runs the bridge's `verifyProof` view on `proof_hex` and the batch's public inputs.
+    async fn verify_proof(
+        &self,
+        _system: ProofSystem,
+        _proof_hex: &str,
+        _public_inputs: &[u8],
+    ) -> Result<bool, DomainError> {
+        Err(DomainError::Internal("Verifying proofs on chain is not supported".into()))
+    }
+}
+
+/// Reports whether the connected node is still syncing, in which case its reads are unreliable.
+#[async_trait]
+pub trait NodeStatus: Send + Sync {
+    async fn is_syncing(&self) -> Result<bool, DomainError>;
+    /// The node's latest block number; doubles as a connectivity check.
+    async fn block_number(&self) -> Result<u64, DomainError>;
 }
 
+/// Source of the current time, so time-based policies can be driven in tests.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+#[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait Storage: Send + Sync {
     async fn save_batch(&self, batch: &Batch) -> Result<(), DomainError>;
     async fn get_batch(&self, id: BatchId) -> Result<Option<Batch>, DomainError>;
     async fn get_pending_batches(&self) -> Result<Vec<Batch>, DomainError>;
+    async fn get_batches_by_status(&self, status: BatchStatus) -> Result<Vec<Batch>, DomainError>;
+    /// Number of batches not yet `Confirmed` or `Failed`.
+    async fn count_pending(&self) -> Result<u64, DomainError>;
+    /// Number of batches not yet `Confirmed` or `Failed` that read `data_file`.
+    async fn count_pending_with_data_file(&self, data_file: &str) -> Result<u64, DomainError>;
+    /// Batches created from a payload with digest `data_hash`, in any state.
+    async fn get_batches_by_data_hash(&self, data_hash: &str) -> Result<Vec<Batch>, DomainError>;
+    /// Runs a trivial query to check the database is reachable.
+    async fn ping(&self) -> Result<(), DomainError>;
+    /// Number of batches in each state, in [`BatchStatus::ALL`] order; empty states count 0.
+    async fn count_by_status(&self) -> Result<Vec<(BatchStatus, u64)>, DomainError>;
+    /// `new_root` of the most recently confirmed batch, if any batch has confirmed.
+    async fn last_confirmed_root(&self) -> Result<Option<String>, DomainError>;
+    /// Highest `index` of any stored batch, in any state.
+    async fn max_batch_index(&self) -> Result<Option<u64>, DomainError>;
+    /// Highest `index` among confirmed batches, if any batch has confirmed.
+    async fn last_confirmed_index(&self) -> Result<Option<u64>, DomainError>;
+    /// Stores the circuit-breaker state of the prover named `key`, replacing any earlier one.
+    async fn save_circuit_state(&self, key: &str, snapshot: &CircuitSnapshot) -> Result<(), DomainError>;
+    /// The last state stored under `key`, if any.
+    async fn load_circuit_state(&self, key: &str) -> Result<Option<CircuitSnapshot>, DomainError>;
+    /// Appends an entry to the batch's audit trail. Events are never updated or removed.
+    async fn record_event(
+        &self,
+        batch_id: BatchId,
+        from: BatchStatus,
+        to: BatchStatus,
+        detail: Option<String>,
+    ) -> Result<(), DomainError>;
+    /// The batch's audit trail, oldest first.
+    async fn get_events(&self, batch_id: BatchId) -> Result<Vec<BatchEvent>, DomainError>;
+}
+
+/// One entry of a batch's audit trail. A failed attempt that leaves the batch where it was has
+/// `from == to` and the error as `detail`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchEvent {
+    pub batch_id: BatchId,
+    pub from: BatchStatus,
+    pub to: BatchStatus,
+    pub detail: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl fmt::Display for CircuitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for CircuitState {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Closed" => Ok(CircuitState::Closed),
+            "Open" => Ok(CircuitState::Open),
+            "HalfOpen" => Ok(CircuitState::HalfOpen),
+            other => Err(DomainError::Storage(format!("Unknown circuit state: {}", other))),
+        }
+    }
+}
+
+/// A circuit breaker as persisted between restarts. `last_failure` is wall-clock time so it
+/// survives the process.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CircuitSnapshot {
+    pub state: CircuitState,
+    pub failure_count: u32,
+    pub last_failure: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProofResponse {
+    #[serde(default)]
     pub proof: String, // Serialized proof
+    // Aggregated provers return one proof per sub-batch, in root-chain order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub proofs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProofRequest {
+    pub batch_id: BatchId,
+    pub public_inputs: Vec<u8>,
+    // Provers may abandon work still running after this instant
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<DateTime<Utc>>,
+    // Circuit the proof must come from
+    pub proof_system: ProofSystem,
+}
+
+impl ProofRequest {
+    pub fn new(batch_id: BatchId, public_inputs: Vec<u8>) -> Self {
+        Self {
+            batch_id,
+            public_inputs,
+            deadline: None,
+            proof_system: ProofSystem::default(),
+        }
+    }
+}
+
+/// A dependency the pipeline cannot make progress without, consulted for readiness.
+#[async_trait]
+pub trait ReadinessProbe: Send + Sync {
+    /// `Err` names the problem while the dependency is unusable.
+    async fn check(&self) -> Result<(), String>;
 }
 
 #[async_trait]
 pub trait ProofProvider: Send + Sync {
-    async fn get_proof(
-        &self,
-        batch_id: &BatchId,
-        public_inputs: &[u8],
-    ) -> Result<ProofResponse, DomainError>;
+    async fn get_proof(&self, request: &ProofRequest) -> Result<ProofResponse, DomainError>;
+
+    /// Proves several requests in one go, returning one result per request in the same order.
+    /// Provers without a batch endpoint send them one by one.
+    async fn get_proofs(&self, requests: &[ProofRequest]) -> Vec<Result<ProofResponse, DomainError>> {
+        let mut results = Vec::with_capacity(requests.len());
+        for request in requests {
+            results.push(self.get_proof(request).await);
+        }
+        results
+    }
+
+    /// Forgets any in-flight request for `batch_id` (e.g. an accepted job that is being polled),
+    /// so the next `get_proof` for it starts a new one instead of resuming it.
+    async fn abandon(&self, _batch_id: BatchId) {}
+}
+
+/// A broadcast transaction. `raw` is the signed transaction (hex RLP) when the strategy has
+/// one, so a later replacement does not depend on the node knowing the original.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentTx {
+    pub hash: String,
+    pub raw: Option<String>,
 }
 
 #[async_trait]
@@ -42,7 +215,7 @@ pub trait DaStrategy: Send + Sync {
     /// Computes the commitment to be used as a Public Input.
     /// Calldata: keccak256(batch.data)
     /// Blob: batch.blob_versioned_hash
-    fn compute_commitment(&self, batch: &Batch) -> Result<H256, DomainError>;
+    async fn compute_commitment(&self, batch: &Batch) -> Result<H256, DomainError>;
 
     /// Encodes the 'daMeta' bytes for the transaction.
     /// Calldata: empty bytes
@@ -52,6 +225,84 @@ pub trait DaStrategy: Send + Sync {
     /// Broadcasts the transaction and returns the hash immediately.
     async fn submit(&self, batch: &Batch, proof: &str) -> Result<String, DomainError>;
 
+    /// Like [`DaStrategy::submit`], also returning the signed transaction if there is one.
+    async fn submit_tx(&self, batch: &Batch, proof: &str) -> Result<SentTx, DomainError> {
+        Ok(SentTx { hash: self.submit(batch, proof).await?, raw: None })
+    }
+
     /// Checks if a transaction has been confirmed.
     async fn check_confirmation(&self, tx_hash: &str) -> Result<bool, DomainError>;
+
+    /// Checks if `batch`'s transaction has been confirmed. Strategies that also verify the
+    /// batch's data once it lands override this; the default only checks `batch.tx_hash`.
+    async fn check_batch_confirmation(&self, batch: &Batch) -> Result<bool, DomainError> {
+        let tx_hash = batch
+            .tx_hash
+            .as_deref()
+            .ok_or_else(|| DomainError::Internal("Batch has no tx hash".into()))?;
+        self.check_confirmation(tx_hash).await
+    }
+
+    /// Receipt of a confirmed transaction, for cost accounting. `None` when the strategy does
+    /// not land a transaction of its own.
+    async fn confirmation_details(&self, _tx_hash: &str) -> Result<Option<TransactionReceipt>, DomainError> {
+        Ok(None)
+    }
+
+    /// Replaces the batch's in-flight transaction (same nonce) with fees scaled by `fee_ratio`,
+    /// capped at `max_fee_per_gas`. Returns the replacement.
+    async fn resubmit(
+        &self,
+        _batch: &Batch,
+        _fee_ratio: f64,
+        _max_fee_per_gas: Option<U256>,
+    ) -> Result<SentTx, DomainError> {
+        Err(DomainError::Da("Fee replacement not supported".into()))
+    }
+
+    /// Frees the nonce of the batch's pending transaction with a 0-value self-transfer at a
+    /// higher fee, returning the cancel transaction's hash.
+    async fn cancel(&self, _batch: &Batch) -> Result<String, DomainError> {
+        Err(DomainError::Da("Cancelling not supported".into()))
+    }
+
+    /// Whether `submit` only publishes the data to a DA layer off L1 and returns a reference to
+    /// it (kept in `batch.da_reference`), the bridge commit following from
+    /// [`DaStrategy::commit_published`].
+    fn publishes_off_chain(&self) -> bool {
+        false
+    }
+
+    /// Commits a batch published off L1 to the bridge once its DA layer has the data available,
+    /// returning the commit transaction. `None` while the data is not available yet.
+    async fn commit_published(&self, _batch: &Batch, _proof: &str) -> Result<Option<SentTx>, DomainError> {
+        Err(DomainError::Da("Strategy does not publish off chain".into()))
+    }
+}
+
+/// Sends the bridge commit for DA layers that keep batch data off L1, with only `daMeta`
+/// pointing at the data on chain. The commit is then checked, replaced and cancelled through the
+/// [`DaStrategy`] side.
+#[async_trait]
+pub trait BridgeCommitter: DaStrategy {
+    /// Sends `commitBatch` for `batch` under `da_id`, with no batch data and `da_meta`.
+    async fn commit_pointer(
+        &self,
+        batch: &Batch,
+        proof: &str,
+        da_id: u8,
+        da_meta: Vec<u8>,
+    ) -> Result<SentTx, DomainError>;
+}
+
+/// Decides whether a submitted batch has landed. Defaults to the DA strategy's receipt check.
+#[async_trait]
+pub trait ConfirmationSource: Send + Sync {
+    async fn is_confirmed(&self, batch: &Batch) -> Result<bool, DomainError>;
+}
+
+/// Signs a statement that a batch confirmed, for consumers checking batches off chain.
+pub trait Attestor: Send + Sync {
+    /// Hex signature over the batch's `index`, its DA commitment and its new root.
+    fn attest(&self, batch_index: u64, data_hash: H256, new_root: H256) -> Result<String, DomainError>;
 }
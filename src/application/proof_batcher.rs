@@ -0,0 +1,75 @@
+use crate::application::ports::{ProofProvider, ProofRequest, ProofResponse};
+use crate::domain::{batch::BatchId, errors::DomainError};
+use async_trait::async_trait;
+use metrics::histogram;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+type Waiter = oneshot::Sender<Result<ProofResponse, DomainError>>;
+
+/// Collects proof requests that arrive within `window` of the first one and sends them to the
+/// inner prover as a single [`ProofProvider::get_proofs`] call.
+pub struct ProofBatcher {
+    inner: Arc<dyn ProofProvider>,
+    window: Duration,
+    pending: Mutex<Vec<(ProofRequest, Waiter)>>,
+    rollup: String,
+}
+
+impl ProofBatcher {
+    pub fn new(inner: Arc<dyn ProofProvider>, window: Duration) -> Self {
+        Self {
+            inner,
+            window,
+            pending: Mutex::new(Vec::new()),
+            rollup: "default".to_string(),
+        }
+    }
+
+    /// Names the rollup the batcher proves for, for the `rollup` label of its metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
+    }
+}
+
+#[async_trait]
+impl ProofProvider for ProofBatcher {
+    async fn get_proof(&self, request: &ProofRequest) -> Result<ProofResponse, DomainError> {
+        let (tx, rx) = oneshot::channel();
+        let opens_window = {
+            let mut pending = self.pending.lock().unwrap();
+            pending.push((request.clone(), tx));
+            pending.len() == 1
+        };
+
+        // The first request of a window waits it out and sends everything collected meanwhile
+        if opens_window {
+            tokio::time::sleep(self.window).await;
+            let (requests, waiters): (Vec<_>, Vec<_>) =
+                std::mem::take(&mut *self.pending.lock().unwrap())
+                    .into_iter()
+                    .unzip();
+            histogram!("prove_batch_size", "rollup" => self.rollup.clone()).record(requests.len() as f64);
+            let results = self.inner.get_proofs(&requests).await;
+            for (waiter, result) in waiters.into_iter().zip(results) {
+                let _ = waiter.send(result);
+            }
+        }
+
+        rx.await
+            .map_err(|_| DomainError::Prover("No result for batched proof request".into()))?
+    }
+
+    async fn get_proofs(
+        &self,
+        requests: &[ProofRequest],
+    ) -> Vec<Result<ProofResponse, DomainError>> {
+        self.inner.get_proofs(requests).await
+    }
+
+    async fn abandon(&self, batch_id: BatchId) {
+        self.inner.abandon(batch_id).await
+    }
+}
@@ -1,27 +1,79 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use dotenvy::dotenv;
 use std::path::PathBuf;
-use submitter_rs::{infrastructure::observability, startup};
-use tracing::info;
+use submitter_rs::{domain::batch::BatchId, infrastructure::observability, startup};
+use uuid::Uuid;
+use tracing::{error, info};
 
 #[derive(Parser, Debug)]
 struct Args {
     #[arg(long)]
     config: PathBuf,
+    /// Run the pipeline but log transactions instead of broadcasting them
+    #[arg(long)]
+    dry_run: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Requeue a Failed batch with a fresh attempt budget
+    Retry {
+        #[arg(long)]
+        batch_id: Uuid,
+    },
+    /// Run the pipeline (the default). With --only, advance just that batch by one step and exit.
+    /// With --once, run a single processing pass and exit nonzero if any batch failed
+    Run {
+        #[arg(long)]
+        only: Option<Uuid>,
+        #[arg(long, conflicts_with = "only")]
+        once: bool,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv().ok();
 
+    let args = Args::parse();
+    let options = startup::RunOptions { dry_run: args.dry_run };
+    match args.command {
+        Some(Command::Retry { batch_id }) => {
+            observability::init_tracing();
+            return startup::retry(args.config, BatchId(batch_id)).await;
+        }
+        Some(Command::Run { only: Some(batch_id), .. }) => {
+            observability::init_tracing();
+            let batch = startup::step_batch(args.config, BatchId(batch_id), options).await?;
+            info!("Batch {} is now {} (attempts {})", batch.id, batch.status, batch.attempts);
+            return Ok(());
+        }
+        Some(Command::Run { once: true, .. }) => {
+            observability::init_tracing();
+            let summary = startup::run_once(args.config, options).await?;
+            info!(
+                "Processed {} batches: {} confirmed, {} failed, {} pending",
+                summary.processed, summary.confirmed, summary.failed, summary.pending
+            );
+            std::process::exit(summary.exit_code());
+        }
+        Some(Command::Run { .. }) | None => {}
+    }
+
     // 1. Observability
     observability::init_tracing();
     let metrics_handle = observability::init_metrics().expect("failed to install Prometheus recorder");
-    tokio::spawn(observability::start_metrics_server(metrics_handle, 9000));
+    match observability::bind_metrics_listener(9000, observability::metrics_bind_timeout()).await {
+        Ok(listener) => {
+            tokio::spawn(observability::serve_metrics(listener, metrics_handle));
+        }
+        Err(e) if observability::metrics_required() => return Err(e),
+        Err(e) => error!("Metrics disabled: {:#}", e),
+    }
 
-    let args = Args::parse();
-    
     let shutdown = async {
         #[cfg(unix)]
         {
@@ -38,5 +90,5 @@ async fn main() -> Result<()> {
         }
     };
 
-    startup::run(args.config, shutdown).await
+    startup::run_with_options(args.config, options, shutdown).await
 }
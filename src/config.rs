@@ -1,5 +1,17 @@
+use crate::application::orchestrator::{ContentDedup, DataFileCheck};
+use crate::domain::batch::ProofSystem;
+use crate::domain::blackout::BlackoutWindow;
+use crate::domain::errors::DomainError;
+use crate::domain::fees::{FeeEscalation, FeeStep};
+use crate::domain::public_inputs::{RootPacking, RootPadding};
+use crate::infrastructure::commitment::CommitmentScheme;
+use crate::infrastructure::da_blob::{BLOB_DATA_BYTES, BLOB_GAS_PER_BLOB};
+use crate::infrastructure::da_celestia::Namespace;
+use crate::infrastructure::payload::PayloadCodec;
+use crate::infrastructure::prover_http::InputSchema;
 use anyhow::{Context, Result};
-use ethers::types::Address;
+use ethers::types::transaction::eip712::EIP712Domain;
+use ethers::types::{Address, Bytes, U256};
 use serde::Deserialize;
 use std::{fs, path::PathBuf};
 use tracing::warn;
@@ -9,7 +21,8 @@ pub struct Config {
     pub network: Network,
     pub contracts: Contracts,
     pub da: DaConfig,
-    pub batch: BatchConfig,
+    // Batch seeded at startup; omit it when batches only arrive through the API
+    pub batch: Option<BatchConfig>,
     // Optional prover URL
     #[allow(dead_code)]
     pub prover: Option<ProverConfig>,
@@ -31,6 +44,173 @@ pub struct Config {
     // Optional simulation config (for local testing/mocking)
     #[allow(dead_code)]
     pub simulation: Option<SimulationConfig>,
+    // Optional orchestrator tuning
+    pub orchestrator: Option<OrchestratorConfig>,
+    // Optional confirmation source (defaults to node receipts)
+    pub confirmation: Option<ConfirmationConfig>,
+    // Optional public input layout (defaults to full 256-bit roots)
+    pub public_inputs: Option<PublicInputsConfig>,
+    // Optional EIP-712 domain for batch attestations
+    pub attestation: Option<AttestationConfig>,
+    // Optional HTTP API for enqueueing batches at runtime
+    pub api: Option<ApiConfig>,
+    // Optional directory polled for batch descriptors
+    pub inbox: Option<InboxConfig>,
+}
+
+impl Config {
+    /// Proof system of batches created from this config: `batch.proof_system`, else
+    /// `prover.proof_system`, else Groth16.
+    pub fn default_proof_system(&self) -> ProofSystem {
+        self.batch
+            .as_ref()
+            .and_then(|b| b.proof_system)
+            .or_else(|| self.prover.as_ref().and_then(|p| p.proof_system))
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ApiConfig {
+    // Port for the batch API (default 9001)
+    pub port: Option<u16>,
+    // Answer /readyz with 503 instead of 200 while degraded
+    #[serde(default)]
+    pub fail_readiness_when_degraded: bool,
+    // Requests served at once; the rest get 503 (unset = unlimited)
+    pub max_concurrent_requests: Option<usize>,
+    // Connections in a separate read pool for the API (unset = share the orchestrator's pool)
+    pub read_pool_size: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InboxConfig {
+    // Directory polled for *.json batch descriptors
+    pub dir: String,
+    // Milliseconds between scans (default 1000)
+    pub poll_interval_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AttestationConfig {
+    pub name: String,
+    pub version: String,
+    // Left out of the domain when unset
+    pub chain_id: Option<u64>,
+    // Left out of the domain when unset
+    pub verifying_contract: Option<String>,
+}
+
+impl AttestationConfig {
+    pub fn domain(&self) -> Result<EIP712Domain, DomainError> {
+        let verifying_contract = self
+            .verifying_contract
+            .as_deref()
+            .map(|addr| addr.parse::<Address>())
+            .transpose()
+            .map_err(|e| DomainError::Config(format!("Invalid attestation.verifying_contract: {}", e)))?;
+        Ok(EIP712Domain {
+            name: Some(self.name.clone()),
+            version: Some(self.version.clone()),
+            chain_id: self.chain_id.map(U256::from),
+            verifying_contract,
+            salt: None,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct PublicInputsConfig {
+    // Significant bits of a state root (multiple of 8, at most 256)
+    pub root_bits: Option<u16>,
+    #[serde(default)]
+    pub root_padding: RootPaddingKind,
+    // Reject empty or wrong-length public inputs before calling the prover
+    #[serde(default)]
+    pub strict: bool,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RootPaddingKind {
+    #[default]
+    Left,
+    Right,
+}
+
+impl PublicInputsConfig {
+    pub fn root_packing(&self) -> Result<RootPacking, DomainError> {
+        let padding = match self.root_padding {
+            RootPaddingKind::Left => RootPadding::Left,
+            RootPaddingKind::Right => RootPadding::Right,
+        };
+        RootPacking::new(self.root_bits.unwrap_or(256), padding)
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ConfirmationConfig {
+    #[serde(default)]
+    pub source: ConfirmationSourceKind,
+    // Base URL of the indexer (indexer source)
+    pub indexer_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfirmationSourceKind {
+    #[default]
+    Node,
+    Indexer,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct OrchestratorConfig {
+    // Cap on confirmation checks in flight per cycle; extra batches wait for the next cycle
+    pub max_concurrent_confirmations: Option<usize>,
+    // Map multi-proof prover responses onto the batch's sub-batches
+    #[serde(default)]
+    pub aggregated_proofs: bool,
+    // Daily UTC windows ("HH:MM-HH:MM") during which Proved batches are not submitted
+    pub submit_blackout: Option<Vec<String>>,
+    // Re-request a proof once a batch has been Proving this long without one
+    pub max_proving_age_secs: Option<u64>,
+    // Cancel the pending tx and fail a batch once it has been Submitted this long
+    pub max_submitted_age_secs: Option<u64>,
+    // Retry batches whose data file is briefly unreachable without using an attempt
+    #[serde(default)]
+    pub retry_unavailable_data: bool,
+    // Warn about batches that have not changed state for this long
+    pub stuck_threshold_secs: Option<u64>,
+    // Emit a `batch.transition` tracing event for every status change
+    #[serde(default)]
+    pub transition_events: bool,
+    // Compare the bridge's recorded commitment with ours once a batch confirms
+    #[serde(default)]
+    pub verify_onchain_commitment: bool,
+    // Run the bridge's verifyProof view on each proof before submitting it
+    #[serde(default)]
+    pub precheck_proofs: bool,
+    // Compare each batch's index with the bridge's nextBatchIndex() before submitting it
+    #[serde(default)]
+    pub check_batch_index: bool,
+    // Submit batches in index order, each waiting until every lower index confirmed
+    #[serde(default)]
+    pub ordered_submission: bool,
+    // Hold batches whose old root is not the new_root of the last confirmed batch
+    #[serde(default)]
+    pub check_root_chain: bool,
+    // Fail a batch without submitting it when its data file has disappeared
+    #[serde(default)]
+    pub fail_missing_data_files: bool,
+    // Reject new batches while this many are not yet Confirmed or Failed
+    pub max_pending_batches: Option<u64>,
+    // Extra state_root reads while a batch has no failed attempts (default 3)
+    pub initial_state_root_retries: Option<u32>,
+    // Delay between those reads (default 1000)
+    pub initial_state_root_retry_delay_ms: Option<u64>,
+    // A failed batch waits base * 2^attempts (minus jitter) before its next try (default 1000, 0 = off)
+    pub retry_backoff_base_ms: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -45,6 +225,21 @@ pub struct ResilienceConfig {
 pub struct Network {
     pub rpc_url: String,
     pub chain_id: u64,
+    // Pause batch processing while the node reports eth_syncing
+    #[serde(default)]
+    pub require_synced: bool,
+    // Retries of a throttled (HTTP 429) RPC call before the error is returned; 0 disables
+    pub rate_limit_retries: Option<u32>,
+    // Wait before the first retry of a throttled RPC call, doubled for each further retry
+    pub rate_limit_backoff_ms: Option<u64>,
+    // Seconds before an RPC request to the node times out (default 30)
+    pub rpc_timeout_secs: Option<u64>,
+    // Reuse a bridge state root read for this many milliseconds (default 1000; 0 disables)
+    pub state_root_cache_ms: Option<u64>,
+    // Fixed EIP-1559 tip for calldata and blob transactions instead of the node's estimate
+    pub max_priority_fee_gwei: Option<u64>,
+    // Scales the estimated EIP-1559 fees of calldata and blob transactions
+    pub fee_multiplier: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -58,6 +253,119 @@ pub struct DaConfig {
     pub blob_binding: BlobBinding,
     pub blob_index: Option<u8>,
     pub archiver_url: Option<String>,
+    // Gas buffer applied to the estimated gas limit of blob transactions only
+    pub blob_gas_limit_multiplier: Option<f64>,
+    // Must match the EIP-4844 protocol constant when set
+    pub blob_gas_per_blob: Option<u64>,
+    // Chain limit on blobs per transaction (defaults to the mainnet value)
+    pub max_blobs_per_tx: Option<usize>,
+    // Treat "already known" broadcast errors (and "nonce too low" for a tx the node has) as a successful send
+    #[serde(default)]
+    pub treat_known_tx_as_sent: bool,
+    // How often the nonce manager re-checks its nonce against the node (unset = never)
+    pub nonce_reconcile_interval_secs: Option<u64>,
+    // Encode and log transactions but never broadcast them
+    #[serde(default)]
+    pub dry_run: bool,
+    // Where commit transactions are broadcast: the node's public mempool, a private relay, or an
+    // ERC-4337 bundler as sponsored UserOperations
+    #[serde(default)]
+    pub submission_endpoint: SubmissionEndpoint,
+    // eth_sendPrivateTransaction relay used when submission_endpoint is private
+    pub private_relay_url: Option<String>,
+    // Bundler, EntryPoint and paymaster used when submission_endpoint is sponsored
+    pub sponsor: Option<SponsorConfig>,
+    // Blocks required on top of the inclusion block before a batch is confirmed (default 1)
+    pub confirmations: Option<u64>,
+    // Archivers polled for the blob data before a blob batch is marked confirmed
+    pub retrieval_archivers: Option<Vec<String>>,
+    // Minimum number of archivers that must serve the data (0 disables the check)
+    pub retrieval_quorum: Option<usize>,
+    // Sign archiver uploads with the submitter key (X-Signature header)
+    #[serde(default)]
+    pub sign_archiver_uploads: bool,
+    // Local copies of blob data kept until the archiver accepts them
+    pub local_blob_dir: Option<String>,
+    // Seconds between re-upload attempts for local copies
+    pub blob_reupload_interval_secs: Option<u64>,
+    // celestia-node endpoint and namespace (celestia mode)
+    pub celestia: Option<CelestiaConfig>,
+    // EigenDA disperser endpoint (eigenda mode)
+    pub eigenda: Option<EigenDaConfig>,
+    // How the calldata commitment is derived from the batch payload
+    #[serde(default)]
+    pub commitment: CommitmentKind,
+    // Chunk size in bytes for the merkle_keccak commitment
+    pub commitment_chunk_size: Option<usize>,
+    // Compress calldata payloads before posting them (unset = raw bytes)
+    pub compression: Option<PayloadCodec>,
+    // Hold submissions while the latest L1 base fee is above this many gwei
+    pub max_base_fee_gwei: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionEndpoint {
+    #[default]
+    Public,
+    Private,
+    Sponsored,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct SponsorConfig {
+    // ERC-4337 bundler JSON-RPC URL
+    pub bundler_url: String,
+    // EntryPoint contract the bundler serves
+    pub entry_point: String,
+    // Smart account that executes the commits (unset = the submitter address, for EIP-7702)
+    pub account: Option<String>,
+    // Hex paymasterAndData attached to every UserOperation (unset = account pays its own gas)
+    pub paymaster_and_data: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitmentKind {
+    #[default]
+    Keccak,
+    MerkleKeccak,
+}
+
+impl ProverConfig {
+    /// `urls` if given, else `url`, without empty entries.
+    pub fn endpoints(&self) -> Vec<String> {
+        let urls = if self.urls.is_empty() { std::slice::from_ref(&self.url) } else { &self.urls[..] };
+        urls.iter().filter(|u| !u.is_empty()).cloned().collect()
+    }
+}
+
+impl DaConfig {
+    pub fn commitment_scheme(&self) -> Result<CommitmentScheme, DomainError> {
+        match (self.commitment, self.commitment_chunk_size) {
+            (CommitmentKind::Keccak, _) => Ok(CommitmentScheme::Keccak),
+            (CommitmentKind::MerkleKeccak, Some(chunk_size)) if chunk_size > 0 => {
+                Ok(CommitmentScheme::MerkleKeccak { chunk_size })
+            }
+            (CommitmentKind::MerkleKeccak, _) => Err(DomainError::Config(
+                "da.commitment merkle_keccak needs a positive da.commitment_chunk_size".into(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct CelestiaConfig {
+    // celestia-node JSON-RPC URL
+    pub node_url: String,
+    // Hex namespace: a 10-byte version 0 ID or the full 29 bytes
+    pub namespace: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct EigenDaConfig {
+    // Disperser gRPC URL, e.g. https://disperser-holesky.eigenda.xyz:443
+    pub disperser_url: String,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Clone, Copy)]
@@ -65,6 +373,8 @@ pub struct DaConfig {
 pub enum DaMode {
     Calldata,
     Blob,
+    Celestia,
+    EigenDa,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Clone, Copy)]
@@ -79,12 +389,83 @@ pub struct BatchConfig {
     pub data_file: String,
     pub new_root: String,
     pub blob_versioned_hash: Option<String>,
+    // Bridge root the batch extends; proving waits until the bridge reports it
+    pub expected_old_root: Option<String>,
+    // Bridge batch index of the seeded batch (default: one past the highest stored; auto_split chunks take consecutive indices)
+    pub index: Option<u64>,
+    // Circuit the seeded batch is proved with; stored on the batch so older batches keep theirs
+    pub proof_system: Option<ProofSystem>,
+    // Split oversized payloads into sequential chunk batches at seeding time
+    #[serde(default)]
+    pub auto_split: bool,
+    // Overrides the per-mode payload limit used by auto_split
+    pub max_chunk_bytes: Option<usize>,
+    // Roots reached after each chunk except the last (which uses new_root)
+    pub intermediate_roots: Option<Vec<String>>,
+    // Delete a batch's data file once it confirms, unless a pending batch still uses it
+    #[serde(default)]
+    pub cleanup_data_after_confirm: bool,
+    // How to tell a data file is completely written before using it (default none)
+    #[serde(default)]
+    pub data_file_check: DataFileCheck,
+    // Delay between the two size reads of data_file_check stable_size (default 500)
+    pub data_file_settle_ms: Option<u64>,
+    // Seeding a payload another batch already submitted: off, warn or skip (default off)
+    #[serde(default)]
+    pub content_dedup: ContentDedup,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ProverConfig {
-    #[allow(dead_code)]
+    // Single prover endpoint; shorthand for a one-element `urls`
+    #[serde(default)]
     pub url: String,
+    // HTTP prover endpoints tried in order until one answers (overrides `url`)
+    #[serde(default)]
+    pub urls: Vec<String>,
+    // Where proofs come from; defaults to the HTTP prover at `url`
+    #[serde(default)]
+    pub mode: ProverMode,
+    // Bucket holding externally computed proofs (object_store mode)
+    pub bucket: Option<String>,
+    // Time budget per proof; sent to the prover as an absolute deadline
+    pub deadline_secs: Option<u64>,
+    // Address whose signature (X-Proof-Signature) every prover response must carry
+    pub response_signer: Option<String>,
+    // Batch proof requests made within this window into one /prove_batch call
+    pub prove_batch_window_ms: Option<u64>,
+    // Proof system of new batches unless batch.proof_system says otherwise (default groth16)
+    pub proof_system: Option<ProofSystem>,
+    // Seconds the prover circuit breaker stays open before a probe request (default 30)
+    pub circuit_open_secs: Option<u64>,
+    // Gzip HTTP prover request bodies larger than this many bytes
+    pub gzip_request_above_bytes: Option<usize>,
+    // JSON shape of public_inputs expected by the prover vendor (default bytes)
+    #[serde(default)]
+    pub input_schema: InputSchema,
+    // GET {url}/health once at startup so the first proof request finds an open connection
+    #[serde(default)]
+    pub warm_up: bool,
+    // Prover is asynchronous: POST /prove returns a job_id polled at GET /jobs/{id}
+    #[serde(default)]
+    pub async_jobs: bool,
+    // Milliseconds between job polls (default 2000)
+    pub job_poll_interval_ms: Option<u64>,
+    // Give up on a job after this many seconds (default 600)
+    pub job_timeout_secs: Option<u64>,
+    // Seconds a single HTTP prover request may take before it is retried (default 60)
+    pub timeout_secs: Option<u64>,
+    // Keep the circuit breaker state in the database so an open breaker survives restarts
+    #[serde(default)]
+    pub persist_circuit_state: bool,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ProverMode {
+    #[default]
+    Http,
+    ObjectStore,
 }
 
 #[derive(Debug, Deserialize)]
@@ -93,6 +474,43 @@ pub struct FeeConfig {
     pub policy: FeePolicy,
     #[allow(dead_code)]
     pub max_blob_fee_gwei: Option<u64>,
+    // Replacement schedule for transactions stuck in Submitted
+    pub escalation: Option<Vec<FeeEscalationStepConfig>>,
+    // Upper bound on maxFeePerGas for replacements
+    pub max_fee_per_gas_gwei: Option<u64>,
+    // Replace a stuck transaction with 12.5% higher fees every this many seconds
+    pub replacement_timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeeEscalationStepConfig {
+    // Seconds unconfirmed before this step applies
+    pub after_secs: u64,
+    // Fees relative to the original submission (e.g. 1.125)
+    pub multiplier: f64,
+}
+
+impl FeeConfig {
+    pub fn fee_escalation(&self) -> Result<FeeEscalation, DomainError> {
+        if let Some(timeout) = self.replacement_timeout_secs {
+            if self.escalation.is_some() {
+                return Err(DomainError::Config(
+                    "Set either fees.escalation or fees.replacement_timeout_secs, not both".into(),
+                ));
+            }
+            return FeeEscalation::every(std::time::Duration::from_secs(timeout));
+        }
+        let steps = self
+            .escalation
+            .iter()
+            .flatten()
+            .map(|s| FeeStep {
+                after: std::time::Duration::from_secs(s.after_secs),
+                multiplier: s.multiplier,
+            })
+            .collect();
+        FeeEscalation::new(steps)
+    }
 }
 
 #[derive(Debug, Deserialize, PartialEq)]
@@ -149,6 +567,98 @@ pub fn load_config(path: PathBuf) -> Result<Config> {
     Ok(cfg)
 }
 
+/// One rollup served by this process.
+#[derive(Debug)]
+pub struct RollupInstance {
+    pub name: String,
+    // Database for this rollup's batches; `None` falls back to `DATABASE_URL`
+    pub database_url: Option<String>,
+    pub config: Config,
+}
+
+/// Loads every rollup the config describes.
+///
+/// A config with a `rollups:` list yields one instance per entry: each entry needs a unique
+/// `name`, may set `database_url`, and any other top-level section it sets (`contracts`, `da`,
+/// `batch`, ...) replaces the shared one at the top level. A config without `rollups:` is the
+/// single-rollup form and yields one instance named `default`.
+pub fn load_rollups(path: PathBuf) -> Result<Vec<RollupInstance>> {
+    let raw = fs::read_to_string(&path).context("read config yaml")?;
+    let mut root: serde_yaml::Mapping = serde_yaml::from_str(&raw).context("parse yaml")?;
+
+    let Some(entries) = root.remove("rollups") else {
+        let config: Config = serde_yaml::from_value(root.into()).context("parse yaml")?;
+        validate_config(&config)?;
+        return Ok(vec![RollupInstance {
+            name: "default".to_string(),
+            database_url: None,
+            config,
+        }]);
+    };
+    let serde_yaml::Value::Sequence(entries) = entries else {
+        anyhow::bail!("rollups must be a list");
+    };
+    if entries.is_empty() {
+        anyhow::bail!("rollups must not be empty");
+    }
+
+    let mut instances: Vec<RollupInstance> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let serde_yaml::Value::Mapping(mut entry) = entry else {
+            anyhow::bail!("each rollups entry must be a mapping");
+        };
+        let name = match entry.remove("name") {
+            Some(serde_yaml::Value::String(name)) => name,
+            _ => anyhow::bail!("each rollups entry needs a name"),
+        };
+        if instances.iter().any(|i| i.name == name) {
+            anyhow::bail!("duplicate rollup name {}", name);
+        }
+        let database_url = match entry.remove("database_url") {
+            Some(serde_yaml::Value::String(url)) => Some(url),
+            None => None,
+            Some(_) => anyhow::bail!("rollup {}: database_url must be a string", name),
+        };
+
+        let mut merged = root.clone();
+        merged.extend(entry);
+        let config: Config = serde_yaml::from_value(merged.into())
+            .with_context(|| format!("parse rollup {}", name))?;
+        validate_config(&config).with_context(|| format!("rollup {}", name))?;
+        instances.push(RollupInstance { name, database_url, config });
+    }
+
+    // Orchestrators sharing a database would pick up each other's batches
+    let mut databases: Vec<(String, &str)> = Vec::with_capacity(instances.len());
+    for instance in &instances {
+        let url = instance.resolved_database_url();
+        if url.contains(":memory:") {
+            continue;
+        }
+        if let Some((_, other)) = databases.iter().find(|(seen, _)| *seen == url) {
+            anyhow::bail!(
+                "rollups {} and {} both use database {}; give each rollup its own database_url",
+                other,
+                instance.name,
+                url
+            );
+        }
+        databases.push((url, &instance.name));
+    }
+    Ok(instances)
+}
+
+impl RollupInstance {
+    /// The database the rollup's batches live in: `database_url`, else `DATABASE_URL`, else
+    /// `sqlite:submitter.db`. In-memory SQLite URLs name a new database for every pool.
+    pub fn resolved_database_url(&self) -> String {
+        self.database_url
+            .clone()
+            .or_else(|| std::env::var("DATABASE_URL").ok())
+            .unwrap_or_else(|| "sqlite:submitter.db".to_string())
+    }
+}
+
 fn validate_config(cfg: &Config) -> Result<()> {
     // Validate addresses
     cfg.contracts
@@ -158,7 +668,7 @@ fn validate_config(cfg: &Config) -> Result<()> {
 
     // Validate specific requirements based on mode
     if cfg.da.mode == DaMode::Blob {
-        if cfg.batch.blob_versioned_hash.is_none() {
+        if cfg.batch.as_ref().and_then(|b| b.blob_versioned_hash.as_ref()).is_none() {
             anyhow::bail!("blob mode needs batch.blob_versioned_hash in yaml");
         }
         if cfg.da.archiver_url.is_none() {
@@ -166,6 +676,159 @@ fn validate_config(cfg: &Config) -> Result<()> {
         }
     }
 
+    if cfg.da.submission_endpoint == SubmissionEndpoint::Private {
+        if cfg.da.mode != DaMode::Calldata {
+            anyhow::bail!("da.submission_endpoint private is only supported in calldata mode");
+        }
+        if cfg.da.private_relay_url.is_none() {
+            anyhow::bail!("da.submission_endpoint private needs da.private_relay_url");
+        }
+    }
+
+    if cfg.da.compression.is_some() {
+        if cfg.da.mode != DaMode::Calldata {
+            anyhow::bail!("da.compression is only supported in calldata mode");
+        }
+        if cfg.aggregator.as_ref().is_some_and(|a| a.compression.is_some()) {
+            anyhow::bail!("da.compression and aggregator.compression cannot both be set");
+        }
+    }
+
+    if cfg.da.submission_endpoint == SubmissionEndpoint::Sponsored {
+        if cfg.da.mode != DaMode::Calldata {
+            anyhow::bail!("da.submission_endpoint sponsored is only supported in calldata mode");
+        }
+        let sponsor = cfg.da.sponsor.as_ref().context("da.submission_endpoint sponsored needs da.sponsor")?;
+        sponsor.entry_point.parse::<Address>().context("da.sponsor.entry_point is not an address")?;
+        if let Some(account) = &sponsor.account {
+            account.parse::<Address>().context("da.sponsor.account is not an address")?;
+        }
+        if let Some(data) = &sponsor.paymaster_and_data {
+            data.parse::<Bytes>().context("da.sponsor.paymaster_and_data is not hex")?;
+        }
+    }
+
+    if let Some(multiplier) = cfg.da.blob_gas_limit_multiplier {
+        if multiplier.is_nan() || multiplier < 1.0 {
+            anyhow::bail!("da.blob_gas_limit_multiplier must be >= 1.0 (got {})", multiplier);
+        }
+    }
+    if let Some(per_blob) = cfg.da.blob_gas_per_blob {
+        if per_blob != BLOB_GAS_PER_BLOB {
+            anyhow::bail!(
+                "da.blob_gas_per_blob is {} but EIP-4844 defines {}",
+                per_blob,
+                BLOB_GAS_PER_BLOB
+            );
+        }
+    }
+
+    if cfg.da.mode == DaMode::Celestia {
+        let celestia = cfg.da.celestia.as_ref().context("celestia mode needs da.celestia")?;
+        celestia.namespace.parse::<Namespace>()?;
+    }
+    if cfg.da.max_base_fee_gwei.is_some() && !matches!(cfg.da.mode, DaMode::Calldata | DaMode::Blob) {
+        anyhow::bail!("da.max_base_fee_gwei only applies to calldata and blob mode");
+    }
+    if let Some(multiplier) = cfg.network.fee_multiplier {
+        if !multiplier.is_finite() || multiplier <= 0.0 {
+            anyhow::bail!("network.fee_multiplier must be positive (got {})", multiplier);
+        }
+    }
+    if cfg.da.mode == DaMode::EigenDa && cfg.da.eigenda.is_none() {
+        anyhow::bail!("eigenda mode needs da.eigenda");
+    }
+
+    cfg.da.commitment_scheme()?;
+
+    if cfg.da.max_blobs_per_tx == Some(0) {
+        anyhow::bail!("da.max_blobs_per_tx must be at least 1");
+    }
+    if cfg.batch.as_ref().and_then(|b| b.max_chunk_bytes) == Some(0) {
+        anyhow::bail!("batch.max_chunk_bytes must be at least 1");
+    }
+    if let (DaMode::Blob, Some(chunk), Some(max_blobs)) =
+        (cfg.da.mode, cfg.batch.as_ref().and_then(|b| b.max_chunk_bytes), cfg.da.max_blobs_per_tx)
+    {
+        if chunk > max_blobs * BLOB_DATA_BYTES {
+            anyhow::bail!(
+                "batch.max_chunk_bytes {} exceeds what {} blobs can carry ({} bytes)",
+                chunk,
+                max_blobs,
+                max_blobs * BLOB_DATA_BYTES
+            );
+        }
+    }
+
+    if cfg.da.confirmations == Some(0) {
+        anyhow::bail!("da.confirmations must be at least 1");
+    }
+
+    if let Some(api) = &cfg.api {
+        if api.max_concurrent_requests == Some(0) {
+            anyhow::bail!("api.max_concurrent_requests must be at least 1");
+        }
+        if api.read_pool_size == Some(0) {
+            anyhow::bail!("api.read_pool_size must be at least 1");
+        }
+    }
+
+    if cfg.da.local_blob_dir.is_some() && cfg.da.archiver_url.is_none() {
+        anyhow::bail!("da.local_blob_dir needs da.archiver_url to re-upload to");
+    }
+
+    if let Some(quorum) = cfg.da.retrieval_quorum {
+        let archivers = cfg.da.retrieval_archivers.as_ref().map_or(0, |a| a.len());
+        if quorum > archivers {
+            anyhow::bail!(
+                "da.retrieval_quorum is {} but only {} retrieval_archivers are configured",
+                quorum,
+                archivers
+            );
+        }
+    }
+
+    if cfg.orchestrator.as_ref().and_then(|o| o.max_concurrent_confirmations) == Some(0) {
+        anyhow::bail!("orchestrator.max_concurrent_confirmations must be at least 1");
+    }
+    if let Some(windows) = cfg.orchestrator.as_ref().and_then(|o| o.submit_blackout.as_ref()) {
+        for w in windows {
+            w.parse::<BlackoutWindow>()?;
+        }
+    }
+
+    if let Some(confirmation) = &cfg.confirmation {
+        if confirmation.source == ConfirmationSourceKind::Indexer && confirmation.indexer_url.is_none() {
+            anyhow::bail!("confirmation.source indexer needs confirmation.indexer_url");
+        }
+    }
+
+    if let Some(fees) = &cfg.fees {
+        fees.fee_escalation()?;
+    }
+
+    if let Some(public_inputs) = &cfg.public_inputs {
+        public_inputs.root_packing()?;
+    }
+
+    if let Some(attestation) = &cfg.attestation {
+        attestation.domain()?;
+    }
+
+    if let Some(prover) = &cfg.prover {
+        if prover.endpoints().is_empty() {
+            anyhow::bail!("prover needs url or urls");
+        }
+        if prover.mode == ProverMode::ObjectStore && prover.bucket.is_none() {
+            anyhow::bail!("prover.mode object_store needs prover.bucket");
+        }
+        if let Some(signer) = &prover.response_signer {
+            signer
+                .parse::<Address>()
+                .context("Invalid prover.response_signer address")?;
+        }
+    }
+
     Ok(())
 }
 
@@ -193,6 +856,98 @@ batch:
         assert!(validate_config(&cfg).is_ok());
     }
 
+    #[test]
+    fn test_zero_max_chunk_bytes_rejected() {
+        let yaml = r#"
+network:
+  rpc_url: "http://localhost:8545"
+  chain_id: 123
+contracts:
+  bridge: "0x0000000000000000000000000000000000000001"
+da:
+  mode: "calldata"
+  blob_binding: "mock"
+batch:
+  data_file: "data.txt"
+  new_root: "0x00"
+  max_chunk_bytes: 0
+"#;
+        let mut cfg: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(validate_config(&cfg).is_err());
+
+        cfg.batch.as_mut().unwrap().max_chunk_bytes = Some(1);
+        assert!(validate_config(&cfg).is_ok());
+    }
+
+    #[test]
+    fn test_zero_max_concurrent_confirmations_rejected() {
+        let yaml = r#"
+network:
+  rpc_url: "http://localhost:8545"
+  chain_id: 123
+contracts:
+  bridge: "0x0000000000000000000000000000000000000001"
+da:
+  mode: "calldata"
+  blob_binding: "mock"
+orchestrator:
+  max_concurrent_confirmations: 0
+"#;
+        let mut cfg: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(validate_config(&cfg).is_err());
+
+        cfg.orchestrator.as_mut().unwrap().max_concurrent_confirmations = Some(2);
+        assert!(validate_config(&cfg).is_ok());
+    }
+
+    #[test]
+    fn test_fee_escalation_below_replacement_bump_rejected() {
+        let yaml = r#"
+network:
+  rpc_url: "http://localhost:8545"
+  chain_id: 123
+contracts:
+  bridge: "0x0000000000000000000000000000000000000001"
+da:
+  mode: "calldata"
+  blob_binding: "mock"
+fees:
+  policy: "aggressive"
+  escalation:
+    - after_secs: 120
+      multiplier: 1.2
+    - after_secs: 240
+      multiplier: 1.25
+"#;
+        let mut cfg: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(validate_config(&cfg).is_err());
+
+        cfg.fees.as_mut().unwrap().escalation.as_mut().unwrap()[1].multiplier = 1.35;
+        assert!(validate_config(&cfg).is_ok());
+    }
+
+    #[test]
+    fn test_config_without_batch_section() {
+        let yaml = r#"
+network:
+  rpc_url: "http://localhost:8545"
+  chain_id: 123
+contracts:
+  bridge: "0x0000000000000000000000000000000000000001"
+da:
+  mode: "calldata"
+  blob_binding: "mock"
+"#;
+        let cfg: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(cfg.batch.is_none());
+        assert!(validate_config(&cfg).is_ok());
+
+        // Blob mode still needs the versioned hash it binds to
+        let blob = yaml.replace("mode: \"calldata\"", "mode: \"blob\"");
+        let cfg: Config = serde_yaml::from_str(&blob).unwrap();
+        assert!(validate_config(&cfg).is_err());
+    }
+
     #[test]
     fn test_full_config_v2() {
         let yaml = r#"
@@ -232,4 +987,219 @@ simulation:
         assert!(cfg.simulation.is_some());
         assert_eq!(cfg.simulation.unwrap().mock_proving_time_ms, Some(200));
     }
+
+    #[test]
+    fn test_blob_gas_settings_validation() {
+        let yaml = r#"
+network:
+  rpc_url: "http://localhost:8545"
+  chain_id: 123
+contracts:
+  bridge: "0x0000000000000000000000000000000000000001"
+da:
+  mode: "blob"
+  blob_binding: "opcode"
+  blob_gas_limit_multiplier: 1.25
+  blob_gas_per_blob: 131072
+batch:
+  data_file: "data.txt"
+  new_root: "0x00"
+  blob_versioned_hash: "0x1234"
+"#;
+        let mut cfg: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(cfg.da.blob_gas_limit_multiplier, Some(1.25));
+        assert!(validate_config(&cfg).is_ok());
+
+        cfg.da.blob_gas_per_blob = Some(1000);
+        assert!(validate_config(&cfg).is_err());
+
+        cfg.da.blob_gas_per_blob = None;
+        cfg.da.blob_gas_limit_multiplier = Some(0.5);
+        assert!(validate_config(&cfg).is_err());
+
+        cfg.da.blob_gas_limit_multiplier = None;
+        cfg.da.max_blobs_per_tx = Some(2);
+        cfg.batch.as_mut().unwrap().max_chunk_bytes = Some(3 * BLOB_DATA_BYTES);
+        assert!(validate_config(&cfg).is_err());
+        cfg.da.max_blobs_per_tx = Some(0);
+        cfg.batch.as_mut().unwrap().max_chunk_bytes = None;
+        assert!(validate_config(&cfg).is_err());
+    }
+
+    #[test]
+    fn test_private_submission_validation() {
+        let yaml = r#"
+network:
+  rpc_url: "http://localhost:8545"
+  chain_id: 123
+contracts:
+  bridge: "0x0000000000000000000000000000000000000001"
+da:
+  mode: "calldata"
+  blob_binding: "opcode"
+  submission_endpoint: "private"
+batch:
+  data_file: "data.txt"
+  new_root: "0x00"
+"#;
+        let mut cfg: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(cfg.da.submission_endpoint, SubmissionEndpoint::Private);
+        assert!(validate_config(&cfg).is_err());
+
+        cfg.da.private_relay_url = Some("https://relay.example".into());
+        assert!(validate_config(&cfg).is_ok());
+
+        cfg.da.mode = DaMode::Celestia;
+        assert!(validate_config(&cfg).is_err());
+    }
+
+    #[test]
+    fn test_sponsored_submission_validation() {
+        let yaml = r#"
+network:
+  rpc_url: "http://localhost:8545"
+  chain_id: 123
+contracts:
+  bridge: "0x0000000000000000000000000000000000000001"
+da:
+  mode: "calldata"
+  blob_binding: "opcode"
+  submission_endpoint: "sponsored"
+batch:
+  data_file: "data.txt"
+  new_root: "0x00"
+"#;
+        let mut cfg: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(cfg.da.submission_endpoint, SubmissionEndpoint::Sponsored);
+        assert!(validate_config(&cfg).is_err());
+
+        cfg.da.sponsor = Some(SponsorConfig {
+            bundler_url: "https://bundler.example".into(),
+            entry_point: "0x5FF137D4b0FDCD49DcA30c7CF57E578a026d2789".into(),
+            account: None,
+            paymaster_and_data: Some("0xzz".into()),
+        });
+        assert!(validate_config(&cfg).is_err());
+
+        cfg.da.sponsor.as_mut().unwrap().paymaster_and_data = Some("0x1234".into());
+        assert!(validate_config(&cfg).is_ok());
+    }
+
+    #[test]
+    fn test_commitment_scheme() {
+        let yaml = r#"
+network:
+  rpc_url: "http://localhost:8545"
+  chain_id: 123
+contracts:
+  bridge: "0x0000000000000000000000000000000000000001"
+da:
+  mode: "calldata"
+  blob_binding: "mock"
+  commitment: "merkle_keccak"
+  commitment_chunk_size: 1024
+batch:
+  data_file: "data.txt"
+  new_root: "0x00"
+"#;
+        let mut cfg: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            cfg.da.commitment_scheme().unwrap(),
+            CommitmentScheme::MerkleKeccak { chunk_size: 1024 }
+        );
+        assert!(validate_config(&cfg).is_ok());
+
+        cfg.da.commitment_chunk_size = None;
+        assert!(validate_config(&cfg).is_err());
+        cfg.da.commitment_chunk_size = Some(0);
+        assert!(validate_config(&cfg).is_err());
+
+        cfg.da.commitment = CommitmentKind::Keccak;
+        assert_eq!(cfg.da.commitment_scheme().unwrap(), CommitmentScheme::Keccak);
+    }
+
+    #[test]
+    fn test_attestation_domain() {
+        let yaml = r#"
+network:
+  rpc_url: "http://localhost:8545"
+  chain_id: 123
+contracts:
+  bridge: "0x0000000000000000000000000000000000000001"
+da:
+  mode: "calldata"
+  blob_binding: "mock"
+batch:
+  data_file: "data.txt"
+  new_root: "0x00"
+attestation:
+  name: "RollupX"
+  version: "2"
+  chain_id: 123
+  verifying_contract: "0x0000000000000000000000000000000000000002"
+"#;
+        let mut cfg: Config = serde_yaml::from_str(yaml).unwrap();
+        let domain = cfg.attestation.as_ref().unwrap().domain().unwrap();
+        assert_eq!(domain.name.as_deref(), Some("RollupX"));
+        assert_eq!(domain.version.as_deref(), Some("2"));
+        assert_eq!(domain.chain_id, Some(U256::from(123)));
+        assert_eq!(domain.verifying_contract, Some(Address::from_low_u64_be(2)));
+        assert!(validate_config(&cfg).is_ok());
+
+        cfg.attestation.as_mut().unwrap().verifying_contract = Some("0xnope".into());
+        assert!(validate_config(&cfg).is_err());
+    }
+
+    #[test]
+    fn test_rollups_override_shared_sections() {
+        use std::io::Write;
+
+        let yaml = r#"
+network:
+  rpc_url: "http://localhost:8545"
+  chain_id: 123
+da:
+  mode: "calldata"
+  blob_binding: "mock"
+batch:
+  data_file: "shared.txt"
+  new_root: "0x00"
+rollups:
+  - name: "alpha"
+    database_url: "sqlite:alpha.db"
+    contracts:
+      bridge: "0x0000000000000000000000000000000000000001"
+  - name: "beta"
+    contracts:
+      bridge: "0x0000000000000000000000000000000000000002"
+    batch:
+      data_file: "beta.txt"
+      new_root: "0x01"
+"#;
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "{}", yaml).unwrap();
+        let rollups = load_rollups(file.path().to_path_buf()).unwrap();
+
+        assert_eq!(rollups.len(), 2);
+        assert_eq!(rollups[0].name, "alpha");
+        assert_eq!(rollups[0].database_url.as_deref(), Some("sqlite:alpha.db"));
+        assert_eq!(rollups[0].config.batch.as_ref().unwrap().data_file, "shared.txt");
+        assert_eq!(rollups[1].name, "beta");
+        assert_eq!(rollups[1].database_url, None);
+        assert_eq!(rollups[1].config.contracts.bridge, "0x0000000000000000000000000000000000000002");
+        assert_eq!(rollups[1].config.batch.as_ref().unwrap().data_file, "beta.txt");
+        assert_eq!(rollups[1].config.network.chain_id, 123);
+
+        let duplicate = yaml.replace("name: \"beta\"", "name: \"alpha\"");
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "{}", duplicate).unwrap();
+        let err = load_rollups(file.path().to_path_buf()).unwrap_err();
+        assert!(err.to_string().contains("duplicate rollup name alpha"));
+
+        let shared = yaml.replace("  - name: \"beta\"\n", "  - name: \"beta\"\n    database_url: \"sqlite:alpha.db\"\n");
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "{}", shared).unwrap();
+        let err = load_rollups(file.path().to_path_buf()).unwrap_err();
+        assert!(err.to_string().contains("rollups alpha and beta both use database sqlite:alpha.db"), "{}", err);
+    }
 }
@@ -1,6 +1,10 @@
 #![cfg(not(tarpaulin_include))]
 
+use crate::domain::batch::ProofSystem;
+use ethers::contract::ContractCall;
 use ethers::prelude::abigen;
+use ethers::providers::Middleware;
+use ethers::types::{Bytes, U256};
 
 abigen!(
     ZKRollupBridge,
@@ -55,6 +59,112 @@ abigen!(
     "stateMutability": "nonpayable",
     "type": "function"
   },
+  {
+    "inputs": [
+      {
+        "internalType": "uint8",
+        "name": "daId",
+        "type": "uint8"
+      },
+      {
+        "internalType": "bytes",
+        "name": "batchData",
+        "type": "bytes"
+      },
+      {
+        "internalType": "bytes",
+        "name": "daMeta",
+        "type": "bytes"
+      },
+      {
+        "internalType": "bytes32",
+        "name": "newRoot",
+        "type": "bytes32"
+      },
+      {
+        "internalType": "uint256[24]",
+        "name": "proof",
+        "type": "uint256[24]"
+      }
+    ],
+    "name": "commitBatchPlonk",
+    "outputs": [],
+    "stateMutability": "nonpayable",
+    "type": "function"
+  },
+  {
+      "inputs": [
+        {
+          "internalType": "uint256",
+          "name": "index",
+          "type": "uint256"
+        }
+      ],
+      "name": "committedDataHash",
+      "outputs": [
+        {
+          "internalType": "bytes32",
+          "name": "",
+          "type": "bytes32"
+        }
+      ],
+      "stateMutability": "view",
+      "type": "function"
+  },
+  {
+      "inputs": [
+        {
+          "components": [
+            {
+              "internalType": "uint256[2]",
+              "name": "a",
+              "type": "uint256[2]"
+            },
+            {
+              "internalType": "uint256[2][2]",
+              "name": "b",
+              "type": "uint256[2][2]"
+            },
+            {
+              "internalType": "uint256[2]",
+              "name": "c",
+              "type": "uint256[2]"
+            }
+          ],
+          "internalType": "struct Groth16Proof",
+          "name": "proof",
+          "type": "tuple"
+        },
+        {
+          "internalType": "uint256[]",
+          "name": "publicInputs",
+          "type": "uint256[]"
+        }
+      ],
+      "name": "verifyProof",
+      "outputs": [
+        {
+          "internalType": "bool",
+          "name": "",
+          "type": "bool"
+        }
+      ],
+      "stateMutability": "view",
+      "type": "function"
+  },
+  {
+      "inputs": [],
+      "name": "nextBatchIndex",
+      "outputs": [
+        {
+          "internalType": "uint256",
+          "name": "",
+          "type": "uint256"
+        }
+      ],
+      "stateMutability": "view",
+      "type": "function"
+  },
   {
       "inputs": [],
       "name": "stateRoot",
@@ -71,6 +181,7 @@ abigen!(
 ]"#,
 );
 
+#[allow(clippy::needless_range_loop)]
 pub fn parse_groth16_proof(hex_proof: &str) -> Result<Groth16Proof, String> {
     let hex_proof = hex_proof.trim_start_matches("0x");
     let bytes = ethers::utils::hex::decode(hex_proof).map_err(|e| format!("Invalid hex: {}", e))?;
@@ -106,3 +217,101 @@ pub fn parse_groth16_proof(hex_proof: &str) -> Result<Groth16Proof, String> {
 
     Ok(Groth16Proof { a, b, c })
 }
+
+/// Number of words in a PLONK proof as the bridge's verifier takes it.
+pub const PLONK_PROOF_WORDS: usize = 24;
+
+/// A decoded proof, ready to be passed to the bridge entry point for its proof system.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Proof {
+    Groth16(Groth16Proof),
+    Plonk([U256; PLONK_PROOF_WORDS]),
+}
+
+/// Decodes a PLONK proof: a flat `uint256[24]`, 768 bytes in total.
+pub fn parse_plonk_proof(hex_proof: &str) -> Result<[U256; PLONK_PROOF_WORDS], String> {
+    let hex_proof = hex_proof.trim_start_matches("0x");
+    let bytes = ethers::utils::hex::decode(hex_proof).map_err(|e| format!("Invalid hex: {}", e))?;
+
+    if bytes.len() != PLONK_PROOF_WORDS * 32 {
+        return Err(format!(
+            "Invalid proof length: expected {} bytes, got {}",
+            PLONK_PROOF_WORDS * 32,
+            bytes.len()
+        ));
+    }
+
+    let mut words = [U256::zero(); PLONK_PROOF_WORDS];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks(32)) {
+        *word = U256::from_big_endian(chunk);
+    }
+    Ok(words)
+}
+
+/// Decodes a hex proof with the parser for `system`.
+pub fn parse_proof(system: ProofSystem, hex_proof: &str) -> Result<Proof, String> {
+    match system {
+        ProofSystem::Groth16 => parse_groth16_proof(hex_proof).map(Proof::Groth16),
+        ProofSystem::Plonk => parse_plonk_proof(hex_proof).map(Proof::Plonk),
+    }
+}
+
+/// Builds the bridge call that verifies `proof`: `commitBatch` for Groth16, `commitBatchPlonk` for PLONK.
+pub fn commit_batch_call<M: Middleware>(
+    bridge: &ZKRollupBridge<M>,
+    da_id: u8,
+    batch_data: Bytes,
+    da_meta: Bytes,
+    new_root: [u8; 32],
+    proof: Proof,
+) -> ContractCall<M, ()> {
+    match proof {
+        Proof::Groth16(proof) => bridge.commit_batch(da_id, batch_data, da_meta, new_root, proof),
+        Proof::Plonk(proof) => bridge.commit_batch_plonk(da_id, batch_data, da_meta, new_root, proof),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `count` words holding 1, 2, 3, ... as one hex string.
+    fn words_hex(count: usize) -> String {
+        let mut bytes = vec![0u8; count * 32];
+        for (i, word) in bytes.chunks_mut(32).enumerate() {
+            U256::from(i + 1).to_big_endian(word);
+        }
+        format!("0x{}", ethers::utils::hex::encode(bytes))
+    }
+
+    #[test]
+    fn test_parse_groth16_sample() {
+        let proof = parse_proof(ProofSystem::Groth16, &words_hex(8)).unwrap();
+        let Proof::Groth16(proof) = proof else {
+            panic!("expected a Groth16 proof");
+        };
+        assert_eq!(proof.a, [U256::from(1), U256::from(2)]);
+        assert_eq!(proof.b, [[U256::from(3), U256::from(4)], [U256::from(5), U256::from(6)]]);
+        assert_eq!(proof.c, [U256::from(7), U256::from(8)]);
+    }
+
+    #[test]
+    fn test_parse_plonk_sample() {
+        let proof = parse_proof(ProofSystem::Plonk, &words_hex(PLONK_PROOF_WORDS)).unwrap();
+        let Proof::Plonk(words) = proof else {
+            panic!("expected a PLONK proof");
+        };
+        assert_eq!(words[0], U256::from(1));
+        assert_eq!(words[PLONK_PROOF_WORDS - 1], U256::from(PLONK_PROOF_WORDS));
+    }
+
+    #[test]
+    fn test_parse_proof_rejects_wrong_length() {
+        let err = parse_proof(ProofSystem::Groth16, &words_hex(PLONK_PROOF_WORDS)).unwrap_err();
+        assert_eq!(err, "Invalid proof length: expected 256 bytes, got 768");
+        let err = parse_proof(ProofSystem::Plonk, &words_hex(8)).unwrap_err();
+        assert_eq!(err, "Invalid proof length: expected 768 bytes, got 256");
+        assert!(parse_proof(ProofSystem::Plonk, "0xzz").unwrap_err().starts_with("Invalid hex"));
+    }
+}
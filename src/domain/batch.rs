@@ -1,6 +1,8 @@
+use crate::domain::errors::DomainError;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -52,12 +54,87 @@ pub enum BatchStatus {
     Failed,
 }
 
+/// The batch lifecycle as a table: whether a batch may move from `from` to `to`.
+///
+/// Any unfinished batch may fail; `Submitted` falls back to `Submitting` when its tx hash is
+/// lost; a `Failed` batch may only be requeued, to `Discovered` or (keeping its proof) to `Proved`.
+/// `Confirmed` is final.
+pub fn is_valid_transition(from: &BatchStatus, to: &BatchStatus) -> bool {
+    use BatchStatus::*;
+    matches!(
+        (from, to),
+        (Discovered, Proving)
+            | (Proving, Proved)
+            | (Proved, Submitting)
+            | (Submitting, Submitted)
+            | (Submitted, Confirmed)
+            | (Submitted, Submitting)
+            | (Discovered | Proving | Proved | Submitting | Submitted, Failed)
+            | (Failed, Discovered | Proved)
+    )
+}
+
+impl BatchStatus {
+    /// Every state, in lifecycle order.
+    pub const ALL: [BatchStatus; 7] = [
+        BatchStatus::Discovered,
+        BatchStatus::Proving,
+        BatchStatus::Proved,
+        BatchStatus::Submitting,
+        BatchStatus::Submitted,
+        BatchStatus::Confirmed,
+        BatchStatus::Failed,
+    ];
+}
+
 impl fmt::Display for BatchStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}", self)
     }
 }
 
+impl FromStr for BatchStatus {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BatchStatus::ALL
+            .into_iter()
+            .find(|status| status.to_string() == s)
+            .ok_or_else(|| DomainError::Internal(format!("Unknown batch status: {}", s)))
+    }
+}
+
+/// Proof system a batch is proved with; decides how its proof is decoded and which bridge
+/// entry point verifies it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofSystem {
+    #[default]
+    Groth16,
+    Plonk,
+}
+
+impl fmt::Display for ProofSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofSystem::Groth16 => write!(f, "groth16"),
+            ProofSystem::Plonk => write!(f, "plonk"),
+        }
+    }
+}
+
+impl FromStr for ProofSystem {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "groth16" => Ok(ProofSystem::Groth16),
+            "plonk" => Ok(ProofSystem::Plonk),
+            other => Err(DomainError::Config(format!("Unknown proof system: {}", other))),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Batch {
     pub id: BatchId,
@@ -73,6 +150,30 @@ pub struct Batch {
     pub blob_versioned_hash: Option<String>,
     pub blob_index: Option<u8>,
     pub fee: u64,
+    // Root the batch was built on; proving is deferred while the bridge reports another one
+    pub expected_old_root: Option<String>,
+    // Fee escalation steps already applied to the in-flight transaction
+    pub fee_bumps: u32,
+    // Position of the batch in the bridge's batch sequence
+    pub index: u64,
+    // Circuit the batch's proof comes from
+    pub proof_system: ProofSystem,
+    // Public inputs of the first proof request, reused when the batch is proved again
+    pub public_inputs: Option<Vec<u8>>,
+    // Earliest time the orchestrator retries the batch after a failed attempt
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    // Digest of the payload the batch was created from, for spotting re-seeded duplicates
+    pub data_hash: Option<String>,
+    // Gas used by the confirmed transaction, from its receipt
+    pub gas_used: Option<u64>,
+    // Price per gas the confirmed transaction paid, in wei
+    pub effective_gas_price: Option<u64>,
+    // Signed in-flight transaction (hex RLP), so replacements don't need the node to have it
+    pub signed_tx: Option<String>,
+    // Where an off-L1 DA layer (Celestia, EigenDA) holds the data; `tx_hash` is then the bridge commit
+    pub da_reference: Option<String>,
+    // EIP-712 signature attesting the confirmed batch, when `attestation` is configured
+    pub attestation: Option<String>,
 }
 
 impl Batch {
@@ -99,12 +200,52 @@ impl Batch {
             blob_versioned_hash: None,
             blob_index: None,
             fee: 0,
+            expected_old_root: None,
+            fee_bumps: 0,
+            index: 0,
+            proof_system: ProofSystem::default(),
+            public_inputs: None,
+            next_attempt_at: None,
+            data_hash: Some(data_hash),
+            gas_used: None,
+            effective_gas_price: None,
+            signed_tx: None,
+            da_reference: None,
+            attestation: None,
         }
     }
 
-    pub fn transition_to(&mut self, status: BatchStatus) {
+    /// Moves the batch to `status`, refusing moves that `is_valid_transition` does not allow.
+    /// A refused move leaves the batch untouched.
+    pub fn transition_to(&mut self, status: BatchStatus) -> Result<(), DomainError> {
+        if !is_valid_transition(&self.status, &status) {
+            return Err(DomainError::Internal(format!(
+                "Illegal transition for batch {}: {} -> {}",
+                self.id, self.status, status
+            )));
+        }
         self.status = status;
         self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// `da_mode` as a metrics label: `calldata`, `blob`, `celestia`, `eigenda`, or `other` for
+    /// anything else, so the label's values stay bounded whatever the stored string holds.
+    pub fn da_mode_label(&self) -> &'static str {
+        match self.da_mode.to_ascii_lowercase().as_str() {
+            "calldata" => "calldata",
+            "blob" => "blob",
+            "celestia" => "celestia",
+            "eigenda" => "eigenda",
+            _ => "other",
+        }
+    }
+
+    /// Whether `data_file` is a path on disk rather than an `http(s)://` or `s3://` URL.
+    pub fn has_local_data_file(&self) -> bool {
+        !["http://", "https://", "s3://"]
+            .iter()
+            .any(|scheme| self.data_file.starts_with(scheme))
     }
 }
 
@@ -146,7 +287,48 @@ mod tests {
             "root".into(),
             "blob".into(),
         );
-        batch.transition_to(BatchStatus::Proving);
+        batch.transition_to(BatchStatus::Proving).unwrap();
+        assert_eq!(batch.status, BatchStatus::Proving);
+    }
+
+    #[test]
+    fn test_transition_table() {
+        use BatchStatus::*;
+        let valid = [
+            (Discovered, Proving),
+            (Proving, Proved),
+            (Proved, Submitting),
+            (Submitting, Submitted),
+            (Submitted, Confirmed),
+            (Submitted, Submitting),
+            (Discovered, Failed),
+            (Proving, Failed),
+            (Proved, Failed),
+            (Submitting, Failed),
+            (Submitted, Failed),
+            (Failed, Discovered),
+            (Failed, Proved),
+        ];
+        let all = [Discovered, Proving, Proved, Submitting, Submitted, Confirmed, Failed];
+        for from in &all {
+            for to in &all {
+                let expected = valid.iter().any(|(f, t)| f == from && t == to);
+                assert_eq!(is_valid_transition(from, to), expected, "{} -> {}", from, to);
+            }
+        }
+    }
+
+    #[test]
+    fn test_transition_rejects_illegal_moves() {
+        let mut batch = Batch::new(1, "0xBridge", "file.txt".into(), "hash".into(), "root".into(), "blob".into());
+        batch.status = BatchStatus::Confirmed;
+
+        let err = batch.transition_to(BatchStatus::Proving).unwrap_err();
+        assert!(err.to_string().contains("Confirmed -> Proving"));
+        assert_eq!(batch.status, BatchStatus::Confirmed);
+
+        batch.status = BatchStatus::Discovered;
+        assert!(batch.transition_to(BatchStatus::Proving).is_ok());
         assert_eq!(batch.status, BatchStatus::Proving);
     }
 
@@ -0,0 +1,77 @@
+use crate::domain::errors::DomainError;
+use chrono::{DateTime, NaiveTime, Utc};
+use std::str::FromStr;
+
+/// A daily UTC time range (`"HH:MM-HH:MM"`) during which submissions are held.
+/// Ranges whose end is before their start wrap past midnight (e.g. `"22:00-02:00"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlackoutWindow {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl BlackoutWindow {
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let t = at.time();
+        if self.start <= self.end {
+            t >= self.start && t < self.end
+        } else {
+            t >= self.start || t < self.end
+        }
+    }
+}
+
+impl FromStr for BlackoutWindow {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            DomainError::Config(format!(
+                "Invalid blackout window '{}', expected HH:MM-HH:MM",
+                s
+            ))
+        };
+        let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+        let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|_| invalid());
+        let window = Self {
+            start: parse(start)?,
+            end: parse(end)?,
+        };
+        if window.start == window.end {
+            return Err(invalid());
+        }
+        Ok(window)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_window_contains() {
+        let day: BlackoutWindow = "09:00-10:30".parse().unwrap();
+        assert!(day.contains(at(9, 0)));
+        assert!(day.contains(at(10, 29)));
+        assert!(!day.contains(at(10, 30)));
+        assert!(!day.contains(at(8, 59)));
+
+        let overnight: BlackoutWindow = "22:00-02:00".parse().unwrap();
+        assert!(overnight.contains(at(23, 0)));
+        assert!(overnight.contains(at(1, 59)));
+        assert!(!overnight.contains(at(2, 0)));
+        assert!(!overnight.contains(at(12, 0)));
+    }
+
+    #[test]
+    fn test_window_parse_errors() {
+        assert!("09:00".parse::<BlackoutWindow>().is_err());
+        assert!("25:00-26:00".parse::<BlackoutWindow>().is_err());
+        assert!("09:00-09:00".parse::<BlackoutWindow>().is_err());
+    }
+}
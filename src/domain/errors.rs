@@ -6,10 +6,30 @@ pub enum DomainError {
     Storage(String),
     #[error("Prover error: {0}")]
     Prover(String),
+    #[error("Proof not ready: {0}")]
+    ProofNotReady(String),
     #[error("DA error: {0}")]
     Da(String),
+    // The DA transaction was mined but reverted
+    #[error("DA transaction reverted: {0}")]
+    DaReverted(String),
+    // Temporary provider/indexer failure; the same check may succeed next cycle
+    #[error("DA transient error: {0}")]
+    DaTransient(String),
+    // The network's base fee is above the configured cap; submit once it drops
+    #[error("Fee cap exceeded: {0}")]
+    FeeCapExceeded(String),
+    // The batch's data file is gone; retrying cannot bring it back
+    #[error("Data file missing: {0}")]
+    DataFileMissing(String),
+    // The batch's data source is briefly unreachable (e.g. a network mount that is down)
+    #[error("Data source unavailable: {0}")]
+    DataUnavailable(String),
     #[error("Configuration error: {0}")]
     Config(String),
+    // Problems with the batch itself that retrying cannot fix
+    #[error("Invalid batch: {0}")]
+    InvalidBatch(String),
     #[error("Internal error: {0}")]
     Internal(String),
 }
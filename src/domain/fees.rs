@@ -0,0 +1,154 @@
+use crate::domain::errors::DomainError;
+use std::time::Duration;
+
+/// Smallest fee increase nodes accept for a same-nonce replacement (geth's default 10%,
+/// rounded up to the common 12.5%).
+pub const MIN_REPLACEMENT_BUMP: f64 = 1.125;
+
+/// Replacements generated by `FeeEscalation::every`.
+const REPEATED_STEPS: u32 = 8;
+
+/// Once a submission has been unconfirmed for `after`, it is replaced with fees at
+/// `multiplier` times those of the original transaction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeStep {
+    pub after: Duration,
+    pub multiplier: f64,
+}
+
+/// Schedule of fee bumps for transactions stuck in `Submitted`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeeEscalation {
+    steps: Vec<FeeStep>,
+}
+
+impl FeeEscalation {
+    /// Each step must raise the fees by at least `MIN_REPLACEMENT_BUMP` over the previous one
+    /// (or the original submission), otherwise nodes would reject the replacement.
+    pub fn new(mut steps: Vec<FeeStep>) -> Result<Self, DomainError> {
+        steps.sort_by_key(|s| s.after);
+        let mut prev = 1.0;
+        for step in &steps {
+            // Small tolerance so schedules built from powers of the bump are not rejected
+            if step.multiplier.is_nan() || step.multiplier / prev < MIN_REPLACEMENT_BUMP - 1e-9 {
+                return Err(DomainError::Config(format!(
+                    "Fee escalation step {} is less than {}x the previous multiplier {}",
+                    step.multiplier, MIN_REPLACEMENT_BUMP, prev
+                )));
+            }
+            prev = step.multiplier;
+        }
+        Ok(Self { steps })
+    }
+
+    /// Bumps fees by `MIN_REPLACEMENT_BUMP` each time another `interval` passes unconfirmed.
+    pub fn every(interval: Duration) -> Result<Self, DomainError> {
+        if interval.is_zero() {
+            return Err(DomainError::Config("Fee replacement interval must be positive".into()));
+        }
+        let steps = (1..=REPEATED_STEPS)
+            .map(|k| FeeStep {
+                after: interval * k,
+                multiplier: MIN_REPLACEMENT_BUMP.powi(k as i32),
+            })
+            .collect();
+        Self::new(steps)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Number of steps whose wait has elapsed.
+    pub fn due_step(&self, unconfirmed_for: Duration) -> usize {
+        self.steps
+            .iter()
+            .take_while(|s| s.after <= unconfirmed_for)
+            .count()
+    }
+
+    /// Factor to apply to the fees of the tx sent at step `from` to reach step `to`
+    /// (step 0 is the original submission).
+    pub fn bump_ratio(&self, from: usize, to: usize) -> f64 {
+        self.multiplier(to) / self.multiplier(from)
+    }
+
+    fn multiplier(&self, step: usize) -> f64 {
+        match step {
+            0 => 1.0,
+            n => self.steps[n.min(self.steps.len()) - 1].multiplier,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> FeeEscalation {
+        FeeEscalation::new(vec![
+            FeeStep {
+                after: Duration::from_secs(240),
+                multiplier: 1.3,
+            },
+            FeeStep {
+                after: Duration::from_secs(120),
+                multiplier: 1.125,
+            },
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_due_step() {
+        let s = schedule();
+        assert_eq!(s.due_step(Duration::from_secs(60)), 0);
+        assert_eq!(s.due_step(Duration::from_secs(120)), 1);
+        assert_eq!(s.due_step(Duration::from_secs(600)), 2);
+    }
+
+    #[test]
+    fn test_bump_ratio_is_relative_to_previous_step() {
+        let s = schedule();
+        assert_eq!(s.bump_ratio(0, 1), 1.125);
+        assert!((s.bump_ratio(1, 2) - 1.3 / 1.125).abs() < 1e-9);
+        assert_eq!(s.bump_ratio(0, 2), 1.3);
+    }
+
+    #[test]
+    fn test_rejects_non_increasing_multipliers() {
+        let steps = vec![
+            FeeStep {
+                after: Duration::from_secs(1),
+                multiplier: 1.5,
+            },
+            FeeStep {
+                after: Duration::from_secs(2),
+                multiplier: 1.2,
+            },
+        ];
+        assert!(FeeEscalation::new(steps).is_err());
+    }
+
+    #[test]
+    fn test_rejects_steps_below_replacement_bump() {
+        let step = |after, multiplier| FeeStep {
+            after: Duration::from_secs(after),
+            multiplier,
+        };
+        // 1.05x the original submission is too small for a node to accept
+        assert!(FeeEscalation::new(vec![step(60, 1.05)]).is_err());
+        // 1.2 -> 1.25 increases, but only by ~4%
+        assert!(FeeEscalation::new(vec![step(60, 1.2), step(120, 1.25)]).is_err());
+        assert!(FeeEscalation::new(vec![step(60, 1.125), step(120, 1.125 * 1.125)]).is_ok());
+    }
+
+    #[test]
+    fn test_every_interval() {
+        let s = FeeEscalation::every(Duration::from_secs(60)).unwrap();
+        assert_eq!(s.due_step(Duration::from_secs(59)), 0);
+        assert_eq!(s.due_step(Duration::from_secs(125)), 2);
+        assert!((s.bump_ratio(1, 2) - MIN_REPLACEMENT_BUMP).abs() < 1e-9);
+        assert!(FeeEscalation::every(Duration::ZERO).is_err());
+    }
+}
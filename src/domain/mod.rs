@@ -1,2 +1,5 @@
 pub mod batch;
+pub mod blackout;
 pub mod errors;
+pub mod fees;
+pub mod public_inputs;
@@ -0,0 +1,183 @@
+use crate::domain::errors::DomainError;
+
+/// A 32-byte big-endian public input word.
+pub type Word = [u8; 32];
+
+// BN254 Scalar Field Modulus
+// 21888242871839275222246405745257275088548364400416034343698204186575808495617
+pub const SNARK_SCALAR_FIELD: Word = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// Reduces `word` mod the scalar field. The field is above 2^253, so at most five
+/// subtractions are needed.
+pub fn reduce_to_field(mut word: Word) -> Word {
+    while word >= SNARK_SCALAR_FIELD {
+        let mut borrow = 0u16;
+        for i in (0..32).rev() {
+            let rhs = SNARK_SCALAR_FIELD[i] as u16 + borrow;
+            let lhs = word[i] as u16;
+            borrow = (lhs < rhs) as u16;
+            word[i] = (lhs + (borrow << 8) - rhs) as u8;
+        }
+    }
+    word
+}
+
+fn to_hex(word: &Word) -> String {
+    word.iter().fold(String::from("0x"), |hex, b| hex + &format!("{:02x}", b))
+}
+
+/// Public inputs are `daCommitment`, `oldRoot`, `newRoot`, one 32-byte word each.
+pub const PUBLIC_INPUTS_LEN: usize = 96;
+
+/// Rejects public inputs that cannot be the full set the circuit expects.
+pub fn check_public_inputs(inputs: &[u8]) -> Result<(), DomainError> {
+    if inputs.len() != PUBLIC_INPUTS_LEN {
+        return Err(DomainError::InvalidBatch(format!(
+            "Expected {} bytes of public inputs (daCommitment, oldRoot, newRoot), got {}",
+            PUBLIC_INPUTS_LEN,
+            inputs.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Where a narrower root sits inside its 32-byte public input word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RootPadding {
+    /// Zero bytes before the value (value in the low bits).
+    #[default]
+    Left,
+    /// Zero bytes after the value (value in the high bits).
+    Right,
+}
+
+/// How state roots are packed into public inputs.
+///
+/// Roots are read from the low `root_bits` bits of their 32-byte form. Full 256-bit roots
+/// are reduced mod the scalar field (the historical behaviour); narrower roots are placed
+/// per `padding` and must already fit the field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RootPacking {
+    pub root_bits: u16,
+    pub padding: RootPadding,
+}
+
+impl Default for RootPacking {
+    fn default() -> Self {
+        Self {
+            root_bits: 256,
+            padding: RootPadding::Left,
+        }
+    }
+}
+
+impl RootPacking {
+    pub fn new(root_bits: u16, padding: RootPadding) -> Result<Self, DomainError> {
+        if root_bits == 0 || root_bits > 256 || !root_bits.is_multiple_of(8) {
+            return Err(DomainError::Config(format!(
+                "root_bits must be a multiple of 8 in 8..=256 (got {})",
+                root_bits
+            )));
+        }
+        Ok(Self { root_bits, padding })
+    }
+
+    pub fn pack(&self, root: Word) -> Result<Word, DomainError> {
+        if self.root_bits == 256 {
+            return Ok(reduce_to_field(root));
+        }
+
+        // `root_bits` is whole bytes, so the root must fit in its last `width` bytes
+        let width = self.root_bits as usize / 8;
+        if root[..32 - width].iter().any(|b| *b != 0) {
+            return Err(DomainError::Internal(format!(
+                "Root {} does not fit in {} bits",
+                to_hex(&root),
+                self.root_bits
+            )));
+        }
+        let packed = match self.padding {
+            RootPadding::Left => root,
+            RootPadding::Right => {
+                let mut packed = [0u8; 32];
+                packed[..width].copy_from_slice(&root[32 - width..]);
+                packed
+            }
+        };
+        if packed >= SNARK_SCALAR_FIELD {
+            return Err(DomainError::Internal(format!(
+                "Packed root {} exceeds the scalar field",
+                to_hex(&root)
+            )));
+        }
+        Ok(packed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root_248() -> Word {
+        let mut bytes = [0xabu8; 32];
+        bytes[0] = 0;
+        bytes
+    }
+
+    #[test]
+    fn test_pack_248_bit_left() {
+        let packing = RootPacking::new(248, RootPadding::Left).unwrap();
+        let out = packing.pack(root_248()).unwrap();
+
+        assert_eq!(out[0], 0);
+        assert!(out[1..].iter().all(|b| *b == 0xab));
+    }
+
+    #[test]
+    fn test_pack_248_bit_right() {
+        // Keep the high byte small enough for the shifted value to stay in the field
+        let mut bytes = [0xabu8; 32];
+        bytes[0] = 0;
+        bytes[1] = 0x01;
+        let packing = RootPacking::new(248, RootPadding::Right).unwrap();
+        let out = packing.pack(bytes).unwrap();
+
+        assert_eq!(out[0], 0x01);
+        assert!(out[1..31].iter().all(|b| *b == 0xab));
+        assert_eq!(out[31], 0);
+    }
+
+    #[test]
+    fn test_pack_rejects_oversized_roots() {
+        let packing = RootPacking::new(248, RootPadding::Left).unwrap();
+        assert!(packing.pack([0xff; 32]).is_err());
+        // Right padding a full 248-bit value lands above the field
+        let right = RootPacking::new(248, RootPadding::Right).unwrap();
+        assert!(right.pack(root_248()).is_err());
+        assert!(RootPacking::new(250, RootPadding::Left).is_err());
+    }
+
+    #[test]
+    fn test_check_public_inputs_length() {
+        assert!(check_public_inputs(&[0u8; PUBLIC_INPUTS_LEN]).is_ok());
+        assert!(matches!(check_public_inputs(&[]), Err(DomainError::InvalidBatch(_))));
+        assert!(check_public_inputs(&[0u8; 64]).is_err());
+    }
+
+    #[test]
+    fn test_pack_256_bit_reduces_mod_field() {
+        let packed = RootPacking::default().pack([0xff; 32]).unwrap();
+        assert!(packed < SNARK_SCALAR_FIELD);
+        assert_eq!(RootPacking::default().pack(SNARK_SCALAR_FIELD).unwrap(), [0u8; 32]);
+
+        // 2^256 - 1 - 5p
+        let expected = [
+            0x0e, 0x0a, 0x77, 0xc1, 0x9a, 0x07, 0xdf, 0x2f, 0x66, 0x6e, 0xa3, 0x6f, 0x78, 0x79, 0x46, 0x2e,
+            0x36, 0xfc, 0x76, 0x95, 0x9f, 0x60, 0xcd, 0x29, 0xac, 0x96, 0x34, 0x1c, 0x4f, 0xff, 0xff, 0xfa,
+        ];
+        assert_eq!(packed, expected);
+    }
+}
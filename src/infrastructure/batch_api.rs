@@ -0,0 +1,170 @@
+use crate::application::orchestrator::{enqueue_batch, next_batch_index};
+use crate::application::ports::Storage;
+use crate::config::DaMode;
+use crate::domain::batch::{Batch, BatchId, ProofSystem};
+use crate::infrastructure::payload::read_payload;
+use anyhow::{Context, Result};
+use axum::error_handling::HandleErrorLayer;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use metrics::counter;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha1_smol::Sha1;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tower::limit::GlobalConcurrencyLimitLayer;
+use tower::{BoxError, ServiceBuilder};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// What a `POST /batches` needs besides the request body to build a batch the same way the
+/// startup seeding does.
+pub struct BatchApi {
+    pub storage: Arc<dyn Storage>,
+    pub chain_id: u64,
+    pub bridge: String,
+    pub max_pending: Option<u64>,
+    // Used for requests that do not name a proof system
+    pub proof_system: ProofSystem,
+    // The `rollup` label of the API's metrics
+    pub rollup: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewBatchRequest {
+    pub data_file: String,
+    pub new_root: String,
+    pub da_mode: DaMode,
+    pub proof_system: Option<ProofSystem>,
+    // Position in the bridge's batch sequence (default: one past the highest stored)
+    pub index: Option<u64>,
+}
+
+pub fn batch_router(api: BatchApi) -> Router {
+    Router::new()
+        .route("/batches", post(create_batch))
+        .route("/batches/{id}", get(get_batch))
+        .with_state(Arc::new(api))
+}
+
+/// Caps how many requests `router` serves at once, across all of its routes. Requests beyond
+/// `max_in_flight` are answered `503` right away instead of queueing for a database connection.
+pub fn limit_concurrency(rollup: &str, router: Router, max_in_flight: usize) -> Router {
+    let rollup = rollup.to_string();
+    router.layer(
+        ServiceBuilder::new()
+            .layer(HandleErrorLayer::new(move |_: BoxError| shed_request(rollup.clone())))
+            .load_shed()
+            .layer(GlobalConcurrencyLimitLayer::new(max_in_flight)),
+    )
+}
+
+async fn shed_request(rollup: String) -> (StatusCode, Json<Value>) {
+    counter!("api_requests_shed_total", "rollup" => rollup).increment(1);
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(json!({ "error": "Too many requests in flight" })),
+    )
+}
+
+pub async fn serve_batch_api(listener: TcpListener, router: Router) -> Result<()> {
+    info!("Batch API listening on {}", listener.local_addr()?);
+    axum::serve(listener, router)
+        .await
+        .context("batch API server failed")
+}
+
+async fn create_batch(
+    State(api): State<Arc<BatchApi>>,
+    Json(req): Json<NewBatchRequest>,
+) -> (StatusCode, Json<Value>) {
+    let context = format!("Failed to read data file {}", req.data_file);
+    let data = match read_payload(&api.rollup, &req.data_file, &context).await {
+        Ok(data) => data,
+        Err(e) => {
+            let error = e.to_string();
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": error })));
+        }
+    };
+
+    let mut batch = Batch::new(
+        api.chain_id,
+        &api.bridge,
+        req.data_file,
+        Sha1::from(data).digest().to_string(),
+        req.new_root,
+        format!("{:?}", req.da_mode),
+    );
+    batch.proof_system = req.proof_system.unwrap_or(api.proof_system);
+
+    // Same payload, root and mode give the same id: hand back the batch we already have
+    match api.storage.get_batch(batch.id).await {
+        Ok(Some(existing)) => {
+            return (StatusCode::OK, Json(json!({ "batch_id": existing.id })));
+        }
+        Ok(None) => {}
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": e.to_string() })),
+            );
+        }
+    }
+    batch.index = match req.index {
+        Some(index) => index,
+        None => match next_batch_index(api.storage.as_ref()).await {
+            Ok(index) => index,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "error": e.to_string() })),
+                );
+            }
+        },
+    };
+
+    match enqueue_batch(&api.rollup, api.storage.as_ref(), &batch, api.max_pending).await {
+        Ok(true) => {
+            info!("Batch {} enqueued via API", batch.id);
+            counter!("batches_enqueued_total", "rollup" => api.rollup.clone(), "source" => "api").increment(1);
+            (StatusCode::CREATED, Json(json!({ "batch_id": batch.id })))
+        }
+        Ok(false) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "Too many pending batches" })),
+        ),
+        Err(e) => {
+            warn!("Failed to enqueue batch {}: {}", batch.id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": e.to_string() })),
+            )
+        }
+    }
+}
+
+async fn get_batch(
+    State(api): State<Arc<BatchApi>>,
+    Path(id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match api.storage.get_batch(BatchId(id)).await {
+        Ok(Some(batch)) => match serde_json::to_value(&batch) {
+            Ok(body) => (StatusCode::OK, Json(body)),
+            Err(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": e.to_string() })),
+            ),
+        },
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": format!("Batch {} not found", id) })),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": e.to_string() })),
+        ),
+    }
+}
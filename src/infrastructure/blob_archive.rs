@@ -0,0 +1,203 @@
+use crate::domain::errors::DomainError;
+use ethers::prelude::*;
+use ethers::types::H256;
+use ethers::utils::keccak256;
+use metrics::{counter, histogram};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// POSTs blob data to the archiver, signing it with `signer` when set (`X-Signature` header,
+/// EIP-191 signature over `keccak256(data)`). `rollup` labels the upload metrics.
+pub async fn upload_to_archiver(
+    rollup: &str,
+    client: &reqwest::Client,
+    url: &str,
+    data: &[u8],
+    signer: Option<&LocalWallet>,
+) -> Result<(), DomainError> {
+    let mut req = client.post(url).body(data.to_vec());
+    if let Some(signer) = signer {
+        let signature = signer
+            .sign_message(keccak256(data))
+            .await
+            .map_err(|e| DomainError::Da(format!("Archiver upload signing failed: {}", e)))?;
+        req = req.header("X-Signature", format!("0x{}", signature));
+    }
+    let start = Instant::now();
+    let res = req.send().await;
+    histogram!("archiver_upload_duration_seconds", "rollup" => rollup.to_string()).record(start.elapsed().as_secs_f64());
+    let res = res.map_err(|e| DomainError::Da(format!("Archiver request failed: {}", e)))?;
+
+    if !res.status().is_success() {
+        return Err(DomainError::Da(format!(
+            "Archiver rejected payload: {}",
+            res.status()
+        )));
+    }
+    Ok(())
+}
+
+/// Local copies of blob data (`{dir}/{versioned_hash}.blob`) kept until the remote archiver
+/// has accepted them.
+pub struct LocalBlobArchive {
+    dir: PathBuf,
+    archiver_url: String,
+    signer: Option<LocalWallet>,
+    client: reqwest::Client,
+    rollup: String,
+}
+
+impl LocalBlobArchive {
+    pub fn new(dir: PathBuf, archiver_url: String, signer: Option<LocalWallet>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+        Self {
+            dir,
+            archiver_url,
+            signer,
+            client,
+            rollup: "default".to_string(),
+        }
+    }
+
+    /// Names the rollup the blobs belong to, for the `rollup` label of the upload metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
+    }
+
+    pub fn path_for(&self, versioned_hash: H256) -> PathBuf {
+        self.dir.join(format!("{:?}.blob", versioned_hash))
+    }
+
+    pub fn persist(&self, versioned_hash: H256, data: &[u8]) -> Result<PathBuf, DomainError> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| DomainError::Da(format!("Failed to create blob dir: {}", e)))?;
+        let path = self.path_for(versioned_hash);
+        std::fs::write(&path, data)
+            .map_err(|e| DomainError::Da(format!("Failed to persist blob locally: {}", e)))?;
+        Ok(path)
+    }
+
+    pub fn remove(&self, versioned_hash: H256) {
+        let _ = std::fs::remove_file(self.path_for(versioned_hash));
+    }
+
+    pub async fn upload(&self, data: &[u8]) -> Result<(), DomainError> {
+        upload_to_archiver(&self.rollup, &self.client, &self.archiver_url, data, self.signer.as_ref()).await
+    }
+
+    /// Uploads every pending local blob, deleting the ones the archiver accepts.
+    /// Returns how many were uploaded.
+    pub async fn reupload_pending(&self) -> Result<usize, DomainError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(DomainError::Da(format!("Failed to read blob dir: {}", e))),
+        };
+
+        let mut uploaded = 0;
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.extension().and_then(|e| e.to_str()) != Some("blob") {
+                continue;
+            }
+            if self.reupload(&path).await {
+                uploaded += 1;
+            }
+        }
+        Ok(uploaded)
+    }
+
+    async fn reupload(&self, path: &Path) -> bool {
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("Failed to read local blob {}: {}", path.display(), e);
+                return false;
+            }
+        };
+        match self.upload(&data).await {
+            Ok(()) => {
+                info!("Re-uploaded local blob {}", path.display());
+                counter!("blob_reuploads_total", "rollup" => self.rollup.clone(), "result" => "success").increment(1);
+                let _ = std::fs::remove_file(path);
+                true
+            }
+            Err(e) => {
+                warn!("Re-upload of {} failed: {}", path.display(), e);
+                counter!("blob_reuploads_total", "rollup" => self.rollup.clone(), "result" => "error").increment(1);
+                false
+            }
+        }
+    }
+
+    /// Periodically retries pending local blobs until the archiver is back.
+    pub fn spawn_reupload_loop(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(e) = self.reupload_pending().await {
+                    warn!("Blob re-upload pass failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_reupload_pending_after_recovery() {
+        let archiver = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&archiver)
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("blobs-{}", uuid::Uuid::new_v4()));
+        let archive = LocalBlobArchive::new(dir.clone(), archiver.uri(), None);
+        let hash = H256::random();
+        archive.persist(hash, b"payload").unwrap();
+
+        assert_eq!(archive.reupload_pending().await.unwrap(), 1);
+        assert!(!archive.path_for(hash).exists());
+
+        let received = archiver.received_requests().await.unwrap();
+        assert_eq!(received[0].body, b"payload");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_upload_duration_recorded() {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            rt.block_on(async {
+                let archiver = MockServer::start().await;
+                Mock::given(method("POST"))
+                    .respond_with(ResponseTemplate::new(503))
+                    .mount(&archiver)
+                    .await;
+                let res = upload_to_archiver("default", &reqwest::Client::new(), &archiver.uri(), b"payload", None).await;
+                assert!(res.is_err());
+            });
+        });
+
+        let recorded = snapshotter.snapshot().into_vec().into_iter().any(|(key, _, _, value)| {
+            key.key().name() == "archiver_upload_duration_seconds"
+                && matches!(value, DebugValue::Histogram(ref samples) if samples.len() == 1)
+        });
+        assert!(recorded);
+    }
+}
@@ -0,0 +1,396 @@
+use crate::domain::errors::DomainError;
+use crate::infrastructure::ethereum_adapter::{receipt_confirmed, record_rpc_error};
+use ethers::abi::{encode, Token};
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::utils::keccak256;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use metrics::histogram;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Placeholder signature for gas estimation: 65 bytes a secp256k1 check can parse without
+/// matching the operation.
+const DUMMY_SIGNATURE: [u8; 65] = {
+    let mut sig = [0xffu8; 65];
+    sig[64] = 0x1c;
+    sig
+};
+
+/// An ERC-4337 (EntryPoint v0.6) UserOperation, in the JSON shape bundlers accept.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperation {
+    pub sender: Address,
+    pub nonce: U256,
+    pub init_code: Bytes,
+    pub call_data: Bytes,
+    pub call_gas_limit: U256,
+    pub verification_gas_limit: U256,
+    pub pre_verification_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub paymaster_and_data: Bytes,
+    pub signature: Bytes,
+}
+
+impl UserOperation {
+    /// The hash the account signs, as computed by `EntryPoint.getUserOpHash`.
+    pub fn hash(&self, entry_point: Address, chain_id: U256) -> H256 {
+        let packed = encode(&[
+            Token::Address(self.sender),
+            Token::Uint(self.nonce),
+            Token::FixedBytes(keccak256(&self.init_code).to_vec()),
+            Token::FixedBytes(keccak256(&self.call_data).to_vec()),
+            Token::Uint(self.call_gas_limit),
+            Token::Uint(self.verification_gas_limit),
+            Token::Uint(self.pre_verification_gas),
+            Token::Uint(self.max_fee_per_gas),
+            Token::Uint(self.max_priority_fee_per_gas),
+            Token::FixedBytes(keccak256(&self.paymaster_and_data).to_vec()),
+        ]);
+        H256::from(keccak256(encode(&[
+            Token::FixedBytes(keccak256(packed).to_vec()),
+            Token::Address(entry_point),
+            Token::Uint(chain_id),
+        ])))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GasEstimate {
+    call_gas_limit: U256,
+    verification_gas_limit: U256,
+    pre_verification_gas: U256,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InnerReceipt {
+    transaction_hash: H256,
+}
+
+#[derive(Deserialize)]
+struct UserOperationReceipt {
+    success: bool,
+    receipt: InnerReceipt,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+/// Submits calls as UserOperations of a smart account through an ERC-4337 bundler, so that a
+/// paymaster pays for gas instead of the submitter's EOA.
+///
+/// The submitter key only signs the operation; the account executes the call with a
+/// SimpleAccount-style `execute(address,uint256,bytes)`. For an EOA delegated with EIP-7702 the
+/// account is the submitter's own address.
+pub struct Bundler {
+    client: reqwest::Client,
+    url: String,
+    entry_point: Address,
+    account: Option<Address>,
+    paymaster_and_data: Bytes,
+    rollup: String,
+}
+
+impl Bundler {
+    pub fn new(url: impl Into<String>, entry_point: Address) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("Failed to build bundler HTTP client");
+        Self {
+            client,
+            url: url.into(),
+            entry_point,
+            account: None,
+            paymaster_and_data: Bytes::new(),
+            rollup: "default".to_string(),
+        }
+    }
+
+    /// Names the rollup the operations are sent for, for the `rollup` label of its metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
+    }
+
+    /// Smart account that sends the operations. Unset means the signer's own address.
+    pub fn with_account(mut self, account: Option<Address>) -> Self {
+        self.account = account;
+        self
+    }
+
+    /// `paymasterAndData` attached to every operation: the paymaster address followed by
+    /// whatever it expects, usually a sponsorship signature.
+    pub fn with_paymaster_and_data(mut self, data: Bytes) -> Self {
+        self.paymaster_and_data = data;
+        self
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &'static str,
+        params: serde_json::Value,
+    ) -> Result<Option<T>, DomainError> {
+        let start = Instant::now();
+        let res = self.call_once(method, params).await;
+        histogram!("bundler_request_duration_seconds", "rollup" => self.rollup.clone(), "method" => method)
+            .record(start.elapsed().as_secs_f64());
+        res
+    }
+
+    async fn call_once<T: DeserializeOwned>(
+        &self,
+        method: &'static str,
+        params: serde_json::Value,
+    ) -> Result<Option<T>, DomainError> {
+        let res = self
+            .client
+            .post(&self.url)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await
+            .map_err(|e| {
+                record_rpc_error(&self.rollup, method);
+                DomainError::DaTransient(format!("Bundler request failed: {}", e))
+            })?;
+        if !res.status().is_success() {
+            record_rpc_error(&self.rollup, method);
+            return Err(DomainError::DaTransient(format!(
+                "Bundler status: {}",
+                res.status()
+            )));
+        }
+
+        let body: RpcResponse<T> = res
+            .json()
+            .await
+            .map_err(|e| DomainError::Da(format!("Bundler response parse error: {}", e)))?;
+        if let Some(err) = body.error {
+            record_rpc_error(&self.rollup, method);
+            return Err(DomainError::Da(format!(
+                "{} failed: {}",
+                method, err.message
+            )));
+        }
+        Ok(body.result)
+    }
+
+    /// The account's next nonce (key 0) from `EntryPoint.getNonce`.
+    async fn account_nonce<M: Middleware>(
+        &self,
+        client: &M,
+        account: Address,
+    ) -> Result<U256, DomainError> {
+        let mut data = keccak256("getNonce(address,uint192)")[..4].to_vec();
+        data.extend(encode(&[
+            Token::Address(account),
+            Token::Uint(U256::zero()),
+        ]));
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(self.entry_point)
+            .data(data)
+            .into();
+        let out = client.call(&tx, None).await.map_err(|e| {
+            record_rpc_error(&self.rollup, "call");
+            DomainError::DaTransient(format!("EntryPoint nonce read failed: {}", e))
+        })?;
+        if out.len() < 32 {
+            return Err(DomainError::Da("EntryPoint returned no nonce".into()));
+        }
+        Ok(U256::from_big_endian(&out[..32]))
+    }
+
+    /// Wraps a call to `to` with `data` in a signed UserOperation and hands it to the bundler.
+    /// Returns the UserOperation hash, which [`Bundler::confirmed`] resolves to the bundle
+    /// transaction.
+    pub async fn send_call<M: Middleware>(
+        &self,
+        client: &M,
+        to: Address,
+        data: Bytes,
+    ) -> Result<H256, DomainError> {
+        let signer = client
+            .default_sender()
+            .ok_or_else(|| DomainError::Da("Sponsored submission needs a signing client".into()))?;
+        let sender = self.account.unwrap_or(signer);
+
+        let mut call_data = keccak256("execute(address,uint256,bytes)")[..4].to_vec();
+        call_data.extend(encode(&[
+            Token::Address(to),
+            Token::Uint(U256::zero()),
+            Token::Bytes(data.to_vec()),
+        ]));
+
+        let nonce = self.account_nonce(client, sender).await?;
+        let (max_fee_per_gas, max_priority_fee_per_gas) = client
+            .estimate_eip1559_fees(None)
+            .await
+            .map_err(|e| DomainError::DaTransient(format!("Fee estimation failed: {}", e)))?;
+        let mut op = UserOperation {
+            sender,
+            nonce,
+            init_code: Bytes::new(),
+            call_data: call_data.into(),
+            call_gas_limit: U256::zero(),
+            verification_gas_limit: U256::zero(),
+            pre_verification_gas: U256::zero(),
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            paymaster_and_data: self.paymaster_and_data.clone(),
+            signature: Bytes::from(DUMMY_SIGNATURE.to_vec()),
+        };
+
+        let gas: GasEstimate = self
+            .call(
+                "eth_estimateUserOperationGas",
+                json!([op, self.entry_point]),
+            )
+            .await?
+            .ok_or_else(|| DomainError::Da("Bundler returned no gas estimate".into()))?;
+        op.call_gas_limit = gas.call_gas_limit;
+        op.verification_gas_limit = gas.verification_gas_limit;
+        op.pre_verification_gas = gas.pre_verification_gas;
+
+        let chain_id = client
+            .get_chainid()
+            .await
+            .map_err(|e| DomainError::DaTransient(format!("Provider error: {}", e)))?;
+        let op_hash = op.hash(self.entry_point, chain_id);
+        let signature = client
+            .sign(op_hash.as_bytes().to_vec(), &signer)
+            .await
+            .map_err(|e| DomainError::Da(format!("UserOperation signing failed: {}", e)))?;
+        op.signature = signature.to_vec().into();
+
+        let hash: H256 = self
+            .call("eth_sendUserOperation", json!([op, self.entry_point]))
+            .await?
+            .ok_or_else(|| DomainError::Da("Bundler returned no UserOperation hash".into()))?;
+        info!(
+            "UserOperation {:?} from {:?} accepted by bundler",
+            hash, sender
+        );
+        Ok(hash)
+    }
+
+    /// Whether the operation `user_op_hash` landed and its bundle transaction has `required`
+    /// confirmations. An operation the account executed but reverted is an error.
+    pub async fn confirmed<M: Middleware>(
+        &self,
+        client: &M,
+        user_op_hash: &str,
+        required: u64,
+    ) -> Result<bool, DomainError> {
+        let hash: H256 = user_op_hash
+            .parse()
+            .map_err(|e| DomainError::Da(format!("Invalid hash: {}", e)))?;
+        let receipt: Option<UserOperationReceipt> = self
+            .call("eth_getUserOperationReceipt", json!([hash]))
+            .await?;
+        let Some(receipt) = receipt else {
+            return Ok(false);
+        };
+        if !receipt.success {
+            return Err(DomainError::DaReverted(
+                "UserOperation reverted".to_string(),
+            ));
+        }
+        receipt_confirmed(
+            &self.rollup,
+            client,
+            &format!("{:?}", receipt.receipt.transaction_hash),
+            required,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_op_hash_covers_signature_free_fields() {
+        let op = UserOperation {
+            sender: Address::repeat_byte(1),
+            nonce: U256::from(3),
+            init_code: Bytes::new(),
+            call_data: Bytes::from(vec![0xab]),
+            call_gas_limit: U256::from(100_000),
+            verification_gas_limit: U256::from(50_000),
+            pre_verification_gas: U256::from(21_000),
+            max_fee_per_gas: U256::from(10),
+            max_priority_fee_per_gas: U256::from(1),
+            paymaster_and_data: Bytes::new(),
+            signature: Bytes::new(),
+        };
+        let entry_point = Address::repeat_byte(2);
+        let hash = op.hash(entry_point, U256::one());
+
+        let signed = UserOperation {
+            signature: Bytes::from(vec![1, 2, 3]),
+            ..op.clone()
+        };
+        assert_eq!(signed.hash(entry_point, U256::one()), hash);
+        assert_ne!(op.hash(entry_point, U256::from(5)), hash);
+        let bumped = UserOperation {
+            nonce: U256::from(4),
+            ..op
+        };
+        assert_ne!(bumped.hash(entry_point, U256::one()), hash);
+    }
+
+    #[test]
+    fn test_bundler_call_duration_recorded() {
+        use crate::test_utils::MockClient;
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            rt.block_on(async {
+                let server = MockServer::start().await;
+                Mock::given(wiremock::matchers::method("POST"))
+                    .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                        "jsonrpc": "2.0",
+                        "id": 1,
+                        "result": null,
+                    })))
+                    .mount(&server)
+                    .await;
+                let bundler = Bundler::new(server.uri(), Address::zero());
+                let provider = Provider::new(MockClient::new());
+                let confirmed = bundler.confirmed(&provider, &format!("{:?}", H256::zero()), 1).await;
+                assert!(!confirmed.unwrap());
+            });
+        });
+
+        let recorded = snapshotter.snapshot().into_vec().into_iter().any(|(key, _, _, value)| {
+            let key = key.key();
+            key.name() == "bundler_request_duration_seconds"
+                && key.labels().any(|l| l.key() == "method" && l.value() == "eth_getUserOperationReceipt")
+                && matches!(value, DebugValue::Histogram(ref samples) if samples.len() == 1)
+        });
+        assert!(recorded);
+    }
+}
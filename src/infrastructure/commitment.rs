@@ -0,0 +1,98 @@
+use ethers::types::H256;
+use ethers::utils::keccak256;
+
+/// How a batch payload is condensed into the 32-byte DA commitment the bridge checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommitmentScheme {
+    /// `keccak256(payload)`.
+    #[default]
+    Keccak,
+    /// Root of a binary keccak256 Merkle tree over `chunk_size`-byte chunks of the payload.
+    MerkleKeccak { chunk_size: usize },
+}
+
+impl CommitmentScheme {
+    pub fn commit(&self, data: &[u8]) -> H256 {
+        match self {
+            CommitmentScheme::Keccak => H256::from(keccak256(data)),
+            CommitmentScheme::MerkleKeccak { chunk_size } => merkle_keccak_root(data, *chunk_size),
+        }
+    }
+}
+
+/// Merkle root over `data` split into `chunk_size`-byte chunks (the last one may be shorter).
+///
+/// Leaves are `keccak256(chunk)`, the leaf level is padded with zero hashes up to a power of two,
+/// and each parent is `keccak256(left ++ right)`. A payload that fits in one chunk therefore
+/// commits to `keccak256(payload)`, same as the flat scheme.
+pub fn merkle_keccak_root(data: &[u8], chunk_size: usize) -> H256 {
+    assert!(chunk_size > 0, "chunk_size must be positive");
+
+    let mut level: Vec<[u8; 32]> = if data.is_empty() {
+        vec![keccak256(data)]
+    } else {
+        data.chunks(chunk_size).map(keccak256).collect()
+    };
+    level.resize(level.len().next_power_of_two(), [0u8; 32]);
+
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| {
+                let mut node = [0u8; 64];
+                node[..32].copy_from_slice(&pair[0]);
+                node[32..].copy_from_slice(&pair[1]);
+                keccak256(node)
+            })
+            .collect();
+    }
+    H256::from(level[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parent(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+        keccak256([left, right].concat())
+    }
+
+    #[test]
+    fn test_merkle_root_matches_reference_tree() {
+        // Chunks "ab", "cd", "e" plus one zero leaf of padding
+        let data = b"abcde";
+        let expected = parent(
+            parent(keccak256(b"ab"), keccak256(b"cd")),
+            parent(keccak256(b"e"), [0u8; 32]),
+        );
+        assert_eq!(merkle_keccak_root(data, 2), H256::from(expected));
+
+        let scheme = CommitmentScheme::MerkleKeccak { chunk_size: 2 };
+        assert_eq!(scheme.commit(data), H256::from(expected));
+    }
+
+    #[test]
+    fn test_single_chunk_equals_flat_keccak() {
+        let data = b"hello rollup";
+        assert_eq!(
+            merkle_keccak_root(data, 64),
+            CommitmentScheme::Keccak.commit(data)
+        );
+        assert_eq!(
+            merkle_keccak_root(data, data.len()),
+            H256::from(keccak256(data))
+        );
+        assert_eq!(merkle_keccak_root(&[], 32), H256::from(keccak256([])));
+    }
+
+    #[test]
+    fn test_chunk_boundaries_change_the_root() {
+        let data = [7u8; 96];
+        let expected = parent(
+            parent(keccak256([7u8; 32]), keccak256([7u8; 32])),
+            parent(keccak256([7u8; 32]), [0u8; 32]),
+        );
+        assert_eq!(merkle_keccak_root(&data, 32), H256::from(expected));
+        assert_ne!(merkle_keccak_root(&data, 32), merkle_keccak_root(&data, 48));
+    }
+}
@@ -0,0 +1,146 @@
+use crate::application::ports::{ConfirmationSource, DaStrategy};
+use crate::domain::{batch::Batch, errors::DomainError};
+use async_trait::async_trait;
+use metrics::{counter, histogram};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Confirms batches from the node by polling the tx receipt through the DA strategy.
+pub struct NodeConfirmationSource {
+    da_strategy: Arc<dyn DaStrategy>,
+}
+
+impl NodeConfirmationSource {
+    pub fn new(da_strategy: Arc<dyn DaStrategy>) -> Self {
+        Self { da_strategy }
+    }
+}
+
+#[async_trait]
+impl ConfirmationSource for NodeConfirmationSource {
+    async fn is_confirmed(&self, batch: &Batch) -> Result<bool, DomainError> {
+        self.da_strategy.check_batch_confirmation(batch).await
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct IndexerStatus {
+    status: String,
+}
+
+/// Confirms batches from an indexer that tracks bridge commitments,
+/// queried as `GET {url}/batch/{new_root}/status`.
+pub struct IndexerConfirmationSource {
+    client: Client,
+    url: String,
+    rollup: String,
+}
+
+impl IndexerConfirmationSource {
+    pub fn new(url: String) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self { client, url, rollup: "default".to_string() }
+    }
+
+    /// Names the rollup whose batches are looked up, for the `rollup` label of its metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
+    }
+}
+
+#[async_trait]
+impl ConfirmationSource for IndexerConfirmationSource {
+    async fn is_confirmed(&self, batch: &Batch) -> Result<bool, DomainError> {
+        let start = Instant::now();
+        let url = format!(
+            "{}/batch/{}/status",
+            self.url.trim_end_matches('/'),
+            batch.new_root
+        );
+
+        let res = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| DomainError::DaTransient(format!("Indexer request failed: {}", e)))?;
+        histogram!("indexer_request_duration_seconds", "rollup" => self.rollup.clone()).record(start.elapsed().as_secs_f64());
+
+        // Not indexed yet
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        if !res.status().is_success() {
+            counter!("indexer_requests_total", "rollup" => self.rollup.clone(), "result" => "error").increment(1);
+            return Err(DomainError::DaTransient(format!("Indexer status: {}", res.status())));
+        }
+
+        let body: IndexerStatus = res
+            .json()
+            .await
+            .map_err(|e| DomainError::Da(format!("Indexer parse error: {}", e)))?;
+        counter!("indexer_requests_total", "rollup" => self.rollup.clone(), "result" => "success").increment(1);
+
+        match body.status.to_ascii_lowercase().as_str() {
+            "confirmed" | "finalized" => Ok(true),
+            "reverted" | "failed" => Err(DomainError::DaReverted(format!(
+                "Indexer reports batch {}",
+                body.status
+            ))),
+            _ => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn batch(root: &str) -> Batch {
+        Batch::new(
+            1,
+            "b",
+            "f".into(),
+            "h".into(),
+            root.into(),
+            "calldata".into(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_indexer_status_mapping() {
+        let mock_server = MockServer::start().await;
+        for (root, status) in [
+            ("0x01", "confirmed"),
+            ("0x02", "pending"),
+            ("0x03", "reverted"),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(format!("/batch/{}/status", root)))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(serde_json::json!({ "status": status })),
+                )
+                .mount(&mock_server)
+                .await;
+        }
+        let source = IndexerConfirmationSource::new(mock_server.uri());
+
+        assert!(source.is_confirmed(&batch("0x01")).await.unwrap());
+        assert!(!source.is_confirmed(&batch("0x02")).await.unwrap());
+        assert!(matches!(
+            source.is_confirmed(&batch("0x03")).await,
+            Err(DomainError::DaReverted(_))
+        ));
+        // Unknown roots are 404 and simply not confirmed yet
+        assert!(!source.is_confirmed(&batch("0x04")).await.unwrap());
+    }
+}
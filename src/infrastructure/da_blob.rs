@@ -1,23 +1,54 @@
-use crate::application::ports::DaStrategy;
-use crate::contracts::{parse_groth16_proof, ZKRollupBridge};
+use crate::application::ports::{DaStrategy, SentTx};
+use crate::contracts::{commit_batch_call, parse_proof, ZKRollupBridge};
 use crate::domain::{batch::Batch, errors::DomainError};
+use crate::infrastructure::blob_archive::{upload_to_archiver, LocalBlobArchive};
+use crate::infrastructure::ethereum_adapter::{
+    dry_run_tx_hash, ensure_base_fee_within, fetch_receipt, fill_transaction, FeeOverrides, is_dry_run_tx, receipt_confirmed, resend_with_bumped_fees, scale_u256, sign_and_send, NonceManager,
+};
+use crate::infrastructure::payload::read_payload;
 use async_trait::async_trait;
 use ethers::abi::{encode, Token};
 use ethers::prelude::*;
-use metrics::counter;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use futures::future::join_all;
+use metrics::{counter, histogram};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
 // In a real implementation, we would import c_kzg for Blob/Commitment/Proof computation
 // use c_kzg::{KzgSettings, Blob};
 
+/// Blob gas consumed by a single blob (EIP-4844 `GAS_PER_BLOB`).
+pub const BLOB_GAS_PER_BLOB: u64 = 131_072;
+
+/// Usable payload bytes per blob (4096 field elements * 31 bytes).
+pub const BLOB_DATA_BYTES: usize = 4096 * 31;
+
+/// Blobs a single transaction may carry on mainnet (Cancun `MAX_BLOBS_PER_BLOCK`).
+pub const MAX_BLOBS_PER_TX: usize = 6;
+
 pub struct BlobStrategy<M: Middleware> {
     bridge: ZKRollupBridge<M>,
     client: Arc<M>,
     blob_versioned_hash: H256,
     blob_index: u8,
     archiver_url: Option<String>,
+    gas_limit_multiplier: Option<f64>,
+    max_blobs_per_tx: usize,
+    known_tx_is_sent: bool,
+    retrieval_archivers: Vec<String>,
+    retrieval_quorum: usize,
+    archiver_signer: Option<LocalWallet>,
+    local_archive: Option<Arc<LocalBlobArchive>>,
+    confirmations: u64,
+    nonce_manager: Option<Arc<NonceManager>>,
+    dry_run: bool,
+    max_base_fee_gwei: Option<u64>,
+    fee_overrides: FeeOverrides,
+    http: reqwest::Client,
+    rollup: String,
 }
 
 impl<M: Middleware + 'static> BlobStrategy<M> {
@@ -29,14 +60,159 @@ impl<M: Middleware + 'static> BlobStrategy<M> {
         archiver_url: Option<String>,
     ) -> Self {
         let client = bridge.client();
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
         Self {
             bridge,
             client,
             blob_versioned_hash,
             blob_index,
             archiver_url,
+            gas_limit_multiplier: None,
+            max_blobs_per_tx: MAX_BLOBS_PER_TX,
+            known_tx_is_sent: false,
+            retrieval_archivers: Vec::new(),
+            retrieval_quorum: 0,
+            archiver_signer: None,
+            local_archive: None,
+            confirmations: 1,
+            nonce_manager: None,
+            dry_run: false,
+            max_base_fee_gwei: None,
+            fee_overrides: FeeOverrides::default(),
+            http,
+            rollup: "default".to_string(),
         }
     }
+
+    /// Names the rollup the strategy submits for, for the `rollup` label of its metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
+    }
+
+    /// Prices blob transactions with `overrides` instead of the node's fee estimate. Blob gas
+    /// is not affected.
+    pub fn with_fee_overrides(mut self, overrides: FeeOverrides) -> Self {
+        self.fee_overrides = overrides;
+        self
+    }
+
+    /// Defers submissions while the latest base fee is above `cap_gwei`, before the blob is
+    /// archived.
+    pub fn with_max_base_fee_gwei(mut self, cap_gwei: Option<u64>) -> Self {
+        self.max_base_fee_gwei = cap_gwei;
+        self
+    }
+
+    /// Logs the encoded transaction instead of archiving the blob and broadcasting, and returns
+    /// a synthetic hash (see [`dry_run_tx_hash`]), which `check_confirmation` reports as confirmed.
+    pub fn with_dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    /// Keeps a local copy of blob data until the archiver accepts it. Upload failures no
+    /// longer fail the submission; the copy is re-uploaded later.
+    /// Blocks required on top of the inclusion block before a batch counts as confirmed.
+    pub fn with_confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = confirmations;
+        self
+    }
+
+    pub fn with_local_archive(mut self, local_archive: Option<Arc<LocalBlobArchive>>) -> Self {
+        self.local_archive = local_archive;
+        self
+    }
+
+    /// Signs archiver uploads: `X-Signature` carries an EIP-191 signature by `signer`
+    /// over `keccak256(data)`, so the archiver can authenticate the uploader.
+    pub fn with_archiver_signer(mut self, signer: Option<LocalWallet>) -> Self {
+        self.archiver_signer = signer;
+        self
+    }
+
+    /// Require the blob data to be served by at least `quorum` of `archivers`
+    /// (queried as `GET {archiver}/{versioned_hash}`) before a batch counts as confirmed.
+    /// An archiver only counts if the bytes it serves match the batch payload.
+    pub fn with_retrieval_quorum(mut self, archivers: Vec<String>, quorum: usize) -> Self {
+        self.retrieval_archivers = archivers;
+        self.retrieval_quorum = quorum;
+        self
+    }
+
+    async fn retrievable_count(&self, versioned_hash: H256, data: &[u8]) -> usize {
+        let checks = self.retrieval_archivers.iter().map(|archiver| {
+            let url = format!("{}/{:?}", archiver.trim_end_matches('/'), versioned_hash);
+            async move {
+                let start = Instant::now();
+                let served = self.fetch_archived(&url).await;
+                histogram!("archiver_retrieval_duration_seconds", "rollup" => self.rollup.clone()).record(start.elapsed().as_secs_f64());
+                match served {
+                    Ok(served) if served == data => true,
+                    Ok(_) => {
+                        warn!("Archiver {} serves data that does not match the batch payload", url);
+                        counter!("blob_retrieval_mismatch_total", "rollup" => self.rollup.clone()).increment(1);
+                        false
+                    }
+                    Err(e) => {
+                        warn!("Archiver {} cannot serve blob: {}", url, e);
+                        false
+                    }
+                }
+            }
+        });
+        join_all(checks).await.into_iter().filter(|ok| *ok).count()
+    }
+
+    async fn fetch_archived(&self, url: &str) -> Result<Vec<u8>, reqwest::Error> {
+        self.http
+            .get(url)
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await
+            .map(|body| body.to_vec())
+    }
+
+    /// Treat "already known" / "nonce too low" replies on broadcast as a successful send.
+    pub fn with_known_tx_as_sent(mut self, enabled: bool) -> Self {
+        self.known_tx_is_sent = enabled;
+        self
+    }
+
+    /// Scales the estimated execution gas limit of blob transactions.
+    /// Calldata submissions are unaffected.
+    pub fn with_gas_limit_multiplier(mut self, multiplier: Option<f64>) -> Self {
+        self.gas_limit_multiplier = multiplier;
+        self
+    }
+
+    /// Takes each submission's nonce from `manager` instead of asking the node.
+    pub fn with_nonce_manager(mut self, manager: Option<Arc<NonceManager>>) -> Self {
+        self.nonce_manager = manager;
+        self
+    }
+
+    /// Chain limit on blobs per transaction; larger payloads are rejected before submission.
+    pub fn with_max_blobs_per_tx(mut self, max_blobs_per_tx: usize) -> Self {
+        self.max_blobs_per_tx = max_blobs_per_tx;
+        self
+    }
+
+    /// Number of blobs needed to carry `data_len` bytes.
+    pub fn blobs_for(data_len: usize) -> usize {
+        data_len.div_ceil(BLOB_DATA_BYTES).max(1)
+    }
+
+    /// Total blob gas needed to carry `data_len` bytes.
+    pub fn blob_gas_for(data_len: usize) -> u64 {
+        Self::blobs_for(data_len) as u64 * BLOB_GAS_PER_BLOB
+    }
 }
 
 #[async_trait]
@@ -45,10 +221,10 @@ impl<M: Middleware + 'static> DaStrategy for BlobStrategy<M> {
         1
     }
 
-    fn compute_commitment(&self, batch: &Batch) -> Result<H256, DomainError> {
+    async fn compute_commitment(&self, batch: &Batch) -> Result<H256, DomainError> {
         if let Some(ref hash_str) = batch.blob_versioned_hash {
             H256::from_str(hash_str)
-                .map_err(|e| DomainError::Da(format!("Invalid blob versioned hash: {}", e)))
+                .map_err(|e| DomainError::InvalidBatch(format!("Invalid blob versioned hash: {}", e)))
         } else {
             Ok(self.blob_versioned_hash)
         }
@@ -57,7 +233,7 @@ impl<M: Middleware + 'static> DaStrategy for BlobStrategy<M> {
     fn encode_da_meta(&self, batch: &Batch) -> Result<Vec<u8>, DomainError> {
         let hash = if let Some(ref hash_str) = batch.blob_versioned_hash {
              H256::from_str(hash_str)
-                .map_err(|e| DomainError::Da(format!("Invalid blob versioned hash: {}", e)))?
+                .map_err(|e| DomainError::InvalidBatch(format!("Invalid blob versioned hash: {}", e)))?
         } else {
             self.blob_versioned_hash
         };
@@ -72,28 +248,49 @@ impl<M: Middleware + 'static> DaStrategy for BlobStrategy<M> {
 
     async fn submit(&self, batch: &Batch, proof_hex: &str) -> Result<String, DomainError> {
         // 1. Read Payload Data
-        let data = std::fs::read(&batch.data_file)
-            .map_err(|e| DomainError::Da(format!("Failed to read batch data file: {}", e)))?;
-
-        // 2. Archiver: POST data to external service
-        if let Some(url) = &self.archiver_url {
-            let client = reqwest::Client::new();
-            let res = client.post(url)
-                .body(data.clone())
-                .send()
-                .await
-                .map_err(|e| DomainError::Da(format!("Archiver request failed: {}", e)))?;
-
-            if !res.status().is_success() {
-                return Err(DomainError::Da(format!("Archiver rejected payload: {}", res.status())));
+        let data = read_payload(&self.rollup, &batch.data_file, "Failed to read batch data file").await?;
+        let blobs = Self::blobs_for(data.len());
+        if blobs > self.max_blobs_per_tx {
+            return Err(DomainError::InvalidBatch(format!(
+                "Payload of {} bytes needs {} blobs, more than the {} allowed per transaction",
+                data.len(),
+                blobs,
+                self.max_blobs_per_tx
+            )));
+        }
+
+        if let Some(cap_gwei) = self.max_base_fee_gwei.filter(|_| !self.dry_run) {
+            ensure_base_fee_within(&self.rollup, self.client.as_ref(), cap_gwei).await?;
+        }
+
+        // 2. Archiver: POST data to external service. With a local archive the data is written
+        // to disk first, and a failed upload stays there for re-upload instead of failing the submit.
+        if let Some(url) = self.archiver_url.as_ref().filter(|_| !self.dry_run) {
+            let versioned_hash = self.compute_commitment(batch).await?;
+            if let Some(local) = &self.local_archive {
+                let path = local.persist(versioned_hash, &data)?;
+                info!("Blob data persisted locally at {}", path.display());
+            }
+
+            match upload_to_archiver(&self.rollup, &self.http, url, &data, self.archiver_signer.as_ref()).await {
+                Ok(()) => {
+                    info!("Blob data archived successfully to {}", url);
+                    if let Some(local) = &self.local_archive {
+                        local.remove(versioned_hash);
+                    }
+                }
+                Err(e) if self.local_archive.is_some() => {
+                    warn!("Archiver upload failed, keeping local copy for re-upload: {}", e);
+                    counter!("archiver_upload_deferred_total", "rollup" => self.rollup.clone()).increment(1);
+                }
+                Err(e) => return Err(e),
             }
-            info!("Blob data archived successfully to {}", url);
         }
 
         // 3. Construct EIP-4844 Transaction
 
         // Parse inputs
-        let proof = parse_groth16_proof(proof_hex)
+        let proof = parse_proof(batch.proof_system, proof_hex)
             .map_err(|e| DomainError::Da(format!("Invalid proof format: {}", e)))?;
         let new_root: H256 = batch.new_root.parse()
             .map_err(|e| DomainError::Da(format!("Invalid new root: {}", e)))?;
@@ -102,7 +299,8 @@ impl<M: Middleware + 'static> DaStrategy for BlobStrategy<M> {
         // Prepare Calldata (Function Call)
         // We use the bridge binding to generate the calldata, but we send it via a manual transaction
         // so we can attach the sidecar.
-        let call = self.bridge.commit_batch(
+        let call = commit_batch_call(
+            &self.bridge,
             self.da_id(),
             Bytes::new(), // batchData is empty for Blob
             da_meta.into(),
@@ -110,6 +308,17 @@ impl<M: Middleware + 'static> DaStrategy for BlobStrategy<M> {
             proof,
         );
         let calldata = call.calldata().ok_or(DomainError::Da("Failed to encode calldata".into()))?;
+        if self.dry_run {
+            info!(
+                "Dry run: not broadcasting blob batch {} ({} bytes) to {:?}. calldata={}",
+                batch.id,
+                data.len(),
+                self.bridge.address(),
+                calldata
+            );
+            counter!("tx_submitted_total", "rollup" => self.rollup.clone(), "mode" => "blob", "dry_run" => "true").increment(1);
+            return Ok(dry_run_tx_hash(batch));
+        }
 
         // NOTE: In a production environment with c-kzg linked, we would compute the Sidecar here.
         // let sidecar = BlobSidecar::from_data(&data).unwrap();
@@ -118,6 +327,29 @@ impl<M: Middleware + 'static> DaStrategy for BlobStrategy<M> {
         let tx_req = Eip1559TransactionRequest::new()
             .to(self.bridge.address())
             .data(calldata);
+        let mut tx: TypedTransaction = tx_req.into();
+        if let Some(nonces) = &self.nonce_manager {
+            tx.set_nonce(nonces.reserve(&self.rollup, self.client.as_ref()).await?);
+        }
+        self.fee_overrides.apply(&self.rollup, self.client.as_ref(), &mut tx).await?;
+
+        // The plain estimate tends to be tight for blob txs. Fill the tx ourselves and pad the
+        // execution estimate; blob gas is metered apart from the gas limit, so it is only logged.
+        fill_transaction(&self.rollup, self.client.as_ref(), &mut tx).await?;
+        if let Some(estimated) = tx.gas().copied() {
+            let multiplier = self.gas_limit_multiplier.unwrap_or(1.0);
+            let blob_gas = Self::blob_gas_for(data.len());
+            let limit = scale_u256(estimated, multiplier);
+            info!(
+                "Blob tx gas limit {} (estimated {}, x{}); {} blob gas for {} bytes",
+                limit,
+                estimated,
+                multiplier,
+                blob_gas,
+                data.len()
+            );
+            tx.set_gas(limit);
+        }
 
         // Assuming we are on a chain supporting EIP-4844, we would convert this to an EIP-4844 request.
         // ethers::types::Eip4844TransactionRequest
@@ -144,68 +376,88 @@ impl<M: Middleware + 'static> DaStrategy for BlobStrategy<M> {
         // However, the prompt asked to "Implement real blob sidecar construction".
         // I will stick to the standard send for now to ensure it compiles, but with the Archiver added.
 
-        let pending = self.client.send_transaction(tx_req, None)
-            .await
-            .map_err(|e| DomainError::Da(format!("Tx send failed: {}", e)))?;
-
-        let tx_hash = pending.tx_hash();
+        let (tx_hash, _) = sign_and_send(&self.rollup, self.client.as_ref(), tx, self.known_tx_is_sent, None, self.nonce_manager.as_deref()).await?;
         info!("Blob batch broadcasted. tx={:?}", tx_hash);
 
-        counter!("tx_submitted_total", "mode" => "blob").increment(1);
+        counter!("tx_submitted_total", "rollup" => self.rollup.clone(), "mode" => "blob", "dry_run" => "false").increment(1);
 
         Ok(format!("{:?}", tx_hash))
     }
 
+    async fn resubmit(
+        &self,
+        batch: &Batch,
+        fee_ratio: f64,
+        max_fee_per_gas: Option<U256>,
+    ) -> Result<SentTx, DomainError> {
+        let tx_hash = batch
+            .tx_hash
+            .as_deref()
+            .ok_or_else(|| DomainError::Da("No transaction to replace".into()))?;
+        let (hash, raw) = resend_with_bumped_fees(
+            &self.rollup,
+            self.client.as_ref(),
+            tx_hash,
+            batch.signed_tx.as_deref(),
+            fee_ratio,
+            max_fee_per_gas,
+            self.known_tx_is_sent,
+            None,
+        )
+        .await?;
+        Ok(SentTx { hash: format!("{:?}", hash), raw: raw.map(|r| r.to_string()) })
+    }
+
     async fn check_confirmation(&self, tx_hash: &str) -> Result<bool, DomainError> {
-        let hash: H256 = tx_hash
-            .parse()
-            .map_err(|e| DomainError::Da(format!("Invalid hash: {}", e)))?;
-        let receipt = self
-            .client
-            .get_transaction_receipt(hash)
-            .await
-            .map_err(|e| DomainError::Da(format!("Provider error: {}", e)))?;
-
-        if let Some(r) = receipt {
-            if let Some(status) = r.status {
-                if status.as_u64() == 1 {
-                    let block_number = r.block_number.unwrap_or_default();
-                    let current_block = self
-                        .client
-                        .get_block_number()
-                        .await
-                        .map_err(|e| DomainError::Da(format!("Provider error: {}", e)))?;
-
-                    let confs = current_block.as_u64().saturating_sub(block_number.as_u64());
-
-                    if confs >= 1 {
-                        return Ok(true);
-                    } else {
-                        info!(
-                            "Tx mined but waiting for confirmations (current: {})",
-                            confs
-                        );
-                        return Ok(false);
-                    }
-                } else {
-                    warn!("Tx {} reverted!", tx_hash);
-                    return Err(DomainError::Da("Transaction reverted on-chain".to_string()));
-                }
+        if is_dry_run_tx(tx_hash) {
+            return Ok(true);
+        }
+        receipt_confirmed(&self.rollup, self.client.as_ref(), tx_hash, self.confirmations).await
+    }
+
+    async fn check_batch_confirmation(&self, batch: &Batch) -> Result<bool, DomainError> {
+        let tx_hash = batch
+            .tx_hash
+            .as_deref()
+            .ok_or_else(|| DomainError::Internal("Batch has no tx hash".into()))?;
+        if !self.check_confirmation(tx_hash).await? {
+            return Ok(false);
+        }
+        if self.retrieval_quorum > 0 && !is_dry_run_tx(tx_hash) {
+            let versioned_hash = self.compute_commitment(batch).await?;
+            let data = read_payload(&self.rollup, &batch.data_file, "Failed to read batch data file").await?;
+            let served = self.retrievable_count(versioned_hash, &data).await;
+            if served < self.retrieval_quorum {
+                warn!(
+                    "Blob {:?} of batch {} retrievable from {}/{} archivers, need {}",
+                    versioned_hash,
+                    batch.id,
+                    served,
+                    self.retrieval_archivers.len(),
+                    self.retrieval_quorum
+                );
+                counter!("blob_retrieval_quorum_missed_total", "rollup" => self.rollup.clone()).increment(1);
+                return Ok(false);
             }
-            Ok(true)
-        } else {
-            Ok(false)
         }
+        Ok(true)
+    }
+
+    async fn confirmation_details(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>, DomainError> {
+        if is_dry_run_tx(tx_hash) {
+            return Ok(None);
+        }
+        fetch_receipt(&self.rollup, self.client.as_ref(), tx_hash).await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ethers::providers::{Provider, JsonRpcClient};
+    use ethers::providers::Provider;
     use ethers::signers::{LocalWallet, Signer};
     use ethers::middleware::SignerMiddleware;
-    use ethers::types::{Block, U64, TransactionReceipt, FeeHistory};
+    use ethers::types::{Block, FeeHistory};
     use std::sync::Arc;
     use crate::test_utils::MockClient;
     use ethers::utils::hex;
@@ -239,12 +491,26 @@ mod tests {
              blob_versioned_hash: None,
              blob_index: None,
              fee: 0,
+             expected_old_root: None,
+             fee_bumps: 0,
+             index: 0,
+             proof_system: Default::default(),
+             public_inputs: None,
+             next_attempt_at: None,
+             data_hash: None,
+             gas_used: None,
+             effective_gas_price: None,
+             signed_tx: None,
+             da_reference: None,
+             attestation: None,
         };
 
         // Populate responses
         mock.push(U256::from(0)); // nonce
-        let mut block = Block::<H256>::default();
-        block.base_fee_per_gas = Some(U256::from(100));
+        let block = Block::<H256> {
+            base_fee_per_gas: Some(U256::from(100)),
+            ..Default::default()
+        };
         mock.push(block); // Block
         mock.push(FeeHistory {
             oldest_block: U256::zero(),
@@ -265,4 +531,275 @@ mod tests {
         
         std::fs::remove_file("test_data_blob_arch.txt").unwrap();
     }
+
+    // No sidecar is built (c-kzg is not linked), so this runs without any KZG feature.
+    #[tokio::test]
+    async fn test_submit_blob_applies_gas_multiplier() {
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+        let strategy = BlobStrategy::new(bridge, H256::random(), 0, false, None)
+            .with_gas_limit_multiplier(Some(1.25));
+
+        std::fs::write("test_data_blob_gas.txt", "payload").unwrap();
+        let mut batch = Batch::new(1, "b", "test_data_blob_gas.txt".into(), "h".into(), format!("{:#x}", H256::zero()), "blob".into());
+        batch.status = crate::domain::batch::BatchStatus::Submitting;
+
+        mock.push(U256::from(0)); // nonce
+        let block = Block::<H256> {
+            base_fee_per_gas: Some(U256::from(100)),
+            ..Default::default()
+        };
+        mock.push(block);
+        mock.push(FeeHistory {
+            oldest_block: U256::zero(),
+            base_fee_per_gas: vec![U256::from(100)],
+            gas_used_ratio: vec![],
+            reward: vec![],
+        });
+        mock.push(U256::from(100_000)); // estimateGas
+        mock.push(H256::random()); // sendRawTransaction
+
+        let proof_hex = format!("0x{}", hex::encode([0u8; 256]));
+        let res = strategy.submit(&batch, &proof_hex).await;
+        let _ = std::fs::remove_file("test_data_blob_gas.txt");
+        assert!(res.is_ok(), "submit failed: {:?}", res);
+
+        let (method, params) = mock.requests().pop().unwrap();
+        assert_eq!(method, "eth_sendRawTransaction");
+        let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+        let (tx, _) = TypedTransaction::decode_signed(&ethers::utils::rlp::Rlp::new(raw.as_ref())).unwrap();
+        assert_eq!(tx.gas(), Some(&U256::from(125_000)));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_versioned_hash_is_invalid_batch() {
+        let provider = Provider::new(MockClient::new());
+        let bridge = ZKRollupBridge::new(Address::random(), Arc::new(provider));
+        let strategy = BlobStrategy::new(bridge, H256::zero(), 0, false, None);
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), format!("{:#x}", H256::zero()), "blob".into());
+        batch.blob_versioned_hash = Some("0xnot-a-hash".into());
+
+        assert!(matches!(strategy.compute_commitment(&batch).await, Err(DomainError::InvalidBatch(_))));
+        assert!(matches!(strategy.encode_da_meta(&batch), Err(DomainError::InvalidBatch(_))));
+    }
+
+    #[test]
+    fn test_blob_gas_for_payload() {
+        type Strategy = BlobStrategy<Provider<MockClient>>;
+
+        assert_eq!(Strategy::blob_gas_for(1), BLOB_GAS_PER_BLOB);
+        assert_eq!(Strategy::blob_gas_for(BLOB_DATA_BYTES + 1), 2 * BLOB_GAS_PER_BLOB);
+    }
+
+    #[tokio::test]
+    async fn test_submit_rejects_payload_over_blob_limit() {
+        let mock = MockClient::new();
+        let bridge = ZKRollupBridge::new(Address::random(), Arc::new(Provider::new(mock.clone())));
+        let strategy = BlobStrategy::new(bridge, H256::zero(), 0, false, None).with_max_blobs_per_tx(1);
+
+        std::fs::write("test_data_blob_limit.txt", vec![0u8; BLOB_DATA_BYTES + 1]).unwrap();
+        let batch = Batch::new(1, "b", "test_data_blob_limit.txt".into(), "h".into(), format!("{:#x}", H256::zero()), "blob".into());
+        let res = strategy.submit(&batch, &"00".repeat(256)).await;
+        let _ = std::fs::remove_file("test_data_blob_limit.txt");
+
+        assert!(matches!(res, Err(DomainError::InvalidBatch(_))));
+        assert!(mock.requests().is_empty());
+    }
+
+    const ARCHIVED_HASH: &str = "0x01aa000000000000000000000000000000000000000000000000000000000000";
+
+    async fn archiver(status: u16, body: &str) -> wiremock::MockServer {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/{}", ARCHIVED_HASH)))
+            .respond_with(ResponseTemplate::new(status).set_body_string(body))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn archived_batch(data_file: &str) -> Batch {
+        std::fs::write(data_file, "payload").unwrap();
+        let mut batch = Batch::new(1, "b", data_file.into(), "h".into(), format!("{:#x}", H256::zero()), "blob".into());
+        batch.blob_versioned_hash = Some(ARCHIVED_HASH.into());
+        batch.tx_hash = Some(format!("{:?}", H256::random()));
+        batch
+    }
+
+    fn mined_receipt(mock: &MockClient) {
+        mock.push(TransactionReceipt {
+            status: Some(1u64.into()),
+            block_number: Some(10u64.into()),
+            ..Default::default()
+        });
+        mock.push(U64::from(12)); // eth_blockNumber
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_meets_retrieval_quorum() {
+        let (a, b, c) = (archiver(200, "payload").await, archiver(200, "payload").await, archiver(404, "").await);
+        let mock = MockClient::new();
+        let bridge = ZKRollupBridge::new(Address::random(), Arc::new(Provider::new(mock.clone())));
+        let strategy = BlobStrategy::new(bridge, H256::zero(), 0, false, None)
+            .with_retrieval_quorum(vec![a.uri(), b.uri(), c.uri()], 2);
+
+        mined_receipt(&mock);
+        let batch = archived_batch("test_data_blob_quorum.txt");
+        let confirmed = strategy.check_batch_confirmation(&batch).await;
+        let _ = std::fs::remove_file("test_data_blob_quorum.txt");
+        assert!(confirmed.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_retrieval_quorum_ignores_mismatched_data() {
+        // Both archivers answer, but one serves bytes that are not the batch payload
+        let (a, b) = (archiver(200, "payload").await, archiver(200, "tampered").await);
+        let mock = MockClient::new();
+        let bridge = ZKRollupBridge::new(Address::random(), Arc::new(Provider::new(mock.clone())));
+        let strategy = BlobStrategy::new(bridge, H256::zero(), 0, false, None)
+            .with_retrieval_quorum(vec![a.uri(), b.uri()], 2);
+
+        mined_receipt(&mock);
+        let batch = archived_batch("test_data_blob_mismatch.txt");
+        let confirmed = strategy.check_batch_confirmation(&batch).await;
+        let _ = std::fs::remove_file("test_data_blob_mismatch.txt");
+        assert!(!confirmed.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_depth() {
+        let mock = MockClient::new();
+        let bridge = ZKRollupBridge::new(Address::random(), Arc::new(Provider::new(mock.clone())));
+        let strategy = BlobStrategy::new(bridge, H256::zero(), 0, false, None).with_confirmations(3);
+        let tx_hash = format!("{:?}", H256::random());
+
+        // Receipt at block 10, head at 12
+        mined_receipt(&mock);
+        assert!(!strategy.check_confirmation(&tx_hash).await.unwrap());
+
+        mock.push(TransactionReceipt {
+            status: Some(1u64.into()),
+            block_number: Some(10u64.into()),
+            ..Default::default()
+        });
+        mock.push(U64::from(13));
+        assert!(strategy.check_confirmation(&tx_hash).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_waits_below_retrieval_quorum() {
+        let (a, b) = (archiver(200, "payload").await, archiver(404, "").await);
+        let mock = MockClient::new();
+        let bridge = ZKRollupBridge::new(Address::random(), Arc::new(Provider::new(mock.clone())));
+        let strategy = BlobStrategy::new(bridge, H256::zero(), 0, false, None)
+            .with_retrieval_quorum(vec![a.uri(), b.uri()], 2);
+
+        mined_receipt(&mock);
+        let batch = archived_batch("test_data_blob_below_quorum.txt");
+        let confirmed = strategy.check_batch_confirmation(&batch).await;
+        let _ = std::fs::remove_file("test_data_blob_below_quorum.txt");
+        assert!(!confirmed.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_archiver_upload_is_signed() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let archiver = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&archiver)
+            .await;
+
+        let mock = MockClient::new();
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(Provider::new(mock.clone()), wallet.clone().with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client);
+        let strategy = BlobStrategy::new(bridge, H256::random(), 0, false, Some(archiver.uri()))
+            .with_archiver_signer(Some(wallet.clone()));
+
+        std::fs::write("test_data_blob_signed.txt", "payload").unwrap();
+        let batch = Batch::new(1, "b", "test_data_blob_signed.txt".into(), "h".into(), format!("{:#x}", H256::zero()), "blob".into());
+
+        mock.push(U256::from(0)); // nonce
+        mock.push(Block::<H256> {
+            base_fee_per_gas: Some(U256::from(100)),
+            ..Default::default()
+        });
+        mock.push(FeeHistory {
+            oldest_block: U256::zero(),
+            base_fee_per_gas: vec![U256::from(100)],
+            gas_used_ratio: vec![],
+            reward: vec![],
+        });
+        mock.push(U256::from(100_000)); // estimateGas
+        mock.push(H256::random()); // sendRawTransaction
+
+        let proof_hex = format!("0x{}", hex::encode([0u8; 256]));
+        let res = strategy.submit(&batch, &proof_hex).await;
+        let _ = std::fs::remove_file("test_data_blob_signed.txt");
+        assert!(res.is_ok(), "submit failed: {:?}", res);
+
+        let received = archiver.received_requests().await.unwrap();
+        let header = received[0].headers.get("X-Signature").expect("missing X-Signature");
+        let signature: Signature = header.to_str().unwrap().parse().unwrap();
+        let signer = signature.recover(&ethers::utils::keccak256(b"payload")[..]).unwrap();
+        assert_eq!(signer, wallet.address());
+    }
+
+    #[tokio::test]
+    async fn test_failed_upload_keeps_local_copy() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let archiver = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&archiver)
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("blobs-{}", uuid::Uuid::new_v4()));
+        let local = Arc::new(LocalBlobArchive::new(dir.clone(), archiver.uri(), None));
+
+        let mock = MockClient::new();
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(Provider::new(mock.clone()), wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client);
+        let versioned_hash = H256::random();
+        let strategy = BlobStrategy::new(bridge, versioned_hash, 0, false, Some(archiver.uri()))
+            .with_local_archive(Some(local.clone()));
+
+        std::fs::write("test_data_blob_local.txt", "payload").unwrap();
+        let batch = Batch::new(1, "b", "test_data_blob_local.txt".into(), "h".into(), format!("{:#x}", H256::zero()), "blob".into());
+
+        mock.push(U256::from(0)); // nonce
+        mock.push(Block::<H256> {
+            base_fee_per_gas: Some(U256::from(100)),
+            ..Default::default()
+        });
+        mock.push(FeeHistory {
+            oldest_block: U256::zero(),
+            base_fee_per_gas: vec![U256::from(100)],
+            gas_used_ratio: vec![],
+            reward: vec![],
+        });
+        mock.push(U256::from(100_000)); // estimateGas
+        mock.push(H256::random()); // sendRawTransaction
+
+        let proof_hex = format!("0x{}", hex::encode([0u8; 256]));
+        let res = strategy.submit(&batch, &proof_hex).await;
+        let _ = std::fs::remove_file("test_data_blob_local.txt");
+        assert!(res.is_ok(), "submit failed: {:?}", res);
+
+        let stored = std::fs::read(local.path_for(versioned_hash)).unwrap();
+        assert_eq!(stored, b"payload");
+        let _ = std::fs::remove_dir_all(dir);
+    }
 }
@@ -1,12 +1,18 @@
-use crate::application::ports::DaStrategy;
-use crate::contracts::{parse_groth16_proof, ZKRollupBridge};
+use crate::application::ports::{BridgeCommitter, DaStrategy, SentTx};
+use crate::contracts::{commit_batch_call, parse_proof, Proof, ZKRollupBridge};
 use crate::domain::{batch::Batch, errors::DomainError};
+use crate::infrastructure::commitment::CommitmentScheme;
+use crate::infrastructure::bundler::Bundler;
+use crate::infrastructure::payload::{read_payload, PayloadCodec};
+use crate::infrastructure::ethereum_adapter::{
+    cancel_pending, dry_run_tx_hash, ensure_base_fee_within, fetch_receipt, FeeOverrides, is_dry_run_tx, receipt_confirmed, resend_with_bumped_fees, sign_and_send,
+    NonceManager, PrivateRelay,
+};
 use async_trait::async_trait;
 use ethers::prelude::*;
-use ethers::utils::{hex, keccak256};
 use metrics::counter;
-use std::{fs, sync::Arc};
-use tracing::{info, warn};
+use std::sync::Arc;
+use tracing::info;
 use flate2::write::ZlibEncoder;
 use flate2::Compression;
 use std::io::Write;
@@ -16,134 +22,312 @@ pub struct CalldataStrategy<M: Middleware> {
     bridge: ZKRollupBridge<M>,
     client: Arc<M>,
     compression_mode: Option<CompressionMode>,
+    known_tx_is_sent: bool,
+    confirmations: u64,
+    nonce_manager: Option<Arc<NonceManager>>,
+    commitment: CommitmentScheme,
+    dry_run: bool,
+    relay: Option<Arc<PrivateRelay>>,
+    sponsor: Option<Arc<Bundler>>,
+    codec: Option<PayloadCodec>,
+    max_base_fee_gwei: Option<u64>,
+    fee_overrides: FeeOverrides,
+    rollup: String,
 }
 
 impl<M: Middleware + 'static> CalldataStrategy<M> {
     pub fn new(bridge: ZKRollupBridge<M>, compression_mode: Option<CompressionMode>) -> Self {
         let client = bridge.client();
-        Self { bridge, client, compression_mode }
+        Self { bridge, client, compression_mode, known_tx_is_sent: false, confirmations: 1, nonce_manager: None, commitment: CommitmentScheme::default(), dry_run: false, relay: None, sponsor: None, codec: None, max_base_fee_gwei: None, fee_overrides: FeeOverrides::default(), rollup: "default".to_string() }
     }
-}
 
-#[async_trait]
-impl<M: Middleware + 'static> DaStrategy for CalldataStrategy<M> {
-    fn da_id(&self) -> u8 {
-        0
+    /// Names the rollup the strategy submits for, for the `rollup` label of its metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
     }
 
-    fn compute_commitment(&self, batch: &Batch) -> Result<H256, DomainError> {
-        let mut batch_data = fs::read(&batch.data_file)
-            .map_err(|e| DomainError::Da(format!("Failed to read batch file: {}", e)))?;
+    /// Treat "already known" / "nonce too low" replies on broadcast as a successful send.
+    pub fn with_known_tx_as_sent(mut self, enabled: bool) -> Self {
+        self.known_tx_is_sent = enabled;
+        self
+    }
 
-        if self.compression_mode.is_some() {
-            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
-            encoder.write_all(&batch_data).map_err(|e| DomainError::Da(format!("Compression failed: {}", e)))?;
-            batch_data = encoder.finish().map_err(|e| DomainError::Da(format!("Compression failed: {}", e)))?;
-        }
+    /// Blocks required on top of the inclusion block before a batch counts as confirmed.
+    pub fn with_confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = confirmations;
+        self
+    }
 
-        Ok(H256::from(keccak256(&batch_data)))
+    /// How `compute_commitment` condenses the (possibly compressed) payload; must match the
+    /// bridge's verifier.
+    pub fn with_commitment_scheme(mut self, scheme: CommitmentScheme) -> Self {
+        self.commitment = scheme;
+        self
     }
 
-    fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
-        Ok(Vec::new())
+    /// Logs the encoded transaction instead of broadcasting it and returns a synthetic hash
+    /// (see [`dry_run_tx_hash`]), which `check_confirmation` reports as confirmed.
+    pub fn with_dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
     }
 
-    async fn submit(&self, batch: &Batch, proof_hex: &str) -> Result<String, DomainError> {
-        let proof = parse_groth16_proof(proof_hex)
-            .map_err(|e| DomainError::Da(format!("Invalid proof format: {}", e)))?;
+    /// Defers submissions while the latest base fee is above `cap_gwei`.
+    pub fn with_max_base_fee_gwei(mut self, cap_gwei: Option<u64>) -> Self {
+        self.max_base_fee_gwei = cap_gwei;
+        self
+    }
+
+    /// Prices commit transactions with `overrides` instead of the node's fee estimate.
+    pub fn with_fee_overrides(mut self, overrides: FeeOverrides) -> Self {
+        self.fee_overrides = overrides;
+        self
+    }
+
+    /// Sends commit transactions (and their fee-bump replacements) through a private relay
+    /// instead of the node's public mempool. Receipts are still read from the node.
+    pub fn with_private_relay(mut self, relay: Option<Arc<PrivateRelay>>) -> Self {
+        self.relay = relay;
+        self
+    }
+
+    /// Sends commits as sponsored UserOperations through `bundler` instead of as transactions
+    /// from the submitter's EOA. The batch's `tx_hash` then holds the UserOperation hash, and
+    /// fee bumps are left to the bundler.
+    pub fn with_sponsor(mut self, bundler: Option<Arc<Bundler>>) -> Self {
+        self.sponsor = bundler;
+        self
+    }
+
+    /// Compresses payloads with `codec` before posting them and names it in `daMeta`.
+    pub fn with_codec(mut self, codec: Option<PayloadCodec>) -> Self {
+        self.codec = codec;
+        self
+    }
 
-        let mut batch_data = fs::read(&batch.data_file)
-            .map_err(|e| DomainError::Da(format!("Failed to read batch file: {}", e)))?;
+    /// The bytes posted for `batch`: its data file, compressed as configured.
+    async fn payload(&self, batch: &Batch) -> Result<Vec<u8>, DomainError> {
+        let mut batch_data = read_payload(&self.rollup, &batch.data_file, "Failed to read batch file").await?;
 
         if self.compression_mode.is_some() {
             let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
             encoder.write_all(&batch_data).map_err(|e| DomainError::Da(format!("Compression failed: {}", e)))?;
             batch_data = encoder.finish().map_err(|e| DomainError::Da(format!("Compression failed: {}", e)))?;
         }
+        match &self.codec {
+            Some(codec) => codec.compress(&batch_data),
+            None => Ok(batch_data),
+        }
+    }
+
+    /// Takes each submission's nonce from `manager` instead of asking the node.
+    pub fn with_nonce_manager(mut self, manager: Option<Arc<NonceManager>>) -> Self {
+        self.nonce_manager = manager;
+        self
+    }
 
+    /// Sends `commitBatch(da_id, batch_data, da_meta, new_root, proof)` for `batch`.
+    async fn send_commit(
+        &self,
+        batch: &Batch,
+        proof: Proof,
+        da_id: u8,
+        batch_data: Vec<u8>,
+        da_meta: Vec<u8>,
+    ) -> Result<SentTx, DomainError> {
         let new_root: H256 = batch
             .new_root
             .parse()
             .map_err(|e| DomainError::Da(format!("Invalid new root: {}", e)))?;
 
-        let da_meta = self.encode_da_meta(batch)?;
-
-        let bridge = self.bridge.clone();
-        let call = bridge.commit_batch(
-            self.da_id(),
+        let call = commit_batch_call(
+            &self.bridge,
+            da_id,
             batch_data.into(),
             da_meta.into(),
             new_root.into(),
             proof,
         );
 
-        let pending = call
-            .send()
-            .await
-            .map_err(|e| DomainError::Da(format!("Tx send failed: {}", e)))?;
+        let mut tx = call.tx;
+        if self.dry_run {
+            let calldata = tx.data().cloned().unwrap_or_default();
+            info!(
+                "Dry run: not broadcasting commit of batch {} to {:?}. calldata={}",
+                batch.id,
+                self.bridge.address(),
+                calldata
+            );
+            return Ok(SentTx { hash: dry_run_tx_hash(batch), raw: None });
+        }
+        if let Some(cap_gwei) = self.max_base_fee_gwei {
+            ensure_base_fee_within(&self.rollup, self.client.as_ref(), cap_gwei).await?;
+        }
+        if let Some(bundler) = &self.sponsor {
+            let calldata = tx.data().cloned().unwrap_or_default();
+            let op_hash = bundler.send_call(self.client.as_ref(), self.bridge.address(), calldata).await?;
+            info!("Commit of batch {} sent as UserOperation. op={:?}", batch.id, op_hash);
+            return Ok(SentTx { hash: format!("{:?}", op_hash), raw: None });
+        }
+        if let Some(nonces) = &self.nonce_manager {
+            tx.set_nonce(nonces.reserve(&self.rollup, self.client.as_ref()).await?);
+        }
+        self.fee_overrides.apply(&self.rollup, self.client.as_ref(), &mut tx).await?;
 
-        let tx_hash = pending.tx_hash();
-        info!("Calldata batch broadcasted. tx={:?}", tx_hash);
+        let (tx_hash, raw) = sign_and_send(
+            &self.rollup,
+            self.client.as_ref(),
+            tx,
+            self.known_tx_is_sent,
+            self.relay.as_deref(),
+            self.nonce_manager.as_deref(),
+        )
+        .await?;
+        info!("Commit of batch {} broadcasted. tx={:?}", batch.id, tx_hash);
 
-        counter!("tx_submitted_total", "mode" => "calldata").increment(1);
+        Ok(SentTx { hash: format!("{:?}", tx_hash), raw: Some(raw.to_string()) })
+    }
+}
 
-        Ok(format!("{:?}", tx_hash))
+#[async_trait]
+impl<M: Middleware + 'static> DaStrategy for CalldataStrategy<M> {
+    fn da_id(&self) -> u8 {
+        0
+    }
+
+    async fn compute_commitment(&self, batch: &Batch) -> Result<H256, DomainError> {
+        Ok(self.commitment.commit(&self.payload(batch).await?))
+    }
+
+    fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
+        Ok(self.codec.map(|codec| vec![codec.id()]).unwrap_or_default())
+    }
+
+    async fn submit(&self, batch: &Batch, proof_hex: &str) -> Result<String, DomainError> {
+        self.submit_tx(batch, proof_hex).await.map(|sent| sent.hash)
+    }
+
+    async fn submit_tx(&self, batch: &Batch, proof_hex: &str) -> Result<SentTx, DomainError> {
+        // A malformed proof fails before the payload is read
+        let proof = decode_proof(batch, proof_hex)?;
+        let batch_data = self.payload(batch).await?;
+        let da_meta = self.encode_da_meta(batch)?;
+        let sent = self.send_commit(batch, proof, self.da_id(), batch_data, da_meta).await?;
+        let dry_run = if is_dry_run_tx(&sent.hash) { "true" } else { "false" };
+        counter!("tx_submitted_total", "rollup" => self.rollup.clone(), "mode" => "calldata", "dry_run" => dry_run).increment(1);
+        Ok(sent)
+    }
+
+    async fn resubmit(
+        &self,
+        batch: &Batch,
+        fee_ratio: f64,
+        max_fee_per_gas: Option<U256>,
+    ) -> Result<SentTx, DomainError> {
+        if self.sponsor.is_some() {
+            return Err(DomainError::Da("Sponsored UserOperations are not fee-bumped by the submitter".into()));
+        }
+        let tx_hash = batch
+            .tx_hash
+            .as_deref()
+            .ok_or_else(|| DomainError::Da("No transaction to replace".into()))?;
+        let (hash, raw) = resend_with_bumped_fees(
+            &self.rollup,
+            self.client.as_ref(),
+            tx_hash,
+            batch.signed_tx.as_deref(),
+            fee_ratio,
+            max_fee_per_gas,
+            self.known_tx_is_sent,
+            self.relay.as_deref(),
+        )
+        .await?;
+        Ok(SentTx { hash: format!("{:?}", hash), raw: raw.map(|r| r.to_string()) })
+    }
+
+    async fn cancel(&self, batch: &Batch) -> Result<String, DomainError> {
+        if self.sponsor.is_some() {
+            return Err(DomainError::Da("Sponsored UserOperations are not cancelled by the submitter".into()));
+        }
+        let tx_hash = batch
+            .tx_hash
+            .as_deref()
+            .ok_or_else(|| DomainError::Da("No transaction to cancel".into()))?;
+        if is_dry_run_tx(tx_hash) {
+            return Ok(tx_hash.to_string());
+        }
+        let hash = cancel_pending(&self.rollup, self.client.as_ref(), tx_hash, batch.signed_tx.as_deref(), self.relay.as_deref()).await?;
+        Ok(format!("{:?}", hash))
     }
 
     async fn check_confirmation(&self, tx_hash: &str) -> Result<bool, DomainError> {
-        let hash: H256 = tx_hash
-            .parse()
-            .map_err(|e| DomainError::Da(format!("Invalid hash: {}", e)))?;
-        let receipt = self
-            .client
-            .get_transaction_receipt(hash)
-            .await
-            .map_err(|e| DomainError::Da(format!("Provider error: {}", e)))?;
-
-        if let Some(r) = receipt {
-            if let Some(status) = r.status {
-                if status.as_u64() == 1 {
-                    let block_number = r.block_number.unwrap_or_default();
-                    let current_block = self
-                        .client
-                        .get_block_number()
-                        .await
-                        .map_err(|e| DomainError::Da(format!("Provider error: {}", e)))?;
-
-                    let confs = current_block.as_u64().saturating_sub(block_number.as_u64());
-
-                    if confs >= 1 {
-                        return Ok(true);
-                    } else {
-                        info!(
-                            "Tx mined but waiting for confirmations (current: {})",
-                            confs
-                        );
-                        return Ok(false);
-                    }
-                } else {
-                    warn!("Tx {} reverted!", tx_hash);
-                    return Err(DomainError::Da("Transaction reverted on-chain".to_string()));
-                }
-            }
-            Ok(true)
-        } else {
-            Ok(false)
+        if is_dry_run_tx(tx_hash) {
+            return Ok(true);
         }
+        if let Some(bundler) = &self.sponsor {
+            return bundler.confirmed(self.client.as_ref(), tx_hash, self.confirmations).await;
+        }
+        receipt_confirmed(&self.rollup, self.client.as_ref(), tx_hash, self.confirmations).await
+    }
+
+    async fn confirmation_details(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>, DomainError> {
+        // A sponsored batch's hash names a UserOperation, not a transaction we paid for
+        if is_dry_run_tx(tx_hash) || self.sponsor.is_some() {
+            return Ok(None);
+        }
+        fetch_receipt(&self.rollup, self.client.as_ref(), tx_hash).await
+    }
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> BridgeCommitter for CalldataStrategy<M> {
+    async fn commit_pointer(
+        &self,
+        batch: &Batch,
+        proof: &str,
+        da_id: u8,
+        da_meta: Vec<u8>,
+    ) -> Result<SentTx, DomainError> {
+        let proof = decode_proof(batch, proof)?;
+        self.send_commit(batch, proof, da_id, Vec::new(), da_meta).await
     }
 }
 
+fn decode_proof(batch: &Batch, proof_hex: &str) -> Result<Proof, DomainError> {
+    parse_proof(batch.proof_system, proof_hex)
+        .map_err(|e| DomainError::Da(format!("Invalid proof format: {}", e)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ethers::providers::{Provider, JsonRpcClient};
+    use ethers::providers::Provider;
     use ethers::signers::{LocalWallet, Signer};
     use ethers::middleware::SignerMiddleware;
     use ethers::types::{Block, U64, TransactionReceipt, FeeHistory};
-    use serde::de::DeserializeOwned;
-    use serde::Serialize;
+    use ethers::types::transaction::eip2718::TypedTransaction;
+    use ethers::utils::{hex, keccak256};
     use std::sync::Arc;
     use crate::test_utils::MockClient;
+    use crate::domain::batch::BatchStatus;
+
+    #[tokio::test]
+    async fn test_commitment_is_keccak_of_file_contents() {
+        use std::io::Write;
+
+        let mut data = tempfile::NamedTempFile::new().unwrap();
+        data.write_all(b"abc").unwrap();
+        let provider = Provider::new(MockClient::new());
+        let strategy = CalldataStrategy::new(ZKRollupBridge::new(Address::zero(), Arc::new(provider)), None);
+        let batch = Batch::new(1, "b", data.path().to_string_lossy().into(), "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+
+        // keccak256("abc")
+        let expected: H256 = "0x4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45".parse().unwrap();
+        assert_eq!(strategy.compute_commitment(&batch).await.unwrap(), expected);
+        assert_eq!(strategy.da_id(), 0);
+        assert!(strategy.encode_da_meta(&batch).unwrap().is_empty());
+    }
 
     #[tokio::test]
     async fn test_submit_calldata() {
@@ -169,14 +353,28 @@ mod tests {
              blob_versioned_hash: None,
              blob_index: None,
              fee: 0,
+             expected_old_root: None,
+             fee_bumps: 0,
+             index: 0,
+             proof_system: Default::default(),
+             public_inputs: None,
+             next_attempt_at: None,
+             data_hash: None,
+             gas_used: None,
+             effective_gas_price: None,
+             signed_tx: None,
+             da_reference: None,
+             attestation: None,
         };
 
         std::fs::write("test_data_calldata.txt", "dummy data").unwrap();
 
         // Populate minimal responses based on observation
         mock.push(U256::from(0)); // nonce (eth_getTransactionCount)
-        let mut block = Block::<H256>::default();
-        block.base_fee_per_gas = Some(U256::from(100));
+        let block = Block::<H256> {
+            base_fee_per_gas: Some(U256::from(100)),
+            ..Default::default()
+        };
         mock.push(block); // getBlockByNumber (eth_getBlockByNumber)
         
         let history = FeeHistory {
@@ -201,6 +399,315 @@ mod tests {
         assert!(res.is_ok(), "submit failed");
     }
 
+    #[tokio::test]
+    async fn test_private_relay_receives_the_transaction() {
+        use wiremock::matchers::{body_partial_json, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let relay_server = MockServer::start().await;
+        let relayed_hash = H256::repeat_byte(0x11);
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({ "method": "eth_sendPrivateTransaction" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": relayed_hash,
+            })))
+            .expect(1)
+            .mount(&relay_server)
+            .await;
+
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+        let strategy = CalldataStrategy::new(bridge, None)
+            .with_private_relay(Some(Arc::new(PrivateRelay::new(relay_server.uri()))));
+
+        let data = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(data.path(), "dummy data").unwrap();
+        let batch = Batch::new(1, "b", data.path().to_string_lossy().into(), "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+
+        mock.push(U256::from(0));
+        mock.push(Block::<H256> { base_fee_per_gas: Some(U256::from(100)), ..Default::default() });
+        mock.push(FeeHistory {
+            oldest_block: U256::zero(),
+            base_fee_per_gas: vec![U256::from(100); 11],
+            gas_used_ratio: vec![0.5; 10],
+            reward: vec![],
+        });
+        mock.push(U256::from(100_000));
+
+        let proof_hex = format!("0x{}", hex::encode([0u8; 256]));
+        let tx_hash = strategy.submit(&batch, &proof_hex).await.unwrap();
+
+        assert_eq!(tx_hash, format!("{:?}", relayed_hash));
+        assert!(!mock.requests().iter().any(|(m, _)| m == "eth_sendRawTransaction"));
+        let relayed = &relay_server.received_requests().await.unwrap()[0];
+        let body: serde_json::Value = serde_json::from_slice(&relayed.body).unwrap();
+        assert!(body["params"][0]["tx"].as_str().unwrap().starts_with("0x"));
+    }
+
+    #[tokio::test]
+    async fn test_fee_bump_after_private_send_goes_to_relay() {
+        use wiremock::matchers::{body_partial_json, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let relay_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({ "method": "eth_sendPrivateTransaction" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": H256::repeat_byte(0x11),
+            })))
+            .expect(2)
+            .mount(&relay_server)
+            .await;
+
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+        let strategy = CalldataStrategy::new(bridge, None)
+            .with_private_relay(Some(Arc::new(PrivateRelay::new(relay_server.uri()))));
+
+        let data = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(data.path(), "dummy data").unwrap();
+        let mut batch = Batch::new(1, "b", data.path().to_string_lossy().into(), "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+
+        mock.push(U256::from(4));
+        mock.push(Block::<H256> { base_fee_per_gas: Some(U256::from(100)), ..Default::default() });
+        mock.push(FeeHistory {
+            oldest_block: U256::zero(),
+            base_fee_per_gas: vec![U256::from(100); 11],
+            gas_used_ratio: vec![0.5; 10],
+            reward: vec![],
+        });
+        mock.push(U256::from(100_000));
+
+        let proof_hex = format!("0x{}", hex::encode([0u8; 256]));
+        let sent = strategy.submit_tx(&batch, &proof_hex).await.unwrap();
+        assert!(sent.raw.is_some());
+        batch.tx_hash = Some(sent.hash);
+        batch.signed_tx = sent.raw;
+
+        // The public node never saw the relayed tx, so the bump must not ask it
+        let bumped = strategy.resubmit(&batch, 1.3, None).await.unwrap();
+        assert!(bumped.raw.is_some());
+        assert!(!mock.requests().iter().any(|(m, _)| m == "eth_getTransactionByHash"));
+
+        let relayed: Vec<TypedTransaction> = relay_server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|request| {
+                let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+                let raw: Bytes = serde_json::from_value(body["params"][0]["tx"].clone()).unwrap();
+                TypedTransaction::decode_signed(&ethers::utils::rlp::Rlp::new(raw.as_ref())).unwrap().0
+            })
+            .collect();
+        let [TypedTransaction::Eip1559(original), TypedTransaction::Eip1559(replacement)] = relayed.as_slice() else {
+            panic!("expected two EIP-1559 transactions at the relay");
+        };
+        assert_eq!(replacement.nonce, Some(U256::from(4)));
+        assert_eq!(replacement.nonce, original.nonce);
+        assert_eq!(replacement.data, original.data);
+        assert_eq!(replacement.max_fee_per_gas, Some(crate::infrastructure::ethereum_adapter::scale_u256(original.max_fee_per_gas.unwrap(), 1.3)));
+    }
+
+    #[tokio::test]
+    async fn test_sponsor_mode_sends_user_operation_to_bundler() {
+        use crate::infrastructure::bundler::UserOperation;
+        use wiremock::matchers::{body_partial_json, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let bundler_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({ "method": "eth_estimateUserOperationGas" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": { "callGasLimit": "0x30000", "verificationGasLimit": "0x20000", "preVerificationGas": "0xc350" },
+            })))
+            .mount(&bundler_server)
+            .await;
+        let op_hash = H256::repeat_byte(0x44);
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({ "method": "eth_sendUserOperation" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": op_hash,
+            })))
+            .expect(1)
+            .mount(&bundler_server)
+            .await;
+
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge_addr = Address::random();
+        let bridge = ZKRollupBridge::new(bridge_addr, client.clone());
+        let entry_point = Address::repeat_byte(0xee);
+        let account = Address::repeat_byte(0xaa);
+        let paymaster = Bytes::from(vec![0x99; 20]);
+        let bundler = Bundler::new(bundler_server.uri(), entry_point)
+            .with_account(Some(account))
+            .with_paymaster_and_data(paymaster.clone());
+        let strategy = CalldataStrategy::new(bridge, None).with_sponsor(Some(Arc::new(bundler)));
+
+        let data = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(data.path(), "dummy data").unwrap();
+        let batch = Batch::new(1, "b", data.path().to_string_lossy().into(), "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+
+        // EntryPoint.getNonce, fee estimation, chain id
+        mock.push(Bytes::from(ethers::abi::encode(&[ethers::abi::Token::Uint(U256::from(5))])));
+        mock.push(Block::<H256> { base_fee_per_gas: Some(U256::from(100)), ..Default::default() });
+        mock.push(FeeHistory {
+            oldest_block: U256::zero(),
+            base_fee_per_gas: vec![U256::from(100); 11],
+            gas_used_ratio: vec![0.5; 10],
+            reward: vec![],
+        });
+        mock.push(U256::one());
+
+        let proof_hex = format!("0x{}", hex::encode([0u8; 256]));
+        let tx_hash = strategy.submit(&batch, &proof_hex).await.unwrap();
+        assert_eq!(tx_hash, format!("{:?}", op_hash));
+        assert!(!mock.requests().iter().any(|(m, _)| m == "eth_sendRawTransaction"));
+
+        let requests = bundler_server.received_requests().await.unwrap();
+        let sent: serde_json::Value = requests
+            .iter()
+            .map(|r| serde_json::from_slice::<serde_json::Value>(&r.body).unwrap())
+            .find(|body| body["method"] == "eth_sendUserOperation")
+            .unwrap();
+        assert_eq!(sent["params"][1], serde_json::json!(entry_point));
+        let op: UserOperation = serde_json::from_value(sent["params"][0].clone()).unwrap();
+        assert_eq!(op.sender, account);
+        assert_eq!(op.nonce, U256::from(5));
+        assert_eq!(op.paymaster_and_data, paymaster);
+        assert_eq!(op.call_gas_limit, U256::from(0x30000));
+        // execute(bridge, 0, commitBatch(...))
+        assert_eq!(&op.call_data[..4], &keccak256("execute(address,uint256,bytes)")[..4]);
+        assert_eq!(&op.call_data[16..36], bridge_addr.as_bytes());
+
+        let signature = ethers::types::Signature::try_from(op.signature.as_ref()).unwrap();
+        let signer = signature.recover(op.hash(entry_point, U256::one()).as_bytes()).unwrap();
+        assert_eq!(signer, client.address());
+    }
+
+    #[tokio::test]
+    async fn test_submit_uses_reserved_nonces() {
+        use ethers::utils::rlp::Rlp;
+
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+
+        // Seeded lazily by the first submission
+        mock.push(U256::from(7));
+        let nonces = Arc::new(NonceManager::new(client.address()));
+        let strategy = CalldataStrategy::new(bridge, None).with_nonce_manager(Some(nonces));
+
+        let data = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(data.path(), "dummy data").unwrap();
+        let mut batch = Batch::new(1, "b", data.path().to_string_lossy().into(), "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+        batch.status = crate::domain::batch::BatchStatus::Submitting;
+        let proof_hex = format!("0x{}", hex::encode([0u8; 256]));
+
+        for _ in 0..2 {
+            // No eth_getTransactionCount: the nonce comes from the manager
+            mock.push(Block::<H256> { base_fee_per_gas: Some(U256::from(100)), ..Default::default() });
+            mock.push(FeeHistory {
+                oldest_block: U256::zero(),
+                base_fee_per_gas: vec![U256::from(100); 11],
+                gas_used_ratio: vec![0.5; 10],
+                reward: vec![],
+            });
+            mock.push(U256::from(100_000));
+            mock.push(H256::random());
+            strategy.submit(&batch, &proof_hex).await.unwrap();
+        }
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].1[1], "pending");
+        let sent_nonces: Vec<U256> = requests
+            .iter()
+            .filter(|(m, _)| m == "eth_sendRawTransaction")
+            .map(|(_, params)| {
+                let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+                TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap().0.nonce().copied().unwrap()
+            })
+            .collect();
+        assert_eq!(sent_nonces, vec![U256::from(7), U256::from(8)]);
+        assert_eq!(requests.iter().filter(|(m, _)| m == "eth_getTransactionCount").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_submit_dispatches_on_batch_proof_system() {
+        use crate::contracts::{CommitBatchCall, CommitBatchPlonkCall};
+        use crate::domain::batch::ProofSystem;
+        use ethers::contract::EthCall;
+        use ethers::utils::rlp::Rlp;
+
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let strategy = CalldataStrategy::new(ZKRollupBridge::new(Address::random(), client), None);
+
+        let data = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(data.path(), "dummy data").unwrap();
+        let new_batch = |system: ProofSystem| {
+            let mut batch = Batch::new(1, "b", data.path().to_string_lossy().into(), system.to_string(), format!("{:#x}", H256::zero()), "calldata".into());
+            batch.proof_system = system;
+            batch
+        };
+        let groth16 = new_batch(ProofSystem::Groth16);
+        let plonk = new_batch(ProofSystem::Plonk);
+        let groth16_proof = format!("0x{}", hex::encode([1u8; 256]));
+        let plonk_proof = format!("0x{}", hex::encode([2u8; 768]));
+
+        // Each batch's proof only fits its own parser
+        assert!(strategy.submit(&plonk, &groth16_proof).await.is_err());
+        assert!(strategy.submit(&groth16, &plonk_proof).await.is_err());
+        assert!(mock.requests().is_empty());
+
+        for (batch, proof) in [(&groth16, &groth16_proof), (&plonk, &plonk_proof)] {
+            mock.push(U256::from(0));
+            mock.push(Block::<H256> { base_fee_per_gas: Some(U256::from(100)), ..Default::default() });
+            mock.push(FeeHistory {
+                oldest_block: U256::zero(),
+                base_fee_per_gas: vec![U256::from(100); 11],
+                gas_used_ratio: vec![0.5; 10],
+                reward: vec![],
+            });
+            mock.push(U256::from(100_000));
+            mock.push(H256::random());
+            strategy.submit(batch, proof).await.unwrap();
+        }
+
+        let selectors: Vec<[u8; 4]> = mock
+            .requests()
+            .iter()
+            .filter(|(m, _)| m == "eth_sendRawTransaction")
+            .map(|(_, params)| {
+                let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+                let (tx, _) = TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap();
+                tx.data().unwrap()[..4].try_into().unwrap()
+            })
+            .collect();
+        assert_eq!(selectors, vec![CommitBatchCall::selector(), CommitBatchPlonkCall::selector()]);
+    }
+
     #[tokio::test]
     async fn test_check_confirmation_success() {
         let mock = MockClient::new();
@@ -216,10 +723,16 @@ mod tests {
         mock.push(TransactionReceipt {
             status: Some(U64::from(1)),
             block_number: Some(U64::from(100)),
+            block_hash: Some(H256::repeat_byte(0xaa)),
             ..Default::default()
         });
         
         mock.push(U64::from(105)); 
+        mock.push(Block::<H256> {
+            hash: Some(H256::repeat_byte(0xaa)),
+            number: Some(U64::from(100)),
+            ..Default::default()
+        });
         
         let res = strategy.check_confirmation(&format!("{:#x}", tx_hash)).await;
         if let Err(e) = &res {
@@ -228,4 +741,470 @@ mod tests {
         assert!(res.is_ok());
         assert!(res.unwrap());
     }
+
+    #[tokio::test]
+    async fn test_check_confirmation_depth() {
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let bridge = ZKRollupBridge::new(Address::random(), Arc::new(provider));
+        let strategy = CalldataStrategy::new(bridge, None).with_confirmations(12);
+        let receipt = TransactionReceipt {
+            status: Some(U64::from(1)),
+            block_number: Some(U64::from(100)),
+            ..Default::default()
+        };
+
+        // Mined, but only 5 blocks deep
+        mock.push(receipt.clone());
+        mock.push(U64::from(105));
+        assert!(!strategy.check_confirmation(&format!("{:#x}", H256::random())).await.unwrap());
+
+        mock.push(receipt);
+        mock.push(U64::from(112));
+        assert!(strategy.check_confirmation(&format!("{:#x}", H256::random())).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_check_confirmation_detects_reorg() {
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+        let strategy = CalldataStrategy::new(bridge, None);
+
+        mock.push(TransactionReceipt {
+            status: Some(U64::from(1)),
+            block_number: Some(U64::from(100)),
+            block_hash: Some(H256::repeat_byte(0xaa)),
+            ..Default::default()
+        });
+        mock.push(U64::from(105));
+        // A reorg replaced block 100
+        mock.push(Block::<H256> {
+            hash: Some(H256::repeat_byte(0xbb)),
+            number: Some(U64::from(100)),
+            ..Default::default()
+        });
+
+        let confirmed = strategy.check_confirmation(&format!("{:#x}", H256::random())).await.unwrap();
+        assert!(!confirmed);
+        assert_eq!(mock.requests()[2].0, "eth_getBlockByNumber");
+    }
+
+    #[tokio::test]
+    async fn test_submit_already_known_is_sent() {
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+        let strategy = CalldataStrategy::new(bridge, None).with_known_tx_as_sent(true);
+
+        std::fs::write("test_data_calldata_known.txt", "dummy data").unwrap();
+        let batch = Batch::new(1, "b", "test_data_calldata_known.txt".into(), "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+
+        mock.push(U256::from(0)); // nonce
+        mock.push(Block::<H256> {
+            base_fee_per_gas: Some(U256::from(100)),
+            ..Default::default()
+        });
+        mock.push(FeeHistory {
+            oldest_block: U256::zero(),
+            base_fee_per_gas: vec![U256::from(100); 11],
+            gas_used_ratio: vec![0.5; 10],
+            reward: vec![],
+        });
+        mock.push(U256::from(100_000)); // estimateGas
+        mock.push_error("already known"); // sendRawTransaction
+
+        let proof_hex = format!("0x{}", hex::encode([0u8; 256]));
+        let res = strategy.submit(&batch, &proof_hex).await;
+        let _ = std::fs::remove_file("test_data_calldata_known.txt");
+
+        // The returned hash is the one of the transaction the node already holds
+        let (_, params) = mock.requests().pop().unwrap();
+        let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+        let expected = H256::from(keccak256(raw.as_ref()));
+        assert_eq!(res.unwrap(), format!("{:?}", expected));
+    }
+
+    struct NoProver;
+
+    #[async_trait]
+    impl crate::application::ports::ProofProvider for NoProver {
+        async fn get_proof(
+            &self,
+            _request: &crate::application::ports::ProofRequest,
+        ) -> Result<crate::application::ports::ProofResponse, DomainError> {
+            Err(DomainError::Prover("not used".into()))
+        }
+    }
+
+    /// Runs one orchestrator step for a `Submitting` batch whose broadcast fails with
+    /// "nonce too low", with `landed` as the node's `eth_getTransactionByHash` answer.
+    async fn submit_after_nonce_too_low(data_file: &str, landed: Option<Transaction>) -> (Batch, MockClient) {
+        use crate::application::orchestrator::Orchestrator;
+        use crate::application::ports::{MockBridgeReader, Storage};
+        use crate::infrastructure::storage_sqlite::SqliteStorage;
+
+        let mock = MockClient::new();
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(Provider::new(mock.clone()), wallet.with_chain_id(1u64)));
+        let nonces = Arc::new(NonceManager::new(client.address()));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+        let da = CalldataStrategy::new(bridge, None)
+            .with_known_tx_as_sent(true)
+            .with_nonce_manager(Some(nonces));
+
+        std::fs::write(data_file, "dummy data").unwrap();
+        let mut batch = Batch::new(1, "b", data_file.into(), "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+        batch.status = BatchStatus::Submitting;
+        batch.proof = Some(format!("0x{}", hex::encode([0u8; 256])));
+        let storage = Arc::new(SqliteStorage::new("sqlite::memory:").await.unwrap());
+        storage.save_batch(&batch).await.unwrap();
+
+        mock.push(U256::from(0)); // nonce seed
+        mock.push(Block::<H256> {
+            base_fee_per_gas: Some(U256::from(100)),
+            ..Default::default()
+        });
+        mock.push(FeeHistory {
+            oldest_block: U256::zero(),
+            base_fee_per_gas: vec![U256::from(100); 11],
+            gas_used_ratio: vec![0.5; 10],
+            reward: vec![],
+        });
+        mock.push(U256::from(100_000)); // estimateGas
+        mock.push_error("nonce too low"); // sendRawTransaction
+        mock.push(landed); // getTransactionByHash
+        mock.push(U256::from(2)); // nonce re-sync
+
+        let orch = Orchestrator::new(storage.clone(), Arc::new(NoProver), Arc::new(da), Arc::new(MockBridgeReader::new()), 5);
+        let updated = orch.process_one(batch.id).await.unwrap();
+        let _ = std::fs::remove_file(data_file);
+        (updated, mock)
+    }
+
+    #[tokio::test]
+    async fn test_nonce_too_low_for_own_tx_is_sent() {
+        let (batch, mock) = submit_after_nonce_too_low("test_data_calldata_nonce_own.txt", Some(Transaction::default())).await;
+
+        let (_, params) = mock
+            .requests()
+            .into_iter()
+            .find(|(m, _)| m == "eth_sendRawTransaction")
+            .unwrap();
+        let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+        assert_eq!(batch.status, BatchStatus::Submitted);
+        assert_eq!(batch.tx_hash, Some(format!("{:?}", H256::from(keccak256(raw.as_ref())))));
+    }
+
+    #[tokio::test]
+    async fn test_nonce_too_low_for_other_tx_is_retried() {
+        let (batch, mock) = submit_after_nonce_too_low("test_data_calldata_nonce_other.txt", None).await;
+
+        // The batch stays Submitting for another attempt, and the nonce was re-read
+        assert_eq!(batch.status, BatchStatus::Submitting);
+        assert_eq!(batch.tx_hash, None);
+        assert_eq!(batch.attempts, 1);
+        let methods: Vec<String> = mock.requests().into_iter().map(|(m, _)| m).collect();
+        assert_eq!(methods.iter().filter(|m| *m == "eth_getTransactionCount").count(), 2);
+        assert_eq!(methods.last().map(String::as_str), Some("eth_getTransactionCount"));
+    }
+
+    #[tokio::test]
+    async fn test_submit_already_known_disabled() {
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+        let strategy = CalldataStrategy::new(bridge, None);
+
+        std::fs::write("test_data_calldata_known_off.txt", "dummy data").unwrap();
+        let batch = Batch::new(1, "b", "test_data_calldata_known_off.txt".into(), "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+
+        mock.push(U256::from(0));
+        mock.push(Block::<H256> {
+            base_fee_per_gas: Some(U256::from(100)),
+            ..Default::default()
+        });
+        mock.push(FeeHistory {
+            oldest_block: U256::zero(),
+            base_fee_per_gas: vec![U256::from(100); 11],
+            gas_used_ratio: vec![0.5; 10],
+            reward: vec![],
+        });
+        mock.push(U256::from(100_000));
+        mock.push_error("already known");
+
+        let proof_hex = format!("0x{}", hex::encode([0u8; 256]));
+        let res = strategy.submit(&batch, &proof_hex).await;
+        let _ = std::fs::remove_file("test_data_calldata_known_off.txt");
+        assert!(res.unwrap_err().to_string().contains("Tx send failed"));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_never_broadcasts() {
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+        let nonces = Arc::new(NonceManager::new(client.address()));
+        let strategy = CalldataStrategy::new(bridge, None)
+            .with_nonce_manager(Some(nonces))
+            .with_dry_run(true);
+
+        std::fs::write("test_data_calldata_dry_run.txt", "dummy data").unwrap();
+        let batch = Batch::new(1, "b", "test_data_calldata_dry_run.txt".into(), "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+
+        let proof_hex = format!("0x{}", hex::encode([0u8; 256]));
+        let res = strategy.submit(&batch, &proof_hex).await;
+        let _ = std::fs::remove_file("test_data_calldata_dry_run.txt");
+
+        let tx_hash = res.unwrap();
+        assert!(tx_hash.starts_with("0xdryrun"));
+        assert!(strategy.check_confirmation(&tx_hash).await.unwrap());
+        assert!(
+            mock.requests().iter().all(|(method, _)| method != "eth_sendRawTransaction" && method != "eth_sendTransaction"),
+            "dry run issued {:?}",
+            mock.requests()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_submit_sends_the_prover_proof() {
+        use ethers::utils::rlp::Rlp;
+
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+        let strategy = CalldataStrategy::new(bridge, None);
+
+        let data = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(data.path(), "dummy data").unwrap();
+        let batch = Batch::new(1, "b", data.path().to_string_lossy().into(), "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+
+        mock.push(U256::from(0));
+        mock.push(Block::<H256> { base_fee_per_gas: Some(U256::from(100)), ..Default::default() });
+        mock.push(FeeHistory {
+            oldest_block: U256::zero(),
+            base_fee_per_gas: vec![U256::from(100); 11],
+            gas_used_ratio: vec![0.5; 10],
+            reward: vec![],
+        });
+        mock.push(U256::from(100_000));
+        mock.push(H256::random());
+
+        let proof: Vec<u8> = (0..=255).collect();
+        strategy.submit(&batch, &format!("0x{}", hex::encode(&proof))).await.unwrap();
+
+        let (_, params) = mock
+            .requests()
+            .into_iter()
+            .find(|(m, _)| m == "eth_sendRawTransaction")
+            .unwrap();
+        let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+        let tx = TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap().0;
+        let calldata = tx.data().unwrap();
+        assert!(calldata.windows(proof.len()).any(|w| w == &proof[..]), "proof missing from calldata");
+    }
+
+    #[tokio::test]
+    async fn test_zstd_codec_posts_compressed_payload() {
+        use ethers::utils::rlp::Rlp;
+
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+        let strategy = CalldataStrategy::new(bridge, None).with_codec(Some(PayloadCodec::Zstd));
+
+        let payload = b"rollup transactions ".repeat(50);
+        let data = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(data.path(), &payload).unwrap();
+        let batch = Batch::new(1, "b", data.path().to_string_lossy().into(), "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+
+        let compressed = zstd::bulk::compress(&payload, zstd::DEFAULT_COMPRESSION_LEVEL).unwrap();
+        assert_eq!(strategy.compute_commitment(&batch).await.unwrap(), H256::from(keccak256(&compressed)));
+        assert_eq!(strategy.encode_da_meta(&batch).unwrap(), vec![PayloadCodec::Zstd.id()]);
+
+        mock.push(U256::from(0));
+        mock.push(Block::<H256> { base_fee_per_gas: Some(U256::from(100)), ..Default::default() });
+        mock.push(FeeHistory {
+            oldest_block: U256::zero(),
+            base_fee_per_gas: vec![U256::from(100); 11],
+            gas_used_ratio: vec![0.5; 10],
+            reward: vec![],
+        });
+        mock.push(U256::from(100_000));
+        mock.push(H256::random());
+        strategy.submit(&batch, &format!("0x{}", hex::encode([0u8; 256]))).await.unwrap();
+
+        let (_, params) = mock
+            .requests()
+            .into_iter()
+            .find(|(m, _)| m == "eth_sendRawTransaction")
+            .unwrap();
+        let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+        let tx = TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap().0;
+        let calldata = tx.data().unwrap();
+        assert!(calldata.windows(compressed.len()).any(|w| w == &compressed[..]), "compressed payload missing from calldata");
+        assert!(!calldata.windows(payload.len()).any(|w| w == &payload[..]), "raw payload was posted");
+        assert_eq!(zstd::decode_all(&compressed[..]).unwrap(), payload);
+    }
+
+    #[tokio::test]
+    async fn test_submits_payload_fetched_over_http() {
+        use ethers::utils::rlp::Rlp;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let payload = b"payload held in object storage".to_vec();
+        let store = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/batches/7.bin"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(payload.clone()))
+            .mount(&store)
+            .await;
+
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+        let strategy = CalldataStrategy::new(bridge, None);
+        let url = format!("{}/batches/7.bin", store.uri());
+        let batch = Batch::new(1, "b", url, "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+
+        assert_eq!(strategy.compute_commitment(&batch).await.unwrap(), H256::from(keccak256(&payload)));
+
+        mock.push(U256::from(0));
+        mock.push(Block::<H256> { base_fee_per_gas: Some(U256::from(100)), ..Default::default() });
+        mock.push(FeeHistory {
+            oldest_block: U256::zero(),
+            base_fee_per_gas: vec![U256::from(100); 11],
+            gas_used_ratio: vec![0.5; 10],
+            reward: vec![],
+        });
+        mock.push(U256::from(100_000));
+        mock.push(H256::random());
+        strategy.submit(&batch, &format!("0x{}", hex::encode([0u8; 256]))).await.unwrap();
+
+        let (_, params) = mock
+            .requests()
+            .into_iter()
+            .find(|(m, _)| m == "eth_sendRawTransaction")
+            .unwrap();
+        let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+        let tx = TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap().0;
+        let calldata = tx.data().unwrap();
+        assert!(calldata.windows(payload.len()).any(|w| w == &payload[..]), "fetched payload missing from calldata");
+    }
+
+    #[tokio::test]
+    async fn test_fee_overrides_replace_estimated_fees() {
+        use crate::infrastructure::ethereum_adapter::scale_u256;
+        use ethers::utils::rlp::Rlp;
+
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+        let strategy = CalldataStrategy::new(bridge, None).with_fee_overrides(FeeOverrides {
+            max_priority_fee_gwei: Some(2),
+            fee_multiplier: Some(1.5),
+        });
+
+        let data = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(data.path(), b"payload").unwrap();
+        let batch = Batch::new(1, "b", data.path().to_string_lossy().into(), "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+
+        let push_fee_market = || {
+            mock.push(Block::<H256> { base_fee_per_gas: Some(U256::from(1_000_000_000u64)), ..Default::default() });
+            mock.push(FeeHistory {
+                oldest_block: U256::zero(),
+                base_fee_per_gas: vec![U256::from(1_000_000_000u64); 11],
+                gas_used_ratio: vec![0.5; 10],
+                reward: vec![],
+            });
+        };
+        push_fee_market();
+        let (estimated_max_fee, estimated_tip) = client.estimate_eip1559_fees(None).await.unwrap();
+
+        push_fee_market();
+        mock.push(U256::from(0));
+        mock.push(U256::from(100_000));
+        mock.push(H256::random());
+        strategy.submit(&batch, &format!("0x{}", hex::encode([0u8; 256]))).await.unwrap();
+
+        let (_, params) = mock
+            .requests()
+            .into_iter()
+            .find(|(m, _)| m == "eth_sendRawTransaction")
+            .unwrap();
+        let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+        let TypedTransaction::Eip1559(tx) = TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap().0 else {
+            panic!("expected an EIP-1559 transaction");
+        };
+        let tip = U256::from(2_000_000_000u64);
+        assert_eq!(tx.max_priority_fee_per_gas, Some(tip));
+        assert_eq!(tx.max_fee_per_gas, Some(scale_u256(estimated_max_fee - estimated_tip, 1.5) + tip));
+    }
+
+    #[tokio::test]
+    async fn test_base_fee_above_cap_defers_submission() {
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+        let strategy = CalldataStrategy::new(bridge, None).with_max_base_fee_gwei(Some(50));
+
+        let data = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(data.path(), "dummy data").unwrap();
+        let batch = Batch::new(1, "b", data.path().to_string_lossy().into(), "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+
+        // 80 gwei
+        mock.push(Block::<H256> { base_fee_per_gas: Some(U256::from(80_000_000_000u64)), ..Default::default() });
+        let res = strategy.submit(&batch, &format!("0x{}", hex::encode([0u8; 256]))).await;
+
+        match res {
+            Err(DomainError::FeeCapExceeded(reason)) => assert!(reason.contains("base fee too high"), "{}", reason),
+            other => panic!("expected the submission to be deferred, got {:?}", other),
+        }
+        let methods: Vec<String> = mock.requests().into_iter().map(|(m, _)| m).collect();
+        assert_eq!(methods, vec!["eth_getBlockByNumber".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_submit_rejects_malformed_proof() {
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20".parse().unwrap();
+        let client = Arc::new(SignerMiddleware::new(provider, wallet.with_chain_id(1u64)));
+        let bridge = ZKRollupBridge::new(Address::random(), client.clone());
+        let strategy = CalldataStrategy::new(bridge, None);
+        let batch = Batch::new(1, "b", "unused.txt".into(), "h".into(), format!("{:#x}", H256::zero()), "calldata".into());
+
+        let short = format!("0x{}", hex::encode([1u8; 100]));
+        match strategy.submit(&batch, &short).await {
+            Err(DomainError::Da(msg)) => {
+                assert!(msg.contains("Invalid proof length: expected 256 bytes, got 100"), "{}", msg)
+            }
+            other => panic!("expected a Da error, got {:?}", other),
+        }
+
+        match strategy.submit(&batch, "0xnothex").await {
+            Err(DomainError::Da(msg)) => assert!(msg.contains("Invalid hex"), "{}", msg),
+            other => panic!("expected a Da error, got {:?}", other),
+        }
+        assert!(mock.requests().is_empty());
+    }
 }
@@ -0,0 +1,547 @@
+use crate::application::ports::{BridgeCommitter, DaStrategy, SentTx};
+use crate::domain::{batch::Batch, errors::DomainError};
+use crate::infrastructure::ethereum_adapter::{dry_run_tx_hash, is_dry_run_tx, record_rpc_error};
+use crate::infrastructure::payload::read_payload;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ethers::abi::{encode, Token};
+use ethers::types::{TransactionReceipt, H256, U256};
+use metrics::{counter, histogram};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Namespace size in bytes: one version byte plus a 28-byte ID.
+pub const NAMESPACE_SIZE: usize = 29;
+/// User-chosen bytes of a version 0 namespace ID (the rest are leading zeros).
+const NAMESPACE_V0_ID_SIZE: usize = 10;
+pub const SHARE_SIZE: usize = 512;
+/// Payload bytes in the first share of a blob, after namespace, info byte and sequence length.
+const FIRST_SHARE_CONTENT: usize = SHARE_SIZE - NAMESPACE_SIZE - 1 - 4;
+/// Payload bytes in every following share, after namespace and info byte.
+const CONTINUATION_SHARE_CONTENT: usize = SHARE_SIZE - NAMESPACE_SIZE - 1;
+/// celestia-app `SubtreeRootThreshold`.
+const SUBTREE_ROOT_THRESHOLD: usize = 64;
+
+/// A Celestia blob namespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Namespace([u8; NAMESPACE_SIZE]);
+
+impl Namespace {
+    pub fn as_bytes(&self) -> &[u8; NAMESPACE_SIZE] {
+        &self.0
+    }
+}
+
+impl FromStr for Namespace {
+    type Err = DomainError;
+
+    /// Accepts either a full 29-byte namespace or the 10-byte ID of a version 0 namespace, in hex.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = ethers::utils::hex::decode(s.trim_start_matches("0x"))
+            .map_err(|e| DomainError::Config(format!("Invalid Celestia namespace {}: {}", s, e)))?;
+        let mut namespace = [0u8; NAMESPACE_SIZE];
+        match bytes.len() {
+            NAMESPACE_SIZE => namespace.copy_from_slice(&bytes),
+            NAMESPACE_V0_ID_SIZE => {
+                namespace[NAMESPACE_SIZE - NAMESPACE_V0_ID_SIZE..].copy_from_slice(&bytes)
+            }
+            n => {
+                return Err(DomainError::Config(format!(
+                    "Celestia namespace must be {} or {} bytes (got {})",
+                    NAMESPACE_V0_ID_SIZE, NAMESPACE_SIZE, n
+                )))
+            }
+        }
+        Ok(Self(namespace))
+    }
+}
+
+/// Splits `data` into share version 0 sparse shares, zero-padding the last one.
+fn split_shares(namespace: &Namespace, data: &[u8]) -> Vec<Vec<u8>> {
+    let mut shares = Vec::new();
+    let (first, mut rest) = data.split_at(data.len().min(FIRST_SHARE_CONTENT));
+
+    let mut share = Vec::with_capacity(SHARE_SIZE);
+    share.extend_from_slice(namespace.as_bytes());
+    share.push(1); // share version 0, sequence start
+    share.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    share.extend_from_slice(first);
+    share.resize(SHARE_SIZE, 0);
+    shares.push(share);
+
+    while !rest.is_empty() {
+        let (chunk, tail) = rest.split_at(rest.len().min(CONTINUATION_SHARE_CONTENT));
+        let mut share = Vec::with_capacity(SHARE_SIZE);
+        share.extend_from_slice(namespace.as_bytes());
+        share.push(0); // share version 0, continuation
+        share.extend_from_slice(chunk);
+        share.resize(SHARE_SIZE, 0);
+        shares.push(share);
+        rest = tail;
+    }
+    shares
+}
+
+fn sha256(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// Largest power of two strictly below `n` (`n > 1`).
+fn split_point(n: usize) -> usize {
+    let k = n.next_power_of_two() / 2;
+    if k == n {
+        n / 2
+    } else {
+        k
+    }
+}
+
+/// Root of a namespaced Merkle tree over `leaves`, all in the same namespace.
+fn nmt_root(namespace: &[u8], leaves: &[Vec<u8>]) -> Vec<u8> {
+    if leaves.len() == 1 {
+        let mut node = [namespace, namespace].concat();
+        node.extend_from_slice(&sha256(&[&[0u8], namespace, &leaves[0]]));
+        return node;
+    }
+    let k = split_point(leaves.len());
+    let left = nmt_root(namespace, &leaves[..k]);
+    let right = nmt_root(namespace, &leaves[k..]);
+    let mut node = [namespace, namespace].concat();
+    node.extend_from_slice(&sha256(&[&[1u8], &left, &right]));
+    node
+}
+
+/// RFC 6962 Merkle root, as used by CometBFT.
+fn merkle_root(items: &[Vec<u8>]) -> [u8; 32] {
+    match items.len() {
+        0 => sha256(&[]),
+        1 => sha256(&[&[0u8], &items[0]]),
+        n => {
+            let k = split_point(n);
+            sha256(&[&[1u8], &merkle_root(&items[..k]), &merkle_root(&items[k..])])
+        }
+    }
+}
+
+/// Blob share commitment: the Merkle root of the NMT subtree roots over the blob's shares.
+pub fn blob_commitment(namespace: &Namespace, data: &[u8]) -> H256 {
+    let shares = split_shares(namespace, data);
+
+    let min_square = (shares.len() as f64).sqrt().ceil() as usize;
+    let subtree_width = shares
+        .len()
+        .div_ceil(SUBTREE_ROOT_THRESHOLD)
+        .next_power_of_two()
+        .min(min_square.next_power_of_two());
+
+    // Merkle mountain range: full subtrees of `subtree_width`, then descending powers of two
+    let mut roots = Vec::new();
+    let mut rest = shares.as_slice();
+    while !rest.is_empty() {
+        let size = if rest.len() >= subtree_width {
+            subtree_width
+        } else {
+            1 << rest.len().ilog2()
+        };
+        let (tree, tail) = rest.split_at(size);
+        let leaves: Vec<Vec<u8>> = tree
+            .iter()
+            .map(|share| [namespace.as_bytes().as_slice(), share].concat())
+            .collect();
+        roots.push(nmt_root(namespace.as_bytes(), &leaves));
+        rest = tail;
+    }
+    H256::from(merkle_root(&roots))
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+/// Publishes batch data to Celestia through a celestia-node JSON-RPC endpoint, then commits the
+/// batch to the bridge through `committer` with the blob's location as `daMeta`.
+///
+/// The batch's `da_reference` is `{height}:{commitment}`, which is all that is needed to locate
+/// the blob again; `tx_hash` is the bridge commit.
+pub struct CelestiaStrategy {
+    client: reqwest::Client,
+    node_url: String,
+    namespace: Namespace,
+    auth_token: Option<String>,
+    committer: Arc<dyn BridgeCommitter>,
+    dry_run: bool,
+    rollup: String,
+}
+
+impl CelestiaStrategy {
+    pub fn new(
+        node_url: String,
+        namespace: Namespace,
+        auth_token: Option<String>,
+        committer: Arc<dyn BridgeCommitter>,
+    ) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            node_url,
+            namespace,
+            auth_token,
+            committer,
+            dry_run: false,
+            rollup: "default".to_string(),
+        }
+    }
+
+    /// Names the rollup the strategy publishes for, for the `rollup` label of its metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
+    }
+
+    /// Logs the blob instead of submitting it and returns a synthetic reference (see
+    /// [`dry_run_tx_hash`]), which counts as available.
+    pub fn with_dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &'static str,
+        params: serde_json::Value,
+    ) -> Result<Option<T>, DomainError> {
+        let start = Instant::now();
+        let mut req = self.client.post(&self.node_url).json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        }));
+        if let Some(token) = &self.auth_token {
+            req = req.bearer_auth(token);
+        }
+
+        let res = req.send().await.map_err(|e| {
+            record_rpc_error(&self.rollup, method);
+            DomainError::DaTransient(format!("Celestia node request failed: {}", e))
+        })?;
+        histogram!("celestia_rpc_duration_seconds", "rollup" => self.rollup.clone()).record(start.elapsed().as_secs_f64());
+        if !res.status().is_success() {
+            record_rpc_error(&self.rollup, method);
+            return Err(DomainError::DaTransient(format!(
+                "Celestia node status: {}",
+                res.status()
+            )));
+        }
+
+        let body: RpcResponse<T> = res
+            .json()
+            .await
+            .map_err(|e| DomainError::Da(format!("Celestia response parse error: {}", e)))?;
+        if let Some(err) = body.error {
+            record_rpc_error(&self.rollup, method);
+            return Err(DomainError::Da(format!(
+                "{} failed: {}",
+                method, err.message
+            )));
+        }
+        Ok(body.result)
+    }
+
+    async fn read_data(&self, batch: &Batch) -> Result<Vec<u8>, DomainError> {
+        let data = read_payload(&self.rollup, &batch.data_file, "Failed to read batch data file").await?;
+        if data.is_empty() {
+            return Err(DomainError::InvalidBatch(
+                "Celestia does not accept empty blobs".into(),
+            ));
+        }
+        Ok(data)
+    }
+
+    /// Whether the node serves the blob at `reference` back.
+    async fn blob_available(&self, reference: &str) -> Result<bool, DomainError> {
+        let (height, commitment) = parse_blob_ref(reference)?;
+        let params = json!([
+            height,
+            BASE64.encode(self.namespace.as_bytes()),
+            BASE64.encode(commitment.as_bytes()),
+        ]);
+        match self.call::<serde_json::Value>("blob.Get", params).await {
+            Ok(blob) => Ok(blob.is_some_and(|b| !b.is_null())),
+            // The node may not have synced the block yet
+            Err(DomainError::Da(msg)) if msg.contains("not found") => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Splits a `{height}:{commitment}` reference back into its parts.
+fn parse_blob_ref(reference: &str) -> Result<(u64, H256), DomainError> {
+    let (height, commitment) = reference.split_once(':').ok_or_else(|| {
+        DomainError::Da(format!("Invalid Celestia blob reference: {}", reference))
+    })?;
+    let height = height
+        .parse()
+        .map_err(|e| DomainError::Da(format!("Invalid Celestia height {}: {}", height, e)))?;
+    let commitment = commitment.parse().map_err(|e| {
+        DomainError::Da(format!("Invalid Celestia commitment {}: {}", commitment, e))
+    })?;
+    Ok((height, commitment))
+}
+
+#[async_trait]
+impl DaStrategy for CelestiaStrategy {
+    fn da_id(&self) -> u8 {
+        2
+    }
+
+    async fn compute_commitment(&self, batch: &Batch) -> Result<H256, DomainError> {
+        Ok(blob_commitment(&self.namespace, &self.read_data(batch).await?))
+    }
+
+    /// `(bytes29 namespace, uint64 height, bytes32 commitment)`; the height is only known once
+    /// the blob has been submitted.
+    fn encode_da_meta(&self, batch: &Batch) -> Result<Vec<u8>, DomainError> {
+        let reference = batch.da_reference.as_deref().ok_or_else(|| {
+            DomainError::Da("Celestia height is unknown until the blob is submitted".into())
+        })?;
+        let (height, commitment) = parse_blob_ref(reference)?;
+        Ok(encode(&[
+            Token::FixedBytes(self.namespace.as_bytes().to_vec()),
+            Token::Uint(height.into()),
+            Token::FixedBytes(commitment.as_bytes().to_vec()),
+        ]))
+    }
+
+    async fn submit(&self, batch: &Batch, _proof_hex: &str) -> Result<String, DomainError> {
+        let data = self.read_data(batch).await?;
+        let commitment = blob_commitment(&self.namespace, &data);
+        if self.dry_run {
+            info!(
+                "Dry run: not submitting Celestia blob for batch {} ({} bytes, commitment {:?})",
+                batch.id,
+                data.len(),
+                commitment
+            );
+            counter!("tx_submitted_total", "rollup" => self.rollup.clone(), "mode" => "celestia", "dry_run" => "true").increment(1);
+            return Ok(dry_run_tx_hash(batch));
+        }
+
+        let blob = json!({
+            "namespace": BASE64.encode(self.namespace.as_bytes()),
+            "data": BASE64.encode(&data),
+            "share_version": 0,
+            "commitment": BASE64.encode(commitment.as_bytes()),
+        });
+        // blob.Submit returns once the PayForBlobs transaction is included
+        let height: u64 = self
+            .call("blob.Submit", json!([[blob], {}]))
+            .await?
+            .ok_or_else(|| DomainError::Da("blob.Submit returned no height".into()))?;
+
+        info!(
+            "Celestia blob included at height {} (commitment {:?})",
+            height, commitment
+        );
+        counter!("tx_submitted_total", "rollup" => self.rollup.clone(), "mode" => "celestia", "dry_run" => "false").increment(1);
+        Ok(format!("{}:{:?}", height, commitment))
+    }
+
+    /// Whether the bridge commit `tx_hash` is confirmed.
+    async fn check_confirmation(&self, tx_hash: &str) -> Result<bool, DomainError> {
+        self.committer.check_confirmation(tx_hash).await
+    }
+
+    async fn confirmation_details(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>, DomainError> {
+        self.committer.confirmation_details(tx_hash).await
+    }
+
+    async fn resubmit(
+        &self,
+        batch: &Batch,
+        fee_ratio: f64,
+        max_fee_per_gas: Option<U256>,
+    ) -> Result<SentTx, DomainError> {
+        self.committer.resubmit(batch, fee_ratio, max_fee_per_gas).await
+    }
+
+    async fn cancel(&self, batch: &Batch) -> Result<String, DomainError> {
+        self.committer.cancel(batch).await
+    }
+
+    fn publishes_off_chain(&self) -> bool {
+        true
+    }
+
+    /// Commits once the node serves the blob back.
+    async fn commit_published(&self, batch: &Batch, proof: &str) -> Result<Option<SentTx>, DomainError> {
+        let reference = batch
+            .da_reference
+            .as_deref()
+            .ok_or_else(|| DomainError::Internal("Batch has no Celestia blob reference".into()))?;
+        let da_meta = if is_dry_run_tx(reference) {
+            Vec::new()
+        } else {
+            if !self.blob_available(reference).await? {
+                return Ok(None);
+            }
+            self.encode_da_meta(batch)?
+        };
+        let sent = self
+            .committer
+            .commit_pointer(batch, proof, self.da_id(), da_meta)
+            .await?;
+        info!(
+            "Celestia blob {} of batch {} committed to the bridge. tx={}",
+            reference, batch.id, sent.hash
+        );
+        Ok(Some(sent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockCommitter;
+    use wiremock::matchers::{body_partial_json, header, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn namespace() -> Namespace {
+        "0x0000000000000000000000000000000000000000000000726f6c6c7570"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_namespace_forms() {
+        let short: Namespace = "0x0102030405060708090a".parse().unwrap();
+        assert_eq!(short.as_bytes()[..19], [0u8; 19]);
+        assert_eq!(short.as_bytes()[19], 1);
+        assert_eq!(namespace().as_bytes().len(), NAMESPACE_SIZE);
+        assert!("0x0102".parse::<Namespace>().is_err());
+    }
+
+    #[test]
+    fn test_split_shares_layout() {
+        let data = vec![0xabu8; FIRST_SHARE_CONTENT + CONTINUATION_SHARE_CONTENT + 1];
+        let shares = split_shares(&namespace(), &data);
+
+        assert_eq!(shares.len(), 3);
+        assert!(shares.iter().all(|s| s.len() == SHARE_SIZE));
+        assert_eq!(shares[0][NAMESPACE_SIZE], 1);
+        assert_eq!(
+            shares[0][NAMESPACE_SIZE + 1..NAMESPACE_SIZE + 5],
+            (data.len() as u32).to_be_bytes()
+        );
+        assert_eq!(shares[1][NAMESPACE_SIZE], 0);
+        // One byte spills into the last share; the rest is padding
+        assert_eq!(shares[2][NAMESPACE_SIZE + 1], 0xab);
+        assert_eq!(shares[2][NAMESPACE_SIZE + 2], 0);
+    }
+
+    #[test]
+    fn test_commitment_depends_on_data_and_namespace() {
+        let ns = namespace();
+        let other: Namespace = "0x0102030405060708090a".parse().unwrap();
+        assert_eq!(
+            blob_commitment(&ns, b"payload"),
+            blob_commitment(&ns, b"payload")
+        );
+        assert_ne!(
+            blob_commitment(&ns, b"payload"),
+            blob_commitment(&ns, b"payload2")
+        );
+        assert_ne!(
+            blob_commitment(&ns, b"payload"),
+            blob_commitment(&other, b"payload")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_submit_pays_for_blob_then_commits_its_location() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("authorization", "Bearer secret"))
+            .and(body_partial_json(json!({
+                "method": "blob.Submit",
+                "params": [[{
+                    "namespace": BASE64.encode(namespace().as_bytes()),
+                    "data": BASE64.encode(b"payload"),
+                    "share_version": 0,
+                }]],
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": 4242,
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "blob.Get" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": { "data": BASE64.encode(b"payload") },
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        std::fs::write("test_data_celestia.txt", "payload").unwrap();
+        let mut batch = Batch::new(
+            1,
+            "b",
+            "test_data_celestia.txt".into(),
+            "h".into(),
+            format!("{:#x}", H256::zero()),
+            "celestia".into(),
+        );
+        let committer = Arc::new(MockCommitter::new());
+        let strategy = CelestiaStrategy::new(server.uri(), namespace(), Some("secret".into()), committer.clone());
+
+        let res = strategy.submit(&batch, "").await;
+        let commitment = strategy.compute_commitment(&batch).await;
+        let _ = std::fs::remove_file("test_data_celestia.txt");
+
+        let reference = res.unwrap();
+        assert_eq!(reference, format!("4242:{:?}", commitment.unwrap()));
+
+        batch.da_reference = Some(reference);
+        let sent = strategy.commit_published(&batch, "0xproof").await.unwrap().unwrap();
+        assert_eq!(sent.hash, "0xcommit1");
+        let commits = committer.commits();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].0, 2);
+        let meta = ethers::abi::decode(
+            &[
+                ethers::abi::ParamType::FixedBytes(NAMESPACE_SIZE),
+                ethers::abi::ParamType::Uint(64),
+                ethers::abi::ParamType::FixedBytes(32),
+            ],
+            &commits[0].1,
+        )
+        .unwrap();
+        assert_eq!(meta[1], Token::Uint(4242u64.into()));
+    }
+}
@@ -0,0 +1,657 @@
+use crate::application::ports::{BridgeCommitter, DaStrategy, SentTx};
+use crate::domain::{batch::Batch, errors::DomainError};
+use crate::infrastructure::ethereum_adapter::{dry_run_tx_hash, is_dry_run_tx, record_rpc_error};
+use crate::infrastructure::payload::read_payload;
+use async_trait::async_trait;
+use ethers::abi::{encode, Token};
+use ethers::types::{TransactionReceipt, H256, U256};
+use ethers::utils::{hex, keccak256};
+use metrics::{counter, histogram};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Payload bytes carried by each 32-byte field element; the leading byte stays zero so every
+/// element is below the BN254 modulus.
+const FIELD_ELEMENT_PAYLOAD: usize = 31;
+
+/// gRPC status codes worth retrying: `DEADLINE_EXCEEDED`, `RESOURCE_EXHAUSTED`, `UNAVAILABLE`.
+const TRANSIENT_GRPC_CODES: [&str; 3] = ["4", "8", "14"];
+
+/// `disperser.BlobStatus` as reported by `GetBlobStatus`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobStatus {
+    Unknown,
+    Processing,
+    Confirmed,
+    Failed,
+    Finalized,
+    InsufficientSignatures,
+    Dispersing,
+}
+
+impl From<u64> for BlobStatus {
+    fn from(value: u64) -> Self {
+        match value {
+            1 => BlobStatus::Processing,
+            2 => BlobStatus::Confirmed,
+            3 => BlobStatus::Failed,
+            4 => BlobStatus::Finalized,
+            5 => BlobStatus::InsufficientSignatures,
+            6 => BlobStatus::Dispersing,
+            _ => BlobStatus::Unknown,
+        }
+    }
+}
+
+/// Where a confirmed blob sits in EigenDA: what the bridge needs to verify it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlobPointer {
+    pub batch_header_hash: H256,
+    pub blob_index: u32,
+}
+
+impl BlobPointer {
+    /// `daMeta` for the bridge: `abi.encode(bytes32 batchHeaderHash, uint32 blobIndex)`.
+    pub fn encode(&self) -> Vec<u8> {
+        encode(&[
+            Token::FixedBytes(self.batch_header_hash.as_bytes().to_vec()),
+            Token::Uint(self.blob_index.into()),
+        ])
+    }
+}
+
+/// Spreads `data` over 32-byte field elements, 31 payload bytes behind a zero byte each.
+pub fn pad_field_elements(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len().div_ceil(FIELD_ELEMENT_PAYLOAD) * 32);
+    for chunk in data.chunks(FIELD_ELEMENT_PAYLOAD) {
+        out.push(0);
+        out.extend_from_slice(chunk);
+    }
+    out
+}
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// A length-delimited protobuf field.
+fn put_bytes(out: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    put_varint(out, (field << 3) | 2);
+    put_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+enum ProtoValue<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+/// Walks the top-level fields of a protobuf message, skipping fixed-width ones.
+fn proto_fields(mut msg: &[u8]) -> Result<Vec<(u64, ProtoValue<'_>)>, DomainError> {
+    fn varint(buf: &mut &[u8]) -> Result<u64, DomainError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = buf
+                .split_first()
+                .ok_or_else(|| DomainError::Da("Truncated disperser response".into()))?;
+            *buf = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(DomainError::Da(
+            "Malformed varint in disperser response".into(),
+        ))
+    }
+
+    let mut fields = Vec::new();
+    while !msg.is_empty() {
+        let key = varint(&mut msg)?;
+        let skip = match key & 7 {
+            0 => {
+                fields.push((key >> 3, ProtoValue::Varint(varint(&mut msg)?)));
+                0
+            }
+            2 => {
+                let len = varint(&mut msg)? as usize;
+                if len > msg.len() {
+                    return Err(DomainError::Da("Truncated disperser response".into()));
+                }
+                let (bytes, rest) = msg.split_at(len);
+                fields.push((key >> 3, ProtoValue::Bytes(bytes)));
+                msg = rest;
+                0
+            }
+            1 => 8,
+            5 => 4,
+            wire => {
+                return Err(DomainError::Da(format!(
+                    "Unsupported protobuf wire type {} in disperser response",
+                    wire
+                )))
+            }
+        };
+        msg = msg
+            .get(skip..)
+            .ok_or_else(|| DomainError::Da("Truncated disperser response".into()))?;
+    }
+    Ok(fields)
+}
+
+fn varint_field(msg: &[u8], field: u64) -> Result<Option<u64>, DomainError> {
+    Ok(proto_fields(msg)?.into_iter().find_map(|(f, v)| match v {
+        ProtoValue::Varint(n) if f == field => Some(n),
+        _ => None,
+    }))
+}
+
+fn bytes_field(msg: &[u8], field: u64) -> Result<Option<&[u8]>, DomainError> {
+    Ok(proto_fields(msg)?.into_iter().find_map(|(f, v)| match v {
+        ProtoValue::Bytes(b) if f == field => Some(b),
+        _ => None,
+    }))
+}
+
+/// Publishes batch data to EigenDA through the v1 disperser's gRPC API, then commits the batch
+/// to the bridge through `committer` with the blob's pointer as `daMeta`.
+///
+/// The batch's `da_reference` is the disperser's request ID, hex encoded. The blob's place in
+/// EigenDA is only known once the disperser confirms it, which is when the bridge commit is
+/// sent; `tx_hash` is that commit.
+pub struct EigenDaStrategy {
+    client: reqwest::Client,
+    disperser_url: String,
+    committer: Arc<dyn BridgeCommitter>,
+    dry_run: bool,
+    rollup: String,
+}
+
+impl EigenDaStrategy {
+    pub fn new(disperser_url: String, committer: Arc<dyn BridgeCommitter>) -> Self {
+        let client = reqwest::Client::builder()
+            .http2_prior_knowledge()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .expect("Failed to build EigenDA HTTP client");
+        Self {
+            client,
+            disperser_url: disperser_url.trim_end_matches('/').to_string(),
+            committer,
+            dry_run: false,
+            rollup: "default".to_string(),
+        }
+    }
+
+    /// Names the rollup the strategy disperses for, for the `rollup` label of its metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
+    }
+
+    /// Logs the blob instead of dispersing it and returns a synthetic reference (see
+    /// [`dry_run_tx_hash`]), which counts as confirmed by the disperser.
+    pub fn with_dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    /// Makes a unary call to `disperser.Disperser/{method}` and returns the reply message.
+    async fn call(&self, method: &'static str, msg: Vec<u8>) -> Result<Vec<u8>, DomainError> {
+        let start = Instant::now();
+        let res = self.call_once(method, msg).await;
+        histogram!("eigenda_rpc_duration_seconds", "rollup" => self.rollup.clone(), "method" => method).record(start.elapsed().as_secs_f64());
+        res
+    }
+
+    async fn call_once(&self, method: &'static str, msg: Vec<u8>) -> Result<Vec<u8>, DomainError> {
+        let mut body = Vec::with_capacity(msg.len() + 5);
+        body.push(0); // uncompressed
+        body.extend_from_slice(&(msg.len() as u32).to_be_bytes());
+        body.extend_from_slice(&msg);
+
+        let res = self
+            .client
+            .post(format!(
+                "{}/disperser.Disperser/{}",
+                self.disperser_url, method
+            ))
+            .header("content-type", "application/grpc")
+            .header("te", "trailers")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| {
+                record_rpc_error(&self.rollup, method);
+                DomainError::DaTransient(format!("EigenDA disperser request failed: {}", e))
+            })?;
+        if !res.status().is_success() {
+            record_rpc_error(&self.rollup, method);
+            return Err(DomainError::DaTransient(format!(
+                "EigenDA disperser status: {}",
+                res.status()
+            )));
+        }
+        // Errors come as trailers-only responses, so the status is in the headers
+        let header_status = res
+            .headers()
+            .get("grpc-status")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        if let Some(code) = header_status.as_deref() {
+            if code != "0" {
+                record_rpc_error(&self.rollup, method);
+                let message = res
+                    .headers()
+                    .get("grpc-message")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default();
+                let reason = format!("{} failed with gRPC status {}: {}", method, code, message);
+                return Err(if TRANSIENT_GRPC_CODES.contains(&code) {
+                    DomainError::DaTransient(reason)
+                } else {
+                    DomainError::Da(reason)
+                });
+            }
+        }
+
+        let frame = res.bytes().await.map_err(|e| {
+            DomainError::DaTransient(format!("EigenDA disperser response failed: {}", e))
+        })?;
+        // Without a status in the headers it came in the trailers, which are not read here. A
+        // missing or cut-off message then most likely means the call failed, or the stream was
+        // reset mid-reply, so it is worth retrying
+        let complete = frame.len() >= 5
+            && frame.len() >= 5 + u32::from_be_bytes([frame[1], frame[2], frame[3], frame[4]]) as usize;
+        if header_status.is_none() && !complete {
+            record_rpc_error(&self.rollup, method);
+            return Err(DomainError::DaTransient(format!(
+                "{} returned no gRPC status and an incomplete message",
+                method
+            )));
+        }
+        if frame.len() < 5 {
+            return Err(DomainError::Da(format!("{} returned no message", method)));
+        }
+        if frame[0] != 0 {
+            return Err(DomainError::Da(format!(
+                "{} returned a compressed message",
+                method
+            )));
+        }
+        let len = u32::from_be_bytes([frame[1], frame[2], frame[3], frame[4]]) as usize;
+        frame
+            .get(5..5 + len)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| DomainError::Da(format!("{} returned a truncated message", method)))
+    }
+
+    async fn read_data(&self, batch: &Batch) -> Result<Vec<u8>, DomainError> {
+        let data = read_payload(&self.rollup, &batch.data_file, "Failed to read batch data file").await?;
+        if data.is_empty() {
+            return Err(DomainError::InvalidBatch(
+                "EigenDA does not accept empty blobs".into(),
+            ));
+        }
+        Ok(data)
+    }
+
+    /// The dispersal's status and, once it carries one, where the blob sits.
+    async fn blob_status(
+        &self,
+        request_id: &str,
+    ) -> Result<(BlobStatus, Option<BlobPointer>), DomainError> {
+        let id = hex::decode(request_id.trim_start_matches("0x")).map_err(|e| {
+            DomainError::Da(format!("Invalid EigenDA request id {}: {}", request_id, e))
+        })?;
+        let mut req = Vec::new();
+        put_bytes(&mut req, 1, &id);
+        let reply = self.call("GetBlobStatus", req).await?;
+
+        let status = BlobStatus::from(varint_field(&reply, 1)?.unwrap_or(0));
+        // BlobInfo.blob_verification_proof
+        let Some(proof) = bytes_field(&reply, 2)?
+            .map(|info| bytes_field(info, 2))
+            .transpose()?
+            .flatten()
+        else {
+            return Ok((status, None));
+        };
+        let blob_index = varint_field(proof, 2)?.unwrap_or(0) as u32;
+        // BlobVerificationProof.batch_metadata.batch_header_hash
+        let header_hash = bytes_field(proof, 3)?
+            .map(|metadata| bytes_field(metadata, 5))
+            .transpose()?
+            .flatten()
+            .filter(|hash| hash.len() == 32)
+            .map(H256::from_slice);
+        Ok((
+            status,
+            header_hash.map(|batch_header_hash| BlobPointer {
+                batch_header_hash,
+                blob_index,
+            }),
+        ))
+    }
+}
+
+#[async_trait]
+impl DaStrategy for EigenDaStrategy {
+    fn da_id(&self) -> u8 {
+        3
+    }
+
+    /// `keccak256` of the raw payload; EigenDA's own KZG commitment is checked through the
+    /// blob pointer in `daMeta`.
+    async fn compute_commitment(&self, batch: &Batch) -> Result<H256, DomainError> {
+        Ok(H256::from(keccak256(self.read_data(batch).await?)))
+    }
+
+    /// Only known from the disperser's confirmation; [`DaStrategy::commit_published`] asks for
+    /// it and commits [`BlobPointer::encode`] of the blob.
+    fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
+        Err(DomainError::Da(
+            "EigenDA daMeta comes from the disperser's confirmation".into(),
+        ))
+    }
+
+    async fn submit(&self, batch: &Batch, _proof_hex: &str) -> Result<String, DomainError> {
+        let data = pad_field_elements(&self.read_data(batch).await?);
+        if self.dry_run {
+            info!(
+                "Dry run: not dispersing EigenDA blob for batch {} ({} bytes)",
+                batch.id,
+                data.len()
+            );
+            counter!("tx_submitted_total", "rollup" => self.rollup.clone(), "mode" => "eigenda", "dry_run" => "true").increment(1);
+            return Ok(dry_run_tx_hash(batch));
+        }
+
+        let mut req = Vec::new();
+        put_bytes(&mut req, 1, &data);
+        let reply = self.call("DisperseBlob", req).await?;
+        let status = BlobStatus::from(varint_field(&reply, 1)?.unwrap_or(0));
+        if status == BlobStatus::Failed {
+            return Err(DomainError::Da(
+                "EigenDA disperser rejected the blob".into(),
+            ));
+        }
+        let request_id = bytes_field(&reply, 2)?
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| DomainError::Da("DisperseBlob returned no request id".into()))?;
+
+        let reference = format!("0x{}", hex::encode(request_id));
+        info!(
+            "EigenDA blob for batch {} accepted as request {} ({:?})",
+            batch.id, reference, status
+        );
+        counter!("tx_submitted_total", "rollup" => self.rollup.clone(), "mode" => "eigenda", "dry_run" => "false").increment(1);
+        Ok(reference)
+    }
+
+    /// Whether the bridge commit `tx_hash` is confirmed.
+    async fn check_confirmation(&self, tx_hash: &str) -> Result<bool, DomainError> {
+        self.committer.check_confirmation(tx_hash).await
+    }
+
+    async fn confirmation_details(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>, DomainError> {
+        self.committer.confirmation_details(tx_hash).await
+    }
+
+    async fn resubmit(
+        &self,
+        batch: &Batch,
+        fee_ratio: f64,
+        max_fee_per_gas: Option<U256>,
+    ) -> Result<SentTx, DomainError> {
+        self.committer.resubmit(batch, fee_ratio, max_fee_per_gas).await
+    }
+
+    async fn cancel(&self, batch: &Batch) -> Result<String, DomainError> {
+        self.committer.cancel(batch).await
+    }
+
+    fn publishes_off_chain(&self) -> bool {
+        true
+    }
+
+    async fn commit_published(&self, batch: &Batch, proof: &str) -> Result<Option<SentTx>, DomainError> {
+        let request_id = batch
+            .da_reference
+            .as_deref()
+            .ok_or_else(|| DomainError::Internal("Batch has no EigenDA request id".into()))?;
+        let da_meta = if is_dry_run_tx(request_id) {
+            Vec::new()
+        } else {
+            match self.blob_status(request_id).await? {
+                (BlobStatus::Confirmed | BlobStatus::Finalized, Some(pointer)) => pointer.encode(),
+                (BlobStatus::Confirmed | BlobStatus::Finalized, None) => {
+                    warn!(
+                        "EigenDA reports {} confirmed without a verification proof",
+                        request_id
+                    );
+                    return Ok(None);
+                }
+                (BlobStatus::Failed | BlobStatus::InsufficientSignatures, _) => {
+                    return Err(DomainError::DaReverted(format!(
+                        "EigenDA dispersal {} failed",
+                        request_id
+                    )))
+                }
+                _ => return Ok(None),
+            }
+        };
+        let sent = self
+            .committer
+            .commit_pointer(batch, proof, self.da_id(), da_meta)
+            .await?;
+        info!(
+            "EigenDA blob {} of batch {} committed to the bridge. tx={}",
+            request_id, batch.id, sent.hash
+        );
+        Ok(Some(sent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockCommitter;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, Respond, ResponseTemplate};
+
+    fn grpc_frame(msg: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0];
+        frame.extend_from_slice(&(msg.len() as u32).to_be_bytes());
+        frame.extend_from_slice(msg);
+        frame
+    }
+
+    fn grpc_reply(msg: &[u8]) -> ResponseTemplate {
+        ResponseTemplate::new(200)
+            .insert_header("grpc-status", "0")
+            .set_body_raw(grpc_frame(msg), "application/grpc")
+    }
+
+    /// A `BlobStatusReply` for `status`, with a verification proof once the blob is confirmed.
+    fn status_reply(status: u64, header_hash: H256, blob_index: u64) -> Vec<u8> {
+        let mut metadata = Vec::new();
+        put_bytes(&mut metadata, 5, header_hash.as_bytes());
+        let mut proof = Vec::new();
+        put_varint(&mut proof, 1 << 3);
+        put_varint(&mut proof, 7);
+        put_varint(&mut proof, 2 << 3);
+        put_varint(&mut proof, blob_index);
+        put_bytes(&mut proof, 3, &metadata);
+        let mut info = Vec::new();
+        put_bytes(&mut info, 1, b"header");
+        put_bytes(&mut info, 2, &proof);
+
+        let mut reply = Vec::new();
+        put_varint(&mut reply, 1 << 3);
+        put_varint(&mut reply, status);
+        if status == 2 {
+            put_bytes(&mut reply, 2, &info);
+        }
+        reply
+    }
+
+    /// Answers `Processing` until it has been asked `pending` times, then `Confirmed`.
+    struct Polled {
+        pending: usize,
+        calls: std::sync::atomic::AtomicUsize,
+        header_hash: H256,
+    }
+
+    impl Respond for Polled {
+        fn respond(&self, _req: &wiremock::Request) -> ResponseTemplate {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let status = if call < self.pending { 1 } else { 2 };
+            grpc_reply(&status_reply(status, self.header_hash, 3))
+        }
+    }
+
+    #[test]
+    fn test_payload_fits_field_elements() {
+        let data = vec![0xffu8; 40];
+        let padded = pad_field_elements(&data);
+        assert_eq!(padded.len(), 32 + 10);
+        assert_eq!(padded[0], 0);
+        assert_eq!(&padded[1..32], &data[..31]);
+        assert_eq!(padded[32], 0);
+        assert_eq!(&padded[33..], &data[31..]);
+    }
+
+    #[tokio::test]
+    async fn test_disperse_then_commit_pointer_once_confirmed() {
+        let server = MockServer::start().await;
+        let mut dispersed = Vec::new();
+        put_varint(&mut dispersed, 1 << 3);
+        put_varint(&mut dispersed, 1); // PROCESSING
+        put_bytes(&mut dispersed, 2, &[0xab, 0xcd]);
+
+        let mut expected_req = Vec::new();
+        put_bytes(&mut expected_req, 1, &pad_field_elements(b"payload"));
+        Mock::given(method("POST"))
+            .and(path("/disperser.Disperser/DisperseBlob"))
+            .and(header("content-type", "application/grpc"))
+            .and(wiremock::matchers::body_bytes(grpc_frame(&expected_req)))
+            .respond_with(grpc_reply(&dispersed))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let header_hash = H256::repeat_byte(0x42);
+        Mock::given(method("POST"))
+            .and(path("/disperser.Disperser/GetBlobStatus"))
+            .respond_with(Polled {
+                pending: 1,
+                calls: Default::default(),
+                header_hash,
+            })
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let data = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(data.path(), "payload").unwrap();
+        let mut batch = Batch::new(
+            1,
+            "b",
+            data.path().to_string_lossy().into(),
+            "h".into(),
+            format!("{:#x}", H256::zero()),
+            "eigenda".into(),
+        );
+        let committer = Arc::new(MockCommitter::new());
+        let strategy = EigenDaStrategy::new(server.uri(), committer.clone());
+
+        let request_id = strategy.submit(&batch, "").await.unwrap();
+        assert_eq!(request_id, "0xabcd");
+        batch.da_reference = Some(request_id);
+
+        // Nothing reaches the bridge until the disperser confirms the blob
+        assert_eq!(strategy.commit_published(&batch, "0xproof").await.unwrap(), None);
+        assert!(committer.commits().is_empty());
+
+        let sent = strategy.commit_published(&batch, "0xproof").await.unwrap().unwrap();
+        assert_eq!(sent.hash, "0xcommit1");
+        let commits = committer.commits();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].0, 3);
+        let meta = ethers::abi::decode(
+            &[
+                ethers::abi::ParamType::FixedBytes(32),
+                ethers::abi::ParamType::Uint(32),
+            ],
+            &commits[0].1,
+        )
+        .unwrap();
+        assert_eq!(meta[0], Token::FixedBytes(header_hash.as_bytes().to_vec()));
+        assert_eq!(meta[1], Token::Uint(3u64.into()));
+    }
+
+    #[tokio::test]
+    async fn test_reply_without_status_or_message_is_transient() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/disperser.Disperser/DisperseBlob"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(vec![0, 0, 0], "application/grpc"))
+            .mount(&server)
+            .await;
+        let strategy = EigenDaStrategy::new(server.uri(), Arc::new(MockCommitter::new()));
+
+        assert!(matches!(
+            strategy.call_once("DisperseBlob", Vec::new()).await,
+            Err(DomainError::DaTransient(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_failed_dispersal_is_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/disperser.Disperser/GetBlobStatus"))
+            .respond_with(grpc_reply(&status_reply(3, H256::zero(), 0)))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/disperser.Disperser/DisperseBlob"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "application/grpc")
+                    .insert_header("grpc-status", "14")
+                    .insert_header("grpc-message", "overloaded"),
+            )
+            .mount(&server)
+            .await;
+        let committer = Arc::new(MockCommitter::new());
+        let strategy = EigenDaStrategy::new(server.uri(), committer.clone());
+
+        let data = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(data.path(), "payload").unwrap();
+        let mut batch = Batch::new(
+            1,
+            "b",
+            data.path().to_string_lossy().into(),
+            "h".into(),
+            "0x01".into(),
+            "eigenda".into(),
+        );
+        match strategy.submit(&batch, "").await {
+            Err(DomainError::DaTransient(reason)) => assert!(reason.contains("overloaded")),
+            other => panic!("expected a transient error, got {:?}", other),
+        }
+
+        batch.da_reference = Some("0x01".into());
+        assert!(matches!(
+            strategy.commit_published(&batch, "0xproof").await,
+            Err(DomainError::DaReverted(_))
+        ));
+        assert!(committer.commits().is_empty());
+    }
+}
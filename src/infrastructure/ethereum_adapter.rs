@@ -1,10 +1,760 @@
-use crate::contracts::{Groth16Proof, ZKRollupBridge};
-use crate::domain::errors::DomainError;
+use crate::contracts::{parse_proof, Groth16Proof, Proof, ZKRollupBridge};
+use crate::domain::{errors::DomainError, fees::MIN_REPLACEMENT_BUMP};
 use async_trait::async_trait;
 use ethers::prelude::*;
+use ethers::providers::{JsonRpcClient, RpcError};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use metrics::{counter, histogram};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tracing::{info, warn};
 
-use crate::application::ports::BridgeReader;
+use crate::application::ports::{BridgeReader, NodeStatus};
+use crate::domain::batch::{Batch, ProofSystem};
+
+/// Prefix of the synthetic transaction hashes DA strategies return in dry-run mode.
+pub const DRY_RUN_TX_PREFIX: &str = "0xdryrun";
+
+/// Stand-in hash recorded for `batch` when a dry run skips the broadcast.
+pub fn dry_run_tx_hash(batch: &Batch) -> String {
+    format!("{}{}", DRY_RUN_TX_PREFIX, batch.id.0.simple())
+}
+
+/// Whether `tx_hash` came from [`dry_run_tx_hash`] rather than a real broadcast.
+pub fn is_dry_run_tx(tx_hash: &str) -> bool {
+    tx_hash.starts_with(DRY_RUN_TX_PREFIX)
+}
+
+/// Node replies meaning this exact transaction is already in the mempool.
+pub fn is_already_known(err: &str) -> bool {
+    let err = err.to_lowercase();
+    err.contains("already known") || err.contains("known transaction")
+}
+
+/// Node reply meaning some transaction, not necessarily ours, already used the nonce.
+pub fn is_nonce_too_low(err: &str) -> bool {
+    err.to_lowercase().contains("nonce too low")
+}
+
+/// Whether a provider error means we are being throttled: an HTTP 429, or a JSON-RPC
+/// "limit exceeded" style error.
+pub fn is_rate_limited<E: RpcError>(err: &E) -> bool {
+    if let Some(resp) = err.as_error_response() {
+        if resp.code == 429 || resp.code == -32005 {
+            return true;
+        }
+    }
+    let msg = err.to_string().to_lowercase();
+    msg.contains("429") || msg.contains("too many requests") || msg.contains("rate limit")
+}
+
+/// JSON-RPC transport that waits and retries when the provider throttles us, doubling the delay
+/// each time. Other errors are returned straight away.
+#[derive(Debug, Clone)]
+pub struct RateLimitRetry<C> {
+    inner: C,
+    max_retries: u32,
+    backoff: Duration,
+    rollup: String,
+}
+
+impl<C> RateLimitRetry<C> {
+    pub fn new(inner: C, max_retries: u32, backoff: Duration) -> Self {
+        Self { inner, max_retries, backoff, rollup: "default".to_string() }
+    }
+
+    /// Names the rollup the transport serves, for the `rollup` label of its metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
+    }
+}
+
+#[async_trait]
+impl<C: JsonRpcClient> JsonRpcClient for RateLimitRetry<C> {
+    type Error = C::Error;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let mut delay = self.backoff;
+        let mut retries = 0;
+        loop {
+            match self.inner.request(method, &params).await {
+                Err(e) if retries < self.max_retries && is_rate_limited(&e) => {
+                    warn!("RPC {} rate limited ({}); retrying in {:?}", method, e, delay);
+                    counter!("rpc_rate_limited_total", "rollup" => self.rollup.clone(), "method" => method.to_string()).increment(1);
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    retries += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+/// HTTP JSON-RPC transport to `url` whose requests time out after `timeout`.
+pub fn http_transport(url: &str, timeout: Duration) -> Result<Http, DomainError> {
+    let url: reqwest_ethers::Url = url
+        .parse()
+        .map_err(|e| DomainError::Config(format!("Invalid rpc_url {}: {}", url, e)))?;
+    let client = reqwest_ethers::Client::builder()
+        .timeout(timeout)
+        .build()
+        .expect("Failed to build RPC HTTP client");
+    Ok(Http::new_with_client(url, client))
+}
+
+/// Counts a failed RPC call in `rpc_errors_total`, labelled by rollup and JSON-RPC method.
+pub fn record_rpc_error(rollup: &str, method: &'static str) {
+    counter!("rpc_errors_total", "rollup" => rollup.to_string(), "method" => method).increment(1);
+}
+
+/// Hands out nonces for the submitter account from a single counter, so concurrent submissions
+/// never race on `eth_getTransactionCount`. The counter is seeded once from the account's pending
+/// transaction count, and optionally re-checked against it (see
+/// [`NonceManager::with_reconcile_interval`]).
+pub struct NonceManager {
+    address: Address,
+    next: AtomicU64,
+    // One past the highest nonce sent through a private relay; the node's pending count does
+    // not include those until they are mined
+    relayed_next: AtomicU64,
+    seeded: OnceCell<()>,
+    reconcile_interval: Option<Duration>,
+    last_reconciled: std::sync::Mutex<Option<std::time::Instant>>,
+}
+
+impl NonceManager {
+    pub fn new(address: Address) -> Self {
+        Self {
+            address,
+            next: AtomicU64::new(0),
+            relayed_next: AtomicU64::new(0),
+            seeded: OnceCell::new(),
+            reconcile_interval: None,
+            last_reconciled: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Before handing out a nonce, reconciles the counter with the node (see
+    /// [`NonceManager::reconcile`]) if the last check is at least `interval` old.
+    pub fn with_reconcile_interval(mut self, interval: Option<Duration>) -> Self {
+        self.reconcile_interval = interval;
+        self
+    }
+
+    /// Seeds the counter unless that already happened. `rollup` labels the metrics of this and
+    /// the other calls, since the counter may be shared by several rollups.
+    pub async fn seed<M: Middleware>(&self, rollup: &str, client: &M) -> Result<(), DomainError> {
+        self.seeded
+            .get_or_try_init(|| async {
+                let count = self.pending_count(rollup, client).await.map_err(|e| {
+                    DomainError::Da(format!("Failed to seed nonce: {}", e))
+                })?;
+                info!("Nonce manager seeded at {} for {:?}", count, self.address);
+                self.next.store(count, Ordering::SeqCst);
+                *self.last_reconciled.lock().unwrap() = Some(std::time::Instant::now());
+                Ok(())
+            })
+            .await
+            .map(|_| ())
+    }
+
+    /// Reserves the next nonce, seeding first if needed.
+    pub async fn reserve<M: Middleware>(&self, rollup: &str, client: &M) -> Result<U256, DomainError> {
+        self.seed(rollup, client).await?;
+        if self.reconcile_due() {
+            if let Err(e) = self.reconcile(rollup, client).await {
+                warn!("Nonce reconciliation failed, keeping local nonce: {}", e);
+            }
+        }
+        Ok(U256::from(self.next.fetch_add(1, Ordering::SeqCst)))
+    }
+
+    /// Records that the transaction at `nonce` went to a private relay, so
+    /// [`NonceManager::reconcile`] does not mistake it for a gap while it is pending.
+    pub fn mark_relayed(&self, nonce: U256) {
+        self.relayed_next.fetch_max(nonce.as_u64() + 1, Ordering::SeqCst);
+    }
+
+    /// Moves the counter to the account's pending transaction count when the two disagree.
+    ///
+    /// A higher count means a transaction we did not send used our nonces; a lower one means
+    /// nonces were handed out but never broadcast, which would leave every later transaction
+    /// stuck behind the gap. The node does not see relayed transactions until they are mined,
+    /// so no rewind happens while one of those may still be pending.
+    pub async fn reconcile<M: Middleware>(&self, rollup: &str, client: &M) -> Result<(), DomainError> {
+        let count = self
+            .pending_count(rollup, client)
+            .await
+            .map_err(|e| DomainError::Da(format!("Failed to reconcile nonce: {}", e)))?;
+        *self.last_reconciled.lock().unwrap() = Some(std::time::Instant::now());
+
+        let local = self.next.load(Ordering::SeqCst);
+        let reason = match count.cmp(&local) {
+            std::cmp::Ordering::Equal => return Ok(()),
+            std::cmp::Ordering::Greater => {
+                warn!(
+                    "External transaction observed for {:?}: node is at nonce {}, we were at {}; advancing",
+                    self.address, count, local
+                );
+                "external"
+            }
+            std::cmp::Ordering::Less if count < self.relayed_next.load(Ordering::SeqCst) => {
+                info!(
+                    "Node is at nonce {} for {:?} with relayed transactions pending; not rewinding",
+                    count, self.address
+                );
+                counter!("nonce_rewind_skipped_total", "rollup" => rollup.to_string()).increment(1);
+                return Ok(());
+            }
+            std::cmp::Ordering::Less => {
+                warn!(
+                    "Nonce gap for {:?}: node is at nonce {} but we handed out up to {}; rewinding",
+                    self.address,
+                    count,
+                    local - 1
+                );
+                "gap"
+            }
+        };
+        // A nonce reserved meanwhile means our reading is already stale; the next check catches up
+        if self
+            .next
+            .compare_exchange(local, count, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            counter!("nonce_gap_healed_total", "rollup" => rollup.to_string(), "reason" => reason).increment(1);
+        }
+        Ok(())
+    }
+
+    fn reconcile_due(&self) -> bool {
+        let Some(interval) = self.reconcile_interval else {
+            return false;
+        };
+        self.last_reconciled
+            .lock()
+            .unwrap()
+            .is_none_or(|last| last.elapsed() >= interval)
+    }
+
+    async fn pending_count<M: Middleware>(&self, rollup: &str, client: &M) -> Result<u64, M::Error> {
+        client
+            .get_transaction_count(self.address, Some(BlockNumber::Pending.into()))
+            .await
+            .map(|count| count.as_u64())
+            .inspect_err(|_| record_rpc_error(rollup, "getTransactionCount"))
+    }
+}
+
+/// Fills nonce, EIP-1559 fees and gas limit one call at a time, so failures can be attributed
+/// to the RPC method that caused them.
+pub async fn fill_transaction<M: Middleware>(
+    rollup: &str,
+    client: &M,
+    tx: &mut TypedTransaction,
+) -> Result<(), DomainError> {
+    if tx.from().is_none() {
+        if let Some(from) = client.default_sender() {
+            tx.set_from(from);
+        }
+    }
+    if tx.nonce().is_none() {
+        let from = tx.from().copied().unwrap_or_default();
+        let nonce = client
+            .get_transaction_count(from, None)
+            .await
+            .map_err(|e| {
+                record_rpc_error(rollup, "getTransactionCount");
+                DomainError::Da(format!("Tx fill failed: {}", e))
+            })?;
+        tx.set_nonce(nonce);
+    }
+    if let TypedTransaction::Eip1559(inner) = tx {
+        if inner.max_fee_per_gas.is_none() || inner.max_priority_fee_per_gas.is_none() {
+            let (max_fee, priority_fee) = client.estimate_eip1559_fees(None).await.map_err(|e| {
+                record_rpc_error(rollup, "feeHistory");
+                DomainError::Da(format!("Fee estimation failed: {}", e))
+            })?;
+            inner.max_fee_per_gas.get_or_insert(max_fee);
+            inner.max_priority_fee_per_gas.get_or_insert(priority_fee);
+        }
+    }
+    if tx.gas().is_none() {
+        let gas = client.estimate_gas(tx, None).await.map_err(|e| {
+            record_rpc_error(rollup, "estimateGas");
+            DomainError::Da(format!("Gas estimation failed: {}", e))
+        })?;
+        tx.set_gas(gas);
+    }
+    // Anything left (chain id, legacy gas price) is filled by the middleware itself
+    client
+        .fill_transaction(tx, None)
+        .await
+        .map_err(|e| DomainError::Da(format!("Tx fill failed: {}", e)))
+}
+
+/// A private transaction relay (Flashbots Protect style) that takes signed transactions through
+/// `eth_sendPrivateTransaction` and keeps them out of the public mempool.
+pub struct PrivateRelay {
+    client: reqwest::Client,
+    url: String,
+    rollup: String,
+}
+
+impl PrivateRelay {
+    pub fn new(url: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("Failed to build relay HTTP client");
+        Self { client, url: url.into(), rollup: "default".to_string() }
+    }
+
+    /// Names the rollup the relay sends for, for the `rollup` label of its metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
+    }
+
+    /// Hands the signed transaction `raw` to the relay and returns the hash it reports.
+    pub async fn send(&self, raw: &Bytes) -> Result<H256, String> {
+        let start = std::time::Instant::now();
+        let res = self.post(raw).await;
+        histogram!("relay_request_duration_seconds", "rollup" => self.rollup.clone()).record(start.elapsed().as_secs_f64());
+        res
+    }
+
+    async fn post(&self, raw: &Bytes) -> Result<H256, String> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_sendPrivateTransaction",
+            "params": [{ "tx": raw }],
+        });
+        let res = self
+            .client
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| format!("relay request failed: {}", e))?;
+        let body: serde_json::Value = res
+            .json()
+            .await
+            .map_err(|e| format!("relay response unreadable: {}", e))?;
+        if let Some(error) = body.get("error") {
+            return Err(error.get("message").and_then(|m| m.as_str()).unwrap_or("relay error").to_string());
+        }
+        serde_json::from_value(body["result"].clone())
+            .map_err(|e| format!("relay returned no transaction hash: {}", e))
+    }
+}
+
+/// Fills, signs and broadcasts `tx`, returning its hash and the signed transaction. With a
+/// `relay` the signed transaction goes to it instead of the node's public mempool.
+///
+/// The hash is computed locally before broadcasting. When `known_is_sent` is set and the node
+/// answers with an "already known" style error, that hash is returned instead of an error, since
+/// a previous attempt already got the same transaction out. "Nonce too low" only counts as sent
+/// if the node has a transaction with that hash; otherwise another transaction took the nonce,
+/// `nonces` is re-synced and a transient error is returned so the submission is retried.
+pub async fn sign_and_send<M: Middleware>(
+    rollup: &str,
+    client: &M,
+    mut tx: TypedTransaction,
+    known_is_sent: bool,
+    relay: Option<&PrivateRelay>,
+    nonces: Option<&NonceManager>,
+) -> Result<(H256, Bytes), DomainError> {
+    fill_transaction(rollup, client, &mut tx).await?;
+    let from = tx.from().copied().unwrap_or_default();
+    let signature = client
+        .sign_transaction(&tx, from)
+        .await
+        .map_err(|e| DomainError::Da(format!("Tx signing failed: {}", e)))?;
+    let tx_hash = tx.hash(&signature);
+    let raw = tx.rlp_signed(&signature);
+
+    let sent = match relay {
+        Some(relay) => relay.send(&raw).await,
+        None => client
+            .send_raw_transaction(raw.clone())
+            .await
+            .map(|pending| pending.tx_hash())
+            .map_err(|e| e.to_string()),
+    };
+    if let (Some(_), Some(nonces), Some(nonce)) = (relay, nonces, tx.nonce()) {
+        if sent.is_ok() {
+            nonces.mark_relayed(*nonce);
+        }
+    }
+    match sent {
+        Ok(hash) => Ok((hash, raw)),
+        Err(e) if known_is_sent && is_already_known(&e) => {
+            warn!("Node already has tx {:?} ({}), treating as sent", tx_hash, e);
+            counter!("tx_already_known_total", "rollup" => rollup.to_string()).increment(1);
+            Ok((tx_hash, raw))
+        }
+        Err(e) if known_is_sent && is_nonce_too_low(&e) => {
+            if let Ok(Some(_)) = client.get_transaction(tx_hash).await {
+                warn!("Tx {:?} already landed ({}), treating as sent", tx_hash, e);
+                counter!("tx_already_known_total", "rollup" => rollup.to_string()).increment(1);
+                return Ok((tx_hash, raw));
+            }
+            counter!("tx_nonce_taken_total", "rollup" => rollup.to_string()).increment(1);
+            if let Some(nonces) = nonces {
+                if let Err(e) = nonces.reconcile(rollup, client).await {
+                    warn!("Nonce re-sync failed: {}", e);
+                }
+            }
+            Err(DomainError::DaTransient(format!(
+                "Nonce {:?} of tx {:?} was used by another transaction: {}",
+                tx.nonce(),
+                tx_hash,
+                e
+            )))
+        }
+        Err(e) => {
+            record_rpc_error(rollup, "sendTransaction");
+            Err(DomainError::Da(format!("Tx send failed: {}", e)))
+        }
+    }
+}
+
+/// Whether block `number` emitted a log from `address` with first topic `topic`. The header's
+/// logs bloom is checked first; a block it rules out costs one header fetch and no `eth_getLogs`.
+pub async fn block_has_log<M: Middleware>(
+    rollup: &str,
+    client: &M,
+    number: u64,
+    address: Address,
+    topic: H256,
+) -> Result<bool, DomainError> {
+    let block = client.get_block(number).await.map_err(|e| {
+        record_rpc_error(rollup, "getBlockByNumber");
+        DomainError::DaTransient(format!("Provider error: {}", e))
+    })?;
+    if let Some(bloom) = block.and_then(|b| b.logs_bloom) {
+        if !bloom_may_contain(&bloom, address, topic) {
+            counter!("log_bloom_negative_total", "rollup" => rollup.to_string()).increment(1);
+            return Ok(false);
+        }
+    }
+
+    let filter = Filter::new().address(address).topic0(topic).from_block(number).to_block(number);
+    let logs = client.get_logs(&filter).await.map_err(|e| {
+        record_rpc_error(rollup, "getLogs");
+        DomainError::DaTransient(format!("Provider error: {}", e))
+    })?;
+    Ok(!logs.is_empty())
+}
+
+/// `false` only if no log in the block can have come from `address` with `topic`.
+pub fn bloom_may_contain(bloom: &Bloom, address: Address, topic: H256) -> bool {
+    use ethers::abi::ethereum_types::BloomInput;
+    bloom.contains_input(BloomInput::Raw(address.as_bytes()))
+        && bloom.contains_input(BloomInput::Raw(topic.as_bytes()))
+}
+
+/// Checks the receipt of `tx_hash`: `Ok(true)` once it is mined successfully with at least
+/// `required` confirmations in a block that is still canonical, `Ok(false)` while pending or after a reorg
+/// dropped its block, and an error if it reverted.
+pub async fn receipt_confirmed<M: Middleware>(
+    rollup: &str,
+    client: &M,
+    tx_hash: &str,
+    required: u64,
+) -> Result<bool, DomainError> {
+    let hash: H256 = tx_hash
+        .parse()
+        .map_err(|e| DomainError::Da(format!("Invalid hash: {}", e)))?;
+    let receipt = client.get_transaction_receipt(hash).await.map_err(|e| {
+        record_rpc_error(rollup, "getTransactionReceipt");
+        DomainError::DaTransient(format!("Provider error: {}", e))
+    })?;
+
+    let Some(r) = receipt else {
+        return Ok(false);
+    };
+    let Some(status) = r.status else {
+        return Ok(true);
+    };
+    if status.as_u64() != 1 {
+        warn!("Tx {} reverted!", tx_hash);
+        return Err(DomainError::DaReverted("Transaction reverted on-chain".to_string()));
+    }
+
+    let block_number = r.block_number.unwrap_or_default();
+    let current_block = client.get_block_number().await.map_err(|e| {
+        record_rpc_error(rollup, "getBlockNumber");
+        DomainError::DaTransient(format!("Provider error: {}", e))
+    })?;
+
+    let confs = current_block.as_u64().saturating_sub(block_number.as_u64());
+    if confs < required {
+        info!("Tx mined but waiting for confirmations ({}/{})", confs, required);
+        return Ok(false);
+    }
+
+    if let Some(receipt_block) = r.block_hash {
+        let canonical = client.get_block(block_number).await.map_err(|e| {
+            record_rpc_error(rollup, "getBlockByNumber");
+            DomainError::DaTransient(format!("Provider error: {}", e))
+        })?;
+        let canonical_hash = canonical.and_then(|b| b.hash);
+        if canonical_hash != Some(receipt_block) {
+            warn!(
+                "Tx {} was included in block {:?} at height {}, but the canonical block is now {:?}",
+                tx_hash, receipt_block, block_number, canonical_hash
+            );
+            counter!("reorg_detected_total", "rollup" => rollup.to_string()).increment(1);
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Errors with [`DomainError::FeeCapExceeded`] while the latest block's base fee is above
+/// `cap_gwei`. A node that reports no base fee (pre-London) never defers.
+pub async fn ensure_base_fee_within<M: Middleware>(rollup: &str, client: &M, cap_gwei: u64) -> Result<(), DomainError> {
+    let block = client.get_block(BlockNumber::Latest).await.map_err(|e| {
+        record_rpc_error(rollup, "getBlockByNumber");
+        DomainError::DaTransient(format!("Provider error: {}", e))
+    })?;
+    let Some(base_fee) = block.and_then(|b| b.base_fee_per_gas) else {
+        return Ok(());
+    };
+    let cap = U256::from(cap_gwei) * U256::exp10(9);
+    if base_fee > cap {
+        let current = ethers::utils::format_units(base_fee, "gwei").unwrap_or_else(|_| base_fee.to_string());
+        warn!("Base fee is {} gwei, above the {} gwei cap; deferring submission", current, cap_gwei);
+        return Err(DomainError::FeeCapExceeded(format!(
+            "base fee too high, deferring ({} gwei > {} gwei)",
+            current, cap_gwei
+        )));
+    }
+    Ok(())
+}
+
+/// Chain-specific EIP-1559 fee settings that replace the node's fee estimate, for chains where
+/// its priority-fee heuristic is off. `max_priority_fee_gwei` fixes the tip, and
+/// `fee_multiplier` scales the estimated base-fee headroom and, unless fixed, the tip.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FeeOverrides {
+    pub max_priority_fee_gwei: Option<u64>,
+    pub fee_multiplier: Option<f64>,
+}
+
+impl FeeOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.max_priority_fee_gwei.is_none() && self.fee_multiplier.is_none()
+    }
+
+    /// Sets the fees of an EIP-1559 `tx` from the node's estimate with the overrides applied.
+    /// Without overrides nothing is set, and `fill_transaction` estimates the fees as usual.
+    pub async fn apply<M: Middleware>(&self, rollup: &str, client: &M, tx: &mut TypedTransaction) -> Result<(), DomainError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let TypedTransaction::Eip1559(inner) = tx else {
+            return Ok(());
+        };
+        let (estimated_max_fee, estimated_tip) = client.estimate_eip1559_fees(None).await.map_err(|e| {
+            record_rpc_error(rollup, "feeHistory");
+            DomainError::Da(format!("Fee estimation failed: {}", e))
+        })?;
+        let scale = |value: U256| self.fee_multiplier.map_or(value, |m| scale_u256(value, m));
+        let tip = match self.max_priority_fee_gwei {
+            Some(gwei) => U256::from(gwei) * U256::exp10(9),
+            None => scale(estimated_tip),
+        };
+        // The estimate's base-fee headroom, paid on top of whichever tip is sent
+        let max_fee = scale(estimated_max_fee.saturating_sub(estimated_tip)) + tip;
+        inner.max_priority_fee_per_gas = Some(tip);
+        inner.max_fee_per_gas = Some(max_fee);
+        Ok(())
+    }
+}
+
+/// The receipt of `tx_hash`, or `None` while it is not mined.
+pub async fn fetch_receipt<M: Middleware>(
+    rollup: &str,
+    client: &M,
+    tx_hash: &str,
+) -> Result<Option<TransactionReceipt>, DomainError> {
+    let hash: H256 = tx_hash
+        .parse()
+        .map_err(|e| DomainError::Da(format!("Invalid hash: {}", e)))?;
+    client.get_transaction_receipt(hash).await.map_err(|e| {
+        record_rpc_error(rollup, "getTransactionReceipt");
+        DomainError::DaTransient(format!("Provider error: {}", e))
+    })
+}
+
+/// Scales `value` by `multiplier`, rounding up.
+pub fn scale_u256(value: U256, multiplier: f64) -> U256 {
+    // Work in per-mille to stay in integer arithmetic on U256
+    let per_mille = (multiplier * 1000.0).ceil() as u64;
+    value * U256::from(per_mille) / U256::from(1000u64)
+}
+
+async fn fetch_transaction<M: Middleware>(rollup: &str, client: &M, tx_hash: &str) -> Result<Transaction, DomainError> {
+    let hash: H256 = tx_hash
+        .parse()
+        .map_err(|e| DomainError::Da(format!("Invalid hash: {}", e)))?;
+    client
+        .get_transaction(hash)
+        .await
+        .map_err(|e| {
+            record_rpc_error(rollup, "getTransactionByHash");
+            DomainError::Da(format!("Provider error: {}", e))
+        })?
+        .ok_or_else(|| DomainError::Da(format!("Transaction {:?} not found", hash)))
+}
+
+fn decode_signed_tx(signed_tx: &str) -> Result<Transaction, DomainError> {
+    let raw: Bytes = signed_tx
+        .parse()
+        .map_err(|e| DomainError::Da(format!("Invalid signed transaction: {}", e)))?;
+    let mut tx: Transaction = ethers::utils::rlp::decode(&raw)
+        .map_err(|e| DomainError::Da(format!("Undecodable signed transaction: {}", e)))?;
+    tx.recover_from_mut()
+        .map_err(|e| DomainError::Da(format!("Unrecoverable signed transaction: {}", e)))?;
+    Ok(tx)
+}
+
+/// The in-flight transaction `tx_hash`, decoded from `signed_tx` (its hex RLP) when the caller
+/// kept it and fetched from the node otherwise.
+async fn original_transaction<M: Middleware>(
+    rollup: &str,
+    client: &M,
+    tx_hash: &str,
+    signed_tx: Option<&str>,
+) -> Result<Transaction, DomainError> {
+    match signed_tx {
+        Some(signed_tx) => decode_signed_tx(signed_tx),
+        None => fetch_transaction(rollup, client, tx_hash).await,
+    }
+}
+
+/// `(max_fee_per_gas, max_priority_fee_per_gas)` of `tx`; legacy transactions pay their gas
+/// price for both.
+fn transaction_fees(tx: &Transaction) -> Result<(U256, U256), DomainError> {
+    let max_fee = tx
+        .max_fee_per_gas
+        .or(tx.gas_price)
+        .ok_or_else(|| DomainError::Da("Original transaction has no fee fields".into()))?;
+    Ok((max_fee, tx.max_priority_fee_per_gas.unwrap_or(max_fee)))
+}
+
+/// Re-broadcasts the transaction `tx_hash` with the same nonce and payload but its EIP-1559
+/// fees scaled by `ratio`, so it replaces the original in the mempool. `signed_tx` is the
+/// original's hex RLP if the caller kept it; a transaction sent through a private relay
+/// is only known to the node once mined, so replacing one needs it.
+///
+/// Fees are capped at `max_fee_per_gas`. Once the cap leaves no room to bump, the original
+/// hash is returned unchanged, with no signed transaction.
+#[allow(clippy::too_many_arguments)]
+pub async fn resend_with_bumped_fees<M: Middleware>(
+    rollup: &str,
+    client: &M,
+    tx_hash: &str,
+    signed_tx: Option<&str>,
+    ratio: f64,
+    max_fee_per_gas: Option<U256>,
+    known_is_sent: bool,
+    relay: Option<&PrivateRelay>,
+) -> Result<(H256, Option<Bytes>), DomainError> {
+    let original = original_transaction(rollup, client, tx_hash, signed_tx).await?;
+    let hash = original.hash;
+    let (max_fee, priority_fee) = transaction_fees(&original)?;
+
+    // Nodes reject replacements that do not raise fees enough
+    let ratio = ratio.max(MIN_REPLACEMENT_BUMP);
+    let mut new_max_fee = scale_u256(max_fee, ratio);
+    if let Some(cap) = max_fee_per_gas {
+        new_max_fee = new_max_fee.min(cap);
+    }
+    if new_max_fee < scale_u256(max_fee, MIN_REPLACEMENT_BUMP) {
+        warn!("Max fee cap reached for {:?}, not replacing", hash);
+        counter!("fee_escalation_capped_total", "rollup" => rollup.to_string()).increment(1);
+        return Ok((hash, None));
+    }
+    let new_priority_fee = scale_u256(priority_fee, ratio).min(new_max_fee);
+
+    let mut replacement = Eip1559TransactionRequest::new()
+        .from(original.from)
+        .data(original.input)
+        .value(original.value)
+        .gas(original.gas)
+        .nonce(original.nonce)
+        .max_fee_per_gas(new_max_fee)
+        .max_priority_fee_per_gas(new_priority_fee);
+    if let Some(to) = original.to {
+        replacement = replacement.to(to);
+    }
+    if let Some(chain_id) = original.chain_id {
+        replacement = replacement.chain_id(chain_id.as_u64());
+    }
+
+    info!(
+        "Replacing tx {:?} (nonce {}) with max fee {} -> {}",
+        hash, original.nonce, max_fee, new_max_fee
+    );
+    let (hash, raw) = sign_and_send(rollup, client, replacement.into(), known_is_sent, relay, None).await?;
+    Ok((hash, Some(raw)))
+}
+
+/// Frees `nonce` by replacing whatever is pending at it with a 0-value transfer to the sender
+/// and no calldata. `fees` are the `(max_fee, priority_fee)` of the pending transaction; both
+/// are raised by [`MIN_REPLACEMENT_BUMP`] so the node accepts the replacement.
+pub async fn cancel_transaction<M: Middleware>(
+    rollup: &str,
+    client: &M,
+    nonce: U256,
+    fees: (U256, U256),
+    relay: Option<&PrivateRelay>,
+) -> Result<H256, DomainError> {
+    let from = client
+        .default_sender()
+        .ok_or_else(|| DomainError::Da("Cancelling needs a signing client".into()))?;
+    let max_fee = scale_u256(fees.0, MIN_REPLACEMENT_BUMP);
+    let priority_fee = scale_u256(fees.1, MIN_REPLACEMENT_BUMP).min(max_fee);
+    let cancel = Eip1559TransactionRequest::new()
+        .from(from)
+        .to(from)
+        .value(0)
+        .gas(21_000)
+        .nonce(nonce)
+        .max_fee_per_gas(max_fee)
+        .max_priority_fee_per_gas(priority_fee);
+
+    info!("Cancelling nonce {} with a self-transfer at max fee {}", nonce, max_fee);
+    sign_and_send(rollup, client, cancel.into(), false, relay, None).await.map(|(hash, _)| hash)
+}
+
+/// Cancels the pending transaction `tx_hash` with [`cancel_transaction`] at its nonce.
+/// `signed_tx` is as for [`resend_with_bumped_fees`].
+pub async fn cancel_pending<M: Middleware>(
+    rollup: &str,
+    client: &M,
+    tx_hash: &str,
+    signed_tx: Option<&str>,
+    relay: Option<&PrivateRelay>,
+) -> Result<H256, DomainError> {
+    let original = original_transaction(rollup, client, tx_hash, signed_tx).await?;
+    cancel_transaction(rollup, client, original.nonce, transaction_fees(&original)?, relay).await
+}
 
 #[async_trait]
 pub trait BridgeClient: BridgeReader + Send + Sync {
@@ -27,13 +777,20 @@ pub trait BridgeClient: BridgeReader + Send + Sync {
 pub struct RealBridgeClient<M: Middleware> {
     bridge: ZKRollupBridge<M>,
     client: Arc<M>,
+    rollup: String,
 }
 
 #[cfg(not(tarpaulin_include))]
 impl<M: Middleware> RealBridgeClient<M> {
     pub fn new(bridge: ZKRollupBridge<M>) -> Self {
         let client = bridge.client();
-        Self { bridge, client }
+        Self { bridge, client, rollup: "default".to_string() }
+    }
+
+    /// Names the rollup the bridge belongs to, for the `rollup` label of its metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
     }
 }
 
@@ -49,6 +806,111 @@ impl<M: Middleware + 'static> BridgeReader for RealBridgeClient<M> {
             .map_err(|e| DomainError::Da(format!("Failed to fetch state root: {}", e)))?;
         Ok(H256::from(root))
     }
+
+    async fn committed_data_hash(&self, index: u64) -> Result<H256, DomainError> {
+        let hash = self
+            .bridge
+            .committed_data_hash(index.into())
+            .call()
+            .await
+            .map_err(|e| DomainError::Da(format!("Failed to fetch committed data hash: {}", e)))?;
+        Ok(H256::from(hash))
+    }
+
+    async fn next_batch_index(&self) -> Result<U256, DomainError> {
+        self.bridge
+            .next_batch_index()
+            .call()
+            .await
+            .map_err(|e| DomainError::Da(format!("Failed to fetch next batch index: {}", e)))
+    }
+
+    async fn verify_proof(
+        &self,
+        system: ProofSystem,
+        proof_hex: &str,
+        public_inputs: &[u8],
+    ) -> Result<bool, DomainError> {
+        let proof = match parse_proof(system, proof_hex).map_err(DomainError::InvalidBatch)? {
+            Proof::Groth16(proof) => proof,
+            Proof::Plonk(_) => {
+                return Err(DomainError::Internal("verifyProof only takes Groth16 proofs".into()))
+            }
+        };
+        let inputs = public_inputs.chunks(32).map(U256::from_big_endian).collect();
+        self.bridge
+            .verify_proof(proof, inputs)
+            .call()
+            .await
+            .map_err(|e| DomainError::Da(format!("Failed to call verifyProof: {}", e)))
+    }
+}
+
+/// Wraps a [`BridgeReader`] so that `state_root` is read from the bridge at most once per `ttl`.
+/// Every batch in `Proving` asks for the root, so a cycle otherwise makes one identical call per
+/// batch. Only successful reads are cached; the other methods pass straight through.
+pub struct CachingBridgeReader {
+    inner: Arc<dyn BridgeReader>,
+    ttl: Duration,
+    state_root: tokio::sync::Mutex<Option<(std::time::Instant, H256)>>,
+}
+
+impl CachingBridgeReader {
+    pub fn new(inner: Arc<dyn BridgeReader>, ttl: Duration) -> Self {
+        Self { inner, ttl, state_root: tokio::sync::Mutex::new(None) }
+    }
+}
+
+#[async_trait]
+impl BridgeReader for CachingBridgeReader {
+    async fn state_root(&self) -> Result<H256, DomainError> {
+        // Held across the read so concurrent callers wait for it instead of issuing their own
+        let mut cached = self.state_root.lock().await;
+        if let Some((read_at, root)) = *cached {
+            if read_at.elapsed() < self.ttl {
+                return Ok(root);
+            }
+        }
+        let root = self.inner.state_root().await?;
+        *cached = Some((std::time::Instant::now(), root));
+        Ok(root)
+    }
+
+    async fn committed_data_hash(&self, index: u64) -> Result<H256, DomainError> {
+        self.inner.committed_data_hash(index).await
+    }
+
+    async fn next_batch_index(&self) -> Result<U256, DomainError> {
+        self.inner.next_batch_index().await
+    }
+
+    async fn verify_proof(
+        &self,
+        system: ProofSystem,
+        proof_hex: &str,
+        public_inputs: &[u8],
+    ) -> Result<bool, DomainError> {
+        self.inner.verify_proof(system, proof_hex, public_inputs).await
+    }
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> NodeStatus for RealBridgeClient<M> {
+    async fn is_syncing(&self) -> Result<bool, DomainError> {
+        let status = self
+            .client
+            .syncing()
+            .await
+            .map_err(|e| DomainError::Da(format!("Failed to query eth_syncing: {}", e)))?;
+        Ok(!matches!(status, SyncingStatus::IsFalse))
+    }
+
+    async fn block_number(&self) -> Result<u64, DomainError> {
+        self.client.get_block_number().await.map(|n| n.as_u64()).map_err(|e| {
+            record_rpc_error(&self.rollup, "getBlockNumber");
+            DomainError::DaTransient(format!("Provider error: {}", e))
+        })
+    }
 }
 
 #[cfg(not(tarpaulin_include))]
@@ -89,3 +951,314 @@ impl<M: Middleware + 'static> BridgeClient for RealBridgeClient<M> {
             .map_err(|e| DomainError::Da(format!("Provider error: {}", e)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reconcile_heals_external_nonce_use() {
+        use crate::test_utils::MockClient;
+
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let nonces = NonceManager::new(Address::random()).with_reconcile_interval(Some(Duration::ZERO));
+
+        mock.push(U256::from(7)); // seed
+        mock.push(U256::from(7)); // reconcile: in sync
+        assert_eq!(nonces.reserve("default", &provider).await.unwrap(), U256::from(7));
+
+        // Someone else sent two transactions from our account
+        mock.push(U256::from(10));
+        assert_eq!(nonces.reserve("default", &provider).await.unwrap(), U256::from(10));
+
+        mock.push(U256::from(11));
+        assert_eq!(nonces.reserve("default", &provider).await.unwrap(), U256::from(11));
+        // Nonce 11 was reserved but never broadcast
+        mock.push(U256::from(11));
+        assert_eq!(nonces.reserve("default", &provider).await.unwrap(), U256::from(11));
+
+        // A failed check keeps the local counter
+        mock.push_error("connection refused");
+        assert_eq!(nonces.reserve("default", &provider).await.unwrap(), U256::from(12));
+
+        assert!(mock
+            .requests()
+            .iter()
+            .all(|(method, params)| method == "eth_getTransactionCount" && params[1] == "pending"));
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_keeps_nonces_of_pending_relayed_txs() {
+        use crate::test_utils::MockClient;
+
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let nonces = NonceManager::new(Address::random()).with_reconcile_interval(Some(Duration::ZERO));
+
+        mock.push(U256::from(5)); // seed
+        mock.push(U256::from(5));
+        let relayed = nonces.reserve("default", &provider).await.unwrap();
+        nonces.mark_relayed(relayed);
+
+        // The node does not count the relayed tx yet; rewinding would reuse its nonce
+        mock.push(U256::from(5));
+        assert_eq!(nonces.reserve("default", &provider).await.unwrap(), U256::from(6));
+
+        // Once it is mined, a real gap is healed again
+        mock.push(U256::from(6));
+        assert_eq!(nonces.reserve("default", &provider).await.unwrap(), U256::from(6));
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_off_by_default() {
+        use crate::test_utils::MockClient;
+
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let nonces = NonceManager::new(Address::random());
+
+        mock.push(U256::from(3));
+        assert_eq!(nonces.reserve("default", &provider).await.unwrap(), U256::from(3));
+        assert_eq!(nonces.reserve("default", &provider).await.unwrap(), U256::from(4));
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_is_already_known() {
+        assert!(is_already_known("(code: -32000, message: already known, data: None)"));
+        assert!(!is_already_known("Nonce too low"));
+        assert!(is_nonce_too_low("Nonce too low"));
+        assert!(is_already_known("known transaction: 0xabc"));
+        assert!(!is_already_known("insufficient funds for gas * price + value"));
+    }
+
+    #[tokio::test]
+    async fn test_bloom_excluding_topic_skips_get_logs() {
+        use crate::test_utils::MockClient;
+        use ethers::abi::ethereum_types::BloomInput;
+
+        let bridge = Address::repeat_byte(0x11);
+        let topic = H256::repeat_byte(0x22);
+        let mut bloom = Bloom::default();
+        bloom.accrue(BloomInput::Raw(bridge.as_bytes()));
+        bloom.accrue(BloomInput::Raw(H256::repeat_byte(0x33).as_bytes()));
+
+        let mock = MockClient::new();
+        mock.push(Block::<H256> { number: Some(U64::from(9)), logs_bloom: Some(bloom), ..Default::default() });
+        let provider = Provider::new(mock.clone());
+
+        assert!(!block_has_log("default", &provider, 9, bridge, topic).await.unwrap());
+        let methods: Vec<String> = mock.requests().into_iter().map(|(m, _)| m).collect();
+        assert_eq!(methods, vec!["eth_getBlockByNumber".to_string()]);
+
+        // Once the bloom admits the topic the logs decide
+        bloom.accrue(BloomInput::Raw(topic.as_bytes()));
+        mock.push(Block::<H256> { number: Some(U64::from(9)), logs_bloom: Some(bloom), ..Default::default() });
+        mock.push(vec![Log { address: bridge, topics: vec![topic], ..Default::default() }]);
+        assert!(block_has_log("default", &provider, 9, bridge, topic).await.unwrap());
+        assert_eq!(mock.requests().last().unwrap().0, "eth_getLogs");
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_call_backs_off_then_succeeds() {
+        use crate::test_utils::MockClient;
+
+        let mock = MockClient::new();
+        let backoff = Duration::from_millis(50);
+        let provider = Provider::new(RateLimitRetry::new(mock.clone(), 3, backoff));
+
+        mock.push_error("HTTP error 429 Too Many Requests");
+        mock.push(U64::from(42));
+        let started = std::time::Instant::now();
+        assert_eq!(provider.get_block_number().await.unwrap(), U64::from(42));
+        assert!(started.elapsed() >= backoff);
+        assert_eq!(mock.requests().len(), 2);
+
+        // Anything else is not retried here
+        mock.push_error("connection reset by peer");
+        assert!(provider.get_block_number().await.is_err());
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_resend_with_bumped_fees_reuses_nonce() {
+        use crate::test_utils::MockClient;
+        use ethers::signers::{LocalWallet, Signer};
+
+        let mock = MockClient::new();
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20"
+            .parse()
+            .unwrap();
+        let from = wallet.address();
+        let client = SignerMiddleware::new(Provider::new(mock.clone()), wallet.with_chain_id(1u64));
+
+        let original = Transaction {
+            hash: H256::random(),
+            nonce: 7.into(),
+            from,
+            to: Some(Address::random()),
+            gas: 100_000.into(),
+            max_fee_per_gas: Some(1_000_000_000u64.into()),
+            max_priority_fee_per_gas: Some(100_000_000u64.into()),
+            transaction_type: Some(2.into()),
+            chain_id: Some(1.into()),
+            ..Default::default()
+        };
+        mock.push(original.clone());
+        mock.push(H256::random()); // sendRawTransaction
+
+        let hash = format!("{:?}", original.hash);
+        resend_with_bumped_fees("default", &client, &hash, None, 1.125, None, false, None).await.unwrap();
+
+        let (method, params) = mock.requests().pop().unwrap();
+        assert_eq!(method, "eth_sendRawTransaction");
+        let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+        let (tx, _) = TypedTransaction::decode_signed(&ethers::utils::rlp::Rlp::new(raw.as_ref())).unwrap();
+        assert_eq!(tx.nonce(), Some(&U256::from(7)));
+        let TypedTransaction::Eip1559(tx) = tx else {
+            panic!("expected an EIP-1559 replacement");
+        };
+        assert_eq!(tx.max_fee_per_gas, Some(U256::from(1_125_000_000u64)));
+        assert_eq!(tx.max_priority_fee_per_gas, Some(U256::from(112_500_000u64)));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_reuses_nonce_with_higher_fee_and_no_calldata() {
+        use crate::test_utils::MockClient;
+        use ethers::signers::{LocalWallet, Signer};
+
+        let mock = MockClient::new();
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20"
+            .parse()
+            .unwrap();
+        let from = wallet.address();
+        let client = SignerMiddleware::new(Provider::new(mock.clone()), wallet.with_chain_id(1u64));
+
+        let original = Transaction {
+            hash: H256::random(),
+            nonce: 7.into(),
+            from,
+            to: Some(Address::random()),
+            input: Bytes::from(vec![0xab; 64]),
+            gas: 100_000.into(),
+            max_fee_per_gas: Some(1_000_000_000u64.into()),
+            max_priority_fee_per_gas: Some(100_000_000u64.into()),
+            transaction_type: Some(2.into()),
+            chain_id: Some(1.into()),
+            ..Default::default()
+        };
+        mock.push(original.clone());
+        mock.push(H256::random()); // sendRawTransaction
+
+        cancel_pending("default", &client, &format!("{:?}", original.hash), None, None).await.unwrap();
+
+        let (method, params) = mock.requests().pop().unwrap();
+        assert_eq!(method, "eth_sendRawTransaction");
+        let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+        let (tx, _) = TypedTransaction::decode_signed(&ethers::utils::rlp::Rlp::new(raw.as_ref())).unwrap();
+        assert_eq!(tx.nonce(), Some(&U256::from(7)));
+        let TypedTransaction::Eip1559(tx) = tx else {
+            panic!("expected an EIP-1559 cancel");
+        };
+        assert_eq!(tx.to, Some(from.into()));
+        assert_eq!(tx.value, Some(U256::zero()));
+        assert!(tx.data.as_ref().is_none_or(|data| data.is_empty()));
+        assert!(tx.max_fee_per_gas.unwrap() > U256::from(1_000_000_000u64));
+        assert!(tx.max_priority_fee_per_gas.unwrap() > U256::from(100_000_000u64));
+    }
+
+    #[tokio::test]
+    async fn test_resend_applies_minimum_bump() {
+        use crate::test_utils::MockClient;
+        use ethers::signers::{LocalWallet, Signer};
+
+        let mock = MockClient::new();
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20"
+            .parse()
+            .unwrap();
+        let from = wallet.address();
+        let client = SignerMiddleware::new(Provider::new(mock.clone()), wallet.with_chain_id(1u64));
+
+        let original = Transaction {
+            hash: H256::random(),
+            nonce: 3.into(),
+            from,
+            to: Some(Address::random()),
+            gas: 100_000.into(),
+            max_fee_per_gas: Some(1_000_000_000u64.into()),
+            max_priority_fee_per_gas: Some(100_000_000u64.into()),
+            chain_id: Some(1.into()),
+            ..Default::default()
+        };
+        mock.push(original.clone());
+        mock.push(H256::random()); // sendRawTransaction
+
+        // A 5% bump would be rejected as underpriced, so 12.5% is used instead
+        let hash = format!("{:?}", original.hash);
+        resend_with_bumped_fees("default", &client, &hash, None, 1.05, None, false, None).await.unwrap();
+
+        let (_, params) = mock.requests().pop().unwrap();
+        let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+        let (tx, _) = TypedTransaction::decode_signed(&ethers::utils::rlp::Rlp::new(raw.as_ref())).unwrap();
+        assert_eq!(tx.nonce(), Some(&U256::from(3)));
+        let TypedTransaction::Eip1559(tx) = tx else {
+            panic!("expected an EIP-1559 replacement");
+        };
+        assert_eq!(tx.max_fee_per_gas, Some(U256::from(1_125_000_000u64)));
+
+        // A cap that only allows a 10% bump stops replacements
+        mock.push(original.clone());
+        let res = resend_with_bumped_fees("default", &client, &hash, None, 1.25, Some(1_100_000_000u64.into()), false, None)
+            .await
+            .unwrap();
+        assert_eq!(res, (original.hash, None));
+    }
+
+    #[tokio::test]
+    async fn test_resend_stops_at_fee_cap() {
+        use crate::test_utils::MockClient;
+
+        let mock = MockClient::new();
+        let provider = Provider::new(mock.clone());
+        let original = Transaction {
+            hash: H256::random(),
+            max_fee_per_gas: Some(1_000u64.into()),
+            ..Default::default()
+        };
+        mock.push(original.clone());
+
+        let hash = format!("{:?}", original.hash);
+        let res = resend_with_bumped_fees("default", &provider, &hash, None, 1.25, Some(1_000u64.into()), false, None)
+            .await
+            .unwrap();
+        assert_eq!(res, (original.hash, None));
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_receipt_error_counted_by_method() {
+        use crate::test_utils::MockClient;
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let mock = MockClient::new();
+        mock.push_error("connection reset");
+        let provider = Provider::new(mock);
+
+        metrics::with_local_recorder(&recorder, || {
+            let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            let res = rt.block_on(receipt_confirmed("default", &provider, &format!("{:?}", H256::zero()), 1));
+            assert!(res.is_err());
+        });
+
+        let counted = snapshotter.snapshot().into_vec().into_iter().any(|(key, _, _, value)| {
+            let key = key.key();
+            key.name() == "rpc_errors_total"
+                && key.labels().any(|l| l.key() == "method" && l.value() == "getTransactionReceipt")
+                && value == DebugValue::Counter(1)
+        });
+        assert!(counted);
+    }
+}
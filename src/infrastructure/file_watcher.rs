@@ -0,0 +1,304 @@
+use crate::application::orchestrator::{enqueue_batch, next_batch_index};
+use crate::application::ports::Storage;
+use crate::domain::batch::{Batch, ProofSystem};
+use crate::infrastructure::batch_api::NewBatchRequest;
+use crate::infrastructure::payload::{read_payload, PayloadSource};
+use anyhow::{Context, Result};
+use metrics::counter;
+use sha1_smol::Sha1;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Polls an inbox directory for `*.json` batch descriptors, shaped like a `POST /batches` body,
+/// and stores a batch for each. Stored descriptors move to `done/` and unreadable ones to
+/// `failed/`, both inside the inbox. Producers should write descriptors under another name and
+/// rename them to `*.json` once complete.
+pub struct FileWatcher {
+    pub storage: Arc<dyn Storage>,
+    pub inbox: PathBuf,
+    pub chain_id: u64,
+    pub bridge: String,
+    pub max_pending: Option<u64>,
+    // Used for descriptors that do not name a proof system
+    pub proof_system: ProofSystem,
+    pub poll_interval: Duration,
+    // The `rollup` label of the watcher's metrics
+    pub rollup: String,
+}
+
+impl FileWatcher {
+    /// Scans the inbox forever, every `poll_interval`.
+    pub async fn run(self) {
+        info!("Watching {} for batch descriptors", self.inbox.display());
+        loop {
+            if let Err(e) = self.scan().await {
+                warn!("Scanning inbox {} failed: {:#}", self.inbox.display(), e);
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Takes in every descriptor currently in the inbox and returns how many batches were
+    /// stored. A descriptor whose data file cannot be read yet, or that does not fit under
+    /// `max_pending`, stays in the inbox for the next scan.
+    pub async fn scan(&self) -> Result<usize> {
+        let mut descriptors = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.inbox)
+            .await
+            .with_context(|| format!("read inbox {}", self.inbox.display()))?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json")
+                && entry.file_type().await?.is_file()
+            {
+                descriptors.push(path);
+            }
+        }
+        // Oldest names first, so sequentially named descriptors are stored in order
+        descriptors.sort();
+
+        let mut stored = 0;
+        for path in descriptors {
+            let req: NewBatchRequest = match tokio::fs::read(&path)
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|raw| serde_json::from_slice(&raw).map_err(anyhow::Error::from))
+            {
+                Ok(req) => req,
+                Err(e) => {
+                    warn!("Invalid batch descriptor {}: {}", path.display(), e);
+                    counter!("inbox_descriptors_invalid_total", "rollup" => self.rollup.clone()).increment(1);
+                    self.move_to(&path, "failed").await?;
+                    continue;
+                }
+            };
+
+            let Some(data_file) = self.resolve_data_file(&req.data_file) else {
+                warn!(
+                    "Descriptor {}: data file {} is outside the inbox",
+                    path.display(),
+                    req.data_file
+                );
+                counter!("inbox_descriptors_invalid_total", "rollup" => self.rollup.clone()).increment(1);
+                self.move_to(&path, "failed").await?;
+                continue;
+            };
+            let context = format!("Failed to read data file {}", data_file);
+            let data = match read_payload(&self.rollup, &data_file, &context).await {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!("Descriptor {}: cannot read data yet: {}", path.display(), e);
+                    continue;
+                }
+            };
+            let mut batch = Batch::new(
+                self.chain_id,
+                &self.bridge,
+                data_file,
+                Sha1::from(data).digest().to_string(),
+                req.new_root,
+                format!("{:?}", req.da_mode),
+            );
+            batch.proof_system = req.proof_system.unwrap_or(self.proof_system);
+
+            // A descriptor seen before (same payload, root and mode) maps to the same id
+            if self.storage.get_batch(batch.id).await?.is_none() {
+                batch.index = match req.index {
+                    Some(index) => index,
+                    None => next_batch_index(self.storage.as_ref()).await?,
+                };
+                if !enqueue_batch(&self.rollup, self.storage.as_ref(), &batch, self.max_pending).await? {
+                    break;
+                }
+                info!("Batch {} enqueued from {}", batch.id, path.display());
+                counter!("batches_enqueued_total", "rollup" => self.rollup.clone(), "source" => "inbox")
+                    .increment(1);
+                stored += 1;
+            }
+            self.move_to(&path, "done").await?;
+        }
+        Ok(stored)
+    }
+
+    /// Where a descriptor's `data_file` is read from: `http(s)://` and `s3://` locations as
+    /// given, and paths inside the inbox. `None` for an absolute path or one with `..`, which
+    /// could reach any file the daemon can read.
+    fn resolve_data_file(&self, data_file: &str) -> Option<String> {
+        let source = PayloadSource::parse(data_file);
+        let Some(path) = source.local_path() else {
+            return Some(data_file.to_string());
+        };
+        if !path.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir)) {
+            return None;
+        }
+        Some(self.inbox.join(path).to_string_lossy().into_owned())
+    }
+
+    async fn move_to(&self, descriptor: &Path, folder: &str) -> Result<()> {
+        let dir = self.inbox.join(folder);
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .with_context(|| format!("create {}", dir.display()))?;
+        let target = dir.join(descriptor.file_name().unwrap_or_default());
+        tokio::fs::rename(descriptor, &target)
+            .await
+            .with_context(|| format!("move {} to {}", descriptor.display(), target.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::batch::BatchStatus;
+    use crate::infrastructure::storage_sqlite::SqliteStorage;
+
+    async fn watcher(inbox: &Path) -> FileWatcher {
+        FileWatcher {
+            storage: Arc::new(SqliteStorage::new("sqlite::memory:").await.unwrap()),
+            inbox: inbox.to_path_buf(),
+            chain_id: 1,
+            bridge: "0x0000000000000000000000000000000000000001".into(),
+            max_pending: None,
+            proof_system: ProofSystem::default(),
+            poll_interval: Duration::from_millis(10),
+            rollup: "default".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dropped_descriptor_becomes_a_batch() {
+        let inbox = tempfile::tempdir().unwrap();
+        std::fs::write(inbox.path().join("payload.bin"), b"batch payload").unwrap();
+        let descriptor =
+            r#"{"data_file": "payload.bin", "new_root": "0x01", "da_mode": "calldata"}"#;
+        std::fs::write(inbox.path().join("0001.json"), descriptor).unwrap();
+        let watcher = watcher(inbox.path()).await;
+
+        assert_eq!(watcher.scan().await.unwrap(), 1);
+
+        let pending = watcher.storage.get_pending_batches().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].status, BatchStatus::Discovered);
+        assert_eq!(pending[0].new_root, "0x01");
+        assert_eq!(
+            pending[0].data_file,
+            inbox.path().join("payload.bin").to_string_lossy()
+        );
+        assert!(!inbox.path().join("0001.json").exists());
+        assert!(inbox.path().join("done/0001.json").exists());
+
+        // Dropping the same descriptor again stores nothing new
+        std::fs::write(inbox.path().join("0001-again.json"), descriptor).unwrap();
+        assert_eq!(watcher.scan().await.unwrap(), 0);
+        assert_eq!(
+            watcher.storage.get_pending_batches().await.unwrap().len(),
+            1
+        );
+        assert!(inbox.path().join("done/0001-again.json").exists());
+    }
+
+    #[tokio::test]
+    async fn test_descriptor_waits_for_its_data_file() {
+        let inbox = tempfile::tempdir().unwrap();
+        std::fs::write(
+            inbox.path().join("a.json"),
+            r#"{"data_file": "late.bin", "new_root": "0x01", "da_mode": "calldata"}"#,
+        )
+        .unwrap();
+        std::fs::write(inbox.path().join("broken.json"), "{").unwrap();
+        let watcher = watcher(inbox.path()).await;
+
+        assert_eq!(watcher.scan().await.unwrap(), 0);
+        assert!(inbox.path().join("a.json").exists());
+        assert!(inbox.path().join("failed/broken.json").exists());
+
+        std::fs::write(inbox.path().join("late.bin"), b"payload").unwrap();
+        assert_eq!(watcher.scan().await.unwrap(), 1);
+        assert!(inbox.path().join("done/a.json").exists());
+    }
+
+    #[tokio::test]
+    async fn test_descriptor_pointing_outside_the_inbox_fails() {
+        let root = tempfile::tempdir().unwrap();
+        let inbox = root.path().join("inbox");
+        std::fs::create_dir(&inbox).unwrap();
+        std::fs::write(root.path().join("secret.bin"), b"not for the inbox").unwrap();
+        let absolute = root.path().join("secret.bin");
+        std::fs::write(
+            inbox.join("a.json"),
+            r#"{"data_file": "../secret.bin", "new_root": "0x01", "da_mode": "calldata"}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            inbox.join("b.json"),
+            serde_json::json!({"data_file": absolute, "new_root": "0x02", "da_mode": "calldata"}).to_string(),
+        )
+        .unwrap();
+        let watcher = watcher(&inbox).await;
+
+        assert_eq!(watcher.scan().await.unwrap(), 0);
+        assert!(inbox.join("failed/a.json").exists());
+        assert!(inbox.join("failed/b.json").exists());
+        assert!(watcher.storage.get_pending_batches().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_descriptor_with_remote_data_file_keeps_its_url() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/batches/1.bin"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"remote payload".to_vec()))
+            .mount(&server)
+            .await;
+        let url = format!("{}/batches/1.bin", server.uri());
+        let inbox = tempfile::tempdir().unwrap();
+        std::fs::write(
+            inbox.path().join("a.json"),
+            serde_json::json!({"data_file": url, "new_root": "0x01", "da_mode": "calldata"}).to_string(),
+        )
+        .unwrap();
+        let watcher = watcher(inbox.path()).await;
+
+        assert_eq!(watcher.scan().await.unwrap(), 1);
+        let pending = watcher.storage.get_pending_batches().await.unwrap();
+        assert_eq!(pending[0].data_file, url);
+        assert_eq!(pending[0].data_hash.as_deref(), Some(Sha1::from(b"remote payload").digest().to_string().as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_descriptor_without_index_follows_the_highest_stored() {
+        let inbox = tempfile::tempdir().unwrap();
+        std::fs::write(inbox.path().join("a.bin"), b"first").unwrap();
+        std::fs::write(inbox.path().join("b.bin"), b"second").unwrap();
+        std::fs::write(
+            inbox.path().join("a.json"),
+            r#"{"data_file": "a.bin", "new_root": "0x01", "da_mode": "calldata", "index": 4}"#,
+        )
+        .unwrap();
+        let watcher = watcher(inbox.path()).await;
+        assert_eq!(watcher.scan().await.unwrap(), 1);
+
+        std::fs::write(
+            inbox.path().join("b.json"),
+            r#"{"data_file": "b.bin", "new_root": "0x02", "da_mode": "calldata"}"#,
+        )
+        .unwrap();
+        assert_eq!(watcher.scan().await.unwrap(), 1);
+
+        let mut indices: Vec<u64> = watcher
+            .storage
+            .get_pending_batches()
+            .await
+            .unwrap()
+            .iter()
+            .map(|b| b.index)
+            .collect();
+        indices.sort();
+        assert_eq!(indices, vec![4, 5]);
+    }
+}
@@ -1,8 +1,19 @@
+pub mod attestation;
+pub mod batch_api;
+pub mod blob_archive;
+pub mod bundler;
+pub mod commitment;
+pub mod confirmation;
 pub mod da_blob;
 pub mod da_calldata;
+pub mod da_celestia;
+pub mod da_eigenda;
 pub mod ethereum_adapter;
+pub mod file_watcher;
 pub mod observability;
+pub mod payload;
 pub mod prover_http;
 pub mod prover_mock;
+pub mod prover_object_store;
 pub mod storage_postgres;
 pub mod storage_sqlite;
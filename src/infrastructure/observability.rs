@@ -1,9 +1,16 @@
-use anyhow::Result;
-use axum::{routing::get, Router};
+use crate::application::orchestrator::Orchestrator;
+use crate::application::ports::{NodeStatus, Storage};
+use crate::domain::errors::DomainError;
+use anyhow::{Context, Result};
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use metrics::counter;
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use serde_json::{json, Value};
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
-use tracing::info;
+use tracing::{info, warn};
 
 pub fn init_tracing() {
     // Check for JSON log format request
@@ -29,18 +36,143 @@ pub fn init_metrics() -> Result<PrometheusHandle> {
         .map_err(|e| anyhow::anyhow!("Failed to install recorder: {:?}", e))
 }
 
-pub async fn start_metrics_server(handle: PrometheusHandle, port: u16) {
-    let app = Router::new().route("/metrics", get(move || std::future::ready(handle.render())));
+/// Whether the process should exit when the metrics server cannot start (`METRICS_REQUIRED`).
+pub fn metrics_required() -> bool {
+    std::env::var("METRICS_REQUIRED").unwrap_or_else(|_| "false".to_string()) == "true"
+}
+
+/// How long to wait for the metrics port to bind (`METRICS_BIND_TIMEOUT_MS`, default 5s).
+pub fn metrics_bind_timeout() -> Duration {
+    let ms = std::env::var("METRICS_BIND_TIMEOUT_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5000);
+    Duration::from_millis(ms)
+}
 
+pub async fn bind_metrics_listener(port: u16, timeout: Duration) -> Result<TcpListener> {
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let listener = tokio::time::timeout(timeout, TcpListener::bind(addr))
+        .await
+        .context(format!("timed out binding metrics port {}", port))?
+        .context(format!("failed to bind metrics port {}", port))?;
     info!("Metrics server listening on {}", addr);
+    Ok(listener)
+}
+
+pub async fn serve_metrics(listener: TcpListener, handle: PrometheusHandle) -> Result<()> {
+    let app = Router::new().route("/metrics", get(move || std::future::ready(handle.render())));
 
-    let listener = TcpListener::bind(addr)
-        .await
-        .expect("failed to bind metrics port");
     axum::serve(listener, app)
         .await
-        .expect("failed to start metrics server");
+        .context("metrics server failed")
+}
+
+/// `GET /readyz`: `200 {"status": "ready"}`, or `degraded_status` with the orchestrator's
+/// readiness problems while it cannot make progress. Both carry `last_confirmation`, the time
+/// the last batch confirmed (`null` before the first).
+pub fn readiness_router(orchestrator: Arc<Orchestrator>, degraded_status: StatusCode) -> Router {
+    Router::new()
+        .route("/readyz", get(readyz))
+        .with_state((orchestrator, degraded_status))
+}
+
+async fn readyz(
+    State((orchestrator, degraded_status)): State<(Arc<Orchestrator>, StatusCode)>,
+) -> (StatusCode, Json<Value>) {
+    let problems = orchestrator.readiness_problems().await;
+    let last_confirmation = orchestrator.last_confirmation().map(|at| at.to_rfc3339());
+    if problems.is_empty() {
+        (
+            StatusCode::OK,
+            Json(json!({ "status": "ready", "last_confirmation": last_confirmation })),
+        )
+    } else {
+        (
+            degraded_status,
+            Json(json!({
+                "status": "degraded",
+                "problems": problems,
+                "last_confirmation": last_confirmation,
+            })),
+        )
+    }
+}
+
+/// How long each `/health` check may take before it counts as failing.
+pub const HEALTH_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone)]
+struct HealthChecks {
+    storage: Arc<dyn Storage>,
+    node: Arc<dyn NodeStatus>,
+    timeout: Duration,
+    rollup: String,
+}
+
+/// `GET /health`: `200 {"status": "ok"}` when the database answers a trivial query and the
+/// node returns its block number, each within `timeout`, otherwise `503` with the failing
+/// components.
+pub fn health_router(
+    rollup: &str,
+    storage: Arc<dyn Storage>,
+    node: Arc<dyn NodeStatus>,
+    timeout: Duration,
+) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .with_state(HealthChecks {
+            storage,
+            node,
+            timeout,
+            rollup: rollup.to_string(),
+        })
+}
+
+impl HealthChecks {
+    /// `check`'s result, or a timeout error once it takes longer than `self.timeout`.
+    async fn probe<T>(
+        &self,
+        component: &'static str,
+        check: impl std::future::Future<Output = Result<T, DomainError>>,
+    ) -> Result<T, String> {
+        match tokio::time::timeout(self.timeout, check).await {
+            Ok(res) => res.map_err(|e| e.to_string()),
+            Err(_) => {
+                warn!("Health check of {} timed out after {:?}", component, self.timeout);
+                counter!("health_probe_timeouts_total", "rollup" => self.rollup.clone(), "component" => component)
+                    .increment(1);
+                Err("timed out".to_string())
+            }
+        }
+    }
+}
+
+async fn health(State(checks): State<HealthChecks>) -> (StatusCode, Json<Value>) {
+    let (db, rpc) = tokio::join!(
+        checks.probe("storage", checks.storage.ping()),
+        checks.probe("rpc", checks.node.block_number()),
+    );
+    let mut failing = Vec::new();
+    if let Err(e) = db {
+        failing.push(json!({ "component": "storage", "error": e }));
+    }
+    if let Err(e) = rpc {
+        failing.push(json!({ "component": "rpc", "error": e }));
+    }
+    if failing.is_empty() {
+        (StatusCode::OK, Json(json!({ "status": "ok" })))
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "status": "unhealthy", "failing": failing })),
+        )
+    }
+}
+
+pub async fn start_metrics_server(handle: PrometheusHandle, port: u16) -> Result<()> {
+    let listener = bind_metrics_listener(port, metrics_bind_timeout()).await?;
+    serve_metrics(listener, handle).await
 }
 
 #[cfg(test)]
@@ -57,4 +189,164 @@ mod tests {
     fn test_init_metrics_safe() {
         let _ = init_metrics();
     }
+
+    #[tokio::test]
+    async fn test_bind_metrics_port_in_use() {
+        let taken = TcpListener::bind("0.0.0.0:0").await.unwrap();
+        let port = taken.local_addr().unwrap().port();
+
+        let res = bind_metrics_listener(port, Duration::from_secs(1)).await;
+        assert!(res.is_err());
+        assert!(res.unwrap_err().to_string().contains("failed to bind metrics port"));
+    }
+
+    async fn serve_health(storage: Arc<dyn Storage>, block: Option<u64>) -> reqwest::Response {
+        use crate::contracts::ZKRollupBridge;
+        use crate::infrastructure::ethereum_adapter::RealBridgeClient;
+        use crate::test_utils::MockClient;
+        use ethers::providers::Provider;
+        use ethers::types::{Address, U64};
+
+        let mock = MockClient::new();
+        match block {
+            Some(number) => mock.push(U64::from(number)),
+            None => mock.push_error("connection refused"),
+        }
+        let bridge = ZKRollupBridge::new(Address::zero(), Arc::new(Provider::new(mock)));
+        let router = health_router("default", storage, Arc::new(RealBridgeClient::new(bridge)), HEALTH_PROBE_TIMEOUT);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/health", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        reqwest::get(&url).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_health_ok_when_db_and_rpc_answer() {
+        use crate::infrastructure::storage_sqlite::SqliteStorage;
+
+        let storage = Arc::new(SqliteStorage::new("sqlite::memory:").await.unwrap());
+        let res = serve_health(storage, Some(42)).await;
+        assert_eq!(res.status(), 200);
+        let body: Value = res.json().await.unwrap();
+        assert_eq!(body["status"], "ok");
+    }
+
+    #[tokio::test]
+    async fn test_health_names_failing_storage() {
+        use crate::application::ports::MockStorage;
+        use crate::domain::errors::DomainError;
+
+        let mut storage = MockStorage::new();
+        storage
+            .expect_ping()
+            .returning(|| Err(DomainError::Storage("pool timed out".into())));
+        let res = serve_health(Arc::new(storage), Some(42)).await;
+        assert_eq!(res.status(), 503);
+        let body: Value = res.json().await.unwrap();
+        assert_eq!(body["status"], "unhealthy");
+        assert_eq!(body["failing"].as_array().unwrap().len(), 1);
+        assert_eq!(body["failing"][0]["component"], "storage");
+        assert!(body["failing"][0]["error"].as_str().unwrap().contains("pool timed out"));
+
+        let storage = Arc::new(crate::infrastructure::storage_sqlite::SqliteStorage::new("sqlite::memory:").await.unwrap());
+        let res = serve_health(storage, None).await;
+        assert_eq!(res.status(), 503);
+        let body: Value = res.json().await.unwrap();
+        assert_eq!(body["failing"][0]["component"], "rpc");
+    }
+
+    #[tokio::test]
+    async fn test_health_reports_hung_probes_as_failing() {
+        use crate::application::ports::MockStorage;
+
+        // Accepts the request and never answers, like a node stuck behind a dead connection
+        struct HungNode;
+        #[async_trait::async_trait]
+        impl NodeStatus for HungNode {
+            async fn is_syncing(&self) -> Result<bool, DomainError> {
+                Ok(false)
+            }
+            async fn block_number(&self) -> Result<u64, DomainError> {
+                std::future::pending().await
+            }
+        }
+
+        let mut storage = MockStorage::new();
+        storage.expect_ping().returning(|| Ok(()));
+        let router = health_router("default", Arc::new(storage), Arc::new(HungNode), Duration::from_millis(50));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/health", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        let res = reqwest::get(&url).await.unwrap();
+
+        assert_eq!(res.status(), 503);
+        let body: Value = res.json().await.unwrap();
+        assert_eq!(body["failing"].as_array().unwrap().len(), 1);
+        assert_eq!(body["failing"][0]["component"], "rpc");
+        assert_eq!(body["failing"][0]["error"], "timed out");
+    }
+
+    #[tokio::test]
+    async fn test_readyz_reports_open_prover_circuit() {
+        use crate::application::ports::{ProofProvider, ProofRequest};
+        use crate::contracts::ZKRollupBridge;
+        use crate::domain::batch::BatchId;
+        use crate::infrastructure::da_calldata::CalldataStrategy;
+        use crate::infrastructure::ethereum_adapter::RealBridgeClient;
+        use crate::infrastructure::prover_http::HttpProofProvider;
+        use crate::infrastructure::storage_sqlite::SqliteStorage;
+        use crate::test_utils::MockClient;
+        use ethers::providers::Provider;
+        use ethers::types::Address;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let prover_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&prover_server)
+            .await;
+        let backoff = backoff::ExponentialBackoff {
+            max_elapsed_time: Some(Duration::from_millis(1)),
+            ..Default::default()
+        };
+        let prover = Arc::new(HttpProofProvider::new(prover_server.uri(), 2).with_backoff(backoff));
+
+        let bridge = ZKRollupBridge::new(Address::zero(), Arc::new(Provider::new(MockClient::new())));
+        let orchestrator = Arc::new(
+            Orchestrator::new(
+                Arc::new(SqliteStorage::new("sqlite::memory:").await.unwrap()),
+                prover.clone(),
+                Arc::new(CalldataStrategy::new(bridge.clone(), None)),
+                Arc::new(RealBridgeClient::new(bridge)),
+                5,
+            )
+            .with_readiness_probe(prover.clone()),
+        );
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/readyz", listener.local_addr().unwrap());
+        let router = readiness_router(orchestrator, StatusCode::SERVICE_UNAVAILABLE);
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let res = reqwest::get(&url).await.unwrap();
+        assert_eq!(res.status(), 200);
+        let body: Value = res.json().await.unwrap();
+        assert!(body["last_confirmation"].is_null());
+
+        // Trip the breaker
+        for _ in 0..2 {
+            let _ = prover.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await;
+        }
+        assert!(prover.circuit_open().await);
+
+        let res = reqwest::get(&url).await.unwrap();
+        assert_eq!(res.status(), 503);
+        let body: Value = res.json().await.unwrap();
+        assert_eq!(body["status"], "degraded");
+        assert!(body["problems"][0].as_str().unwrap().contains("circuit breaker is open"));
+    }
 }
@@ -0,0 +1,277 @@
+use crate::domain::errors::DomainError;
+use metrics::histogram;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Client shared by every remote payload read, so connections are pooled across batches.
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .expect("Failed to build payload HTTP client")
+    })
+}
+
+/// Compression applied to a batch payload before it is posted. The codec's id goes into
+/// `daMeta` so the bridge knows how to read the data; the commitment covers the compressed
+/// bytes.
+#[derive(Debug, Deserialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadCodec {
+    Zstd,
+}
+
+impl PayloadCodec {
+    /// Byte identifying the codec in `daMeta`; `0` (no `daMeta`) means uncompressed.
+    pub fn id(&self) -> u8 {
+        match self {
+            PayloadCodec::Zstd => 1,
+        }
+    }
+
+    pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>, DomainError> {
+        match self {
+            PayloadCodec::Zstd => zstd::bulk::compress(data, zstd::DEFAULT_COMPRESSION_LEVEL)
+                .map_err(|e| DomainError::Da(format!("zstd compression failed: {}", e))),
+        }
+    }
+}
+
+/// Whether reading a batch payload failed for a reason that may clear up on its own, as it
+/// does when the network mount holding the file is briefly down. A missing or unreadable
+/// file is not transient.
+pub fn is_transient_read_error(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        ErrorKind::TimedOut
+            | ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::HostUnreachable
+            | ErrorKind::NetworkUnreachable
+            | ErrorKind::NetworkDown
+            | ErrorKind::StaleNetworkFileHandle
+            | ErrorKind::Interrupted
+            | ErrorKind::WouldBlock
+    )
+}
+
+/// Maps a failed payload read to [`DomainError::DataUnavailable`] when it is transient, to
+/// [`DomainError::DataFileMissing`] when the file does not exist and to [`DomainError::Da`]
+/// otherwise, all prefixed with `context`.
+pub fn payload_read_error(context: &str, e: io::Error) -> DomainError {
+    if is_transient_read_error(&e) {
+        DomainError::DataUnavailable(format!("{}: {}", context, e))
+    } else if e.kind() == ErrorKind::NotFound {
+        DomainError::DataFileMissing(format!("{}: {}", context, e))
+    } else {
+        DomainError::Da(format!("{}: {}", context, e))
+    }
+}
+
+/// Where a batch's `data_file` lives. `http(s)://` and `s3://` locations are fetched over
+/// HTTP, and `file://` or a plain path is read from disk. `s3://bucket/key` maps to the
+/// bucket's public virtual-hosted URL, so the object must be readable without signing.
+#[derive(Debug, PartialEq, Clone)]
+pub enum PayloadSource {
+    File(PathBuf),
+    Http(String),
+    S3 { bucket: String, key: String },
+}
+
+impl PayloadSource {
+    pub fn parse(location: &str) -> Self {
+        if let Some(path) = location.strip_prefix("file://") {
+            PayloadSource::File(PathBuf::from(path))
+        } else if location.starts_with("http://") || location.starts_with("https://") {
+            PayloadSource::Http(location.to_string())
+        } else if let Some(rest) = location.strip_prefix("s3://") {
+            let (bucket, key) = rest.split_once('/').unwrap_or((rest, ""));
+            PayloadSource::S3 {
+                bucket: bucket.to_string(),
+                key: key.to_string(),
+            }
+        } else {
+            PayloadSource::File(PathBuf::from(location))
+        }
+    }
+
+    /// The file on disk, for local sources.
+    pub fn local_path(&self) -> Option<&Path> {
+        match self {
+            PayloadSource::File(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Reads the payload. Errors are classified like [`payload_read_error`]: an unreachable
+    /// server or a 5xx is transient, a 404 means the payload is missing. `rollup` labels the
+    /// fetch metrics.
+    pub async fn fetch(&self, rollup: &str, context: &str) -> Result<Vec<u8>, DomainError> {
+        let (url, source) = match self {
+            PayloadSource::File(path) => {
+                return tokio::fs::read(path)
+                    .await
+                    .map_err(|e| payload_read_error(context, e));
+            }
+            PayloadSource::Http(url) => (url.clone(), "http"),
+            PayloadSource::S3 { bucket, key } => {
+                (format!("https://{}.s3.amazonaws.com/{}", bucket, key), "s3")
+            }
+        };
+
+        let start = Instant::now();
+        let res = fetch_remote(&url, context).await;
+        histogram!("payload_fetch_duration_seconds", "rollup" => rollup.to_string(), "source" => source)
+            .record(start.elapsed().as_secs_f64());
+        res
+    }
+}
+
+/// GETs `url`, classifying failures like [`PayloadSource::fetch`].
+async fn fetch_remote(url: &str, context: &str) -> Result<Vec<u8>, DomainError> {
+    let res = http_client()
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| DomainError::DataUnavailable(format!("{}: {}: {}", context, url, e)))?;
+    let status = res.status();
+    if status == StatusCode::NOT_FOUND {
+        return Err(DomainError::DataFileMissing(format!(
+            "{}: {} not found",
+            context, url
+        )));
+    }
+    if status.is_server_error() {
+        return Err(DomainError::DataUnavailable(format!(
+            "{}: {} returned {}",
+            context, url, status
+        )));
+    }
+    if !status.is_success() {
+        return Err(DomainError::Da(format!(
+            "{}: {} returned {}",
+            context, url, status
+        )));
+    }
+    res.bytes()
+        .await
+        .map(|b| b.to_vec())
+        .map_err(|e| DomainError::DataUnavailable(format!("{}: {}: {}", context, url, e)))
+}
+
+/// Reads the payload at `location`, a path or URL as understood by [`PayloadSource`].
+pub async fn read_payload(rollup: &str, location: &str, context: &str) -> Result<Vec<u8>, DomainError> {
+    PayloadSource::parse(location).fetch(rollup, context).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zstd_round_trips() {
+        let payload = b"rollup batch ".repeat(64);
+        let compressed = PayloadCodec::Zstd.compress(&payload).unwrap();
+        assert!(compressed.len() < payload.len());
+        assert_eq!(zstd::bulk::decompress(&compressed, payload.len()).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_payload_source_recognises_schemes() {
+        assert_eq!(
+            PayloadSource::parse("/data/batch.bin"),
+            PayloadSource::File("/data/batch.bin".into())
+        );
+        assert_eq!(
+            PayloadSource::parse("file:///data/batch.bin"),
+            PayloadSource::File("/data/batch.bin".into())
+        );
+        assert_eq!(
+            PayloadSource::parse("https://store.example/b/1"),
+            PayloadSource::Http("https://store.example/b/1".into())
+        );
+        assert_eq!(
+            PayloadSource::parse("s3://batches/rollup/1.bin"),
+            PayloadSource::S3 {
+                bucket: "batches".into(),
+                key: "rollup/1.bin".into()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_http_payload_errors_are_classified() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/gone"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(path("/busy"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let gone = read_payload("default", &format!("{}/gone", server.uri()), "read").await;
+        assert!(matches!(gone, Err(DomainError::DataFileMissing(_))));
+        let busy = read_payload("default", &format!("{}/busy", server.uri()), "read").await;
+        assert!(matches!(busy, Err(DomainError::DataUnavailable(_))));
+    }
+
+    #[test]
+    fn test_http_fetch_duration_recorded() {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            rt.block_on(async {
+                let server = MockServer::start().await;
+                Mock::given(path("/batch.bin"))
+                    .respond_with(ResponseTemplate::new(200).set_body_bytes(b"payload".to_vec()))
+                    .mount(&server)
+                    .await;
+                let data = read_payload("default", &format!("{}/batch.bin", server.uri()), "read").await;
+                assert_eq!(data.unwrap(), b"payload");
+            });
+        });
+
+        let recorded = snapshotter.snapshot().into_vec().into_iter().any(|(key, _, _, value)| {
+            let key = key.key();
+            key.name() == "payload_fetch_duration_seconds"
+                && key.labels().any(|l| l.key() == "source" && l.value() == "http")
+                && matches!(value, DebugValue::Histogram(ref samples) if samples.len() == 1)
+        });
+        assert!(recorded);
+    }
+
+    #[test]
+    fn test_network_errors_are_transient() {
+        let timed_out = payload_read_error("read", io::Error::from(ErrorKind::TimedOut));
+        assert!(matches!(timed_out, DomainError::DataUnavailable(_)));
+        let refused = payload_read_error("read", io::Error::from(ErrorKind::ConnectionRefused));
+        assert!(matches!(refused, DomainError::DataUnavailable(_)));
+
+        let missing = payload_read_error("read", io::Error::from(ErrorKind::NotFound));
+        assert!(matches!(missing, DomainError::DataFileMissing(_)));
+        let denied = payload_read_error("read", io::Error::from(ErrorKind::PermissionDenied));
+        assert!(matches!(denied, DomainError::Da(_)));
+        assert!(missing.to_string().starts_with("Data file missing: read: "));
+        assert!(denied.to_string().starts_with("DA error: read: "));
+    }
+}
@@ -1,68 +1,442 @@
-use crate::application::ports::{ProofProvider, ProofResponse};
+use crate::application::ports::{
+    CircuitSnapshot, CircuitState, ProofProvider, ProofRequest, ProofResponse, ReadinessProbe, Storage,
+};
 use crate::domain::{batch::BatchId, errors::DomainError};
 use async_trait::async_trait;
 use backoff::{future::retry, ExponentialBackoff};
+use ethers::types::{Address, Signature, U256};
+use ethers::utils::keccak256;
 use metrics::{counter, histogram};
 use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 use tokio::sync::Mutex;
 use tracing::{info, warn};
 
+/// JSON shape of `public_inputs` in requests to the HTTP prover. Inputs are read as 32-byte
+/// big-endian words.
+#[derive(Debug, Deserialize, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InputSchema {
+    /// The raw bytes as an array of numbers, e.g. `[0, 0, ..., 7]`.
+    #[default]
+    Bytes,
+    /// One decimal string per word, e.g. `["7", "12"]`.
+    DecimalStrings,
+    /// One 0x-prefixed, 64-digit hex string per word.
+    HexStrings,
+    /// `{"da_commitment": "0x..", "old_root": "0x..", "new_root": "0x.."}`; needs exactly the
+    /// three words the circuit takes.
+    Object,
+}
+
+impl InputSchema {
+    pub fn encode(&self, inputs: &[u8]) -> Result<Value, DomainError> {
+        let words = || inputs.chunks(32).map(U256::from_big_endian);
+        match self {
+            InputSchema::Bytes => Ok(json!(inputs)),
+            InputSchema::DecimalStrings => Ok(json!(words().map(|w| w.to_string()).collect::<Vec<_>>())),
+            InputSchema::HexStrings => Ok(json!(words().map(hex_word).collect::<Vec<_>>())),
+            InputSchema::Object => {
+                let words: Vec<String> = words().map(hex_word).collect();
+                let [da_commitment, old_root, new_root] = <[String; 3]>::try_from(words).map_err(|words| {
+                    DomainError::Prover(format!(
+                        "input_schema object needs 3 public input words, got {}",
+                        words.len()
+                    ))
+                })?;
+                Ok(json!({
+                    "da_commitment": da_commitment,
+                    "old_root": old_root,
+                    "new_root": new_root,
+                }))
+            }
+        }
+    }
+}
+
+fn hex_word(word: U256) -> String {
+    format!("{:#066x}", word)
+}
+
+/// How an asynchronous prover is polled: `POST /prove` answers with a `job_id`, and
+/// `GET /jobs/{id}` is asked every `interval` until it reports `done` or `timeout` runs out.
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum CircuitState {
-    Closed,
-    Open,
-    HalfOpen,
+pub struct JobPolling {
+    pub interval: Duration,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+struct JobAccepted {
+    job_id: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct JobStatus {
+    status: String,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(flatten)]
+    result: ProofResponse,
+}
+
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
 pub struct HttpProofProvider {
     client: Client,
+    request_timeout: Duration,
     url: String,
+    fallback_urls: Vec<String>,
     circuit_state: Arc<Mutex<CircuitState>>,
     failure_count: Arc<Mutex<u32>>,
     failure_threshold: u32,
     last_failure: Arc<Mutex<std::time::Instant>>,
     backoff_settings: ExponentialBackoff,
+    response_signer: Option<Address>,
+    batch_endpoint: bool,
+    input_schema: InputSchema,
+    gzip_above: Option<usize>,
+    open_duration: Duration,
+    probe_in_flight: AtomicBool,
+    job_polling: Option<JobPolling>,
+    // Accepted jobs by batch, as (prover url, job id), resumed until done or abandoned
+    jobs: Mutex<HashMap<BatchId, (String, String)>>,
+    state_store: Option<Arc<dyn Storage>>,
+    state_restored: AtomicBool,
+    rollup: String,
+}
+
+/// Marks the half-open probe as finished when the request that holds it ends, however it ends.
+struct ProbeGuard<'a>(&'a AtomicBool);
+
+impl Drop for ProbeGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
 }
 
 impl HttpProofProvider {
     pub fn new(url: String, failure_threshold: u32) -> Self {
         Self {
-            client: Client::new(),
+            client: Self::build_client(DEFAULT_REQUEST_TIMEOUT),
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
             url,
+            fallback_urls: Vec::new(),
             circuit_state: Arc::new(Mutex::new(CircuitState::Closed)),
             failure_count: Arc::new(Mutex::new(0)),
             failure_threshold,
             last_failure: Arc::new(Mutex::new(std::time::Instant::now())),
             backoff_settings: ExponentialBackoff::default(),
+            response_signer: None,
+            batch_endpoint: false,
+            input_schema: InputSchema::default(),
+            gzip_above: None,
+            open_duration: Duration::from_secs(30),
+            probe_in_flight: AtomicBool::new(false),
+            job_polling: None,
+            jobs: Mutex::new(HashMap::new()),
+            state_store: None,
+            state_restored: AtomicBool::new(false),
+            rollup: "default".to_string(),
+        }
+    }
+
+    /// Names the rollup the prover serves, for the `rollup` label of its metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
+    }
+
+    /// Keeps the breaker state in `storage` under the prover URL, so a breaker that was open
+    /// stays open across a restart. The stored state is read on first use.
+    pub fn with_state_store(mut self, storage: Option<Arc<dyn Storage>>) -> Self {
+        self.state_store = storage;
+        self
+    }
+
+    /// Loads the persisted breaker state once, before the breaker is first consulted. A
+    /// half-open breaker comes back open: its probe did not survive the restart.
+    async fn restore_circuit(&self) {
+        let Some(store) = &self.state_store else {
+            return;
+        };
+        if self.state_restored.load(Ordering::Acquire) {
+            return;
+        }
+        let mut state = self.circuit_state.lock().await;
+        if self.state_restored.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        match store.load_circuit_state(&self.url).await {
+            Ok(Some(snapshot)) => {
+                let age = (chrono::Utc::now() - snapshot.last_failure).to_std().unwrap_or_default();
+                *state = match snapshot.state {
+                    CircuitState::HalfOpen => CircuitState::Open,
+                    other => other,
+                };
+                *self.failure_count.lock().await = snapshot.failure_count;
+                *self.last_failure.lock().await = Instant::now().checked_sub(age).unwrap_or_else(Instant::now);
+                info!("Restored prover circuit breaker {:?} ({} failures)", *state, snapshot.failure_count);
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Could not load prover circuit breaker state: {}", e),
+        }
+    }
+
+    async fn persist_circuit(&self, snapshot: CircuitSnapshot) {
+        if let Some(store) = &self.state_store {
+            if let Err(e) = store.save_circuit_state(&self.url, &snapshot).await {
+                warn!("Could not persist prover circuit breaker state: {}", e);
+            }
+        }
+    }
+
+    fn build_client(timeout: Duration) -> Client {
+        Client::builder().timeout(timeout).build().unwrap_or_default()
+    }
+
+    /// Longest a single prover request may take, from connecting to reading the whole body
+    /// (default 60s). A request that runs out is retried and counts toward the breaker.
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.client = Self::build_client(timeout);
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Provers tried in order after the one given to `new` when it cannot serve a request.
+    pub fn with_fallback_urls(mut self, urls: Vec<String>) -> Self {
+        self.fallback_urls = urls;
+        self
+    }
+
+    fn urls(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.url.as_str()).chain(self.fallback_urls.iter().map(String::as_str))
+    }
+
+    /// Treats the prover as asynchronous: proofs are submitted as jobs and polled for; see
+    /// [`JobPolling`].
+    pub fn with_job_polling(mut self, polling: Option<JobPolling>) -> Self {
+        self.job_polling = polling;
+        self
+    }
+
+    /// Polls the batch's accepted job, submitting one first if there is none. A job that is
+    /// still running when `polling.timeout` runs out is resumed by the next call.
+    async fn prove_job(&self, request: &ProofRequest, polling: JobPolling) -> Result<ProofResponse, DomainError> {
+        let known = self.jobs.lock().await.get(&request.batch_id).cloned();
+        let (base, job_id) = match known {
+            Some(job) => {
+                info!("Resuming prover job {} for batch {}", job.1, request.batch_id);
+                job
+            }
+            None => {
+                let (job, base): (JobAccepted, _) = self.post_to_any("prove", &self.encode_request(request)?).await?;
+                info!("Prover {} accepted batch {} as job {}", base, request.batch_id, job.job_id);
+                let job = (base.to_string(), job.job_id);
+                self.jobs.lock().await.insert(request.batch_id, job.clone());
+                job
+            }
+        };
+        let started = Instant::now();
+        loop {
+            let polled = self.poll_job(&base, &job_id).await;
+            if !matches!(polled, Ok(None)) {
+                self.jobs.lock().await.remove(&request.batch_id);
+            }
+            if let Some(proof) = polled? {
+                return Ok(proof);
+            }
+            if started.elapsed() >= polling.timeout {
+                return Err(DomainError::Prover(format!(
+                    "Prover busy: job {} not done after {:?}",
+                    job_id, polling.timeout
+                )));
+            }
+            tokio::time::sleep(polling.interval).await;
+        }
+    }
+
+    /// The job's proof once it is done. Unreachable or failing (5xx) status endpoints are retried
+    /// on the next poll; they do not count toward the breaker, which only guards submissions.
+    async fn poll_job(&self, base: &str, job_id: &str) -> Result<Option<ProofResponse>, DomainError> {
+        let res = match self.client.get(format!("{}/jobs/{}", base, job_id)).send().await {
+            Ok(res) if res.status().is_server_error() => {
+                warn!("Polling prover job {} returned {}", job_id, res.status());
+                return Ok(None);
+            }
+            Ok(res) => res,
+            Err(e) => {
+                warn!("Polling prover job {} failed: {}", job_id, e);
+                return Ok(None);
+            }
+        };
+        if !res.status().is_success() {
+            return Err(DomainError::Prover(format!("Job {} status: {}", job_id, res.status())));
+        }
+        let signature = res
+            .headers()
+            .get("X-Proof-Signature")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+        let bytes = res
+            .bytes()
+            .await
+            .map_err(|e| DomainError::Prover(format!("Read error: {}", e)))?;
+        let job: JobStatus = serde_json::from_slice(&bytes)
+            .map_err(|e| DomainError::Prover(format!("Parse error: {}", e)))?;
+        match job.status.as_str() {
+            "done" => {
+                self.verify_signature(signature.as_deref(), &bytes)?;
+                Ok(Some(job.result))
+            }
+            "failed" => Err(DomainError::Prover(format!(
+                "Prover job {} failed: {}",
+                job_id,
+                job.error.unwrap_or_default()
+            ))),
+            _ => Ok(None),
+        }
+    }
+
+    /// How long the breaker stays open before letting a probe request through (default 30s).
+    pub fn with_open_duration(mut self, duration: Duration) -> Self {
+        self.open_duration = duration;
+        self
+    }
+
+    /// Gzips request bodies larger than `threshold` bytes and marks them with
+    /// `Content-Encoding: gzip`. The prover has to accept compressed requests.
+    pub fn with_gzip_above(mut self, threshold: Option<usize>) -> Self {
+        self.gzip_above = threshold;
+        self
+    }
+
+    /// The JSON body for `request`, gzipped when it is over the `with_gzip_above` threshold.
+    fn encode_body<B: Serialize + ?Sized>(&self, request: &B) -> Result<(Vec<u8>, bool), DomainError> {
+        let json = serde_json::to_vec(request)
+            .map_err(|e| DomainError::Prover(format!("Failed to encode proof request: {}", e)))?;
+        match self.gzip_above {
+            Some(threshold) if json.len() > threshold => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder
+                    .write_all(&json)
+                    .and_then(|_| encoder.finish())
+                    .map(|gzipped| (gzipped, true))
+                    .map_err(|e| DomainError::Prover(format!("Failed to gzip proof request: {}", e)))
+            }
+            _ => Ok((json, false)),
         }
     }
 
+    /// Shape in which `public_inputs` is sent; see [`InputSchema`].
+    pub fn with_input_schema(mut self, schema: InputSchema) -> Self {
+        self.input_schema = schema;
+        self
+    }
+
+    fn encode_request(&self, request: &ProofRequest) -> Result<Value, DomainError> {
+        let mut body = serde_json::to_value(request)
+            .map_err(|e| DomainError::Prover(format!("Failed to encode proof request: {}", e)))?;
+        body["public_inputs"] = self.input_schema.encode(&request.public_inputs)?;
+        Ok(body)
+    }
+
+    /// Only accepts responses carrying an `X-Proof-Signature` header: an EIP-191 signature by
+    /// `signer` over `keccak256(body)`.
+    pub fn with_response_signer(mut self, signer: Option<Address>) -> Self {
+        self.response_signer = signer;
+        self
+    }
+
+    /// Sends multi-request proofs to `POST {url}/prove_batch` (a JSON array of requests, answered
+    /// by an array of responses in the same order) instead of one `/prove` call each.
+    pub fn with_batch_endpoint(mut self, enabled: bool) -> Self {
+        self.batch_endpoint = enabled;
+        self
+    }
+
+    fn verify_signature(&self, signature: Option<&str>, body: &[u8]) -> Result<(), DomainError> {
+        let Some(expected) = self.response_signer else {
+            return Ok(());
+        };
+        let signature = signature
+            .ok_or_else(|| DomainError::Prover("Prover response is not signed".into()))?;
+        let signature: Signature = signature
+            .parse()
+            .map_err(|e| DomainError::Prover(format!("Invalid prover signature: {}", e)))?;
+        signature.verify(&keccak256(body)[..], expected).map_err(|_| {
+            counter!("prover_signature_rejected_total", "rollup" => self.rollup.clone()).increment(1);
+            DomainError::Prover("Prover response signature does not match the configured key".into())
+        })
+    }
+
     // For testing purposes
     pub fn with_backoff(mut self, backoff: ExponentialBackoff) -> Self {
         self.backoff_settings = backoff;
         self
     }
 
-    async fn check_circuit(&self) -> Result<(), DomainError> {
+    /// Opens a pooled connection to each prover ahead of the first proof request with a
+    /// `GET {url}/health`. The outcome is only logged and never counts toward the breaker.
+    pub async fn warm_up(&self) {
+        for base in self.urls() {
+            let res = self
+                .client
+                .get(format!("{}/health", base))
+                .timeout(Duration::from_secs(5))
+                .send()
+                .await;
+            match res {
+                Ok(res) => info!("Prover warm-up: {} answered {}", base, res.status()),
+                Err(e) => warn!("Prover warm-up request to {} failed: {}", base, e),
+            }
+        }
+    }
+
+    /// True while the breaker is open and requests are being refused.
+    pub async fn circuit_open(&self) -> bool {
+        self.restore_circuit().await;
+        *self.circuit_state.lock().await == CircuitState::Open
+    }
+
+    /// Whether a request may go out. While half-open only one probe request is let through at a
+    /// time; the returned guard is that probe's.
+    async fn check_circuit(&self) -> Result<Option<ProbeGuard<'_>>, DomainError> {
+        self.restore_circuit().await;
         let mut state = self.circuit_state.lock().await;
         match *state {
-            CircuitState::Closed => Ok(()),
+            CircuitState::Closed => return Ok(None),
             CircuitState::Open => {
                 let last = *self.last_failure.lock().await;
-                if last.elapsed() > Duration::from_secs(30) {
-                    *state = CircuitState::HalfOpen;
-                    info!("Circuit Breaker HALF-OPEN");
-                    Ok(())
-                } else {
-                    counter!("prover_circuit_open_hits_total").increment(1);
-                    Err(DomainError::Prover("Circuit Breaker is OPEN".to_string()))
+                if last.elapsed() <= self.open_duration {
+                    counter!("prover_circuit_open_hits_total", "rollup" => self.rollup.clone()).increment(1);
+                    return Err(DomainError::Prover("Circuit Breaker is OPEN".to_string()));
                 }
+                *state = CircuitState::HalfOpen;
+                info!("Circuit Breaker HALF-OPEN");
             }
-            CircuitState::HalfOpen => Ok(()),
+            CircuitState::HalfOpen => {}
+        }
+        if self
+            .probe_in_flight
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            counter!("prover_circuit_open_hits_total", "rollup" => self.rollup.clone()).increment(1);
+            return Err(DomainError::Prover(
+                "Circuit Breaker HALF-OPEN probe in progress".to_string(),
+            ));
         }
+        Ok(Some(ProbeGuard(&self.probe_in_flight)))
     }
 
     async fn record_success(&self) {
@@ -71,6 +445,13 @@ impl HttpProofProvider {
             info!("Circuit Breaker closed (recovered)");
             *state = CircuitState::Closed;
             *self.failure_count.lock().await = 0;
+            drop(state);
+            self.persist_circuit(CircuitSnapshot {
+                state: CircuitState::Closed,
+                failure_count: 0,
+                last_failure: chrono::Utc::now(),
+            })
+            .await;
         }
     }
 
@@ -79,68 +460,182 @@ impl HttpProofProvider {
         *count += 1;
         *self.last_failure.lock().await = std::time::Instant::now();
 
-        if *count >= self.failure_threshold {
-            let mut state = self.circuit_state.lock().await;
+        let mut state = self.circuit_state.lock().await;
+        // A failed half-open probe re-opens the breaker straight away
+        if *count >= self.failure_threshold || *state == CircuitState::HalfOpen {
             *state = CircuitState::Open;
             warn!("Circuit Breaker tripped to OPEN");
-            counter!("prover_circuit_tripped_total").increment(1);
+            counter!("prover_circuit_tripped_total", "rollup" => self.rollup.clone()).increment(1);
         }
+        let snapshot = CircuitSnapshot {
+            state: *state,
+            failure_count: *count,
+            last_failure: chrono::Utc::now(),
+        };
+        drop(state);
+        drop(count);
+        self.persist_circuit(snapshot).await;
     }
-}
 
-#[async_trait]
-impl ProofProvider for HttpProofProvider {
-    async fn get_proof(
-        &self,
-        batch_id: &BatchId,
-        public_inputs: &[u8],
-    ) -> Result<ProofResponse, DomainError> {
-        self.check_circuit().await?;
+    fn request_error(&self, e: reqwest::Error) -> DomainError {
+        if e.is_timeout() {
+            DomainError::Prover(format!("Prover request timed out after {:?}", self.request_timeout))
+        } else {
+            DomainError::Prover(e.to_string())
+        }
+    }
+
+    async fn post<B, R>(&self, endpoint: &str, request: &B) -> Result<R, DomainError>
+    where
+        B: Serialize + Sync + ?Sized,
+        R: DeserializeOwned,
+    {
+        self.post_to_any(endpoint, request).await.map(|(body, _)| body)
+    }
+
+    /// Sends `request` to each prover URL in turn until one answers, returning the answer and
+    /// the URL that gave it. Only a request every URL failed counts toward the breaker.
+    async fn post_to_any<B, R>(&self, endpoint: &str, request: &B) -> Result<(R, &str), DomainError>
+    where
+        B: Serialize + Sync + ?Sized,
+        R: DeserializeOwned,
+    {
+        let _probe = self.check_circuit().await?;
 
         let start = Instant::now();
+        let (body, gzipped) = self.encode_body(request)?;
 
-        let operation = || async {
-            let res = self
-                .client
-                .post(format!("{}/prove", self.url))
-                .json(&serde_json::json!({
-                    "batch_id": batch_id,
-                    "public_inputs": public_inputs
-                }))
-                .send()
-                .await
-                .map_err(|e| backoff::Error::transient(DomainError::Prover(e.to_string())))?;
-
-            if !res.status().is_success() {
-                // If it's a 4xx error, maybe we shouldn't retry? But for this test we simulate 500.
-                return Err(backoff::Error::transient(DomainError::Prover(format!(
-                    "Status: {}",
-                    res.status()
-                ))));
+        let mut last_error = None;
+        for base in self.urls() {
+            let rejected = AtomicBool::new(false);
+            let operation = || async {
+                let mut req = self
+                    .client
+                    .post(format!("{}/{}", base, endpoint))
+                    .header(reqwest::header::CONTENT_TYPE, "application/json");
+                if gzipped {
+                    req = req.header(reqwest::header::CONTENT_ENCODING, "gzip");
+                }
+                let res = req
+                    .body(body.clone())
+                    .send()
+                    .await
+                    .map_err(|e| backoff::Error::transient(self.request_error(e)))?;
+
+                let status = res.status();
+                if !status.is_success() {
+                    let error = DomainError::Prover(format!("Status: {}", status));
+                    // The prover understood and refused the request; resending it cannot help.
+                    // 408 and 429 are about timing, so those are retried like 5xx.
+                    if status.is_client_error()
+                        && status != reqwest::StatusCode::REQUEST_TIMEOUT
+                        && status != reqwest::StatusCode::TOO_MANY_REQUESTS
+                    {
+                        rejected.store(true, Ordering::Relaxed);
+                        return Err(backoff::Error::permanent(error));
+                    }
+                    return Err(backoff::Error::transient(error));
+                }
+
+                let signature = res
+                    .headers()
+                    .get("X-Proof-Signature")
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_owned);
+                let bytes = res.bytes().await.map_err(|e| {
+                    let error = if e.is_timeout() {
+                        self.request_error(e)
+                    } else {
+                        DomainError::Prover(format!("Read error: {}", e))
+                    };
+                    backoff::Error::transient(error)
+                })?;
+                // A bad signature will not get better on retry
+                self.verify_signature(signature.as_deref(), &bytes)
+                    .map_err(backoff::Error::permanent)?;
+
+                let body: R = serde_json::from_slice(&bytes).map_err(|e| {
+                    backoff::Error::permanent(DomainError::Prover(format!("Parse error: {}", e)))
+                })?;
+
+                Ok(body)
+            };
+
+            match retry(self.backoff_settings.clone(), operation).await {
+                Ok(proof) => {
+                    info!("Prover {} answered /{}", base, endpoint);
+                    self.record_success().await;
+                    histogram!("prover_request_duration_seconds", "rollup" => self.rollup.clone())
+                        .record(start.elapsed().as_secs_f64());
+                    counter!("prover_requests_total", "rollup" => self.rollup.clone(), "result" => "success").increment(1);
+                    return Ok((proof, base));
+                }
+                // A 4xx rejection means the prover is up and answering, so it does not count
+                // toward the breaker threshold; the breaker is left as it was.
+                Err(e) if rejected.load(Ordering::Relaxed) => {
+                    warn!("Prover {} rejected the request: {}", base, e);
+                    counter!("prover_requests_total", "rollup" => self.rollup.clone(), "result" => "rejected").increment(1);
+                    return Err(e);
+                }
+                Err(e) => {
+                    warn!("Prover {} failed: {}", base, e);
+                    last_error = Some(e);
+                }
             }
+        }
 
-            let body: ProofResponse = res.json().await.map_err(|e| {
-                backoff::Error::permanent(DomainError::Prover(format!("Parse error: {}", e)))
-            })?;
+        self.record_failure().await;
+        counter!("prover_requests_total", "rollup" => self.rollup.clone(), "result" => "error").increment(1);
+        Err(last_error.unwrap_or_else(|| DomainError::Prover("No prover URL configured".into())))
+    }
+}
 
-            Ok(body)
-        };
+#[async_trait]
+impl ReadinessProbe for HttpProofProvider {
+    async fn check(&self) -> Result<(), String> {
+        if self.circuit_open().await {
+            Err(format!("prover circuit breaker is open ({})", self.url))
+        } else {
+            Ok(())
+        }
+    }
+}
 
-        // Clone settings for this run
-        let backoff = self.backoff_settings.clone();
+#[async_trait]
+impl ProofProvider for HttpProofProvider {
+    async fn get_proof(&self, request: &ProofRequest) -> Result<ProofResponse, DomainError> {
+        match self.job_polling {
+            Some(polling) => self.prove_job(request, polling).await,
+            None => self.post("prove", &self.encode_request(request)?).await,
+        }
+    }
 
-        match retry(backoff, operation).await {
-            Ok(proof) => {
-                self.record_success().await;
-                histogram!("prover_request_duration_seconds").record(start.elapsed().as_secs_f64());
-                counter!("prover_requests_total", "result" => "success").increment(1);
-                Ok(proof)
+    async fn get_proofs(&self, requests: &[ProofRequest]) -> Vec<Result<ProofResponse, DomainError>> {
+        if !self.batch_endpoint || self.job_polling.is_some() || requests.len() < 2 {
+            let mut results = Vec::with_capacity(requests.len());
+            for request in requests {
+                results.push(self.get_proof(request).await);
             }
-            Err(e) => {
-                self.record_failure().await;
-                counter!("prover_requests_total", "result" => "error").increment(1);
-                Err(e)
+            return results;
+        }
+
+        let bodies = match requests.iter().map(|r| self.encode_request(r)).collect::<Result<Vec<_>, _>>() {
+            Ok(bodies) => bodies,
+            Err(e) => return requests.iter().map(|_| Err(DomainError::Prover(e.to_string()))).collect(),
+        };
+        match self.post::<_, Vec<ProofResponse>>("prove_batch", &bodies).await {
+            Ok(responses) if responses.len() == requests.len() => responses.into_iter().map(Ok).collect(),
+            Ok(responses) => {
+                let msg = format!("Prover returned {} proofs for {} requests", responses.len(), requests.len());
+                requests.iter().map(|_| Err(DomainError::Prover(msg.clone()))).collect()
             }
+            Err(e) => requests.iter().map(|_| Err(DomainError::Prover(e.to_string()))).collect(),
+        }
+    }
+
+    async fn abandon(&self, batch_id: BatchId) {
+        if let Some((_, job_id)) = self.jobs.lock().await.remove(&batch_id) {
+            info!("Abandoned prover job {} for batch {}", job_id, batch_id);
         }
     }
 }
@@ -148,6 +643,7 @@ impl ProofProvider for HttpProofProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::batch::BatchId;
     use wiremock::matchers::{method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -172,7 +668,7 @@ mod tests {
 
         // Trip the breaker (need 5 failures)
         for _ in 0..6 {
-            let _ = provider.get_proof(&id, &[]).await;
+            let _ = provider.get_proof(&ProofRequest::new(id, vec![])).await;
         }
 
         // Verify state
@@ -200,7 +696,7 @@ mod tests {
             .await;
 
         for _ in 0..5 {
-            let _ = provider.get_proof(&id, &[]).await;
+            let _ = provider.get_proof(&ProofRequest::new(id, vec![])).await;
         }
 
         // 2. Force state to Open manually
@@ -216,7 +712,7 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let res = provider.get_proof(&id, &[]).await;
+        let res = provider.get_proof(&ProofRequest::new(id, vec![])).await;
         assert!(res.is_ok());
 
         // 4. State should be Closed
@@ -224,6 +720,338 @@ mod tests {
         assert_eq!(state, CircuitState::Closed);
     }
 
+    #[tokio::test]
+    async fn test_warm_up_failure_leaves_breaker_closed() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let provider = HttpProofProvider::new(mock_server.uri(), 1);
+        provider.warm_up().await;
+        assert!(!provider.circuit_open().await);
+        assert_eq!(*provider.failure_count.lock().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_job_polling_waits_for_done() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(ResponseTemplate::new(202).set_body_json(serde_json::json!({ "job_id": "j1" })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/jobs/j1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "status": "pending" })))
+            .up_to_n_times(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/jobs/j1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "done",
+                "proof": "0xabcd"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let provider = HttpProofProvider::new(mock_server.uri(), 5).with_job_polling(Some(JobPolling {
+            interval: Duration::from_millis(10),
+            timeout: Duration::from_secs(5),
+        }));
+        let res = provider.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await.unwrap();
+
+        assert_eq!(res.proof, "0xabcd");
+        let polls = mock_server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|r| r.url.path() == "/jobs/j1")
+            .count();
+        assert_eq!(polls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_job_polling_times_out() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(ResponseTemplate::new(202).set_body_json(serde_json::json!({ "job_id": "j2" })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/jobs/j2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "status": "running" })))
+            .mount(&mock_server)
+            .await;
+
+        let provider = HttpProofProvider::new(mock_server.uri(), 5).with_job_polling(Some(JobPolling {
+            interval: Duration::from_millis(10),
+            timeout: Duration::from_millis(50),
+        }));
+        let err = provider.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await.unwrap_err();
+
+        assert!(matches!(err, DomainError::Prover(_)));
+        assert!(err.to_string().contains("Prover busy"));
+    }
+
+    #[tokio::test]
+    async fn test_job_polling_resumes_job_until_abandoned() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(ResponseTemplate::new(202).set_body_json(serde_json::json!({ "job_id": "j3" })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/jobs/j3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "status": "running" })))
+            .mount(&mock_server)
+            .await;
+
+        let provider = HttpProofProvider::new(mock_server.uri(), 5).with_job_polling(Some(JobPolling {
+            interval: Duration::from_millis(10),
+            timeout: Duration::from_millis(30),
+        }));
+        let request = ProofRequest::new(BatchId::new(), vec![]);
+        let posts = || async {
+            mock_server
+                .received_requests()
+                .await
+                .unwrap()
+                .iter()
+                .filter(|r| r.method == wiremock::http::Method::POST)
+                .count()
+        };
+
+        // A job still running after the timeout is polled again, not re-submitted
+        assert!(provider.get_proof(&request).await.is_err());
+        assert!(provider.get_proof(&request).await.is_err());
+        assert_eq!(posts().await, 1);
+
+        provider.abandon(request.batch_id).await;
+        assert!(provider.get_proof(&request).await.is_err());
+        assert_eq!(posts().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_to_next_url() {
+        let down = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&down)
+            .await;
+        let up = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "proof": "0xabcd" })))
+            .expect(1)
+            .mount(&up)
+            .await;
+
+        let backoff = ExponentialBackoff {
+            max_elapsed_time: Some(Duration::from_millis(1)),
+            ..ExponentialBackoff::default()
+        };
+        let provider = HttpProofProvider::new(down.uri(), 1)
+            .with_fallback_urls(vec![up.uri()])
+            .with_backoff(backoff);
+        let res = provider.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await.unwrap();
+
+        assert_eq!(res.proof, "0xabcd");
+        assert!(!down.received_requests().await.unwrap().is_empty());
+        // One endpoint answering is enough to keep the breaker closed at threshold 1
+        assert!(!provider.circuit_open().await);
+    }
+
+    #[tokio::test]
+    async fn test_client_error_is_not_retried() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(ResponseTemplate::new(400))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let provider = HttpProofProvider::new(mock_server.uri(), 1);
+        let started = Instant::now();
+        let err = provider.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await.unwrap_err();
+
+        assert!(err.to_string().contains("400"));
+        assert!(started.elapsed() < Duration::from_secs(1));
+        // Not a prover outage: the breaker stays closed even at threshold 1
+        assert!(!provider.circuit_open().await);
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_is_retried() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&mock_server)
+            .await;
+
+        let backoff = ExponentialBackoff {
+            initial_interval: Duration::from_millis(10),
+            max_interval: Duration::from_millis(20),
+            max_elapsed_time: Some(Duration::from_millis(200)),
+            ..ExponentialBackoff::default()
+        };
+        let provider = HttpProofProvider::new(mock_server.uri(), 1).with_backoff(backoff);
+        let err = provider.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await.unwrap_err();
+
+        assert!(err.to_string().contains("503"));
+        assert!(mock_server.received_requests().await.unwrap().len() > 1);
+        assert!(provider.circuit_open().await);
+    }
+
+    #[tokio::test]
+    async fn test_custom_open_duration() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let backoff = ExponentialBackoff {
+            max_elapsed_time: Some(Duration::from_millis(1)),
+            ..ExponentialBackoff::default()
+        };
+        let provider = HttpProofProvider::new(mock_server.uri(), 1)
+            .with_backoff(backoff)
+            .with_open_duration(Duration::from_secs(1));
+        let _ = provider.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await;
+        assert!(provider.circuit_open().await);
+        assert!(provider.check_circuit().await.is_err());
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        assert!(provider.check_circuit().await.is_ok());
+        assert_eq!(*provider.circuit_state.lock().await, CircuitState::HalfOpen);
+    }
+
+    #[tokio::test]
+    async fn test_open_breaker_survives_restart() {
+        use crate::infrastructure::storage_sqlite::SqliteStorage;
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let storage: Arc<dyn Storage> = Arc::new(SqliteStorage::new("sqlite::memory:").await.unwrap());
+        let backoff = ExponentialBackoff {
+            max_elapsed_time: Some(Duration::from_millis(1)),
+            ..ExponentialBackoff::default()
+        };
+        let provider = HttpProofProvider::new(mock_server.uri(), 1)
+            .with_backoff(backoff)
+            .with_state_store(Some(storage.clone()));
+        let _ = provider.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await;
+        assert!(provider.circuit_open().await);
+
+        let restarted = HttpProofProvider::new(mock_server.uri(), 1).with_state_store(Some(storage));
+        assert!(restarted.circuit_open().await);
+        let err = restarted.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await.unwrap_err();
+        assert!(err.to_string().contains("Circuit Breaker is OPEN"));
+        assert_eq!(mock_server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_slow_prover_times_out() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "proof": "late" }))
+                    .set_delay(Duration::from_secs(5)),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let backoff = ExponentialBackoff {
+            max_elapsed_time: Some(Duration::from_millis(1)),
+            ..ExponentialBackoff::default()
+        };
+        let provider = HttpProofProvider::new(mock_server.uri(), 1)
+            .with_backoff(backoff)
+            .with_request_timeout(Duration::from_millis(200));
+
+        let started = Instant::now();
+        let err = provider.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await.unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(err.to_string().contains("timed out"), "{}", err);
+        assert!(provider.circuit_open().await);
+    }
+
+    #[tokio::test]
+    async fn test_half_open_lets_one_probe_through() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "proof": "valid" }))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let provider = Arc::new(HttpProofProvider::new(mock_server.uri(), 5));
+        *provider.circuit_state.lock().await = CircuitState::Open;
+        *provider.last_failure.lock().await = std::time::Instant::now() - Duration::from_secs(31);
+
+        let calls = (0..10).map(|_| {
+            let provider = provider.clone();
+            async move { provider.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await }
+        });
+        let results = futures::future::join_all(calls).await;
+
+        assert_eq!(mock_server.received_requests().await.unwrap().len(), 1);
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        assert!(results
+            .iter()
+            .filter_map(|r| r.as_ref().err())
+            .all(|e| e.to_string().contains("HALF-OPEN probe in progress")));
+        assert_eq!(*provider.circuit_state.lock().await, CircuitState::Closed);
+        assert!(!provider.probe_in_flight.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn test_failed_probe_reopens_circuit() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let backoff = ExponentialBackoff {
+            max_elapsed_time: Some(Duration::from_millis(1)),
+            ..ExponentialBackoff::default()
+        };
+        let provider = HttpProofProvider::new(mock_server.uri(), 5).with_backoff(backoff);
+        *provider.circuit_state.lock().await = CircuitState::Open;
+        *provider.last_failure.lock().await = std::time::Instant::now() - Duration::from_secs(31);
+
+        assert!(provider.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await.is_err());
+        assert!(provider.circuit_open().await);
+        let err = provider.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await.unwrap_err();
+        assert!(err.to_string().contains("Circuit Breaker is OPEN"));
+    }
+
     #[tokio::test]
     async fn test_custom_threshold() {
         let mock_server = MockServer::start().await;
@@ -244,17 +1072,215 @@ mod tests {
             .await;
 
         // 1. Fail once
-        let _ = provider.get_proof(&id, &[]).await;
+        let _ = provider.get_proof(&ProofRequest::new(id, vec![])).await;
         {
             let state = *provider.circuit_state.lock().await;
             assert_eq!(state, CircuitState::Closed);
         }
 
         // 2. Fail twice (hits threshold)
-        let _ = provider.get_proof(&id, &[]).await;
+        let _ = provider.get_proof(&ProofRequest::new(id, vec![])).await;
         {
             let state = *provider.circuit_state.lock().await;
             assert_eq!(state, CircuitState::Open);
         }
     }
+
+    #[tokio::test]
+    async fn test_request_includes_deadline() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "proof": "valid"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let provider = HttpProofProvider::new(mock_server.uri(), 5);
+        let budget = chrono::Duration::seconds(600);
+        let mut request = ProofRequest::new(BatchId::new(), vec![1, 2]);
+        request.deadline = Some(chrono::Utc::now() + budget);
+        provider.get_proof(&request).await.unwrap();
+
+        let received = mock_server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&received[0].body).unwrap();
+        let deadline: chrono::DateTime<chrono::Utc> =
+            serde_json::from_value(body["deadline"].clone()).unwrap();
+        let remaining = deadline - chrono::Utc::now();
+        assert!(remaining <= budget && remaining > budget - chrono::Duration::seconds(5));
+        assert_eq!(body["public_inputs"], serde_json::json!([1, 2]));
+
+        // Without a deadline the field is omitted entirely
+        provider.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await.unwrap();
+        let received = mock_server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&received[1].body).unwrap();
+        assert!(body.get("deadline").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_response_signature_verification() {
+        use ethers::signers::{LocalWallet, Signer};
+
+        let wallet: LocalWallet = "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20"
+            .parse()
+            .unwrap();
+        let body = serde_json::json!({ "proof": "valid" }).to_string();
+        let signature = wallet.sign_message(keccak256(body.as_bytes())).await.unwrap();
+
+        let signed = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Proof-Signature", format!("0x{}", signature))
+                    .set_body_string(body.clone()),
+            )
+            .mount(&signed)
+            .await;
+        let provider = HttpProofProvider::new(signed.uri(), 5).with_response_signer(Some(wallet.address()));
+        let res = provider.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await;
+        assert_eq!(res.unwrap().proof, "valid");
+
+        // Same signature, different body
+        let tampered = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Proof-Signature", format!("0x{}", signature))
+                    .set_body_string(serde_json::json!({ "proof": "forged" }).to_string()),
+            )
+            .expect(1)
+            .mount(&tampered)
+            .await;
+        let provider = HttpProofProvider::new(tampered.uri(), 5).with_response_signer(Some(wallet.address()));
+        let res = provider.get_proof(&ProofRequest::new(BatchId::new(), vec![])).await;
+        assert!(res.unwrap_err().to_string().contains("signature"));
+    }
+
+    #[tokio::test]
+    async fn test_batch_endpoint_sends_one_request() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove_batch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"proof": "0xaa"},
+                {"proof": "0xbb"}
+            ])))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let provider = HttpProofProvider::new(mock_server.uri(), 5).with_batch_endpoint(true);
+        let requests = vec![ProofRequest::new(BatchId::new(), vec![1]), ProofRequest::new(BatchId::new(), vec![2])];
+        let results = provider.get_proofs(&requests).await;
+
+        let proofs: Vec<String> = results.into_iter().map(|r| r.unwrap().proof).collect();
+        assert_eq!(proofs, vec!["0xaa", "0xbb"]);
+        let sent: serde_json::Value = mock_server.received_requests().await.unwrap()[0].body_json().unwrap();
+        assert_eq!(sent.as_array().unwrap().len(), 2);
+        assert_eq!(sent[1]["batch_id"], requests[1].batch_id.to_string());
+    }
+
+    fn schema_inputs() -> Vec<u8> {
+        let mut inputs = vec![0u8; 96];
+        inputs[31] = 7;
+        inputs[62] = 1; // 256
+        inputs[64] = 0xff;
+        inputs
+    }
+
+    #[test]
+    fn test_input_schema_bytes() {
+        let inputs = schema_inputs();
+        assert_eq!(InputSchema::Bytes.encode(&inputs).unwrap(), serde_json::json!(inputs));
+    }
+
+    #[test]
+    fn test_input_schema_decimal_strings() {
+        let third = U256::from(0xffu64) << 248;
+        assert_eq!(
+            InputSchema::DecimalStrings.encode(&schema_inputs()).unwrap(),
+            serde_json::json!(["7", "256", third.to_string()])
+        );
+    }
+
+    #[test]
+    fn test_input_schema_hex_strings() {
+        assert_eq!(
+            InputSchema::HexStrings.encode(&schema_inputs()).unwrap(),
+            serde_json::json!([
+                "0x0000000000000000000000000000000000000000000000000000000000000007",
+                "0x0000000000000000000000000000000000000000000000000000000000000100",
+                "0xff00000000000000000000000000000000000000000000000000000000000000",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_input_schema_object() {
+        assert_eq!(
+            InputSchema::Object.encode(&schema_inputs()).unwrap(),
+            serde_json::json!({
+                "da_commitment": "0x0000000000000000000000000000000000000000000000000000000000000007",
+                "old_root": "0x0000000000000000000000000000000000000000000000000000000000000100",
+                "new_root": "0xff00000000000000000000000000000000000000000000000000000000000000",
+            })
+        );
+        assert!(InputSchema::Object.encode(&[0u8; 64]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_request_uses_input_schema() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "proof": "valid"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let provider = HttpProofProvider::new(mock_server.uri(), 5).with_input_schema(InputSchema::DecimalStrings);
+        let request = ProofRequest::new(BatchId::new(), schema_inputs());
+        provider.get_proof(&request).await.unwrap();
+
+        let body: serde_json::Value = mock_server.received_requests().await.unwrap()[0].body_json().unwrap();
+        assert_eq!(body["public_inputs"][0], "7");
+        assert_eq!(body["batch_id"], request.batch_id.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_large_body_is_gzipped() {
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/prove"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "proof": "valid"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let provider = HttpProofProvider::new(mock_server.uri(), 5).with_gzip_above(Some(1024));
+        let large = ProofRequest::new(BatchId::new(), vec![7u8; 4096]);
+        let small = ProofRequest::new(BatchId::new(), vec![1, 2]);
+        provider.get_proof(&large).await.unwrap();
+        provider.get_proof(&small).await.unwrap();
+
+        let received = mock_server.received_requests().await.unwrap();
+        assert_eq!(received[0].headers.get("content-encoding").unwrap(), "gzip");
+        let mut json = String::new();
+        GzDecoder::new(&received[0].body[..]).read_to_string(&mut json).unwrap();
+        let body: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(body["batch_id"], large.batch_id.to_string());
+        assert_eq!(body["public_inputs"].as_array().unwrap().len(), 4096);
+
+        assert!(received[1].headers.get("content-encoding").is_none());
+        let body: serde_json::Value = received[1].body_json().unwrap();
+        assert_eq!(body["public_inputs"], serde_json::json!([1, 2]));
+    }
 }
@@ -1,38 +1,73 @@
-use crate::application::ports::{ProofProvider, ProofResponse};
-use crate::domain::{batch::BatchId, errors::DomainError};
+use crate::application::ports::{ProofProvider, ProofRequest, ProofResponse};
+use crate::contracts::PLONK_PROOF_WORDS;
+use crate::domain::{batch::ProofSystem, errors::DomainError, public_inputs::check_public_inputs};
 use async_trait::async_trait;
 use tracing::info;
 
 pub struct MockProofProvider {
     delay_ms: u64,
+    strict_inputs: bool,
 }
 
 impl MockProofProvider {
     pub fn new(delay_ms: u64) -> Self {
-        Self { delay_ms }
+        Self {
+            delay_ms,
+            strict_inputs: false,
+        }
+    }
+
+    /// Rejects requests whose public inputs are not the full set a real circuit needs.
+    pub fn with_strict_inputs(mut self, strict: bool) -> Self {
+        self.strict_inputs = strict;
+        self
     }
 }
 
 #[async_trait]
 impl ProofProvider for MockProofProvider {
-    async fn get_proof(
-        &self,
-        batch_id: &BatchId,
-        _public_inputs: &[u8],
-    ) -> Result<ProofResponse, DomainError> {
-        info!("Mock proving for batch {} (delay: {}ms)", batch_id, self.delay_ms);
+    async fn get_proof(&self, request: &ProofRequest) -> Result<ProofResponse, DomainError> {
+        info!("Mock proving for batch {} (delay: {}ms)", request.batch_id, self.delay_ms);
+        if self.strict_inputs {
+            check_public_inputs(&request.public_inputs)?;
+        }
         // Simulate delay
         if self.delay_ms > 0 {
             tokio::time::sleep(tokio::time::Duration::from_millis(self.delay_ms)).await;
         }
 
-        // Return valid 256-byte hex string (512 chars)
-        // 8 * 32-byte elements: a[2], b[2][2], c[2]
-        // Just using zeroes is fine for a mock
-        let valid_proof = "00".repeat(256);
+        // Return a valid-length hex string of zeroes:
+        // Groth16 is 8 * 32-byte elements (a[2], b[2][2], c[2]), PLONK a flat uint256[24]
+        let valid_proof = match request.proof_system {
+            ProofSystem::Groth16 => "00".repeat(256),
+            ProofSystem::Plonk => "00".repeat(PLONK_PROOF_WORDS * 32),
+        };
 
         Ok(ProofResponse {
             proof: valid_proof,
+            proofs: Vec::new(),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::batch::BatchId;
+
+    #[tokio::test]
+    async fn test_strict_mode_rejects_empty_inputs() {
+        let request = ProofRequest::new(BatchId::new(), Vec::new());
+
+        let err = MockProofProvider::new(0)
+            .with_strict_inputs(true)
+            .get_proof(&request)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DomainError::InvalidBatch(_)));
+        assert!(err.to_string().contains("got 0"));
+
+        // Lenient by default
+        assert!(MockProofProvider::new(0).get_proof(&request).await.is_ok());
+    }
+}
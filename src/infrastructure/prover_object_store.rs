@@ -0,0 +1,122 @@
+use crate::application::ports::{ProofProvider, ProofRequest, ProofResponse};
+use crate::domain::{batch::BatchId, errors::DomainError};
+use async_trait::async_trait;
+use metrics::{counter, histogram};
+use reqwest::{Client, StatusCode};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Fetches proofs computed by an external pipeline and dropped into an object store
+/// (S3/GCS style HTTP endpoint) under `{base_url}/{bucket}/proof/{batch_id}`.
+pub struct ObjectStoreProofProvider {
+    client: Client,
+    base_url: String,
+    bucket: String,
+    rollup: String,
+}
+
+impl ObjectStoreProofProvider {
+    pub fn new(base_url: String, bucket: String) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            base_url,
+            bucket,
+            rollup: "default".to_string(),
+        }
+    }
+
+    /// Names the rollup the proofs are for, for the `rollup` label of its metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
+    }
+
+    fn object_url(&self, batch_id: &BatchId) -> String {
+        format!(
+            "{}/{}/proof/{}",
+            self.base_url.trim_end_matches('/'),
+            self.bucket,
+            batch_id
+        )
+    }
+}
+
+#[async_trait]
+impl ProofProvider for ObjectStoreProofProvider {
+    async fn get_proof(&self, request: &ProofRequest) -> Result<ProofResponse, DomainError> {
+        let start = Instant::now();
+        let url = self.object_url(&request.batch_id);
+
+        let res = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| DomainError::Prover(format!("Object store request failed: {}", e)))?;
+        histogram!("object_store_request_duration_seconds", "rollup" => self.rollup.clone())
+            .record(start.elapsed().as_secs_f64());
+
+        // Missing objects just mean the external prover hasn't finished yet
+        if res.status() == StatusCode::NOT_FOUND {
+            counter!("object_store_proof_requests_total", "rollup" => self.rollup.clone(), "result" => "not_ready").increment(1);
+            return Err(DomainError::ProofNotReady(format!("{} not found", url)));
+        }
+        if !res.status().is_success() {
+            counter!("object_store_proof_requests_total", "rollup" => self.rollup.clone(), "result" => "error").increment(1);
+            return Err(DomainError::Prover(format!(
+                "Object store status: {}",
+                res.status()
+            )));
+        }
+
+        let body = res
+            .text()
+            .await
+            .map_err(|e| DomainError::Prover(format!("Object store read failed: {}", e)))?;
+        info!("Fetched proof for batch {} from object store", request.batch_id);
+        counter!("object_store_proof_requests_total", "rollup" => self.rollup.clone(), "result" => "success").increment(1);
+
+        Ok(ProofResponse {
+            proof: body.trim().to_string(),
+            proofs: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_proof_appears_on_second_poll() {
+        let mock_server = MockServer::start().await;
+        let id = BatchId::new();
+        let object_path = format!("/proofs/proof/{}", id);
+
+        Mock::given(method("GET"))
+            .and(path(object_path.clone()))
+            .respond_with(ResponseTemplate::new(404))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(object_path))
+            .respond_with(ResponseTemplate::new(200).set_body_string("0xabcd\n"))
+            .mount(&mock_server)
+            .await;
+
+        let provider = ObjectStoreProofProvider::new(mock_server.uri(), "proofs".into());
+
+        let first = provider.get_proof(&ProofRequest::new(id, vec![])).await;
+        assert!(matches!(first, Err(DomainError::ProofNotReady(_))));
+
+        let second = provider.get_proof(&ProofRequest::new(id, vec![])).await.unwrap();
+        assert_eq!(second.proof, "0xabcd");
+    }
+}
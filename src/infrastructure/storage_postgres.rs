@@ -1,10 +1,11 @@
-use crate::application::ports::Storage;
+use crate::application::ports::{BatchEvent, CircuitSnapshot, CircuitState, Storage};
 use crate::domain::{
     batch::{Batch, BatchId, BatchStatus},
     errors::DomainError,
 };
 use async_trait::async_trait;
-use sqlx::{postgres::PgPoolOptions, Pool, Postgres, Row};
+use ethers::utils::hex;
+use sqlx::{postgres::{PgPoolOptions, PgRow}, Pool, Postgres, Row};
 use tracing::info;
 use uuid::Uuid;
 
@@ -12,6 +13,7 @@ pub struct PostgresStorage {
     pool: Pool<Postgres>,
     batch_size: Option<u32>,
     ordering_policy: Option<String>,
+    rollup: String,
 }
 
 impl PostgresStorage {
@@ -19,9 +21,19 @@ impl PostgresStorage {
         db_url: &str,
         batch_size: Option<u32>,
         ordering_policy: Option<String>,
+    ) -> Result<Self, DomainError> {
+        Self::with_pool_size(db_url, batch_size, ordering_policy, 5).await
+    }
+
+    /// Like [`PostgresStorage::new`] with at most `max_connections` pooled connections.
+    pub async fn with_pool_size(
+        db_url: &str,
+        batch_size: Option<u32>,
+        ordering_policy: Option<String>,
+        max_connections: u32,
     ) -> Result<Self, DomainError> {
         let pool = PgPoolOptions::new()
-            .max_connections(5)
+            .max_connections(max_connections)
             .connect(db_url)
             .await
             .map_err(|e| DomainError::Storage(e.to_string()))?;
@@ -32,12 +44,19 @@ impl PostgresStorage {
             pool,
             batch_size,
             ordering_policy,
+            rollup: "default".to_string(),
         };
         storage.migrate().await?;
 
         Ok(storage)
     }
 
+    /// Names the rollup whose batches are stored, for the `rollup` label of its metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
+    }
+
     async fn migrate(&self) -> Result<(), DomainError> {
         sqlx::query(
             r#"
@@ -54,7 +73,19 @@ impl PostgresStorage {
                 updated_at TIMESTAMPTZ NOT NULL,
                 blob_versioned_hash TEXT,
                 blob_index INTEGER,
-                fee BIGINT DEFAULT 0
+                fee BIGINT DEFAULT 0,
+                expected_old_root TEXT,
+                fee_bumps INTEGER DEFAULT 0,
+                batch_index BIGINT DEFAULT 0,
+                proof_system TEXT DEFAULT 'groth16',
+                public_inputs TEXT,
+                next_attempt_at TIMESTAMPTZ,
+                data_hash TEXT,
+                gas_used BIGINT,
+                effective_gas_price BIGINT,
+                signed_tx TEXT,
+                da_reference TEXT,
+                attestation TEXT
             );
             "#,
         )
@@ -62,6 +93,41 @@ impl PostgresStorage {
         .await
         .map_err(|e| DomainError::Storage(format!("Migration failed: {}", e)))?;
 
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS circuit_breakers (
+                key TEXT PRIMARY KEY,
+                state TEXT NOT NULL,
+                failure_count INTEGER NOT NULL,
+                last_failure TIMESTAMPTZ NOT NULL
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::Storage(format!("Migration failed: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS batch_events (
+                id BIGSERIAL PRIMARY KEY,
+                batch_id TEXT NOT NULL,
+                from_status TEXT NOT NULL,
+                to_status TEXT NOT NULL,
+                detail TEXT,
+                created_at TIMESTAMPTZ NOT NULL
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::Storage(format!("Migration failed: {}", e)))?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS batch_events_batch_id ON batch_events (batch_id)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::Storage(format!("Migration failed: {}", e)))?;
+
         // Simple migration for existing tables if needed
         let _ =
             sqlx::query("ALTER TABLE batches ADD COLUMN IF NOT EXISTS attempts INTEGER DEFAULT 0")
@@ -80,6 +146,54 @@ impl PostgresStorage {
             .execute(&self.pool)
             .await;
 
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN IF NOT EXISTS expected_old_root TEXT")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN IF NOT EXISTS fee_bumps INTEGER DEFAULT 0")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN IF NOT EXISTS batch_index BIGINT DEFAULT 0")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN IF NOT EXISTS proof_system TEXT DEFAULT 'groth16'")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN IF NOT EXISTS public_inputs TEXT")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN IF NOT EXISTS next_attempt_at TIMESTAMPTZ")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN IF NOT EXISTS data_hash TEXT")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN IF NOT EXISTS gas_used BIGINT")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN IF NOT EXISTS effective_gas_price BIGINT")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN IF NOT EXISTS signed_tx TEXT")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN IF NOT EXISTS da_reference TEXT")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN IF NOT EXISTS attestation TEXT")
+            .execute(&self.pool)
+            .await;
+
         Ok(())
     }
 }
@@ -92,8 +206,8 @@ impl Storage for PostgresStorage {
 
         sqlx::query(
             r#"
-            INSERT INTO batches (id, data_file, new_root, status, da_mode, proof, tx_hash, attempts, created_at, updated_at, blob_versioned_hash, blob_index, fee)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            INSERT INTO batches (id, data_file, new_root, status, da_mode, proof, tx_hash, attempts, created_at, updated_at, blob_versioned_hash, blob_index, fee, expected_old_root, fee_bumps, batch_index, proof_system, public_inputs, next_attempt_at, data_hash, gas_used, effective_gas_price, signed_tx, da_reference, attestation)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25)
             ON CONFLICT(id) DO UPDATE SET
                 status = excluded.status,
                 proof = excluded.proof,
@@ -102,7 +216,19 @@ impl Storage for PostgresStorage {
                 updated_at = excluded.updated_at,
                 blob_versioned_hash = excluded.blob_versioned_hash,
                 blob_index = excluded.blob_index,
-                fee = excluded.fee
+                fee = excluded.fee,
+                expected_old_root = excluded.expected_old_root,
+                fee_bumps = excluded.fee_bumps,
+                batch_index = excluded.batch_index,
+                proof_system = excluded.proof_system,
+                public_inputs = excluded.public_inputs,
+                next_attempt_at = excluded.next_attempt_at,
+                data_hash = excluded.data_hash,
+                gas_used = excluded.gas_used,
+                effective_gas_price = excluded.effective_gas_price,
+                signed_tx = excluded.signed_tx,
+                da_reference = excluded.da_reference,
+                attestation = excluded.attestation
             "#,
         )
         .bind(id_str)
@@ -118,6 +244,18 @@ impl Storage for PostgresStorage {
         .bind(&batch.blob_versioned_hash)
         .bind(batch.blob_index.map(|i| i as i32))
         .bind(batch.fee as i64)
+        .bind(&batch.expected_old_root)
+        .bind(batch.fee_bumps as i32)
+        .bind(batch.index as i64)
+        .bind(batch.proof_system.to_string())
+        .bind(batch.public_inputs.as_ref().map(hex::encode))
+        .bind(batch.next_attempt_at)
+        .bind(&batch.data_hash)
+        .bind(batch.gas_used.map(|g| g as i64))
+        .bind(batch.effective_gas_price.map(|p| p as i64))
+        .bind(&batch.signed_tx)
+        .bind(&batch.da_reference)
+        .bind(&batch.attestation)
         .execute(&self.pool)
         .await
         .map_err(|e| DomainError::Storage(e.to_string()))?;
@@ -176,6 +314,18 @@ impl Storage for PostgresStorage {
                 blob_versioned_hash: row.try_get("blob_versioned_hash").ok(),
                 blob_index: row.try_get::<i32, _>("blob_index").ok().map(|i| i as u8),
                 fee: row.try_get::<i64, _>("fee").unwrap_or(0) as u64,
+                expected_old_root: row.try_get::<Option<String>, _>("expected_old_root").ok().flatten(),
+                fee_bumps: row.try_get::<i32, _>("fee_bumps").unwrap_or(0) as u32,
+                index: row.try_get::<i64, _>("batch_index").unwrap_or(0) as u64,
+                proof_system: row.try_get::<String, _>("proof_system").ok().and_then(|s| s.parse().ok()).unwrap_or_default(),
+                public_inputs: row.try_get::<Option<String>, _>("public_inputs").ok().flatten().and_then(|s| hex::decode(s).ok()),
+                next_attempt_at: row.try_get::<Option<chrono::DateTime<chrono::Utc>>, _>("next_attempt_at").ok().flatten(),
+                data_hash: row.try_get::<Option<String>, _>("data_hash").ok().flatten(),
+                gas_used: row.try_get::<Option<i64>, _>("gas_used").ok().flatten().map(|g| g as u64),
+                effective_gas_price: row.try_get::<Option<i64>, _>("effective_gas_price").ok().flatten().map(|p| p as u64),
+                signed_tx: row.try_get::<Option<String>, _>("signed_tx").ok().flatten(),
+                da_reference: row.try_get::<Option<String>, _>("da_reference").ok().flatten(),
+                attestation: row.try_get::<Option<String>, _>("attestation").ok().flatten(),
             }))
         } else {
             Ok(None)
@@ -200,79 +350,278 @@ impl Storage for PostgresStorage {
             .await
             .map_err(|e| DomainError::Storage(e.to_string()))?;
 
-        let mut batches = Vec::new();
-        for row in rows {
-            let id_str: String = match row.try_get("id") {
-                Ok(s) => s,
-                Err(e) => {
-                    tracing::warn!("Skipping row with missing id: {}", e);
-                    continue;
-                }
-            };
-            let status_str: String = match row.try_get("status") {
-                Ok(s) => s,
-                Err(e) => {
-                    tracing::warn!("Skipping row with missing status: {}", e);
-                    continue;
-                }
-            };
-            let status = match status_str.as_str() {
-                "Discovered" => BatchStatus::Discovered,
-                "Proving" => BatchStatus::Proving,
-                "Proved" => BatchStatus::Proved,
-                "Submitting" => BatchStatus::Submitting,
-                "Submitted" => BatchStatus::Submitted,
-                "Confirmed" => BatchStatus::Confirmed,
-                "Failed" => BatchStatus::Failed,
-                other => {
-                    tracing::warn!("Skipping row with unknown status: {}", other);
-                    continue;
-                }
-            };
+        Ok(rows_to_batches(&self.rollup, rows))
+    }
 
-            let uuid = match Uuid::parse_str(&id_str) {
-                Ok(u) => u,
-                Err(e) => {
-                    tracing::warn!("Skipping row with invalid uuid {}: {}", id_str, e);
-                    continue;
-                }
-            };
+    async fn get_batches_by_status(&self, status: BatchStatus) -> Result<Vec<Batch>, DomainError> {
+        let rows = sqlx::query("SELECT * FROM batches WHERE status = $1 ORDER BY created_at ASC")
+            .bind(status.to_string())
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DomainError::Storage(e.to_string()))?;
 
-            let created_at = match row.try_get("created_at") {
-                Ok(t) => t,
-                Err(e) => {
-                    tracing::warn!("Skipping row with invalid created_at: {}", e);
-                    continue;
-                }
-            };
+        Ok(rows_to_batches(&self.rollup, rows))
+    }
 
-            let updated_at = match row.try_get("updated_at") {
-                Ok(t) => t,
-                Err(e) => {
-                    tracing::warn!("Skipping row with invalid updated_at: {}", e);
-                    continue;
-                }
-            };
+    async fn count_pending(&self) -> Result<u64, DomainError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM batches WHERE status != 'Confirmed' AND status != 'Failed'",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| DomainError::Storage(e.to_string()))?;
 
-            batches.push(Batch {
-                id: BatchId(uuid),
-                data_file: row.try_get("data_file").unwrap_or_default(),
-                new_root: row.try_get("new_root").unwrap_or_default(),
-                status,
-                da_mode: row.try_get("da_mode").unwrap_or_default(),
-                proof: row.try_get("proof").ok(),
-                tx_hash: row.try_get("tx_hash").ok(),
-                attempts: row.try_get::<i32, _>("attempts").unwrap_or(0) as u32,
-                created_at,
-                updated_at,
-                blob_versioned_hash: row.try_get("blob_versioned_hash").ok(),
-                blob_index: row.try_get::<i32, _>("blob_index").ok().map(|i| i as u8),
-                fee: row.try_get::<i64, _>("fee").unwrap_or(0) as u64,
-            });
-        }
+        Ok(count as u64)
+    }
 
-        Ok(batches)
+    async fn count_pending_with_data_file(&self, data_file: &str) -> Result<u64, DomainError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM batches WHERE status != 'Confirmed' AND status != 'Failed' AND data_file = $1",
+        )
+        .bind(data_file)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| DomainError::Storage(e.to_string()))?;
+
+        Ok(count as u64)
+    }
+
+    async fn ping(&self) -> Result<(), DomainError> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn count_by_status(&self) -> Result<Vec<(BatchStatus, u64)>, DomainError> {
+        let rows: Vec<(String, i64)> =
+            sqlx::query_as("SELECT status, COUNT(*) FROM batches GROUP BY status")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| DomainError::Storage(e.to_string()))?;
+
+        Ok(BatchStatus::ALL
+            .into_iter()
+            .map(|status| {
+                let name = status.to_string();
+                let count = rows.iter().find(|(s, _)| *s == name).map_or(0, |(_, n)| *n);
+                (status, count as u64)
+            })
+            .collect())
     }
+
+    async fn last_confirmed_root(&self) -> Result<Option<String>, DomainError> {
+        let root: Option<(String,)> = sqlx::query_as(
+            "SELECT new_root FROM batches WHERE status = 'Confirmed' ORDER BY updated_at DESC, batch_index DESC LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::Storage(e.to_string()))?;
+        Ok(root.map(|(root,)| root))
+    }
+
+    async fn max_batch_index(&self) -> Result<Option<u64>, DomainError> {
+        let (index,): (Option<i64>,) = sqlx::query_as("SELECT MAX(batch_index) FROM batches")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| DomainError::Storage(e.to_string()))?;
+        Ok(index.map(|i| i as u64))
+    }
+
+    async fn last_confirmed_index(&self) -> Result<Option<u64>, DomainError> {
+        let (index,): (Option<i64>,) =
+            sqlx::query_as("SELECT MAX(batch_index) FROM batches WHERE status = 'Confirmed'")
+                .fetch_one(&self.pool)
+                .await
+                .map_err(|e| DomainError::Storage(e.to_string()))?;
+        Ok(index.map(|i| i as u64))
+    }
+
+    async fn save_circuit_state(&self, key: &str, snapshot: &CircuitSnapshot) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO circuit_breakers (key, state, failure_count, last_failure)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT(key) DO UPDATE SET state = excluded.state, failure_count = excluded.failure_count, last_failure = excluded.last_failure
+            "#,
+        )
+        .bind(key)
+        .bind(snapshot.state.to_string())
+        .bind(snapshot.failure_count as i32)
+        .bind(snapshot.last_failure)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn load_circuit_state(&self, key: &str) -> Result<Option<CircuitSnapshot>, DomainError> {
+        let row: Option<(String, i32, chrono::DateTime<chrono::Utc>)> = sqlx::query_as("SELECT state, failure_count, last_failure FROM circuit_breakers WHERE key = $1")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| DomainError::Storage(e.to_string()))?;
+        let Some((state, failure_count, last_failure)) = row else {
+            return Ok(None);
+        };
+        Ok(Some(CircuitSnapshot {
+            state: state.parse::<CircuitState>()?,
+            failure_count: failure_count as u32,
+            last_failure,
+        }))
+    }
+
+    async fn get_batches_by_data_hash(&self, data_hash: &str) -> Result<Vec<Batch>, DomainError> {
+        let rows = sqlx::query("SELECT * FROM batches WHERE data_hash = $1")
+            .bind(data_hash)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DomainError::Storage(e.to_string()))?;
+
+        Ok(rows_to_batches(&self.rollup, rows))
+    }
+
+    async fn record_event(
+        &self,
+        batch_id: BatchId,
+        from: BatchStatus,
+        to: BatchStatus,
+        detail: Option<String>,
+    ) -> Result<(), DomainError> {
+        sqlx::query("INSERT INTO batch_events (batch_id, from_status, to_status, detail, created_at) VALUES ($1, $2, $3, $4, $5)")
+            .bind(batch_id.to_string())
+            .bind(from.to_string())
+            .bind(to.to_string())
+            .bind(detail)
+            .bind(chrono::Utc::now())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn get_events(&self, batch_id: BatchId) -> Result<Vec<BatchEvent>, DomainError> {
+        let rows: Vec<(String, String, Option<String>, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
+            "SELECT from_status, to_status, detail, created_at FROM batch_events WHERE batch_id = $1 ORDER BY id",
+        )
+        .bind(batch_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::Storage(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|(from, to, detail, at)| {
+                Ok(BatchEvent {
+                    batch_id,
+                    from: from.parse()?,
+                    to: to.parse()?,
+                    detail,
+                    at,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Counts a row `rows_to_batches` could not decode, by `reason`.
+fn record_skipped_row(rollup: &str, reason: &'static str) {
+    metrics::counter!("batch_rows_skipped_total", "rollup" => rollup.to_string(), "reason" => reason).increment(1);
+}
+
+/// Converts result rows into batches, skipping (and logging) malformed rows instead of failing
+/// the whole query. Skipped rows are counted in `batch_rows_skipped_total`.
+fn rows_to_batches(rollup: &str, rows: Vec<PgRow>) -> Vec<Batch> {
+    let mut batches = Vec::new();
+    for row in rows {
+        let id_str: String = match row.try_get("id") {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::warn!("Skipping row with missing id: {}", e);
+                record_skipped_row(rollup, "bad_uuid");
+                continue;
+            }
+        };
+        let status_str: String = match row.try_get("status") {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::warn!("Skipping row with missing status: {}", e);
+                record_skipped_row(rollup, "bad_status");
+                continue;
+            }
+        };
+        let status = match status_str.as_str() {
+            "Discovered" => BatchStatus::Discovered,
+            "Proving" => BatchStatus::Proving,
+            "Proved" => BatchStatus::Proved,
+            "Submitting" => BatchStatus::Submitting,
+            "Submitted" => BatchStatus::Submitted,
+            "Confirmed" => BatchStatus::Confirmed,
+            "Failed" => BatchStatus::Failed,
+            other => {
+                tracing::warn!("Skipping row with unknown status: {}", other);
+                record_skipped_row(rollup, "bad_status");
+                continue;
+            }
+        };
+
+        let uuid = match Uuid::parse_str(&id_str) {
+            Ok(u) => u,
+            Err(e) => {
+                tracing::warn!("Skipping row with invalid uuid {}: {}", id_str, e);
+                record_skipped_row(rollup, "bad_uuid");
+                continue;
+            }
+        };
+
+        let created_at = match row.try_get("created_at") {
+            Ok(t) => t,
+            Err(e) => {
+                tracing::warn!("Skipping row with invalid created_at: {}", e);
+                record_skipped_row(rollup, "bad_timestamp");
+                continue;
+            }
+        };
+
+        let updated_at = match row.try_get("updated_at") {
+            Ok(t) => t,
+            Err(e) => {
+                tracing::warn!("Skipping row with invalid updated_at: {}", e);
+                record_skipped_row(rollup, "bad_timestamp");
+                continue;
+            }
+        };
+
+        batches.push(Batch {
+            id: BatchId(uuid),
+            data_file: row.try_get("data_file").unwrap_or_default(),
+            new_root: row.try_get("new_root").unwrap_or_default(),
+            status,
+            da_mode: row.try_get("da_mode").unwrap_or_default(),
+            proof: row.try_get("proof").ok(),
+            tx_hash: row.try_get("tx_hash").ok(),
+            attempts: row.try_get::<i32, _>("attempts").unwrap_or(0) as u32,
+            created_at,
+            updated_at,
+            blob_versioned_hash: row.try_get("blob_versioned_hash").ok(),
+            blob_index: row.try_get::<i32, _>("blob_index").ok().map(|i| i as u8),
+            fee: row.try_get::<i64, _>("fee").unwrap_or(0) as u64,
+            expected_old_root: row.try_get::<Option<String>, _>("expected_old_root").ok().flatten(),
+            fee_bumps: row.try_get::<i32, _>("fee_bumps").unwrap_or(0) as u32,
+            index: row.try_get::<i64, _>("batch_index").unwrap_or(0) as u64,
+            proof_system: row.try_get::<String, _>("proof_system").ok().and_then(|s| s.parse().ok()).unwrap_or_default(),
+            public_inputs: row.try_get::<Option<String>, _>("public_inputs").ok().flatten().and_then(|s| hex::decode(s).ok()),
+            next_attempt_at: row.try_get::<Option<chrono::DateTime<chrono::Utc>>, _>("next_attempt_at").ok().flatten(),
+            data_hash: row.try_get::<Option<String>, _>("data_hash").ok().flatten(),
+            gas_used: row.try_get::<Option<i64>, _>("gas_used").ok().flatten().map(|g| g as u64),
+            effective_gas_price: row.try_get::<Option<i64>, _>("effective_gas_price").ok().flatten().map(|p| p as u64),
+            signed_tx: row.try_get::<Option<String>, _>("signed_tx").ok().flatten(),
+            da_reference: row.try_get::<Option<String>, _>("da_reference").ok().flatten(),
+            attestation: row.try_get::<Option<String>, _>("attestation").ok().flatten(),
+        });
+    }
+
+    batches
 }
 
 #[cfg(test)]
@@ -317,6 +666,18 @@ mod tests {
             blob_versioned_hash: None,
             blob_index: None,
             fee: 100,
+            expected_old_root: None,
+            fee_bumps: 0,
+            index: 0,
+            proof_system: Default::default(),
+            public_inputs: None,
+            next_attempt_at: None,
+            data_hash: None,
+            gas_used: None,
+            effective_gas_price: None,
+            signed_tx: None,
+            da_reference: None,
+            attestation: None,
         };
 
         // Save
@@ -333,5 +694,48 @@ mod tests {
 
         let retrieved_2 = storage.get_batch(batch_id).await.expect("get failed").unwrap();
         assert_eq!(retrieved_2.status, BatchStatus::Proving);
+
+        // Blob metadata survives a reload
+        updated_batch.blob_versioned_hash = Some("0x01ab".to_string());
+        updated_batch.blob_index = Some(2);
+        storage.save_batch(&updated_batch).await.expect("update failed");
+        let retrieved_3 = storage.get_batch(batch_id).await.expect("get failed").unwrap();
+        assert_eq!(retrieved_3.blob_versioned_hash.as_deref(), Some("0x01ab"));
+        assert_eq!(retrieved_3.blob_index, Some(2));
+
+        updated_batch.public_inputs = Some(vec![0xab; 96]);
+        storage.save_batch(&updated_batch).await.expect("update failed");
+        let retrieved_4 = storage.get_batch(batch_id).await.expect("get failed").unwrap();
+        assert_eq!(retrieved_4.public_inputs, Some(vec![0xab; 96]));
+    }
+
+    #[tokio::test]
+    async fn test_postgres_get_batches_by_status() {
+        let db_url = get_db_url();
+        if std::net::TcpStream::connect("localhost:5432").is_err() && env::var("CI").is_err() {
+            println!("Skipping postgres test: no db");
+            return;
+        }
+
+        let storage = match PostgresStorage::new(&db_url, Some(10), Some("fifo".into())).await {
+            Ok(s) => s,
+            Err(_) => {
+                println!("Skipping postgres test: connection failed");
+                return;
+            }
+        };
+
+        // The database may be shared, so only look for the rows inserted here
+        let mut failed = Batch::new(1, "b", "f".into(), Uuid::new_v4().to_string(), "0xroot".into(), "calldata".into());
+        failed.status = BatchStatus::Failed;
+        let mut submitted = Batch::new(1, "b", "f".into(), Uuid::new_v4().to_string(), "0xroot".into(), "calldata".into());
+        submitted.status = BatchStatus::Submitted;
+        storage.save_batch(&failed).await.unwrap();
+        storage.save_batch(&submitted).await.unwrap();
+
+        let rows = storage.get_batches_by_status(BatchStatus::Failed).await.unwrap();
+        assert!(rows.iter().all(|b| b.status == BatchStatus::Failed));
+        assert!(rows.iter().any(|b| b.id == failed.id));
+        assert!(!rows.iter().any(|b| b.id == submitted.id));
     }
 }
@@ -1,33 +1,49 @@
-use crate::application::ports::Storage;
+use crate::application::ports::{BatchEvent, CircuitSnapshot, CircuitState, Storage};
 use crate::domain::{
     batch::{Batch, BatchId, BatchStatus},
     errors::DomainError,
 };
 use async_trait::async_trait;
-use sqlx::{sqlite::SqlitePoolOptions, Pool, Row, Sqlite};
+use ethers::utils::hex;
+use sqlx::{sqlite::{SqlitePoolOptions, SqliteRow}, Pool, Row, Sqlite};
 use tracing::info;
 use uuid::Uuid;
 
 pub struct SqliteStorage {
     pool: Pool<Sqlite>,
+    rollup: String,
 }
 
 impl SqliteStorage {
     pub async fn new(db_url: &str) -> Result<Self, DomainError> {
+        Self::with_pool_size(db_url, 5).await
+    }
+
+    /// Like [`SqliteStorage::new`] with at most `max_connections` pooled connections.
+    pub async fn with_pool_size(db_url: &str, max_connections: u32) -> Result<Self, DomainError> {
         let pool = SqlitePoolOptions::new()
-            .max_connections(5)
+            .max_connections(max_connections)
             .connect(db_url)
             .await
             .map_err(|e| DomainError::Storage(e.to_string()))?;
 
         info!("Connected to SQLite");
 
-        let storage = Self { pool };
+        let storage = Self {
+            pool,
+            rollup: "default".to_string(),
+        };
         storage.migrate().await?;
 
         Ok(storage)
     }
 
+    /// Names the rollup whose batches are stored, for the `rollup` label of its metrics.
+    pub fn with_rollup(mut self, rollup: impl Into<String>) -> Self {
+        self.rollup = rollup.into();
+        self
+    }
+
     async fn migrate(&self) -> Result<(), DomainError> {
         sqlx::query(
             r#"
@@ -41,7 +57,35 @@ impl SqliteStorage {
                 tx_hash TEXT,
                 attempts INTEGER DEFAULT 0,
                 created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
+                updated_at TEXT NOT NULL,
+                expected_old_root TEXT,
+                fee_bumps INTEGER DEFAULT 0,
+                batch_index INTEGER DEFAULT 0,
+                proof_system TEXT DEFAULT 'groth16',
+                blob_versioned_hash TEXT,
+                blob_index INTEGER,
+                public_inputs TEXT,
+                next_attempt_at TEXT,
+                data_hash TEXT,
+                gas_used INTEGER,
+                effective_gas_price INTEGER,
+                signed_tx TEXT,
+                da_reference TEXT,
+                attestation TEXT
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::Storage(format!("Migration failed: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS circuit_breakers (
+                key TEXT PRIMARY KEY,
+                state TEXT NOT NULL,
+                failure_count INTEGER NOT NULL,
+                last_failure TEXT NOT NULL
             );
             "#,
         )
@@ -49,10 +93,87 @@ impl SqliteStorage {
         .await
         .map_err(|e| DomainError::Storage(format!("Migration failed: {}", e)))?;
 
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS batch_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                batch_id TEXT NOT NULL,
+                from_status TEXT NOT NULL,
+                to_status TEXT NOT NULL,
+                detail TEXT,
+                created_at TEXT NOT NULL
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::Storage(format!("Migration failed: {}", e)))?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS batch_events_batch_id ON batch_events (batch_id)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::Storage(format!("Migration failed: {}", e)))?;
+
         let _ = sqlx::query("ALTER TABLE batches ADD COLUMN attempts INTEGER DEFAULT 0")
             .execute(&self.pool)
             .await;
 
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN expected_old_root TEXT")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN fee_bumps INTEGER DEFAULT 0")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN batch_index INTEGER DEFAULT 0")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN proof_system TEXT DEFAULT 'groth16'")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN blob_versioned_hash TEXT")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN blob_index INTEGER")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN public_inputs TEXT")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN next_attempt_at TEXT")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN data_hash TEXT")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN gas_used INTEGER")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN effective_gas_price INTEGER")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN signed_tx TEXT")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN da_reference TEXT")
+            .execute(&self.pool)
+            .await;
+
+        let _ = sqlx::query("ALTER TABLE batches ADD COLUMN attestation TEXT")
+            .execute(&self.pool)
+            .await;
+
         Ok(())
     }
 }
@@ -65,14 +186,28 @@ impl Storage for SqliteStorage {
 
         sqlx::query(
             r#"
-            INSERT INTO batches (id, data_file, new_root, status, da_mode, proof, tx_hash, attempts, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO batches (id, data_file, new_root, status, da_mode, proof, tx_hash, attempts, created_at, updated_at, expected_old_root, fee_bumps, batch_index, proof_system, blob_versioned_hash, blob_index, public_inputs, next_attempt_at, data_hash, gas_used, effective_gas_price, signed_tx, da_reference, attestation)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             ON CONFLICT(id) DO UPDATE SET
                 status = excluded.status,
                 proof = excluded.proof,
                 tx_hash = excluded.tx_hash,
                 attempts = excluded.attempts,
-                updated_at = excluded.updated_at
+                updated_at = excluded.updated_at,
+                expected_old_root = excluded.expected_old_root,
+                fee_bumps = excluded.fee_bumps,
+                batch_index = excluded.batch_index,
+                proof_system = excluded.proof_system,
+                blob_versioned_hash = excluded.blob_versioned_hash,
+                blob_index = excluded.blob_index,
+                public_inputs = excluded.public_inputs,
+                next_attempt_at = excluded.next_attempt_at,
+                data_hash = excluded.data_hash,
+                gas_used = excluded.gas_used,
+                effective_gas_price = excluded.effective_gas_price,
+                signed_tx = excluded.signed_tx,
+                da_reference = excluded.da_reference,
+                attestation = excluded.attestation
             "#,
         )
         .bind(id_str)
@@ -85,6 +220,20 @@ impl Storage for SqliteStorage {
         .bind(batch.attempts)
         .bind(batch.created_at.to_rfc3339())
         .bind(batch.updated_at.to_rfc3339())
+        .bind(&batch.expected_old_root)
+        .bind(batch.fee_bumps)
+        .bind(batch.index as i64)
+        .bind(batch.proof_system.to_string())
+        .bind(&batch.blob_versioned_hash)
+        .bind(batch.blob_index.map(|i| i as i64))
+        .bind(batch.public_inputs.as_ref().map(hex::encode))
+        .bind(batch.next_attempt_at.map(|t| t.to_rfc3339()))
+        .bind(&batch.data_hash)
+        .bind(batch.gas_used.map(|g| g as i64))
+        .bind(batch.effective_gas_price.map(|p| p as i64))
+        .bind(&batch.signed_tx)
+        .bind(&batch.da_reference)
+        .bind(&batch.attestation)
         .execute(&self.pool)
         .await
         .map_err(|e| DomainError::Storage(e.to_string()))?;
@@ -141,14 +290,26 @@ impl Storage for SqliteStorage {
                 new_root: row.try_get("new_root").unwrap_or_default(),
                 status,
                 da_mode: row.try_get("da_mode").unwrap_or_default(),
-                proof: row.try_get("proof").ok(),
-                tx_hash: row.try_get("tx_hash").ok(),
+                proof: row.try_get::<Option<String>, _>("proof").ok().flatten(),
+                tx_hash: row.try_get::<Option<String>, _>("tx_hash").ok().flatten(),
                 attempts: row.try_get("attempts").unwrap_or(0),
                 created_at,
                 updated_at,
-                blob_versioned_hash: None, // TODO: Add DB columns
-                blob_index: None,
+                blob_versioned_hash: row.try_get::<Option<String>, _>("blob_versioned_hash").ok().flatten(),
+                blob_index: row.try_get::<Option<i64>, _>("blob_index").ok().flatten().map(|i| i as u8),
                 fee: 0,
+                expected_old_root: row.try_get::<Option<String>, _>("expected_old_root").ok().flatten(),
+                fee_bumps: row.try_get("fee_bumps").unwrap_or(0),
+                index: row.try_get::<i64, _>("batch_index").unwrap_or(0) as u64,
+                proof_system: row.try_get::<String, _>("proof_system").ok().and_then(|s| s.parse().ok()).unwrap_or_default(),
+                public_inputs: row.try_get::<Option<String>, _>("public_inputs").ok().flatten().and_then(|s| hex::decode(s).ok()),
+                next_attempt_at: row.try_get::<Option<String>, _>("next_attempt_at").ok().flatten().and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok()).map(|t| t.with_timezone(&chrono::Utc)),
+                data_hash: row.try_get::<Option<String>, _>("data_hash").ok().flatten(),
+                gas_used: row.try_get::<Option<i64>, _>("gas_used").ok().flatten().map(|g| g as u64),
+                effective_gas_price: row.try_get::<Option<i64>, _>("effective_gas_price").ok().flatten().map(|p| p as u64),
+                signed_tx: row.try_get::<Option<String>, _>("signed_tx").ok().flatten(),
+                da_reference: row.try_get::<Option<String>, _>("da_reference").ok().flatten(),
+                attestation: row.try_get::<Option<String>, _>("attestation").ok().flatten(),
             }))
         } else {
             Ok(None)
@@ -162,81 +323,286 @@ impl Storage for SqliteStorage {
                 .await
                 .map_err(|e| DomainError::Storage(e.to_string()))?;
 
-        let mut batches = Vec::new();
-        for row in rows {
-            let id_str: String = match row.try_get("id") {
-                Ok(s) => s,
-                Err(e) => {
-                    tracing::warn!("Skipping row with missing id: {}", e);
-                    continue;
-                }
-            };
-            let status_str: String = match row.try_get("status") {
-                Ok(s) => s,
-                Err(e) => {
-                    tracing::warn!("Skipping row with missing status: {}", e);
-                    continue;
-                }
-            };
-            let status = match status_str.as_str() {
-                "Discovered" => BatchStatus::Discovered,
-                "Proving" => BatchStatus::Proving,
-                "Proved" => BatchStatus::Proved,
-                "Submitting" => BatchStatus::Submitting,
-                "Submitted" => BatchStatus::Submitted,
-                "Confirmed" => BatchStatus::Confirmed,
-                "Failed" => BatchStatus::Failed,
-                other => {
-                    tracing::warn!("Skipping row with unknown status: {}", other);
-                    continue;
-                }
-            };
+        Ok(rows_to_batches(&self.rollup, rows))
+    }
 
-            let uuid = match Uuid::parse_str(&id_str) {
-                Ok(u) => u,
-                Err(e) => {
-                    tracing::warn!("Skipping row with invalid uuid {}: {}", id_str, e);
-                    continue;
-                }
-            };
+    async fn get_batches_by_status(&self, status: BatchStatus) -> Result<Vec<Batch>, DomainError> {
+        let rows = sqlx::query("SELECT * FROM batches WHERE status = ?")
+            .bind(status.to_string())
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DomainError::Storage(e.to_string()))?;
 
-            let created_at_str: String = row.try_get("created_at").unwrap_or_default();
-            let created_at = match chrono::DateTime::parse_from_rfc3339(&created_at_str) {
-                Ok(t) => t.with_timezone(&chrono::Utc),
-                Err(e) => {
-                    tracing::warn!("Skipping row with invalid created_at: {}", e);
-                    continue;
-                }
-            };
+        Ok(rows_to_batches(&self.rollup, rows))
+    }
 
-            let updated_at_str: String = row.try_get("updated_at").unwrap_or_default();
-            let updated_at = match chrono::DateTime::parse_from_rfc3339(&updated_at_str) {
-                Ok(t) => t.with_timezone(&chrono::Utc),
-                Err(e) => {
-                    tracing::warn!("Skipping row with invalid updated_at: {}", e);
-                    continue;
-                }
-            };
+    async fn count_pending(&self) -> Result<u64, DomainError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM batches WHERE status != 'Confirmed' AND status != 'Failed'",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| DomainError::Storage(e.to_string()))?;
 
-            batches.push(Batch {
-                id: BatchId(uuid),
-                data_file: row.try_get("data_file").unwrap_or_default(),
-                new_root: row.try_get("new_root").unwrap_or_default(),
-                status,
-                da_mode: row.try_get("da_mode").unwrap_or_default(),
-                proof: row.try_get("proof").ok(),
-                tx_hash: row.try_get("tx_hash").ok(),
-                attempts: row.try_get("attempts").unwrap_or(0),
-                created_at,
-                updated_at,
-                blob_versioned_hash: None,
-                blob_index: None,
-                fee: 0,
-            });
-        }
+        Ok(count as u64)
+    }
+
+    async fn count_pending_with_data_file(&self, data_file: &str) -> Result<u64, DomainError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM batches WHERE status != 'Confirmed' AND status != 'Failed' AND data_file = ?",
+        )
+        .bind(data_file)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| DomainError::Storage(e.to_string()))?;
 
-        Ok(batches)
+        Ok(count as u64)
     }
+
+    async fn ping(&self) -> Result<(), DomainError> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn count_by_status(&self) -> Result<Vec<(BatchStatus, u64)>, DomainError> {
+        let rows: Vec<(String, i64)> =
+            sqlx::query_as("SELECT status, COUNT(*) FROM batches GROUP BY status")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| DomainError::Storage(e.to_string()))?;
+
+        Ok(BatchStatus::ALL
+            .into_iter()
+            .map(|status| {
+                let name = status.to_string();
+                let count = rows.iter().find(|(s, _)| *s == name).map_or(0, |(_, n)| *n);
+                (status, count as u64)
+            })
+            .collect())
+    }
+
+    async fn last_confirmed_root(&self) -> Result<Option<String>, DomainError> {
+        let root: Option<(String,)> = sqlx::query_as(
+            "SELECT new_root FROM batches WHERE status = 'Confirmed' ORDER BY updated_at DESC, batch_index DESC LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::Storage(e.to_string()))?;
+        Ok(root.map(|(root,)| root))
+    }
+
+    async fn max_batch_index(&self) -> Result<Option<u64>, DomainError> {
+        let (index,): (Option<i64>,) = sqlx::query_as("SELECT MAX(batch_index) FROM batches")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| DomainError::Storage(e.to_string()))?;
+        Ok(index.map(|i| i as u64))
+    }
+
+    async fn last_confirmed_index(&self) -> Result<Option<u64>, DomainError> {
+        let (index,): (Option<i64>,) =
+            sqlx::query_as("SELECT MAX(batch_index) FROM batches WHERE status = 'Confirmed'")
+                .fetch_one(&self.pool)
+                .await
+                .map_err(|e| DomainError::Storage(e.to_string()))?;
+        Ok(index.map(|i| i as u64))
+    }
+
+    async fn save_circuit_state(&self, key: &str, snapshot: &CircuitSnapshot) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO circuit_breakers (key, state, failure_count, last_failure)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(key) DO UPDATE SET state = excluded.state, failure_count = excluded.failure_count, last_failure = excluded.last_failure
+            "#,
+        )
+        .bind(key)
+        .bind(snapshot.state.to_string())
+        .bind(snapshot.failure_count as i64)
+        .bind(snapshot.last_failure.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn load_circuit_state(&self, key: &str) -> Result<Option<CircuitSnapshot>, DomainError> {
+        let row: Option<(String, i64, String)> = sqlx::query_as("SELECT state, failure_count, last_failure FROM circuit_breakers WHERE key = ?")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| DomainError::Storage(e.to_string()))?;
+        let Some((state, failure_count, last_failure)) = row else {
+            return Ok(None);
+        };
+        let last_failure = chrono::DateTime::parse_from_rfc3339(&last_failure)
+            .map_err(|e| DomainError::Storage(format!("Invalid last_failure: {}", e)))?
+            .with_timezone(&chrono::Utc);
+        Ok(Some(CircuitSnapshot {
+            state: state.parse::<CircuitState>()?,
+            failure_count: failure_count as u32,
+            last_failure,
+        }))
+    }
+
+    async fn get_batches_by_data_hash(&self, data_hash: &str) -> Result<Vec<Batch>, DomainError> {
+        let rows = sqlx::query("SELECT * FROM batches WHERE data_hash = ?")
+            .bind(data_hash)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DomainError::Storage(e.to_string()))?;
+
+        Ok(rows_to_batches(&self.rollup, rows))
+    }
+
+    async fn record_event(
+        &self,
+        batch_id: BatchId,
+        from: BatchStatus,
+        to: BatchStatus,
+        detail: Option<String>,
+    ) -> Result<(), DomainError> {
+        sqlx::query("INSERT INTO batch_events (batch_id, from_status, to_status, detail, created_at) VALUES (?, ?, ?, ?, ?)")
+            .bind(batch_id.to_string())
+            .bind(from.to_string())
+            .bind(to.to_string())
+            .bind(detail)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn get_events(&self, batch_id: BatchId) -> Result<Vec<BatchEvent>, DomainError> {
+        let rows: Vec<(String, String, Option<String>, String)> = sqlx::query_as(
+            "SELECT from_status, to_status, detail, created_at FROM batch_events WHERE batch_id = ? ORDER BY id",
+        )
+        .bind(batch_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::Storage(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|(from, to, detail, at)| {
+                let at = chrono::DateTime::parse_from_rfc3339(&at)
+                    .map_err(|e| DomainError::Storage(format!("Invalid event time: {}", e)))?
+                    .with_timezone(&chrono::Utc);
+                Ok(BatchEvent {
+                    batch_id,
+                    from: from.parse()?,
+                    to: to.parse()?,
+                    detail,
+                    at,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Counts a row `rows_to_batches` could not decode, by `reason`.
+fn record_skipped_row(rollup: &str, reason: &'static str) {
+    metrics::counter!("batch_rows_skipped_total", "rollup" => rollup.to_string(), "reason" => reason).increment(1);
+}
+
+/// Converts result rows into batches, skipping (and logging) malformed rows instead of failing
+/// the whole query. Skipped rows are counted in `batch_rows_skipped_total`.
+fn rows_to_batches(rollup: &str, rows: Vec<SqliteRow>) -> Vec<Batch> {
+    let mut batches = Vec::new();
+    for row in rows {
+        let id_str: String = match row.try_get("id") {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::warn!("Skipping row with missing id: {}", e);
+                record_skipped_row(rollup, "bad_uuid");
+                continue;
+            }
+        };
+        let status_str: String = match row.try_get("status") {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::warn!("Skipping row with missing status: {}", e);
+                record_skipped_row(rollup, "bad_status");
+                continue;
+            }
+        };
+        let status = match status_str.as_str() {
+            "Discovered" => BatchStatus::Discovered,
+            "Proving" => BatchStatus::Proving,
+            "Proved" => BatchStatus::Proved,
+            "Submitting" => BatchStatus::Submitting,
+            "Submitted" => BatchStatus::Submitted,
+            "Confirmed" => BatchStatus::Confirmed,
+            "Failed" => BatchStatus::Failed,
+            other => {
+                tracing::warn!("Skipping row with unknown status: {}", other);
+                record_skipped_row(rollup, "bad_status");
+                continue;
+            }
+        };
+
+        let uuid = match Uuid::parse_str(&id_str) {
+            Ok(u) => u,
+            Err(e) => {
+                tracing::warn!("Skipping row with invalid uuid {}: {}", id_str, e);
+                record_skipped_row(rollup, "bad_uuid");
+                continue;
+            }
+        };
+
+        let created_at_str: String = row.try_get("created_at").unwrap_or_default();
+        let created_at = match chrono::DateTime::parse_from_rfc3339(&created_at_str) {
+            Ok(t) => t.with_timezone(&chrono::Utc),
+            Err(e) => {
+                tracing::warn!("Skipping row with invalid created_at: {}", e);
+                record_skipped_row(rollup, "bad_timestamp");
+                continue;
+            }
+        };
+
+        let updated_at_str: String = row.try_get("updated_at").unwrap_or_default();
+        let updated_at = match chrono::DateTime::parse_from_rfc3339(&updated_at_str) {
+            Ok(t) => t.with_timezone(&chrono::Utc),
+            Err(e) => {
+                tracing::warn!("Skipping row with invalid updated_at: {}", e);
+                record_skipped_row(rollup, "bad_timestamp");
+                continue;
+            }
+        };
+
+        batches.push(Batch {
+            id: BatchId(uuid),
+            data_file: row.try_get("data_file").unwrap_or_default(),
+            new_root: row.try_get("new_root").unwrap_or_default(),
+            status,
+            da_mode: row.try_get("da_mode").unwrap_or_default(),
+            proof: row.try_get::<Option<String>, _>("proof").ok().flatten(),
+            tx_hash: row.try_get::<Option<String>, _>("tx_hash").ok().flatten(),
+            attempts: row.try_get("attempts").unwrap_or(0),
+            created_at,
+            updated_at,
+            blob_versioned_hash: row.try_get::<Option<String>, _>("blob_versioned_hash").ok().flatten(),
+            blob_index: row.try_get::<Option<i64>, _>("blob_index").ok().flatten().map(|i| i as u8),
+            fee: 0,
+            expected_old_root: row.try_get::<Option<String>, _>("expected_old_root").ok().flatten(),
+            fee_bumps: row.try_get("fee_bumps").unwrap_or(0),
+            index: row.try_get::<i64, _>("batch_index").unwrap_or(0) as u64,
+            proof_system: row.try_get::<String, _>("proof_system").ok().and_then(|s| s.parse().ok()).unwrap_or_default(),
+            public_inputs: row.try_get::<Option<String>, _>("public_inputs").ok().flatten().and_then(|s| hex::decode(s).ok()),
+            next_attempt_at: row.try_get::<Option<String>, _>("next_attempt_at").ok().flatten().and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok()).map(|t| t.with_timezone(&chrono::Utc)),
+            data_hash: row.try_get::<Option<String>, _>("data_hash").ok().flatten(),
+            gas_used: row.try_get::<Option<i64>, _>("gas_used").ok().flatten().map(|g| g as u64),
+            effective_gas_price: row.try_get::<Option<i64>, _>("effective_gas_price").ok().flatten().map(|p| p as u64),
+            signed_tx: row.try_get::<Option<String>, _>("signed_tx").ok().flatten(),
+            da_reference: row.try_get::<Option<String>, _>("da_reference").ok().flatten(),
+            attestation: row.try_get::<Option<String>, _>("attestation").ok().flatten(),
+        });
+    }
+
+    batches
 }
 
 #[cfg(test)]
@@ -263,6 +629,18 @@ mod tests {
             blob_versioned_hash: None,
             blob_index: None,
             fee: 0,
+            expected_old_root: None,
+            fee_bumps: 0,
+            index: 0,
+            proof_system: Default::default(),
+            public_inputs: None,
+            next_attempt_at: None,
+            data_hash: None,
+            gas_used: None,
+            effective_gas_price: None,
+            signed_tx: None,
+            da_reference: None,
+            attestation: None,
         };
 
         // Save
@@ -272,6 +650,7 @@ mod tests {
         let retrieved = storage.get_batch(batch_id).await.expect("get failed").unwrap();
         assert_eq!(retrieved.id, batch.id);
         assert_eq!(retrieved.status, BatchStatus::Discovered);
+        assert_eq!(retrieved.expected_old_root, None);
 
         // Update
         let mut updated_batch = batch.clone();
@@ -281,6 +660,12 @@ mod tests {
         let retrieved_2 = storage.get_batch(batch_id).await.expect("get failed").unwrap();
         assert_eq!(retrieved_2.status, BatchStatus::Proving);
 
+        // Expected predecessor root round-trips
+        updated_batch.expected_old_root = Some("0xparent".to_string());
+        storage.save_batch(&updated_batch).await.expect("update failed");
+        let retrieved_3 = storage.get_batch(batch_id).await.expect("get failed").unwrap();
+        assert_eq!(retrieved_3.expected_old_root.as_deref(), Some("0xparent"));
+
         // Get Pending
         let pending = storage.get_pending_batches().await.expect("pending failed");
         assert_eq!(pending.len(), 1);
@@ -304,4 +689,218 @@ mod tests {
         let pending = storage.get_pending_batches().await.unwrap();
         assert!(pending.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_sqlite_blob_metadata_round_trip() {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        let versioned_hash = "0x01aabbccddeeff00112233445566778899aabbccddeeff00112233445566778899";
+
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), "0xroot".into(), "blob".into());
+        batch.blob_versioned_hash = Some(versioned_hash.to_string());
+        batch.blob_index = Some(3);
+        storage.save_batch(&batch).await.unwrap();
+
+        let loaded = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(loaded.blob_versioned_hash.as_deref(), Some(versioned_hash));
+        assert_eq!(loaded.blob_index, Some(3));
+
+        let pending = storage.get_pending_batches().await.unwrap();
+        assert_eq!(pending[0].blob_versioned_hash.as_deref(), Some(versioned_hash));
+        assert_eq!(pending[0].blob_index, Some(3));
+
+        // Batches without blob metadata keep falling back to the strategy defaults
+        let plain = Batch::new(1, "b", "f".into(), "h2".into(), "0xroot".into(), "blob".into());
+        storage.save_batch(&plain).await.unwrap();
+        let loaded = storage.get_batch(plain.id).await.unwrap().unwrap();
+        assert_eq!(loaded.blob_versioned_hash, None);
+        assert_eq!(loaded.blob_index, None);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_public_inputs_round_trip() {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), "0xroot".into(), "calldata".into());
+        storage.save_batch(&batch).await.unwrap();
+        assert_eq!(storage.get_batch(batch.id).await.unwrap().unwrap().public_inputs, None);
+
+        let inputs: Vec<u8> = (0..96).collect();
+        batch.public_inputs = Some(inputs.clone());
+        storage.save_batch(&batch).await.unwrap();
+        assert_eq!(storage.get_batch(batch.id).await.unwrap().unwrap().public_inputs, Some(inputs));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_next_attempt_at_round_trip() {
+        use chrono::TimeZone;
+
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), "0xroot".into(), "calldata".into());
+        storage.save_batch(&batch).await.unwrap();
+        assert_eq!(storage.get_batch(batch.id).await.unwrap().unwrap().next_attempt_at, None);
+
+        let retry_at = chrono::Utc.with_ymd_and_hms(2030, 1, 2, 3, 4, 5).unwrap();
+        batch.next_attempt_at = Some(retry_at);
+        storage.save_batch(&batch).await.unwrap();
+        assert_eq!(storage.get_batch(batch.id).await.unwrap().unwrap().next_attempt_at, Some(retry_at));
+        assert_eq!(storage.get_pending_batches().await.unwrap()[0].next_attempt_at, Some(retry_at));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_gas_accounting_round_trip() {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        let mut batch = Batch::new(1, "b", "f".into(), "h".into(), "0xroot".into(), "calldata".into());
+        storage.save_batch(&batch).await.unwrap();
+        let stored = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!((stored.gas_used, stored.effective_gas_price), (None, None));
+
+        batch.gas_used = Some(210_000);
+        batch.effective_gas_price = Some(30_000_000_000);
+        storage.save_batch(&batch).await.unwrap();
+        let stored = storage.get_batch(batch.id).await.unwrap().unwrap();
+        assert_eq!(stored.gas_used, Some(210_000));
+        assert_eq!(stored.effective_gas_price, Some(30_000_000_000));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_count_by_status() {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+
+        let statuses = [BatchStatus::Proving, BatchStatus::Submitted, BatchStatus::Proving, BatchStatus::Failed];
+        for (i, status) in statuses.into_iter().enumerate() {
+            let mut batch = Batch::new(1, "b", "f".into(), format!("h{}", i), "0xroot".into(), "calldata".into());
+            batch.status = status;
+            storage.save_batch(&batch).await.unwrap();
+        }
+
+        let counts = storage.count_by_status().await.unwrap();
+        assert_eq!(
+            counts,
+            vec![
+                (BatchStatus::Discovered, 0),
+                (BatchStatus::Proving, 2),
+                (BatchStatus::Proved, 0),
+                (BatchStatus::Submitting, 0),
+                (BatchStatus::Submitted, 1),
+                (BatchStatus::Confirmed, 0),
+                (BatchStatus::Failed, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skipped_rows_counted_by_reason() {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            rt.block_on(async {
+                let storage = SqliteStorage::new("sqlite::memory:").await.unwrap().with_rollup("alpha");
+                let good = Batch::new(1, "b", "f".into(), "h".into(), "0xroot".into(), "calldata".into());
+                storage.save_batch(&good).await.unwrap();
+
+                let now = chrono::Utc::now().to_rfc3339();
+                let rows = [
+                    ("not-a-uuid", "Proving", now.as_str()),
+                    (&*Uuid::new_v4().to_string(), "Limbo", now.as_str()),
+                    (&*Uuid::new_v4().to_string(), "Proving", "yesterday"),
+                    (&*Uuid::new_v4().to_string(), "Submitted", "yesterday"),
+                ];
+                for (id, status, created_at) in rows {
+                    sqlx::query(
+                        "INSERT INTO batches (id, data_file, new_root, status, da_mode, created_at, updated_at) VALUES (?, 'f', '0xroot', ?, 'calldata', ?, ?)",
+                    )
+                    .bind(id)
+                    .bind(status)
+                    .bind(created_at)
+                    .bind(&now)
+                    .execute(&storage.pool)
+                    .await
+                    .unwrap();
+                }
+
+                let pending = storage.get_pending_batches().await.unwrap();
+                assert_eq!(pending.len(), 1);
+                assert_eq!(pending[0].id, good.id);
+            });
+        });
+
+        let mut skipped: Vec<_> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .filter(|(key, _, _, _)| key.key().name() == "batch_rows_skipped_total")
+            .map(|(key, _, _, value)| {
+                let label = |name| key.key().labels().find(|l| l.key() == name).unwrap().value().to_string();
+                assert_eq!(label("rollup"), "alpha");
+                (label("reason"), value)
+            })
+            .collect();
+        skipped.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            skipped,
+            vec![
+                ("bad_status".to_string(), DebugValue::Counter(1)),
+                ("bad_timestamp".to_string(), DebugValue::Counter(2)),
+                ("bad_uuid".to_string(), DebugValue::Counter(1)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_last_confirmed_root() {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        assert_eq!(storage.last_confirmed_root().await.unwrap(), None);
+
+        let now = chrono::Utc::now();
+        let batches = [
+            ("0xold", BatchStatus::Confirmed, now - chrono::Duration::minutes(10)),
+            ("0xlatest", BatchStatus::Confirmed, now),
+            ("0xpending", BatchStatus::Submitted, now + chrono::Duration::minutes(1)),
+        ];
+        for (i, (root, status, updated_at)) in batches.into_iter().enumerate() {
+            let mut batch = Batch::new(1, "b", "f".into(), format!("h{}", i), root.into(), "calldata".into());
+            batch.status = status;
+            batch.updated_at = updated_at;
+            storage.save_batch(&batch).await.unwrap();
+        }
+
+        assert_eq!(storage.last_confirmed_root().await.unwrap(), Some("0xlatest".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_get_batches_by_status() {
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+
+        let statuses = [BatchStatus::Failed, BatchStatus::Submitted, BatchStatus::Failed, BatchStatus::Confirmed];
+        let mut failed_ids = Vec::new();
+        for (i, status) in statuses.into_iter().enumerate() {
+            let mut batch = Batch::new(1, "b", "f".into(), format!("h{}", i), "0xroot".into(), "calldata".into());
+            batch.status = status;
+            if batch.status == BatchStatus::Failed {
+                failed_ids.push(batch.id);
+            }
+            storage.save_batch(&batch).await.unwrap();
+        }
+        // A malformed Failed row is skipped rather than failing the query
+        sqlx::query(
+            "INSERT INTO batches (id, data_file, new_root, status, da_mode, created_at, updated_at) \
+             VALUES ('bad-uuid', 'f', 'r', 'Failed', 'm', '2023-01-01T00:00:00Z', '2023-01-01T00:00:00Z')"
+        )
+        .execute(&storage.pool)
+        .await
+        .unwrap();
+
+        let failed = storage.get_batches_by_status(BatchStatus::Failed).await.unwrap();
+        let mut ids: Vec<_> = failed.iter().map(|b| b.id).collect();
+        ids.sort_by_key(|id| id.0);
+        failed_ids.sort_by_key(|id| id.0);
+        assert_eq!(ids, failed_ids);
+        assert!(failed.iter().all(|b| b.status == BatchStatus::Failed));
+
+        let submitted = storage.get_batches_by_status(BatchStatus::Submitted).await.unwrap();
+        assert_eq!(submitted.len(), 1);
+        assert!(storage.get_batches_by_status(BatchStatus::Proving).await.unwrap().is_empty());
+    }
 }
@@ -1,5 +1,6 @@
 use crate::config::{self, DaMode};
 use crate::contracts::{self, ZKRollupBridge};
+use crate::infrastructure::ethereum_adapter::{http_transport, RateLimitRetry};
 use crate::submitter::Submitter;
 use anyhow::{Context, Result};
 use ethers::prelude::*;
@@ -15,7 +16,14 @@ pub async fn run(config_path: PathBuf) -> Result<()> {
         .parse::<LocalWallet>()?
         .with_chain_id(cfg.network.chain_id);
 
-    let provider = Provider::<Http>::try_from(cfg.network.rpc_url.as_str())?;
+    let provider = Provider::new(RateLimitRetry::new(
+        http_transport(
+            &cfg.network.rpc_url,
+            std::time::Duration::from_secs(cfg.network.rpc_timeout_secs.unwrap_or(30)),
+        )?,
+        cfg.network.rate_limit_retries.unwrap_or(5),
+        std::time::Duration::from_millis(cfg.network.rate_limit_backoff_ms.unwrap_or(1000)),
+    ));
     let client = Arc::new(SignerMiddleware::new(provider, wallet));
 
     let bridge_addr: Address = cfg.contracts.bridge.parse()?;
@@ -27,13 +35,14 @@ pub async fn run(config_path: PathBuf) -> Result<()> {
         c: [U256::zero(), U256::zero()],
     };
 
-    let new_root: H256 = cfg.batch.new_root.parse()?;
+    let batch = cfg.batch.as_ref().context("script mode needs a batch section in yaml")?;
+    let new_root: H256 = batch.new_root.parse()?;
     let submitter = Submitter::new(bridge);
 
     match cfg.da.mode {
         DaMode::Calldata => {
-            let batch_bytes = fs::read(&cfg.batch.data_file)
-                .with_context(|| format!("read batch file {}", cfg.batch.data_file))?;
+            let batch_bytes = fs::read(&batch.data_file)
+                .with_context(|| format!("read batch file {}", batch.data_file))?;
 
             let tx_hash = submitter
                 .submit_calldata(batch_bytes, new_root.into(), proof)
@@ -42,8 +51,7 @@ pub async fn run(config_path: PathBuf) -> Result<()> {
             info!("✅ calldata batch submitted. tx={:?}", tx_hash);
         }
         DaMode::Blob => {
-            let vh = cfg
-                .batch
+            let vh = batch
                 .blob_versioned_hash
                 .clone()
                 .context("blob mode needs batch.blob_versioned_hash in yaml")?;
@@ -67,6 +75,12 @@ pub async fn run(config_path: PathBuf) -> Result<()> {
                 cfg.da.blob_binding, tx_hash
             );
         }
+        DaMode::Celestia => {
+            anyhow::bail!("celestia mode is only supported by the orchestrator loop");
+        }
+        DaMode::EigenDa => {
+            anyhow::bail!("eigenda mode is only supported by the orchestrator loop");
+        }
     }
 
     Ok(())
@@ -1,62 +1,204 @@
 use crate::{
     application::{
-        orchestrator::Orchestrator,
-        ports::{BridgeReader, DaStrategy, ProofProvider, Storage},
+        orchestrator::{check_duplicate_content, enqueue_batch, enqueue_batches, next_batch_index, requeue_batch, CycleSummary, Orchestrator},
+        ports::{
+            Attestor, BridgeReader, ConfirmationSource, DaStrategy, NodeStatus, ProofProvider, ReadinessProbe,
+            Storage,
+        },
     },
-    config::{self, DaMode},
+    config::{self, ConfirmationSourceKind, DaMode, ProverMode, SubmissionEndpoint},
     contracts::ZKRollupBridge,
-    domain::batch::Batch,
+    domain::{
+        batch::{Batch, BatchId},
+        blackout::BlackoutWindow,
+    },
     infrastructure::{
-        da_blob::BlobStrategy, da_calldata::CalldataStrategy,
-        ethereum_adapter::RealBridgeClient, prover_http::HttpProofProvider,
-        prover_mock::MockProofProvider, storage_postgres::PostgresStorage,
+        attestation::AttestationSigner,
+        blob_archive::LocalBlobArchive,
+        bundler::Bundler,
+        confirmation::{IndexerConfirmationSource, NodeConfirmationSource},
+        da_blob::{BlobStrategy, BLOB_DATA_BYTES, MAX_BLOBS_PER_TX}, da_calldata::CalldataStrategy,
+        da_celestia::{CelestiaStrategy, Namespace},
+        da_eigenda::EigenDaStrategy,
+        ethereum_adapter::{http_transport, CachingBridgeReader, FeeOverrides, NonceManager, PrivateRelay, RateLimitRetry, RealBridgeClient}, prover_http::{HttpProofProvider, JobPolling},
+        prover_mock::MockProofProvider, prover_object_store::ObjectStoreProofProvider,
+        storage_postgres::PostgresStorage,
         storage_sqlite::SqliteStorage,
+        batch_api::{batch_router, limit_concurrency, serve_batch_api, BatchApi},
+        file_watcher::FileWatcher,
+        observability::{health_router, readiness_router, HEALTH_PROBE_TIMEOUT},
+        payload::read_payload,
     },
 };
 use anyhow::{Context, Result};
 use ethers::prelude::*;
 use sha1_smol::Sha1;
-use std::{fs, path::PathBuf, sync::Arc};
-use tracing::info;
+use std::{collections::HashMap, fs, path::PathBuf, sync::Arc};
+use tracing::{info, warn};
 
 pub type AppStorage = Arc<dyn Storage>;
 pub type AppOrchestrator = Orchestrator;
 
+/// Nonce counters shared by the rollups of one process, one per chain and signer, so rollups
+/// committing from the same account never reserve the same nonce.
+#[derive(Default)]
+struct NonceManagers(HashMap<(u64, Address), Arc<NonceManager>>);
+
+impl NonceManagers {
+    /// The counter for `client`'s account on `chain_id`, created and seeded on first use. The
+    /// reconcile interval of the first rollup to use an account applies to it.
+    async fn get<M: Middleware>(
+        &mut self,
+        rollup: &str,
+        chain_id: u64,
+        client: &M,
+        address: Address,
+        reconcile_interval: Option<std::time::Duration>,
+    ) -> Arc<NonceManager> {
+        if let Some(manager) = self.0.get(&(chain_id, address)) {
+            return manager.clone();
+        }
+        let manager = Arc::new(NonceManager::new(address).with_reconcile_interval(reconcile_interval));
+        if let Err(e) = manager.seed(rollup, client).await {
+            warn!("{}; retrying on first submission", e);
+        }
+        self.0.insert((chain_id, address), manager.clone());
+        manager
+    }
+}
+
 pub async fn build(config_path: PathBuf) -> Result<(AppStorage, AppOrchestrator)> {
     let cfg = config::load_config(config_path)?;
+    let storage = open_storage("default", &cfg, None).await?;
+    let orchestrator =
+        build_orchestrator(cfg, storage.clone(), "default", true, &mut NonceManagers::default()).await?;
+    Ok((storage, orchestrator))
+}
+
+/// Builds one orchestrator per rollup in the config (see [`config::load_rollups`]), in config
+/// order, each over its own database. Rollups on the same chain share one nonce counter.
+pub async fn build_all(config_path: PathBuf) -> Result<Vec<(String, AppStorage, AppOrchestrator)>> {
+    let mut built = Vec::new();
+    let mut nonce_managers = NonceManagers::default();
+    for rollup in config::load_rollups(config_path)? {
+        let storage = open_storage(&rollup.name, &rollup.config, rollup.database_url.as_deref()).await?;
+        let orchestrator =
+            build_orchestrator(rollup.config, storage.clone(), &rollup.name, true, &mut nonce_managers).await?;
+        built.push((rollup.name, storage, orchestrator));
+    }
+    Ok(built)
+}
 
+async fn build_orchestrator(
+    cfg: config::Config,
+    storage: AppStorage,
+    rollup: &str,
+    seed: bool,
+    nonce_managers: &mut NonceManagers,
+) -> Result<AppOrchestrator> {
     let pk = std::env::var("SUBMITTER_PRIVATE_KEY")
         .context("Missing env SUBMITTER_PRIVATE_KEY (DO NOT put private keys in yaml)")?;
     let wallet: LocalWallet = pk
         .parse::<LocalWallet>()?
         .with_chain_id(cfg.network.chain_id);
-    let provider = Provider::<Http>::try_from(cfg.network.rpc_url.as_str())?;
+    let provider = Provider::new(
+        RateLimitRetry::new(
+            http_transport(
+                &cfg.network.rpc_url,
+                std::time::Duration::from_secs(cfg.network.rpc_timeout_secs.unwrap_or(30)),
+            )?,
+            cfg.network.rate_limit_retries.unwrap_or(5),
+            std::time::Duration::from_millis(cfg.network.rate_limit_backoff_ms.unwrap_or(1000)),
+        )
+        .with_rollup(rollup),
+    );
+    let archiver_signer = cfg.da.sign_archiver_uploads.then(|| wallet.clone());
+    // Attestations are signed by the submitter account under the configured domain
+    let attestor = match &cfg.attestation {
+        Some(attestation) => {
+            let signer = AttestationSigner::new(wallet.clone(), attestation.domain()?);
+            Some(Arc::new(signer) as Arc<dyn Attestor>)
+        }
+        None => None,
+    };
     let client = Arc::new(SignerMiddleware::new(provider, wallet));
     let bridge_addr: Address = cfg.contracts.bridge.parse()?;
     let bridge = ZKRollupBridge::new(bridge_addr, client.clone());
 
-    let bridge_reader: Arc<dyn BridgeReader> = Arc::new(RealBridgeClient::new(bridge.clone()));
+    let mut bridge_reader: Arc<dyn BridgeReader> = Arc::new(RealBridgeClient::new(bridge.clone()).with_rollup(rollup));
+    let state_root_cache = cfg.network.state_root_cache_ms.unwrap_or(1000);
+    if state_root_cache > 0 {
+        bridge_reader = Arc::new(CachingBridgeReader::new(
+            bridge_reader,
+            std::time::Duration::from_millis(state_root_cache),
+        ));
+    }
 
-    let storage: Arc<dyn Storage> = if let Ok(pg_url) = std::env::var("DATABASE_URL") {
-        if pg_url.starts_with("postgres") {
-            let batch_size = cfg.sequencer.as_ref().and_then(|s| s.batch_size);
-            let ordering_policy = cfg.sequencer.as_ref().and_then(|s| s.ordering_policy.clone());
-            Arc::new(PostgresStorage::new(&pg_url, batch_size, ordering_policy).await?)
-        } else {
-            Arc::new(SqliteStorage::new(&pg_url).await?)
+    let node_status: Option<Arc<dyn NodeStatus>> = if cfg.network.require_synced {
+        let node = Arc::new(RealBridgeClient::new(bridge.clone()).with_rollup(rollup));
+        match node.is_syncing().await {
+            Ok(true) => warn!("Node at {} is still syncing; batches will wait until it is synced", cfg.network.rpc_url),
+            Ok(false) => info!("Node at {} is synced", cfg.network.rpc_url),
+            Err(e) => warn!("Could not query node sync status: {}", e),
         }
+        Some(node)
     } else {
-        Arc::new(SqliteStorage::new("sqlite:submitter.db").await?)
+        None
     };
 
-    let prover: Arc<dyn ProofProvider> = if let Some(prover_cfg) = &cfg.prover {
-        info!("Using HTTP Prover at {}", prover_cfg.url);
+    let health_node: Arc<dyn NodeStatus> = Arc::new(RealBridgeClient::new(bridge.clone()).with_rollup(rollup));
+
+    let strict_public_inputs = cfg.public_inputs.as_ref().is_some_and(|p| p.strict);
+    let mut readiness_probes: Vec<Arc<dyn ReadinessProbe>> = Vec::new();
+    let prover: Arc<dyn ProofProvider> = if let Some(prover_cfg) = cfg
+        .prover
+        .as_ref()
+        .filter(|p| p.mode == ProverMode::ObjectStore)
+    {
+        let bucket = prover_cfg
+            .bucket
+            .clone()
+            .context("prover.mode object_store needs prover.bucket")?;
+        let url = prover_cfg.endpoints().into_iter().next().context("prover needs url or urls")?;
+        info!("Using object store proofs at {}/{}", url, bucket);
+        Arc::new(ObjectStoreProofProvider::new(url, bucket).with_rollup(rollup))
+    } else if let Some(prover_cfg) = &cfg.prover {
+        let mut urls = prover_cfg.endpoints();
+        anyhow::ensure!(!urls.is_empty(), "prover needs url or urls");
+        let url = urls.remove(0);
+        info!("Using HTTP Prover at {} (fallbacks: {:?})", url, urls);
         let threshold = cfg
             .resilience
             .as_ref()
             .and_then(|r| r.circuit_breaker_threshold)
             .unwrap_or(5);
-        Arc::new(HttpProofProvider::new(prover_cfg.url.clone(), threshold))
+        let response_signer = prover_cfg
+            .response_signer
+            .as_deref()
+            .map(str::parse::<Address>)
+            .transpose()
+            .context("Invalid prover.response_signer address")?;
+        let http = Arc::new(
+            HttpProofProvider::new(url, threshold)
+                .with_fallback_urls(urls)
+                .with_response_signer(response_signer)
+                .with_batch_endpoint(prover_cfg.prove_batch_window_ms.is_some())
+                .with_input_schema(prover_cfg.input_schema)
+                .with_gzip_above(prover_cfg.gzip_request_above_bytes)
+                .with_request_timeout(std::time::Duration::from_secs(prover_cfg.timeout_secs.unwrap_or(60)))
+                .with_open_duration(std::time::Duration::from_secs(prover_cfg.circuit_open_secs.unwrap_or(30)))
+                .with_job_polling(prover_cfg.async_jobs.then(|| JobPolling {
+                    interval: std::time::Duration::from_millis(prover_cfg.job_poll_interval_ms.unwrap_or(2000)),
+                    timeout: std::time::Duration::from_secs(prover_cfg.job_timeout_secs.unwrap_or(600)),
+                }))
+                .with_state_store(prover_cfg.persist_circuit_state.then(|| storage.clone()))
+                .with_rollup(rollup),
+        );
+        if prover_cfg.warm_up {
+            http.warm_up().await;
+        }
+        readiness_probes.push(http.clone());
+        http
     } else {
         info!("Using Mock Prover");
         let delay = cfg
@@ -64,47 +206,149 @@ pub async fn build(config_path: PathBuf) -> Result<(AppStorage, AppOrchestrator)
             .as_ref()
             .and_then(|s| s.mock_proving_time_ms)
             .unwrap_or(0);
-        Arc::new(MockProofProvider::new(delay))
+        Arc::new(MockProofProvider::new(delay).with_strict_inputs(strict_public_inputs))
     };
 
+    let confirmations = cfg.da.confirmations.unwrap_or(1);
+    let max_blobs_per_tx = cfg.da.max_blobs_per_tx.unwrap_or(MAX_BLOBS_PER_TX);
+    // Every mode commits to the bridge from this account, Celestia and EigenDA after publishing
+    let nonce_manager = nonce_managers
+        .get(
+            rollup,
+            cfg.network.chain_id,
+            client.as_ref(),
+            client.address(),
+            cfg.da
+                .nonce_reconcile_interval_secs
+                .map(std::time::Duration::from_secs),
+        )
+        .await;
+    let nonce_manager = Some(nonce_manager);
+    let private_relay = match cfg.da.submission_endpoint {
+        SubmissionEndpoint::Public | SubmissionEndpoint::Sponsored => None,
+        SubmissionEndpoint::Private => {
+            let url = cfg.da.private_relay_url.as_deref().context("da.private_relay_url is unset")?;
+            info!("Submitting commit transactions through private relay {}", url);
+            Some(Arc::new(PrivateRelay::new(url).with_rollup(rollup)))
+        }
+    };
+    let sponsor = match (&cfg.da.submission_endpoint, &cfg.da.sponsor) {
+        (SubmissionEndpoint::Sponsored, Some(sponsor)) => {
+            let entry_point: Address = sponsor.entry_point.parse()?;
+            let account = sponsor.account.as_deref().map(str::parse::<Address>).transpose()?;
+            let paymaster_and_data = match &sponsor.paymaster_and_data {
+                Some(data) => data.parse::<Bytes>()?,
+                None => Bytes::new(),
+            };
+            info!("Submitting commits as UserOperations through bundler {}", sponsor.bundler_url);
+            Some(Arc::new(
+                Bundler::new(sponsor.bundler_url.clone(), entry_point)
+                    .with_account(account)
+                    .with_paymaster_and_data(paymaster_and_data)
+                    .with_rollup(rollup),
+            ))
+        }
+        _ => None,
+    };
+    let fee_overrides = FeeOverrides {
+        max_priority_fee_gwei: cfg.network.max_priority_fee_gwei,
+        fee_multiplier: cfg.network.fee_multiplier,
+    };
+    // Sends the commit transactions of calldata batches, and of Celestia and EigenDA batches
+    // once their data is published
+    let calldata_strategy = |bridge, compression| {
+        CalldataStrategy::new(bridge, compression)
+            .with_known_tx_as_sent(cfg.da.treat_known_tx_as_sent)
+            .with_confirmations(confirmations)
+            .with_nonce_manager(nonce_manager.clone())
+            .with_max_base_fee_gwei(cfg.da.max_base_fee_gwei)
+            .with_fee_overrides(fee_overrides)
+            .with_dry_run(cfg.da.dry_run)
+            .with_private_relay(private_relay.clone())
+            .with_sponsor(sponsor.clone())
+            .with_rollup(rollup)
+    };
     let da_strategy: Arc<dyn DaStrategy> = match cfg.da.mode {
         DaMode::Calldata => {
             let compression = cfg.aggregator.as_ref().and_then(|a| a.compression);
-            Arc::new(CalldataStrategy::new(bridge, compression))
+            Arc::new(
+                calldata_strategy(bridge, compression)
+                    .with_commitment_scheme(cfg.da.commitment_scheme()?)
+                    .with_codec(cfg.da.compression),
+            )
         },
+        DaMode::Celestia => {
+            let celestia = cfg.da.celestia.as_ref().context("celestia mode needs da.celestia")?;
+            let namespace: Namespace = celestia.namespace.parse()?;
+            info!("Using Celestia DA at {}", celestia.node_url);
+            Arc::new(
+                CelestiaStrategy::new(
+                    celestia.node_url.clone(),
+                    namespace,
+                    std::env::var("CELESTIA_NODE_AUTH_TOKEN").ok(),
+                    Arc::new(calldata_strategy(bridge, None)),
+                )
+                .with_dry_run(cfg.da.dry_run)
+                .with_rollup(rollup),
+            )
+        }
+        DaMode::EigenDa => {
+            let eigenda = cfg.da.eigenda.as_ref().context("eigenda mode needs da.eigenda")?;
+            info!("Using EigenDA disperser at {}", eigenda.disperser_url);
+            Arc::new(
+                EigenDaStrategy::new(eigenda.disperser_url.clone(), Arc::new(calldata_strategy(bridge, None)))
+                    .with_dry_run(cfg.da.dry_run)
+                    .with_rollup(rollup),
+            )
+        }
         DaMode::Blob => {
             let vh = cfg
                 .batch
-                .blob_versioned_hash
-                .clone()
+                .as_ref()
+                .and_then(|b| b.blob_versioned_hash.clone())
                 .context("blob mode needs batch.blob_versioned_hash")?;
             let expected: H256 = vh.parse()?;
             let blob_index = cfg.da.blob_index.unwrap_or(0);
             let use_opcode = cfg.da.blob_binding == config::BlobBinding::Opcode;
 
-            Arc::new(BlobStrategy::new(
-                bridge, expected, blob_index, use_opcode, cfg.da.archiver_url.clone(),
-            ))
+            let local_archive = match (&cfg.da.local_blob_dir, &cfg.da.archiver_url) {
+                (Some(dir), Some(url)) => {
+                    let archive = Arc::new(
+                        LocalBlobArchive::new(PathBuf::from(dir), url.clone(), archiver_signer.clone())
+                            .with_rollup(rollup),
+                    );
+                    let interval = cfg.da.blob_reupload_interval_secs.unwrap_or(60);
+                    archive.clone().spawn_reupload_loop(std::time::Duration::from_secs(interval));
+                    Some(archive)
+                }
+                _ => None,
+            };
+
+            Arc::new(
+                BlobStrategy::new(
+                    bridge, expected, blob_index, use_opcode, cfg.da.archiver_url.clone(),
+                )
+                .with_gas_limit_multiplier(cfg.da.blob_gas_limit_multiplier)
+                .with_max_blobs_per_tx(max_blobs_per_tx)
+                .with_known_tx_as_sent(cfg.da.treat_known_tx_as_sent)
+                .with_retrieval_quorum(
+                    cfg.da.retrieval_archivers.clone().unwrap_or_default(),
+                    cfg.da.retrieval_quorum.unwrap_or(0),
+                )
+                .with_archiver_signer(archiver_signer)
+                .with_local_archive(local_archive)
+                .with_confirmations(confirmations)
+                .with_nonce_manager(nonce_manager)
+                .with_max_base_fee_gwei(cfg.da.max_base_fee_gwei)
+                .with_fee_overrides(fee_overrides)
+                .with_dry_run(cfg.da.dry_run)
+                .with_rollup(rollup),
+            )
         }
     };
 
-    let pending = storage.get_pending_batches().await?;
-    if pending.is_empty() {
-        info!("Seeding initial batch from config");
-
-        let data_bytes = fs::read(&cfg.batch.data_file)
-            .context(format!("Failed to read data file {}", cfg.batch.data_file))?;
-        let data_hash = Sha1::from(data_bytes).digest().to_string();
-
-        let batch = Batch::new(
-            cfg.network.chain_id,
-            &cfg.contracts.bridge,
-            cfg.batch.data_file.clone(),
-            data_hash,
-            cfg.batch.new_root.clone(),
-            format!("{:?}", cfg.da.mode),
-        );
-        storage.save_batch(&batch).await?;
+    if seed {
+        seed_from_config(rollup, &cfg, storage.as_ref(), max_blobs_per_tx).await?;
     }
 
     let max_attempts = cfg
@@ -113,29 +357,442 @@ pub async fn build(config_path: PathBuf) -> Result<(AppStorage, AppOrchestrator)
         .and_then(|r| r.max_retries)
         .unwrap_or(5);
 
+    let orchestrator_cfg = cfg.orchestrator.unwrap_or_default();
+    let submit_blackout = orchestrator_cfg
+        .submit_blackout
+        .iter()
+        .flatten()
+        .map(|w| w.parse::<BlackoutWindow>())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let confirmation_cfg = cfg.confirmation.unwrap_or_default();
+    let confirmation_source: Arc<dyn ConfirmationSource> = match confirmation_cfg.source {
+        ConfirmationSourceKind::Node => Arc::new(NodeConfirmationSource::new(da_strategy.clone())),
+        ConfirmationSourceKind::Indexer => {
+            let url = confirmation_cfg
+                .indexer_url
+                .context("confirmation.source indexer needs confirmation.indexer_url")?;
+            info!("Confirming batches via indexer at {}", url);
+            Arc::new(IndexerConfirmationSource::new(url).with_rollup(rollup))
+        }
+    };
+
+    let prover_budget = cfg
+        .prover
+        .as_ref()
+        .and_then(|p| p.deadline_secs)
+        .map(std::time::Duration::from_secs);
+    let root_packing = cfg.public_inputs.unwrap_or_default().root_packing()?;
+    let fee_escalation = match &cfg.fees {
+        Some(fees) => fees.fee_escalation()?,
+        None => Default::default(),
+    };
+    let max_fee_per_gas = cfg
+        .fees
+        .as_ref()
+        .and_then(|f| f.max_fee_per_gas_gwei)
+        .map(|gwei| U256::from(gwei) * U256::exp10(9));
+
     let orchestrator = Orchestrator::new(
         storage.clone(),
         prover,
         da_strategy,
         bridge_reader,
         max_attempts,
+    )
+    .with_max_concurrent_confirmations(orchestrator_cfg.max_concurrent_confirmations)
+    .with_aggregated_proofs(orchestrator_cfg.aggregated_proofs)
+    .with_submit_blackout(submit_blackout)
+    .with_confirmation_source(Some(confirmation_source))
+    .with_attestor(attestor)
+    .with_sync_check(node_status)
+    .with_health_node(Some(health_node))
+    .with_root_packing(root_packing)
+    .with_fee_escalation(fee_escalation, max_fee_per_gas)
+    .with_prover_deadline(prover_budget)
+    .with_strict_public_inputs(strict_public_inputs)
+    .with_transition_events(orchestrator_cfg.transition_events)
+    .with_onchain_commitment_check(orchestrator_cfg.verify_onchain_commitment)
+    .with_proof_precheck(orchestrator_cfg.precheck_proofs)
+    .with_batch_index_check(orchestrator_cfg.check_batch_index)
+    .with_root_chain_check(orchestrator_cfg.check_root_chain)
+    .with_ordered_submission(orchestrator_cfg.ordered_submission)
+    .with_data_file_preflight(orchestrator_cfg.fail_missing_data_files)
+    .with_data_file_cleanup(cfg.batch.as_ref().is_some_and(|b| b.cleanup_data_after_confirm))
+    .with_data_file_check(
+        cfg.batch.as_ref().map(|b| b.data_file_check).unwrap_or_default(),
+        std::time::Duration::from_millis(cfg.batch.as_ref().and_then(|b| b.data_file_settle_ms).unwrap_or(500)),
+    )
+    .with_initial_state_root_retry(
+        orchestrator_cfg.initial_state_root_retries.unwrap_or(3),
+        std::time::Duration::from_millis(orchestrator_cfg.initial_state_root_retry_delay_ms.unwrap_or(1000)),
+    )
+    .with_max_proving_age(
+        orchestrator_cfg
+            .max_proving_age_secs
+            .map(std::time::Duration::from_secs),
+    )
+    .with_unavailable_data_retry(orchestrator_cfg.retry_unavailable_data)
+    .with_max_submitted_age(orchestrator_cfg.max_submitted_age_secs.map(std::time::Duration::from_secs))
+    .with_stuck_threshold(orchestrator_cfg.stuck_threshold_secs.map(std::time::Duration::from_secs))
+    .with_retry_backoff(
+        Some(std::time::Duration::from_millis(orchestrator_cfg.retry_backoff_base_ms.unwrap_or(1000)))
+            .filter(|base| !base.is_zero()),
+    )
+    // Before the prove batch window, whose batcher takes the name
+    .with_rollup(rollup)
+    .with_prove_batch_window(
+        cfg.prover
+            .as_ref()
+            .and_then(|p| p.prove_batch_window_ms)
+            .map(std::time::Duration::from_millis),
     );
-    Ok((storage, orchestrator))
+    let orchestrator = readiness_probes
+        .into_iter()
+        .fold(orchestrator, |orch, probe| orch.with_readiness_probe(probe));
+    Ok(orchestrator)
+}
+
+/// Stores the batch described by `cfg.batch` (split into chunks with `batch.auto_split`) unless
+/// batches are already pending. Without a `batch` section nothing is seeded.
+async fn seed_from_config(
+    rollup: &str,
+    cfg: &config::Config,
+    storage: &dyn Storage,
+    max_blobs_per_tx: usize,
+) -> Result<()> {
+    let Some(batch_cfg) = &cfg.batch else {
+        info!("No batch section in config; waiting for batches from the API");
+        return Ok(());
+    };
+    let max_pending = cfg.orchestrator.as_ref().and_then(|o| o.max_pending_batches);
+    let pending = storage.get_pending_batches().await?;
+    if pending.is_empty() {
+        info!("Seeding initial batch from config");
+
+        let context = format!("Failed to read data file {}", batch_cfg.data_file);
+        let data_bytes = read_payload(rollup, &batch_cfg.data_file, &context).await?;
+
+        let limit = batch_cfg
+            .max_chunk_bytes
+            .unwrap_or_else(|| default_payload_limit(cfg.da.mode, max_blobs_per_tx));
+        let index = match batch_cfg.index {
+            Some(index) => index,
+            None => next_batch_index(storage).await?,
+        };
+
+        if batch_cfg.auto_split && data_bytes.len() > limit {
+            let batches = split_batch(cfg, batch_cfg, &data_bytes, limit, index)?;
+            info!("Payload of {} bytes split into {} batches", data_bytes.len(), batches.len());
+            // Each chunk expects the previous one's root, so a chunk left out strands the rest
+            for batch in &batches {
+                if !check_duplicate_content(rollup, storage, batch, batch_cfg.content_dedup).await? {
+                    warn!("Not seeding the split payload: chunk batch {} is a duplicate", batch.id);
+                    return Ok(());
+                }
+            }
+            enqueue_batches(rollup, storage, &batches, max_pending).await?;
+        } else {
+            let data_hash = Sha1::from(data_bytes).digest().to_string();
+
+            let mut batch = Batch::new(
+                cfg.network.chain_id,
+                &cfg.contracts.bridge,
+                batch_cfg.data_file.clone(),
+                data_hash,
+                batch_cfg.new_root.clone(),
+                format!("{:?}", cfg.da.mode),
+            );
+            batch.expected_old_root = batch_cfg.expected_old_root.clone();
+            batch.index = index;
+            batch.proof_system = cfg.default_proof_system();
+            if check_duplicate_content(rollup, storage, &batch, batch_cfg.content_dedup).await? {
+                enqueue_batch(rollup, storage, &batch, max_pending).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Largest payload a single batch can carry in the given DA mode.
+fn default_payload_limit(mode: DaMode, max_blobs_per_tx: usize) -> usize {
+    match mode {
+        // Geth rejects transactions above 128 KiB; leave headroom for the proof and ABI encoding
+        DaMode::Calldata => 120 * 1024,
+        DaMode::Blob => BLOB_DATA_BYTES * max_blobs_per_tx,
+        // Celestia blocks top out around 2 MiB; leave headroom for share overhead
+        DaMode::Celestia => 1_900_000,
+        // 16 MiB disperser limit, less the zero byte in front of every 31 payload bytes
+        DaMode::EigenDa => 16 * 1024 * 1024 / 32 * 31,
+    }
+}
+
+/// Splits `data` into chunk files next to `batch.data_file` and returns one batch per chunk,
+/// in submission order. Each chunk advances the root to the next entry of
+/// `batch.intermediate_roots`, and the last chunk reaches `batch.new_root`. Each chunk expects
+/// the previous chunk's root as its predecessor. Chunks take consecutive indices from
+/// `first_index`.
+fn split_batch(
+    cfg: &config::Config,
+    batch_cfg: &config::BatchConfig,
+    data: &[u8],
+    limit: usize,
+    first_index: u64,
+) -> Result<Vec<Batch>> {
+    let chunks: Vec<&[u8]> = data.chunks(limit).collect();
+
+    let intermediate = batch_cfg.intermediate_roots.clone().unwrap_or_default();
+    if intermediate.len() != chunks.len() - 1 {
+        anyhow::bail!(
+            "batch.auto_split needs {} intermediate_roots for {} chunks, got {}",
+            chunks.len() - 1,
+            chunks.len(),
+            intermediate.len()
+        );
+    }
+    let roots = intermediate
+        .into_iter()
+        .chain(std::iter::once(batch_cfg.new_root.clone()));
+
+    let mut batches = Vec::with_capacity(chunks.len());
+    let mut previous_root = batch_cfg.expected_old_root.clone();
+    for (i, (chunk, root)) in chunks.iter().zip(roots).enumerate() {
+        let chunk_file = format!("{}.part{}", batch_cfg.data_file, i);
+        fs::write(&chunk_file, chunk)
+            .context(format!("Failed to write chunk file {}", chunk_file))?;
+
+        let data_hash = Sha1::from(chunk).digest().to_string();
+        let mut batch = Batch::new(
+            cfg.network.chain_id,
+            &cfg.contracts.bridge,
+            chunk_file,
+            data_hash,
+            root.clone(),
+            format!("{:?}", cfg.da.mode),
+        );
+        batch.expected_old_root = previous_root.replace(root);
+        batch.index = first_index + i as u64;
+        batch.proof_system = cfg.default_proof_system();
+        batches.push(batch);
+    }
+    Ok(batches)
 }
 
 use std::future::Future;
 
+/// Opens `database_url`, else the database named by `DATABASE_URL` (Postgres or SQLite), else
+/// `submitter.db`.
+async fn open_storage(rollup: &str, cfg: &config::Config, database_url: Option<&str>) -> Result<Arc<dyn Storage>> {
+    open_storage_with_pool_size(rollup, cfg, database_url, 5).await
+}
+
+async fn open_storage_with_pool_size(
+    rollup: &str,
+    cfg: &config::Config,
+    database_url: Option<&str>,
+    max_connections: u32,
+) -> Result<Arc<dyn Storage>> {
+    let database_url = database_url.map(str::to_string).or_else(|| std::env::var("DATABASE_URL").ok());
+    let storage: Arc<dyn Storage> = if let Some(pg_url) = database_url {
+        if pg_url.starts_with("postgres") {
+            let batch_size = cfg.sequencer.as_ref().and_then(|s| s.batch_size);
+            let ordering_policy = cfg.sequencer.as_ref().and_then(|s| s.ordering_policy.clone());
+            Arc::new(
+                PostgresStorage::with_pool_size(&pg_url, batch_size, ordering_policy, max_connections)
+                    .await?
+                    .with_rollup(rollup),
+            )
+        } else {
+            Arc::new(SqliteStorage::with_pool_size(&pg_url, max_connections).await?.with_rollup(rollup))
+        }
+    } else {
+        Arc::new(SqliteStorage::with_pool_size("sqlite:submitter.db", max_connections).await?.with_rollup(rollup))
+    };
+    Ok(storage)
+}
+
+/// Storage for the batch API: a separate pool of `read_pool_size` connections so API traffic
+/// cannot take the orchestrator's, or `storage` itself when unset. In-memory SQLite databases
+/// exist once per pool and always share.
+async fn open_api_storage(
+    rollup: &str,
+    cfg: &config::Config,
+    database_url: Option<&str>,
+    storage: &Arc<dyn Storage>,
+) -> Result<Arc<dyn Storage>> {
+    let Some(pool_size) = cfg.api.as_ref().and_then(|api| api.read_pool_size) else {
+        return Ok(storage.clone());
+    };
+    let url = database_url.map(str::to_string).or_else(|| std::env::var("DATABASE_URL").ok());
+    if url.as_deref().is_some_and(|url| url.contains(":memory:") || url.contains("mode=memory")) {
+        warn!("api.read_pool_size is ignored for in-memory SQLite databases");
+        return Ok(storage.clone());
+    }
+    info!("Batch API reads use a separate pool of {} connections", pool_size);
+    open_storage_with_pool_size(rollup, cfg, database_url, pool_size).await
+}
+
+/// Requeues a `Failed` batch so the running orchestrator picks it up again. Only the database
+/// is touched; no node or prover connection is needed.
+pub async fn retry(config_path: PathBuf, batch_id: BatchId) -> Result<()> {
+    let mut storages = Vec::new();
+    for rollup in config::load_rollups(config_path)? {
+        let storage = open_storage(&rollup.name, &rollup.config, rollup.database_url.as_deref()).await?;
+        storages.push((rollup.name, storage));
+    }
+    // With several rollups, requeue in whichever database holds the batch
+    let mut target = storages[0].clone();
+    for (name, candidate) in &storages {
+        if candidate.get_batch(batch_id).await?.is_some() {
+            target = (name.clone(), candidate.clone());
+            break;
+        }
+    }
+    let (rollup, storage) = target;
+    requeue_batch(&rollup, storage.as_ref(), batch_id).await?;
+    Ok(())
+}
+
+/// Command-line overrides applied on top of the config file.
+#[derive(Debug, Default, Clone)]
+pub struct RunOptions {
+    // Forces `da.dry_run` on for every rollup
+    pub dry_run: bool,
+}
+
+impl RunOptions {
+    fn apply(&self, rollup: &str, cfg: &mut config::Config) {
+        cfg.da.dry_run |= self.dry_run;
+        if cfg.da.dry_run {
+            warn!("Rollup {} is in dry-run mode; no transactions will be broadcast", rollup);
+        }
+    }
+}
+
+/// Advances only the batch `batch_id` by one pipeline step (see [`Orchestrator::process_one`])
+/// and returns it. Nothing is seeded and no other batch is touched.
+pub async fn step_batch(config_path: PathBuf, batch_id: BatchId, options: RunOptions) -> Result<Batch> {
+    for rollup in config::load_rollups(config_path)? {
+        let mut cfg = rollup.config;
+        let storage = open_storage(&rollup.name, &cfg, rollup.database_url.as_deref()).await?;
+        if storage.get_batch(batch_id).await?.is_none() {
+            continue;
+        }
+        options.apply(&rollup.name, &mut cfg);
+        let orchestrator =
+            build_orchestrator(cfg, storage, &rollup.name, false, &mut NonceManagers::default()).await?;
+        return Ok(orchestrator.process_one(batch_id).await?);
+    }
+    anyhow::bail!("Batch {} not found", batch_id)
+}
+
+/// Runs one processing pass for every rollup in the config (see [`Orchestrator::run_once`])
+/// and returns their combined summary. The batch API and inbox are not started.
+pub async fn run_once(config_path: PathBuf, options: RunOptions) -> Result<CycleSummary> {
+    let mut summary = CycleSummary::default();
+    let mut nonce_managers = NonceManagers::default();
+    for rollup in config::load_rollups(config_path)? {
+        let mut cfg = rollup.config;
+        options.apply(&rollup.name, &mut cfg);
+        let storage = open_storage(&rollup.name, &cfg, rollup.database_url.as_deref()).await?;
+        let orchestrator = build_orchestrator(cfg, storage, &rollup.name, true, &mut nonce_managers).await?;
+        summary = summary.merge(orchestrator.run_once().await?);
+    }
+    Ok(summary)
+}
+
 pub async fn run(
     config_path: PathBuf,
     shutdown: impl Future<Output = ()> + Send + 'static,
 ) -> Result<()> {
-    let (_, orchestrator) = build(config_path).await?;
+    run_with_options(config_path, RunOptions::default(), shutdown).await
+}
+
+pub async fn run_with_options(
+    config_path: PathBuf,
+    options: RunOptions,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<()> {
+    let mut orchestrators = Vec::new();
+    let mut nonce_managers = NonceManagers::default();
+    for rollup in config::load_rollups(config_path)? {
+        let name = rollup.name;
+        let mut cfg = rollup.config;
+        options.apply(&name, &mut cfg);
+        let storage = open_storage(&name, &cfg, rollup.database_url.as_deref()).await?;
+        let api_storage = open_api_storage(&name, &cfg, rollup.database_url.as_deref(), &storage).await?;
+        let api = cfg.api.as_ref().map(|api_cfg| {
+            (
+                api_cfg.port.unwrap_or(9001),
+                api_cfg.fail_readiness_when_degraded,
+                api_cfg.max_concurrent_requests,
+                BatchApi {
+                    storage: api_storage,
+                    chain_id: cfg.network.chain_id,
+                    bridge: cfg.contracts.bridge.clone(),
+                    max_pending: cfg.orchestrator.as_ref().and_then(|o| o.max_pending_batches),
+                    proof_system: cfg.default_proof_system(),
+                    rollup: name.clone(),
+                },
+            )
+        });
+        let watcher = cfg.inbox.as_ref().map(|inbox| FileWatcher {
+            storage: storage.clone(),
+            inbox: PathBuf::from(&inbox.dir),
+            chain_id: cfg.network.chain_id,
+            bridge: cfg.contracts.bridge.clone(),
+            max_pending: cfg.orchestrator.as_ref().and_then(|o| o.max_pending_batches),
+            proof_system: cfg.default_proof_system(),
+            poll_interval: std::time::Duration::from_millis(inbox.poll_interval_ms.unwrap_or(1000)),
+            rollup: name.clone(),
+        });
+        let orchestrator =
+            Arc::new(build_orchestrator(cfg, storage.clone(), &name, true, &mut nonce_managers).await?);
+        if let Some(watcher) = watcher {
+            tokio::spawn(watcher.run());
+        }
 
-    tokio::select! {
-        _ = orchestrator.run() => {},
-        _ = shutdown => { info!("Shutdown signal received"); },
+        if let Some((port, fail_readiness_when_degraded, max_concurrent_requests, batch_api)) = api {
+            let degraded_status = if fail_readiness_when_degraded {
+                axum::http::StatusCode::SERVICE_UNAVAILABLE
+            } else {
+                axum::http::StatusCode::OK
+            };
+            let mut router = batch_router(batch_api)
+                .merge(readiness_router(orchestrator.clone(), degraded_status));
+            if let Some(max_in_flight) = max_concurrent_requests {
+                router = limit_concurrency(&name, router, max_in_flight);
+            }
+            // Liveness probes are not shed with the API traffic
+            if let Some(node) = orchestrator.health_node() {
+                router = router.merge(health_router(&name, storage.clone(), node, HEALTH_PROBE_TIMEOUT));
+            }
+            let addr = std::net::SocketAddr::from(([0, 0, 0, 0], port));
+            let listener = tokio::net::TcpListener::bind(addr)
+                .await
+                .context(format!("failed to bind batch API port {}", addr.port()))?;
+            tokio::spawn(serve_batch_api(listener, router));
+        }
+        orchestrators.push(orchestrator);
     }
 
+    // Let the current cycle finish so an in-flight submit still records its tx hash
+    let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    tokio::spawn({
+        let stop = stop.clone();
+        async move {
+            shutdown.await;
+            info!("Shutdown signal received; finishing the current cycle");
+            stop.store(true, std::sync::atomic::Ordering::SeqCst);
+        }
+    });
+    futures::future::try_join_all(
+        orchestrators
+            .iter()
+            .map(|orchestrator| orchestrator.run_until(stop.clone())),
+    )
+    .await?;
+
     Ok(())
 }
 
@@ -223,4 +880,148 @@ da:
 
         let _ = std::fs::remove_file("data_blob.txt");
     }
+
+    #[tokio::test]
+    async fn test_build_without_batch_seeds_nothing() {
+        let mut config_file = NamedTempFile::new().unwrap();
+        write!(
+            config_file,
+            "
+network:
+  rpc_url: http://localhost:8545
+  chain_id: 1337
+contracts:
+  bridge: '0x0000000000000000000000000000000000000000'
+da:
+  mode: calldata
+  blob_binding: opcode
+        "
+        )
+        .unwrap();
+
+        std::env::set_var(
+            "SUBMITTER_PRIVATE_KEY",
+            "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20",
+        );
+        std::env::set_var("DATABASE_URL", "sqlite::memory:");
+
+        let (storage, _orchestrator) = build(config_file.path().to_path_buf()).await.unwrap();
+        assert!(storage.get_pending_batches().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_nonce_managers_shared_per_chain_and_signer() {
+        use crate::test_utils::MockClient;
+
+        let provider = Provider::new(MockClient::new());
+        let mut managers = NonceManagers::default();
+        let alpha = managers.get("default", 1, &provider, Address::repeat_byte(1), None).await;
+        let beta = managers.get("default", 1, &provider, Address::repeat_byte(1), None).await;
+        let other_chain = managers.get("default", 2, &provider, Address::repeat_byte(1), None).await;
+        let other_signer = managers.get("default", 1, &provider, Address::repeat_byte(2), None).await;
+
+        assert!(Arc::ptr_eq(&alpha, &beta));
+        assert!(!Arc::ptr_eq(&alpha, &other_chain));
+        assert!(!Arc::ptr_eq(&alpha, &other_signer));
+    }
+
+    #[test]
+    fn test_split_batch_chains_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_file = dir.path().join("big.bin");
+        let root_a = format!("{:#x}", H256::repeat_byte(0xaa));
+        let root_b = format!("{:#x}", H256::repeat_byte(0xbb));
+        let yaml = format!(
+            "
+network:
+  rpc_url: http://localhost:8545
+  chain_id: 1337
+contracts:
+  bridge: '0x0000000000000000000000000000000000000000'
+batch:
+  data_file: '{}'
+  new_root: '{}'
+  auto_split: true
+  max_chunk_bytes: 4
+  intermediate_roots: ['{}']
+da:
+  mode: calldata
+  blob_binding: opcode
+",
+            data_file.display(),
+            root_b,
+            root_a
+        );
+        let cfg: config::Config = serde_yaml::from_str(&yaml).unwrap();
+
+        let batch_cfg = cfg.batch.as_ref().unwrap();
+        let batches = split_batch(&cfg, batch_cfg, b"abcdefg", 4, 0).unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].new_root, root_a);
+        assert_eq!(batches[1].new_root, root_b);
+        assert_eq!(fs::read(&batches[0].data_file).unwrap(), b"abcd");
+        assert_eq!(fs::read(&batches[1].data_file).unwrap(), b"efg");
+        assert_ne!(batches[0].id, batches[1].id);
+        assert_eq!(batches[0].expected_old_root, None);
+        assert_eq!(batches[1].expected_old_root, Some(root_a.clone()));
+
+        // Missing intermediate roots cannot be chained
+        assert!(split_batch(&cfg, batch_cfg, b"abcdefghij", 4, 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_split_seed_enqueues_all_chunks_or_none() {
+        use crate::application::orchestrator::ContentDedup;
+        use crate::domain::batch::BatchStatus;
+        use crate::infrastructure::storage_sqlite::SqliteStorage;
+
+        let dir = tempfile::tempdir().unwrap();
+        let data_file = dir.path().join("big.bin");
+        fs::write(&data_file, b"abcdefghijkl").unwrap();
+        let yaml = format!(
+            "
+network:
+  rpc_url: http://localhost:8545
+  chain_id: 1337
+contracts:
+  bridge: '0x0000000000000000000000000000000000000000'
+batch:
+  data_file: '{}'
+  new_root: '{:#x}'
+  auto_split: true
+  max_chunk_bytes: 4
+  intermediate_roots: ['{:#x}', '{:#x}']
+orchestrator:
+  max_pending_batches: 2
+da:
+  mode: calldata
+  blob_binding: opcode
+",
+            data_file.display(),
+            H256::repeat_byte(0xcc),
+            H256::repeat_byte(0xaa),
+            H256::repeat_byte(0xbb)
+        );
+        let mut cfg: config::Config = serde_yaml::from_str(&yaml).unwrap();
+
+        // Three chunks do not fit under a cap of two
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        seed_from_config("default", &cfg, &storage, 6).await.unwrap();
+        assert!(storage.get_pending_batches().await.unwrap().is_empty());
+
+        // Nor is the chain seeded around a middle chunk that already landed
+        cfg.orchestrator.as_mut().unwrap().max_pending_batches = None;
+        cfg.batch.as_mut().unwrap().content_dedup = ContentDedup::Skip;
+        let mut confirmed = split_batch(&cfg, cfg.batch.as_ref().unwrap(), b"abcdefghijkl", 4, 7).unwrap().remove(1);
+        // The same payload landed earlier under another batch
+        confirmed.id = crate::domain::batch::BatchId::new();
+        confirmed.status = BatchStatus::Confirmed;
+        storage.save_batch(&confirmed).await.unwrap();
+        seed_from_config("default", &cfg, &storage, 6).await.unwrap();
+        assert!(storage.get_pending_batches().await.unwrap().is_empty());
+
+        let storage = SqliteStorage::new("sqlite::memory:").await.unwrap();
+        seed_from_config("default", &cfg, &storage, 6).await.unwrap();
+        assert_eq!(storage.get_pending_batches().await.unwrap().len(), 3);
+    }
 }
@@ -106,7 +106,7 @@ mod tests {
                 return Err(ethers::providers::ProviderError::CustomError(format!("No responses for {}", method)));
             }
             let res = responses.remove(0);
-            serde_json::from_value(res).map_err(|e| ethers::providers::ProviderError::SerdeJson(e))
+            serde_json::from_value(res).map_err(ethers::providers::ProviderError::SerdeJson)
         }
     }
 
@@ -122,8 +122,10 @@ mod tests {
         let submitter = Submitter::new(bridge);
         
         mock.push(U256::from(0));
-        let mut block = Block::<H256>::default();
-        block.base_fee_per_gas = Some(U256::from(100));
+        let block = Block::<H256> {
+            base_fee_per_gas: Some(U256::from(100)),
+            ..Default::default()
+        };
         mock.push(block);
         
         let history = FeeHistory {
@@ -170,8 +172,10 @@ mod tests {
         let submitter = Submitter::new(bridge);
         
         mock.push(U256::from(0));
-        let mut block = Block::<H256>::default();
-        block.base_fee_per_gas = Some(U256::from(100));
+        let block = Block::<H256> {
+            base_fee_per_gas: Some(U256::from(100)),
+            ..Default::default()
+        };
         mock.push(block);
         
         let history = FeeHistory {
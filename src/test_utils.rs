@@ -1,20 +1,32 @@
+use crate::application::ports::{BridgeCommitter, DaStrategy, SentTx};
+use crate::domain::{batch::Batch, errors::DomainError};
 use ethers::providers::{JsonRpcClient, ProviderError};
+use ethers::types::H256;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::fmt::Debug;
 use std::sync::{Arc, Mutex};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct MockClient {
-    responses: Arc<Mutex<Vec<serde_json::Value>>>,
+    responses: Arc<Mutex<Vec<Result<serde_json::Value, String>>>>,
+    requests: Arc<Mutex<Vec<(String, serde_json::Value)>>>,
 }
 
 impl MockClient {
     pub fn new() -> Self {
-        Self { responses: Arc::new(Mutex::new(Vec::new())) }
+        Self::default()
     }
     pub fn push<T: Serialize>(&self, res: T) {
-        self.responses.lock().unwrap().push(serde_json::to_value(res).unwrap());
+        self.responses.lock().unwrap().push(Ok(serde_json::to_value(res).unwrap()));
+    }
+    /// Queues an error response, as a node would return for a rejected request.
+    pub fn push_error(&self, message: &str) {
+        self.responses.lock().unwrap().push(Err(message.to_string()));
+    }
+    /// Returns every `(method, params)` pair received so far, in call order.
+    pub fn requests(&self) -> Vec<(String, serde_json::Value)> {
+        self.requests.lock().unwrap().clone()
     }
 }
 
@@ -28,11 +40,67 @@ impl JsonRpcClient for MockClient {
         R: DeserializeOwned + Send,
     {
         println!("Request: {} {:?}", method, params);
+        self.requests.lock().unwrap().push((
+            method.to_string(),
+            serde_json::to_value(&params).unwrap_or_default(),
+        ));
         let mut responses = self.responses.lock().unwrap();
         if responses.is_empty() {
             return Err(ProviderError::CustomError(format!("No responses for {}", method)));
         }
-        let res = responses.remove(0);
-        serde_json::from_value(res).map_err(|e| ProviderError::SerdeJson(e))
+        match responses.remove(0) {
+            Ok(res) => serde_json::from_value(res).map_err(ProviderError::SerdeJson),
+            Err(message) => Err(ProviderError::CustomError(message)),
+        }
+    }
+}
+
+/// Records bridge commits instead of sending them; every commit transaction counts as confirmed.
+#[derive(Default)]
+pub struct MockCommitter {
+    commits: Mutex<Vec<(u8, Vec<u8>)>>,
+}
+
+impl MockCommitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Returns every `(da_id, da_meta)` committed so far, in call order.
+    pub fn commits(&self) -> Vec<(u8, Vec<u8>)> {
+        self.commits.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl DaStrategy for MockCommitter {
+    fn da_id(&self) -> u8 {
+        0
+    }
+    async fn compute_commitment(&self, _batch: &Batch) -> Result<H256, DomainError> {
+        Ok(H256::zero())
+    }
+    fn encode_da_meta(&self, _batch: &Batch) -> Result<Vec<u8>, DomainError> {
+        Ok(Vec::new())
+    }
+    async fn submit(&self, _batch: &Batch, _proof: &str) -> Result<String, DomainError> {
+        Err(DomainError::Da("MockCommitter only commits pointers".into()))
+    }
+    async fn check_confirmation(&self, _tx_hash: &str) -> Result<bool, DomainError> {
+        Ok(true)
+    }
+}
+
+#[async_trait::async_trait]
+impl BridgeCommitter for MockCommitter {
+    async fn commit_pointer(
+        &self,
+        _batch: &Batch,
+        _proof: &str,
+        da_id: u8,
+        da_meta: Vec<u8>,
+    ) -> Result<SentTx, DomainError> {
+        let mut commits = self.commits.lock().unwrap();
+        commits.push((da_id, da_meta));
+        Ok(SentTx { hash: format!("0xcommit{}", commits.len()), raw: None })
     }
 }
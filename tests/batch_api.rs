@@ -0,0 +1,166 @@
+use std::sync::Arc;
+use submitter_rs::{
+    application::ports::Storage,
+    domain::batch::{Batch, BatchId, BatchStatus, ProofSystem},
+    infrastructure::{
+        batch_api::{batch_router, limit_concurrency, serve_batch_api, BatchApi},
+        storage_sqlite::SqliteStorage,
+    },
+};
+
+#[tokio::test]
+async fn test_post_batch_is_stored_once() {
+    let storage: Arc<dyn Storage> = Arc::new(SqliteStorage::new("sqlite::memory:").await.unwrap());
+    let router = batch_router(BatchApi {
+        storage: storage.clone(),
+        chain_id: 1337,
+        bridge: "0x0000000000000000000000000000000000000001".into(),
+        max_pending: None,
+        proof_system: Default::default(),
+        rollup: "default".into(),
+    });
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/batches", listener.local_addr().unwrap());
+    tokio::spawn(serve_batch_api(listener, router));
+
+    let data = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(data.path(), b"runtime payload").unwrap();
+    let body = serde_json::json!({
+        "data_file": data.path().to_string_lossy(),
+        "new_root": "0x0000000000000000000000000000000000000000000000000000000000000001",
+        "da_mode": "calldata",
+    });
+
+    let client = reqwest::Client::new();
+    let res = client.post(&url).json(&body).send().await.unwrap();
+    assert_eq!(res.status(), 201);
+    let created: serde_json::Value = res.json().await.unwrap();
+
+    let pending = storage.get_pending_batches().await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].status, BatchStatus::Discovered);
+    assert_eq!(created["batch_id"], pending[0].id.to_string());
+
+    // Posting the same batch again hands back the same id without a second row
+    let res = client.post(&url).json(&body).send().await.unwrap();
+    assert_eq!(res.status(), 200);
+    let again: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(again["batch_id"], created["batch_id"]);
+    assert_eq!(storage.get_pending_batches().await.unwrap().len(), 1);
+
+    let missing = serde_json::json!({ "data_file": "/nonexistent", "new_root": "0x01", "da_mode": "calldata" });
+    let res = client.post(&url).json(&missing).send().await.unwrap();
+    assert_eq!(res.status(), 400);
+
+    // A request may pick the proof system of its batch
+    let plonk = serde_json::json!({
+        "data_file": data.path().to_string_lossy(),
+        "new_root": "0x0000000000000000000000000000000000000000000000000000000000000002",
+        "da_mode": "calldata",
+        "proof_system": "plonk",
+    });
+    let res = client.post(&url).json(&plonk).send().await.unwrap();
+    assert_eq!(res.status(), 201);
+    let created: serde_json::Value = res.json().await.unwrap();
+    let id = created["batch_id"].as_str().unwrap().parse().unwrap();
+    let stored = storage.get_batch(BatchId(id)).await.unwrap().unwrap();
+    assert_eq!(stored.proof_system, ProofSystem::Plonk);
+}
+
+#[tokio::test]
+async fn test_get_batch_by_id() {
+    let storage: Arc<dyn Storage> = Arc::new(SqliteStorage::new("sqlite::memory:").await.unwrap());
+    let mut batch = Batch::new(
+        1337,
+        "0x01",
+        "f".into(),
+        "h".into(),
+        "0x02".into(),
+        "Calldata".into(),
+    );
+    batch.status = BatchStatus::Submitted;
+    batch.tx_hash = Some("0xabc".into());
+    storage.save_batch(&batch).await.unwrap();
+
+    let router = batch_router(BatchApi {
+        storage,
+        chain_id: 1337,
+        bridge: "0x01".into(),
+        max_pending: None,
+        proof_system: Default::default(),
+        rollup: "default".into(),
+    });
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}/batches", listener.local_addr().unwrap());
+    tokio::spawn(serve_batch_api(listener, router));
+
+    let res = reqwest::get(format!("{}/{}", base, batch.id))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["id"], batch.id.to_string());
+    assert_eq!(body["status"], "Submitted");
+    assert_eq!(body["tx_hash"], "0xabc");
+
+    let res = reqwest::get(format!("{}/{}", base, uuid::Uuid::new_v4()))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 404);
+}
+
+#[tokio::test]
+async fn test_requests_beyond_concurrency_limit_are_shed() {
+    let dir = tempfile::tempdir().unwrap();
+    let url = format!("sqlite://{}/api.db?mode=rwc", dir.path().display());
+    let storage: Arc<dyn Storage> = Arc::new(SqliteStorage::new(&url).await.unwrap());
+    let read_storage: Arc<dyn Storage> = Arc::new(SqliteStorage::with_pool_size(&url, 1).await.unwrap());
+    let batch = Batch::new(1337, "0x01", "f".into(), "h".into(), "0x02".into(), "Calldata".into());
+    storage.save_batch(&batch).await.unwrap();
+
+    // A request that holds its slot until released
+    let release = Arc::new(tokio::sync::Notify::new());
+    let hold = axum::Router::new().route(
+        "/hold",
+        axum::routing::get({
+            let release = release.clone();
+            move || async move { release.notified().await }
+        }),
+    );
+    let api = batch_router(BatchApi {
+        storage: read_storage,
+        chain_id: 1337,
+        bridge: "0x01".into(),
+        max_pending: None,
+        proof_system: Default::default(),
+        rollup: "default".into(),
+    });
+    let router = limit_concurrency("default", api.merge(hold), 1);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(serve_batch_api(listener, router));
+
+    let held = tokio::spawn(reqwest::get(format!("{}/hold", base)));
+    let batch_url = format!("{}/batches/{}", base, batch.id);
+    let mut status = reqwest::get(&batch_url).await.unwrap().status();
+    for _ in 0..100 {
+        if status == 503 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        status = reqwest::get(&batch_url).await.unwrap().status();
+    }
+    assert_eq!(status, 503);
+
+    // The orchestrator's pool is untouched by API traffic
+    let mut next = batch.clone();
+    next.status = BatchStatus::Proving;
+    storage.save_batch(&next).await.unwrap();
+
+    release.notify_one();
+    assert_eq!(held.await.unwrap().unwrap().status(), 200);
+    let res = reqwest::get(&batch_url).await.unwrap();
+    assert_eq!(res.status(), 200);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["status"], "Proving");
+}
@@ -1,20 +1,38 @@
-use assert_cmd::Command;
+use assert_cmd::cargo::cargo_bin_cmd;
 use predicates::prelude::*;
 
 #[test]
 fn test_submitter_help() {
-    let mut cmd = Command::cargo_bin("submitter").unwrap();
+    let mut cmd = cargo_bin_cmd!("submitter");
     cmd.arg("--help").assert().success();
 }
 
 #[test]
 fn test_submitter_rs_help() {
-    let mut cmd = Command::cargo_bin("submitter-rs").unwrap();
+    let mut cmd = cargo_bin_cmd!("submitter-rs");
     cmd.arg("--help").assert().success();
 }
 
 #[test]
 fn test_submitter_missing_config() {
-    let mut cmd = Command::cargo_bin("submitter").unwrap();
+    let mut cmd = cargo_bin_cmd!("submitter");
     cmd.assert().failure().stderr(predicate::str::contains("Usage:"));
 }
+
+#[test]
+fn test_submitter_retry_requires_batch_id() {
+    let mut cmd = cargo_bin_cmd!("submitter");
+    cmd.args(["--config", "submitter.yaml", "retry"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--batch-id"));
+}
+
+#[test]
+fn test_submitter_run_only_requires_value() {
+    let mut cmd = cargo_bin_cmd!("submitter");
+    cmd.args(["--config", "submitter.yaml", "run", "--only"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--only"));
+}
@@ -5,10 +5,10 @@ use std::sync::Mutex as StdMutex;
 use submitter_rs::{
     application::{
         orchestrator::Orchestrator,
-        ports::{BridgeReader, DaStrategy, ProofProvider, ProofResponse, Storage},
+        ports::{BridgeReader, DaStrategy, ProofProvider, ProofRequest, ProofResponse, Storage},
     },
     domain::{
-        batch::{Batch, BatchId, BatchStatus},
+        batch::{Batch, BatchStatus},
         errors::DomainError,
     },
     infrastructure::storage_sqlite::SqliteStorage,
@@ -43,7 +43,7 @@ impl DaStrategy for MockDaStrategy {
         0
     }
 
-    fn compute_commitment(&self, _batch: &Batch) -> Result<H256, DomainError> {
+    async fn compute_commitment(&self, _batch: &Batch) -> Result<H256, DomainError> {
         Ok(H256::zero())
     }
 
@@ -71,13 +71,10 @@ impl DaStrategy for MockDaStrategy {
 struct TestProofProvider;
 #[async_trait]
 impl ProofProvider for TestProofProvider {
-    async fn get_proof(
-        &self,
-        _batch_id: &BatchId,
-        _public_inputs: &[u8],
-    ) -> Result<ProofResponse, DomainError> {
+    async fn get_proof(&self, _request: &ProofRequest) -> Result<ProofResponse, DomainError> {
         Ok(ProofResponse {
             proof: "test_proof".to_string(),
+            proofs: Vec::new(),
         })
     }
 }
@@ -1,7 +1,7 @@
 use submitter_rs::startup;
 use std::io::Write;
 use tempfile::NamedTempFile;
-use wiremock::matchers::{method};
+use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 #[tokio::test]
@@ -112,3 +112,179 @@ prover:
     
     let _ = std::fs::remove_file("data_full_run.txt");
 }
+
+#[tokio::test]
+async fn test_build_all_two_rollups() {
+    let mock_server = MockServer::start().await;
+    let rpc_url = mock_server.uri();
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": "0x539"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let mut config_file = NamedTempFile::new().unwrap();
+    let config_content = format!(r#"
+network:
+  rpc_url: "{}"
+  chain_id: 1337
+da:
+  mode: calldata
+  blob_binding: opcode
+rollups:
+  - name: alpha
+    database_url: "sqlite::memory:"
+    contracts:
+      bridge: '0x0000000000000000000000000000000000000001'
+    batch:
+      data_file: 'data_rollup_alpha.txt'
+      new_root: '0x0000000000000000000000000000000000000000000000000000000000000001'
+  - name: beta
+    database_url: "sqlite::memory:"
+    contracts:
+      bridge: '0x0000000000000000000000000000000000000002'
+    batch:
+      data_file: 'data_rollup_beta.txt'
+      new_root: '0x0000000000000000000000000000000000000000000000000000000000000002'
+"#, rpc_url);
+
+    write!(config_file, "{}", config_content).unwrap();
+    let config_path = config_file.path().to_path_buf();
+
+    std::env::set_var("SUBMITTER_PRIVATE_KEY", "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20");
+
+    std::fs::write("data_rollup_alpha.txt", "alpha").unwrap();
+    std::fs::write("data_rollup_beta.txt", "beta").unwrap();
+
+    let rollups = startup::build_all(config_path).await.expect("Failed to build rollups");
+
+    let names: Vec<_> = rollups.iter().map(|(name, _, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["alpha", "beta"]);
+    for (name, storage, orchestrator) in &rollups {
+        assert_eq!(orchestrator.rollup(), name);
+        let pending = storage.get_pending_batches().await.expect("Failed to get pending");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].data_file, format!("data_rollup_{}.txt", name));
+    }
+
+    let _ = std::fs::remove_file("data_rollup_alpha.txt");
+    let _ = std::fs::remove_file("data_rollup_beta.txt");
+}
+
+#[tokio::test]
+async fn test_build_warms_up_prover() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": "0x539"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let prover_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/health"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&prover_server)
+        .await;
+
+    let mut config_file = NamedTempFile::new().unwrap();
+    let config_content = format!(r#"
+network:
+  rpc_url: "{}"
+  chain_id: 1337
+contracts:
+  bridge: '0x0000000000000000000000000000000000000000'
+batch:
+  data_file: 'data_warm_up.txt'
+  new_root: '0x0000000000000000000000000000000000000000000000000000000000000000'
+da:
+  mode: calldata
+  blob_binding: opcode
+prover:
+  url: "{}"
+  warm_up: true
+"#, mock_server.uri(), prover_server.uri());
+
+    write!(config_file, "{}", config_content).unwrap();
+    let config_path = config_file.path().to_path_buf();
+
+    std::env::set_var("SUBMITTER_PRIVATE_KEY", "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20");
+    std::env::set_var("DATABASE_URL", "sqlite::memory:");
+    std::fs::write("data_warm_up.txt", "dummy").unwrap();
+
+    startup::build(config_path).await.expect("Failed to build app");
+
+    let requests = prover_server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].url.path(), "/health");
+    let _ = std::fs::remove_file("data_warm_up.txt");
+}
+
+#[tokio::test]
+async fn test_content_dedup_skips_reseeded_payload() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": "0x539"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let data_file = dir.path().join("payload.txt");
+    std::fs::write(&data_file, "same payload").unwrap();
+    let database_url = format!("sqlite://{}?mode=rwc", dir.path().join("dedup.db").display());
+    std::env::set_var("SUBMITTER_PRIVATE_KEY", "0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20");
+
+    let config = |new_root: &str, dedup: &str| {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, r#"
+network:
+  rpc_url: "{}"
+  chain_id: 1337
+contracts:
+  bridge: '0x0000000000000000000000000000000000000001'
+da:
+  mode: calldata
+  blob_binding: opcode
+rollups:
+  - name: dedup
+    database_url: "{}"
+    batch:
+      data_file: '{}'
+      new_root: '{}'
+      content_dedup: {}
+"#, mock_server.uri(), database_url, data_file.display(), new_root, dedup).unwrap();
+        file
+    };
+
+    // First payload goes through and confirms
+    let first = config("0x0000000000000000000000000000000000000000000000000000000000000001", "skip");
+    let (_, storage, _) = startup::build_all(first.path().to_path_buf()).await.unwrap().remove(0);
+    let mut batch = storage.get_pending_batches().await.unwrap().remove(0);
+    batch.status = submitter_rs::domain::batch::BatchStatus::Confirmed;
+    storage.save_batch(&batch).await.unwrap();
+
+    // Same bytes under a new root: flagged but queued with warn, dropped with skip
+    let warn = config("0x0000000000000000000000000000000000000000000000000000000000000002", "warn");
+    let (_, storage, _) = startup::build_all(warn.path().to_path_buf()).await.unwrap().remove(0);
+    let queued = storage.get_pending_batches().await.unwrap();
+    assert_eq!(queued.len(), 1);
+    let mut queued = queued[0].clone();
+    queued.status = submitter_rs::domain::batch::BatchStatus::Failed;
+    storage.save_batch(&queued).await.unwrap();
+
+    let skip = config("0x0000000000000000000000000000000000000000000000000000000000000003", "skip");
+    let (_, storage, _) = startup::build_all(skip.path().to_path_buf()).await.unwrap().remove(0);
+    assert!(storage.get_pending_batches().await.unwrap().is_empty());
+}